@@ -0,0 +1,67 @@
+use ark_bls12_381::Fr; //scalar field of BLS12-381
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar; // for defining variables in the circuit
+use ark_r1cs_std::eq::EqGadget; // for enforcing equality constraints
+use ark_r1cs_std::fields::fp::FpVar; //for circuit variables
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError}; //for defining circuits
+use zeroize::{Zeroize, ZeroizeOnDrop}; // for wiping the secrets once we are done with them
+use zk_core::spec::InputSpec; // the names of the public inputs
+
+/*
+This is the circuit from the addition example, pulled out here so the binaries
+(src/bin/addition.rs and the zk-toy cli in zk-cli) can share it.
+x and y are secret, z is public, and the circuit enforces x + y = z.
+We use option because during setup we don't have actual values yet.
+The field defaults to the BLS12-381 scalar field, but any prime field works,
+e.g. BN254 when the proof is headed for ethereum.
+x and y get zeroed when the circuit is dropped (proving consumes it, so right after the proof).
+*/
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct AdditionCircuit<F: PrimeField = Fr> {
+    pub x: Option<F>,
+    pub y: Option<F>,
+    #[zeroize(skip)]
+    pub z: Option<F>,
+}
+
+impl<F: PrimeField> AdditionCircuit<F> {
+    // the "shape only" version of the circuit that setup uses
+    pub fn empty() -> Self {
+        Self {
+            x: None,
+            y: None,
+            z: None,
+        }
+    }
+
+    // fills in the witness, z is computed so the statement is true
+    pub fn new(x: F, y: F) -> Self {
+        Self {
+            x: Some(x),
+            y: Some(y),
+            z: Some(x + y),
+        }
+    }
+
+    // z is the only public input
+    pub fn input_spec() -> InputSpec {
+        InputSpec::new("x + y = z, x and y secret", &["z"])
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for AdditionCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        // sets x and y as the secret inputs (aka witnesses)
+        let x = FpVar::new_witness(cs.clone(), || self.x.ok_or(SynthesisError::AssignmentMissing))?;
+        let y = FpVar::new_witness(cs.clone(), || self.y.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // sets z as public input
+        let z = FpVar::new_input(cs.clone(), || self.z.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // this says that they satisfy the constraint x + y = z
+        let sum = &x + &y;
+        sum.enforce_equal(&z)?;
+
+        Ok(())
+    }
+}
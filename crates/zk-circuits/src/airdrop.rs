@@ -0,0 +1,106 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use zk_gadgets::hasher::CircuitHasher;
+use zk_gadgets::merkle::{self, MerklePath, TreeShape};
+
+/*
+A private airdrop claim. Before the snapshot every eligible address registers a
+commitment H(address, secret), and the airdrop's merkle root is over those. To claim,
+you prove "one of the leaves is H(address, secret) for a secret I know", and publish
+the nullifier H(secret, airdrop id). The contract keeps the nullifiers it has seen, so
+every leaf claims once, and nothing links the nullifier to the leaf or the address.
+
+Public inputs: root, nullifier, recipient, airdrop id.
+Witnesses: the eligible address, the secret and the merkle path.
+
+The recipient isn't used by anything, it is squared once so it ends up in a constraint.
+It is a public input, so the proof is bound to it: someone who copies the proof out of
+the mempool and swaps in their own address gets a proof that doesn't verify.
+*/
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct AirdropCircuit<F: PrimeField, H: CircuitHasher<F>> {
+    #[zeroize(skip)]
+    pub hasher: H,
+    #[zeroize(skip)]
+    pub shape: TreeShape,
+    #[zeroize(skip)]
+    pub root: Option<F>,
+    #[zeroize(skip)]
+    pub nullifier: Option<F>,
+    #[zeroize(skip)]
+    pub recipient: Option<F>,
+    #[zeroize(skip)]
+    pub airdrop_id: Option<F>,
+    pub address: Option<F>,
+    pub secret: Option<F>,
+    pub path: Option<MerklePath<F>>,
+}
+
+pub fn leaf<F: PrimeField, H: CircuitHasher<F>>(hasher: &H, address: F, secret: F) -> F {
+    hasher.hash(&[address, secret])
+}
+
+pub fn nullifier<F: PrimeField, H: CircuitHasher<F>>(hasher: &H, secret: F, airdrop_id: F) -> F {
+    hasher.hash(&[secret, airdrop_id])
+}
+
+impl<F: PrimeField, H: CircuitHasher<F>> AirdropCircuit<F, H> {
+    pub fn empty(hasher: H, shape: TreeShape) -> Self {
+        Self {
+            hasher,
+            shape,
+            root: None,
+            nullifier: None,
+            recipient: None,
+            airdrop_id: None,
+            address: None,
+            secret: None,
+            path: None,
+        }
+    }
+
+    pub fn new(hasher: H, airdrop_id: F, address: F, secret: F, path: MerklePath<F>, recipient: F) -> Self {
+        let root = path.root(&hasher, leaf(&hasher, address, secret));
+        Self {
+            shape: path.shape,
+            root: Some(root),
+            nullifier: Some(nullifier(&hasher, secret, airdrop_id)),
+            recipient: Some(recipient),
+            airdrop_id: Some(airdrop_id),
+            address: Some(address),
+            secret: Some(secret),
+            path: Some(path),
+            hasher,
+        }
+    }
+}
+
+// the public input vector, in the order the circuit allocates it
+pub fn public_inputs<F: PrimeField>(root: F, nullifier: F, recipient: F, airdrop_id: F) -> Vec<F> {
+    vec![root, nullifier, recipient, airdrop_id]
+}
+
+impl<F: PrimeField, H: CircuitHasher<F>> ConstraintSynthesizer<F> for AirdropCircuit<F, H> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let root = FpVar::new_input(cs.clone(), || self.root.ok_or(SynthesisError::AssignmentMissing))?;
+        let nullifier = FpVar::new_input(cs.clone(), || self.nullifier.ok_or(SynthesisError::AssignmentMissing))?;
+        let recipient = FpVar::new_input(cs.clone(), || self.recipient.ok_or(SynthesisError::AssignmentMissing))?;
+        let airdrop_id = FpVar::new_input(cs.clone(), || self.airdrop_id.ok_or(SynthesisError::AssignmentMissing))?;
+
+        let address = FpVar::new_witness(cs.clone(), || self.address.ok_or(SynthesisError::AssignmentMissing))?;
+        let secret = FpVar::new_witness(cs.clone(), || self.secret.ok_or(SynthesisError::AssignmentMissing))?;
+        let (siblings, index_bits) = merkle::path_var(cs.clone(), self.shape, self.path.as_ref())?;
+
+        let leaf = self.hasher.hash_var(cs.clone(), &[address, secret.clone()])?;
+        merkle::root_var(cs.clone(), &self.hasher, self.shape, &leaf, &siblings, &index_bits)?.enforce_equal(&root)?;
+        self.hasher.hash_var(cs, &[secret, airdrop_id])?.enforce_equal(&nullifier)?;
+
+        // the recipient square, see above
+        let _ = &recipient * &recipient;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use zk_gadgets::compare::{enforce_bits, enforce_bits_by_table};
+
+use crate::templates::{RangeCircuit, TemplateError};
+
+/*
+There's more than one way to arithmetize a statement, and the ways differ in what
+they cost more than in what they prove. Two pairs to compare:
+
+  range    lo <= x <= hi with each check done by bits (templates.rs) or by limbs
+           looked up in a small table, RangeCheckCircuit with a RangeMethod
+  merkle   membership of one leaf among 16 in a binary tree of depth 4 or a 4-ary
+           tree of depth 2, MembershipCircuit with TreeShape::binary(4) or
+           TreeShape::new(4, 2)
+
+Both sides of a pair accept exactly the same witnesses, which the arithmetizations
+example asserts; `zk-toy synth range method=bits --versus method=table` puts their
+numbers side by side (zk-circuits/src/dry_run.rs, Comparison).
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeMethod {
+    // one boolean per bit, enforce_bits
+    Bits,
+    // limbs of limb_bits, each a root of the table's vanishing polynomial
+    Table { limb_bits: usize },
+}
+
+impl fmt::Display for RangeMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeMethod::Bits => write!(f, "bits"),
+            RangeMethod::Table { limb_bits } => write!(f, "a table of {limb_bits}-bit limbs"),
+        }
+    }
+}
+
+impl RangeMethod {
+    pub fn enforce<F: PrimeField>(&self, cs: ConstraintSystemRef<F>, value: &FpVar<F>, bits: usize) -> Result<(), SynthesisError> {
+        match self {
+            RangeMethod::Bits => enforce_bits(cs, value, bits),
+            RangeMethod::Table { limb_bits } => enforce_bits_by_table(cs, value, bits, *limb_bits),
+        }
+    }
+}
+
+// the statement of RangeCircuit, with the range checks done by `method`
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct RangeCheckCircuit<F: PrimeField> {
+    #[zeroize(skip)]
+    pub method: RangeMethod,
+    #[zeroize(skip)]
+    pub bits: usize,
+    #[zeroize(skip)]
+    pub lo: Option<F>,
+    #[zeroize(skip)]
+    pub hi: Option<F>,
+    pub x: Option<F>,
+}
+
+impl<F: PrimeField> RangeCheckCircuit<F> {
+    pub fn empty(method: RangeMethod, bits: usize) -> Self {
+        Self {
+            method,
+            bits,
+            lo: None,
+            hi: None,
+            x: None,
+        }
+    }
+
+    pub fn new(method: RangeMethod, bits: usize, x: F, lo: F, hi: F) -> Result<Self, TemplateError> {
+        let checked = RangeCircuit::new(bits, x, lo, hi)?;
+        Ok(Self {
+            method,
+            bits,
+            lo: checked.lo,
+            hi: checked.hi,
+            x: checked.x,
+        })
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for RangeCheckCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let lo = FpVar::new_input(cs.clone(), || self.lo.ok_or(SynthesisError::AssignmentMissing))?;
+        let hi = FpVar::new_input(cs.clone(), || self.hi.ok_or(SynthesisError::AssignmentMissing))?;
+        let x = FpVar::new_witness(cs.clone(), || self.x.ok_or(SynthesisError::AssignmentMissing))?;
+        self.method.enforce(cs.clone(), &x, self.bits)?;
+        self.method.enforce(cs.clone(), &(&x - &lo), self.bits)?;
+        self.method.enforce(cs, &(&hi - &x), self.bits)
+    }
+}
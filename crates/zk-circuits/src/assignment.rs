@@ -0,0 +1,170 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode};
+use serde::{Deserialize, Serialize};
+use tracing::span;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+/*
+The full assignment of a satisfied constraint system as json, for looking at
+witnesses from outside Rust: a notebook plotting how the bits of a range check are
+distributed, a script recomputing a Poseidon output with another implementation and
+comparing it to the witness the gadget made.
+
+R1CS variables have no names, so a variable gets the namespace it was allocated in:
+the path of ark-r1cs-std spans (target "r1cs", the same ones trace.rs prints) open at
+the time, like "new_variable" or "enforce_equal > to_bits_le > new_variable". Those are
+recorded by a tracing layer that watches the variable counts of the system being
+synthesized and files the new variables under the path whenever a span opens or
+closes. Variables allocated outside any span are "(top level)".
+
+Public inputs are numbered from 1 like in the R1CS matrices (0 is the constant one,
+left out), witnesses from 0. Values are decimal strings. The export refuses an
+unsatisfied system and names the first constraint that fails: an assignment that
+doesn't satisfy the circuit is a bug to find first, not data to analyze.
+
+This writes witnesses to disk in the clear. It's only for debugging with test values.
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub index: usize,
+    pub namespace: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assignment {
+    pub circuit: String,
+    // the field, as its modulus in decimal
+    pub modulus: String,
+    pub constraints: usize,
+    pub inputs: Vec<Entry>,
+    pub witnesses: Vec<Entry>,
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Synthesis(SynthesisError),
+    // the namespace of the first constraint that doesn't hold
+    Unsatisfied(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Synthesis(e) => write!(f, "synthesis error: {e}"),
+            ExportError::Unsatisfied(at) => write!(f, "the assignment doesn't satisfy the circuit, first at {at}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<SynthesisError> for ExportError {
+    fn from(e: SynthesisError) -> Self {
+        ExportError::Synthesis(e)
+    }
+}
+
+struct Recording {
+    counts: Box<dyn Fn() -> (usize, usize)>,
+    stack: Vec<&'static str>,
+    inputs: Vec<String>,
+    witnesses: Vec<String>,
+}
+
+impl Recording {
+    // files the variables allocated since the last span event under the current path
+    fn catch_up(&mut self) {
+        let (inputs, witnesses) = (self.counts)();
+        let path = if self.stack.is_empty() { "(top level)".to_string() } else { self.stack.join(" > ") };
+        self.inputs.resize(inputs.max(self.inputs.len()), path.clone());
+        self.witnesses.resize(witnesses.max(self.witnesses.len()), path);
+    }
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+struct Namespaces;
+
+impl<S: tracing::Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Namespaces {
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if span.metadata().target() != "r1cs" {
+            return;
+        }
+        RECORDING.with(|r| {
+            if let Some(recording) = r.borrow_mut().as_mut() {
+                recording.catch_up();
+                recording.stack.push(span.name());
+            }
+        });
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if span.metadata().target() != "r1cs" {
+            return;
+        }
+        RECORDING.with(|r| {
+            if let Some(recording) = r.borrow_mut().as_mut() {
+                recording.catch_up();
+                recording.stack.pop();
+            }
+        });
+    }
+}
+
+fn entries<F: PrimeField>(values: &[F], namespaces: &[String], first: usize) -> Vec<Entry> {
+    values
+        .iter()
+        .zip(namespaces)
+        .enumerate()
+        .map(|(i, (value, namespace))| Entry {
+            index: first + i,
+            namespace: namespace.clone(),
+            value: value.to_string(),
+        })
+        .collect()
+}
+
+// synthesizes the circuit with its witness and records where every variable came from
+pub fn export<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: &str, synthesizer: C) -> Result<Assignment, ExportError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Prove { construct_matrices: true });
+    let counted: ConstraintSystemRef<F> = cs.clone();
+    // the constant one is there before the circuit allocates anything
+    let recording = Recording {
+        counts: Box::new(move || (counted.num_instance_variables(), counted.num_witness_variables())),
+        stack: Vec::new(),
+        inputs: vec!["(constant one)".to_string()],
+        witnesses: Vec::new(),
+    };
+    RECORDING.with(|r| *r.borrow_mut() = Some(recording));
+    let subscriber = Registry::default().with(ConstraintLayer::default()).with(Namespaces);
+    let synthesized = tracing::subscriber::with_default(subscriber, || synthesizer.generate_constraints(cs.clone()));
+    let mut recording = RECORDING.with(|r| r.borrow_mut().take()).expect("the recording was set above");
+    synthesized?;
+    recording.catch_up();
+
+    if let Some(at) = cs.which_is_unsatisfied()? {
+        return Err(ExportError::Unsatisfied(if at.is_empty() { "(top level)".to_string() } else { at }));
+    }
+    let (instance, witness) = {
+        let inner = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+        (inner.instance_assignment.clone(), inner.witness_assignment.clone())
+    };
+    Ok(Assignment {
+        circuit: circuit.to_string(),
+        modulus: F::MODULUS.to_string(),
+        constraints: cs.num_constraints(),
+        inputs: entries(&instance[1..], &recording.inputs[1..], 1),
+        witnesses: entries(&witness, &recording.witnesses, 0),
+    })
+}
@@ -0,0 +1,122 @@
+use ark_bls12_381::Fr;
+use ark_crypto_primitives::crh::sha256::constraints::Sha256Gadget;
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::ToBitsGadget;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use zk_gadgets::compare::enforce_less_or_equal;
+
+/*
+Bitcoin proof of work. A block header is 80 bytes (version, previous block hash,
+merkle root, time, the compact target "bits", nonce), and the block is valid work if
+sha256(sha256(header)), read as a little-endian 256 bit number, is below the target.
+
+The circuit proves "I know a header whose hash is below this public target" without
+showing the header: a miner showing they found a block without saying which one.
+It's two sha256 calls on bytes (three compressions, the 80 bytes take two blocks), so
+nearly all of its ~120k constraints are sha256.
+
+The hash is compared as a field element, so it has to fit in one: the top byte of the
+hash has to be zero and the target below 2^248. Every real target is far below that,
+the easiest one ever (difficulty 1, the genesis block's) is under 2^224.
+*/
+
+pub const HEADER_LEN: usize = 80;
+pub const TARGET_BITS: usize = 248;
+
+pub type Header = [u8; HEADER_LEN];
+
+// the genesis block: version 1, no previous block, 2009-01-03, difficulty 1
+pub const GENESIS: Header = [
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0xa3, 0xed, 0xfd,
+    0x7a, 0x7b, 0x12, 0xb2, 0x7a, 0xc7, 0x2c, 0x3e, 0x67, 0x76, 0x8f, 0x61, 0x7f, 0xc8, 0x1b, 0xc3, 0x88, 0x8a, 0x51, 0x32,
+    0x3a, 0x9f, 0xb8, 0xaa, 0x4b, 0x1e, 0x5e, 0x4a, 0x29, 0xab, 0x5f, 0x49, 0xff, 0xff, 0x00, 0x1d, 0x1d, 0xac, 0x2b, 0x7c,
+];
+
+// sha256(sha256(header)), in the byte order it comes out of sha256
+pub fn block_hash(header: &Header) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(header)).into()
+}
+
+// the way block explorers print a hash, as a big-endian number
+pub fn display_hash(hash: &[u8; 32]) -> String {
+    hash.iter().rev().map(|b| format!("{b:02x}")).collect()
+}
+
+// the hash as a number, None when it's 2^248 or more (and below no target we take)
+pub fn hash_value(hash: &[u8; 32]) -> Option<Fr> {
+    (hash[31] == 0).then(|| Fr::from_le_bytes_mod_order(&hash[..31]))
+}
+
+// the "bits" field of a header: the target is mantissa * 256^(exponent - 3)
+pub fn header_bits(header: &Header) -> u32 {
+    u32::from_le_bytes(header[72..76].try_into().expect("4 bytes"))
+}
+
+// the target a compact "bits" value stands for, None for negative or too big ones
+pub fn target_from_bits(bits: u32) -> Option<Fr> {
+    let (exponent, mantissa) = ((bits >> 24) as usize, bits & 0x00ff_ffff);
+    if mantissa & 0x0080_0000 != 0 || exponent > TARGET_BITS / 8 {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in mantissa.to_le_bytes()[..3].iter().enumerate() {
+        // exponent - 3 + i, with the bytes below 256^0 shifted out
+        if let Some(at) = (exponent + i).checked_sub(3) {
+            bytes[at] = *byte;
+        }
+    }
+    Some(Fr::from_le_bytes_mod_order(&bytes))
+}
+
+pub fn meets_target(header: &Header, target: Fr) -> bool {
+    hash_value(&block_hash(header)).is_some_and(|h| h.into_bigint() < target.into_bigint())
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct PowCircuit {
+    pub header: Option<Header>,
+    #[zeroize(skip)]
+    pub target: Option<Fr>,
+}
+
+impl PowCircuit {
+    pub fn empty() -> Self {
+        Self { header: None, target: None }
+    }
+
+    pub fn new(header: Header, target: Fr) -> Self {
+        Self {
+            header: Some(header),
+            target: Some(target),
+        }
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for PowCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let target = FpVar::new_input(cs.clone(), || self.target.ok_or(SynthesisError::AssignmentMissing))?;
+        let header = UInt8::new_witness_vec(cs.clone(), &self.header.map(|h| h.map(Some)).unwrap_or([None; HEADER_LEN]))?;
+
+        let first = Sha256Gadget::digest(&header)?;
+        let hash = Sha256Gadget::digest(&first.0)?.0;
+
+        // the top byte is zero, the other 31 are the hash as a number below 2^248
+        hash[31].enforce_equal(&UInt8::constant(0))?;
+        let mut bits = Vec::new();
+        for byte in &hash[..31] {
+            bits.extend(byte.to_bits_le()?);
+        }
+        let value = Boolean::le_bits_to_fp_var(&bits)?;
+        // value < target is value + 1 <= target
+        enforce_less_or_equal(cs, &(value + Fr::from(1u32)), &target, TARGET_BITS)
+    }
+}
@@ -0,0 +1,74 @@
+use ark_bls12_377::constraints::{G1Var, G2Var, PairingVar};
+use ark_bls12_377::{Bls12_377, Config as Bls12_377Config, Fq, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::groups::CurveVar;
+use ark_r1cs_std::pairing::PairingVar as PairingGadget;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+use zk_protocols::bls::{PublicKey, Signature};
+
+/*
+Verifying a BLS signature inside a circuit. The pairing check has to be done with
+arithmetic over the field the curve points live in, and BLS12-381's base field
+isn't the scalar field of any pairing friendly curve we have, so we can't do this
+for BLS12-381 directly.
+That's what the BLS12-377 / BW6-761 pair is for: the scalar field of BW6-761 is
+exactly the base field of BLS12-377, so a Groth16 proof over BW6-761 can check
+BLS12-377 pairings with native field arithmetic. This is how e.g. light clients
+prove "this block was signed" in a single small proof.
+
+Public inputs: the public key and the hashed message H(m). The message is hashed
+outside the circuit, hashing onto a curve inside a circuit is a whole project of its
+own. Witness: the signature itself, so the proof shows "I have a valid signature"
+without revealing it.
+*/
+
+pub type BlsSignature377 = Signature<Bls12_377Config>;
+pub type BlsPublicKey377 = PublicKey<Bls12_377Config>;
+
+#[derive(Clone)]
+pub struct BlsVerifyCircuit {
+    pub public_key: Option<G2Affine>,
+    pub message_hash: Option<G1Affine>,
+    pub signature: Option<G1Affine>,
+}
+
+impl BlsVerifyCircuit {
+    pub fn empty() -> Self {
+        Self {
+            public_key: None,
+            message_hash: None,
+            signature: None,
+        }
+    }
+}
+
+// projective points are allocated as (x, y, z), with z = 1 for anything but the identity
+pub fn public_inputs(public_key: &G2Affine, message_hash: &G1Affine) -> Vec<ark_bw6_761::Fr> {
+    use ark_ff::{One, Zero};
+    let (pk_x, pk_y) = public_key.xy().expect("public keys are never the identity");
+    let (h_x, h_y) = message_hash.xy().expect("hashes to curve are never the identity");
+    vec![pk_x.c0, pk_x.c1, pk_y.c0, pk_y.c1, Fq::one(), Fq::zero(), *h_x, *h_y, Fq::one()]
+}
+
+impl ConstraintSynthesizer<ark_bw6_761::Fr> for BlsVerifyCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<ark_bw6_761::Fr>) -> Result<(), SynthesisError> {
+        let pk = G2Var::new_input(cs.clone(), || self.public_key.ok_or(SynthesisError::AssignmentMissing))?;
+        let h = G1Var::new_input(cs.clone(), || self.message_hash.ok_or(SynthesisError::AssignmentMissing))?;
+        // witnesses get a subgroup check for free when they're allocated
+        let sig = G1Var::new_witness(cs.clone(), || self.signature.ok_or(SynthesisError::AssignmentMissing))?;
+        let g2 = G2Var::new_constant(cs.clone(), G2Affine::generator())?;
+
+        // e(sig, g2) == e(H(m), pk) is the same as e(sig, g2) * e(-H(m), pk) == 1,
+        // and the second form only needs one final exponentiation
+        let p = [PairingVar::prepare_g1(&sig)?, PairingVar::prepare_g1(&h.negate()?)?];
+        let q = [PairingVar::prepare_g2(&g2)?, PairingVar::prepare_g2(&pk)?];
+        let result = PairingVar::product_of_pairings(&p, &q)?;
+        result.enforce_equal(&<PairingVar as PairingGadget<Bls12_377, Fq>>::GTVar::one())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,580 @@
+use ark_bls12_381::Fr;
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use zk_gadgets::hasher::PoseidonHasher;
+use zk_gadgets::merkle::{MerklePath, TreeShape};
+use zk_gadgets::poseidon::{self, poseidon_config};
+
+use crate::addition::AdditionCircuit;
+use crate::airdrop::{self, AirdropCircuit};
+use crate::bitcoin::{self, PowCircuit};
+use crate::bristol::{Bristol, BristolCircuit};
+use crate::challenges::{self, Challenge, Verdict};
+use crate::credential::{AgeCircuit, DATE_BITS};
+use crate::document::{self, DocumentCircuit, DocumentLayout};
+use crate::eth_address::{self, AddressCircuit, PublicKey};
+use crate::membership::MembershipCircuit;
+use crate::templates::{RangeCircuit, SumCircuit, ThresholdCircuit};
+use crate::trie::{StorageCircuit, Trie};
+use crate::vss::{Polynomial, Share, VssCircuit};
+
+/*
+Boundary inputs, where off-by-one gadget bugs live: 0 and 1, p - 1 (which is -1, the
+value every missing range check lets through), the halves of the field, the edges of
+the bit widths circuits like to range check, and the same value everywhere.
+
+A check runs a circuit on such inputs and compares the constraint system with the
+native predicate the circuit is supposed to stand for: satisfied exactly when the
+statement holds. A disagreement either way is a bug, an unsatisfiable honest witness
+or (worse) a satisfiable false one.
+
+Witnesses get every boundary value, that's what a malicious prover sends. Public
+values stay in the range the circuit documents for them, a verifier picking a target
+above 2^248 for bitcoin.rs is holding it wrong, not a bug.
+
+The cases for every example circuit are at the bottom, one function per circuit, and
+broken() runs the same values over the challenge circuits, where a disagreement is
+the bug the challenge is about. src/bin/boundaries.rs prints them, the tests assert
+them. Not in here: the bls circuit (a pairing in a circuit, too slow to run hundreds
+of times) and the dkg, which is the vss circuit once per dealer.
+*/
+
+// the boundary values of the field, with names to print them by
+pub fn values<F: PrimeField>() -> Vec<(String, F)> {
+    let minus = |x: u64| -F::from(x);
+    let half = F::from(2u64).inverse().expect("2 is invertible in an odd field");
+    let mut values = vec![
+        ("0".to_string(), F::zero()),
+        ("1".to_string(), F::one()),
+        ("2".to_string(), F::from(2u64)),
+        ("p - 1".to_string(), minus(1)),
+        ("p - 2".to_string(), minus(2)),
+        // (p - 1) / 2 and (p + 1) / 2, where "negative" starts
+        ("(p - 1) / 2".to_string(), minus(1) * half),
+        ("(p + 1) / 2".to_string(), half),
+    ];
+    for bits in [8, 32, 64, F::MODULUS_BIT_SIZE as usize - 1] {
+        values.extend(bounded(bits).into_iter().filter(|(name, _)| name.starts_with('2')));
+    }
+    values
+}
+
+// the edges of a range check to `bits` bits: 0, 2^bits - 1 and 2^bits, and p - 1
+pub fn bounded<F: PrimeField>(bits: usize) -> Vec<(String, F)> {
+    let power = F::from(2u64).pow([bits as u64]);
+    vec![
+        ("0".to_string(), F::zero()),
+        (format!("2^{bits} - 1"), power - F::one()),
+        (format!("2^{bits}"), power),
+        ("p - 1".to_string(), -F::one()),
+    ]
+}
+
+// a value and its neighbours, the other side of every <= and <
+pub fn around<F: PrimeField>(name: &str, value: F) -> Vec<(String, F)> {
+    vec![
+        (format!("{name} - 1"), value - F::one()),
+        (name.to_string(), value),
+        (format!("{name} + 1"), value + F::one()),
+    ]
+}
+
+// `arity` values: one boundary value in one place and another everywhere else, which
+// covers every pair for two values and "the same everywhere" for any number of them
+pub fn tuples<F: PrimeField>(values: &[(String, F)], arity: usize) -> Vec<Vec<(String, F)>> {
+    let mut tuples: Vec<Vec<(String, F)>> = Vec::new();
+    for place in 0..arity {
+        for one in values {
+            for rest in values {
+                let tuple: Vec<(String, F)> = (0..arity).map(|i| if i == place { one.clone() } else { rest.clone() }).collect();
+                if !tuples.contains(&tuple) {
+                    tuples.push(tuple);
+                }
+            }
+        }
+    }
+    tuples
+}
+
+// "x = 0, y = p - 1"
+pub fn describe<F: PrimeField>(names: &[&str], tuple: &[(String, F)]) -> String {
+    names.iter().zip(tuple).map(|(name, (value, _))| format!("{name} = {value}")).collect::<Vec<_>>().join(", ")
+}
+
+// every case run so far, and the ones where the circuit and the predicate disagree
+#[derive(Debug, Default)]
+pub struct BoundaryCheck {
+    pub cases: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+    pub case: String,
+    pub holds: bool,
+    pub satisfied: bool,
+}
+
+impl BoundaryCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // a synthesis error counts as unsatisfied, the prover can't make a proof either way
+    pub fn case<F: PrimeField, C: ConstraintSynthesizer<F>>(&mut self, case: impl Into<String>, holds: bool, circuit: C) {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let satisfied = circuit.generate_constraints(cs.clone()).and_then(|_| cs.is_satisfied()).unwrap_or(false);
+        self.cases += 1;
+        if satisfied != holds {
+            self.disagreements.push(Disagreement {
+                case: case.into(),
+                holds,
+                satisfied,
+            });
+        }
+    }
+
+    pub fn agrees(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+// the cases of one circuit
+pub type Cases = fn() -> BoundaryCheck;
+
+// every example circuit with its cases, in the order the binary prints them
+pub const CIRCUITS: &[(&str, Cases)] = &[
+    ("addition", addition),
+    ("age credential", age),
+    ("membership", membership),
+    ("airdrop", airdrop),
+    ("vss", vss),
+    ("document", document),
+    ("bristol adder", bristol),
+    ("bitcoin proof of work", bitcoin),
+    ("ethereum address", eth_address),
+    ("storage trie", trie),
+    ("range template", range),
+];
+
+pub fn addition() -> BoundaryCheck {
+    let values = values::<Fr>();
+    let mut check = BoundaryCheck::new();
+    for t in tuples(&values, 3) {
+        let (x, y, z) = (t[0].1, t[1].1, t[2].1);
+        check.case(describe(&["x", "y", "z"], &t), x + y == z, AdditionCircuit { x: Some(x), y: Some(y), z: Some(z) });
+    }
+    // z is rarely x + y above, so the sum and its neighbours for every pair
+    for t in tuples(&values, 2) {
+        let (x, y) = (t[0].1, t[1].1);
+        for (name, z) in around("x + y", x + y) {
+            let case = format!("{}, z = {name}", describe(&["x", "y"], &t));
+            check.case(case, x + y == z, AdditionCircuit { x: Some(x), y: Some(y), z: Some(z) });
+        }
+    }
+    check
+}
+
+pub fn age() -> BoundaryCheck {
+    let poseidon = poseidon_config::<Fr>(2);
+    let small = |x: &Fr, bits: usize| x.into_bigint().num_bits() as usize <= bits;
+    let mut check = BoundaryCheck::new();
+    // the cutoff is public and picked by the verifier from a real date, below 2^27
+    for cutoff in [Fr::from(0u32), Fr::from(20070314u32), Fr::from((1u32 << DATE_BITS) - 1)] {
+        let mut births = values::<Fr>();
+        births.extend(bounded(DATE_BITS));
+        births.extend(around("cutoff", cutoff));
+        for (name, birth) in &births {
+            // the other attributes and the salt: zero, or the birth date too
+            for other in [Fr::from(0u32), *birth] {
+                let attributes = [other, other, *birth];
+                let commitment = poseidon::hash(&poseidon, &[other, other, other, *birth]);
+                let holds = small(birth, DATE_BITS) && birth.into_bigint() <= cutoff.into_bigint();
+                for (tampered, commitment) in [(false, commitment), (true, commitment + Fr::from(1u32))] {
+                    let circuit = AgeCircuit {
+                        poseidon: poseidon.clone(),
+                        attributes: Some(attributes),
+                        salt: Some(other),
+                        commitment: Some(commitment),
+                        cutoff: Some(cutoff),
+                    };
+                    let case = format!("birth date {name}, cutoff {cutoff}, other values {other}, commitment tampered {tampered}");
+                    check.case(case, holds && !tampered, circuit);
+                }
+            }
+        }
+    }
+    check
+}
+
+// a path whose siblings are all `sibling`, for the first or the last leaf
+fn edge_path(shape: TreeShape, last: bool, sibling: Fr) -> MerklePath<Fr> {
+    MerklePath {
+        shape,
+        index: if last { shape.leaves() as u64 - 1 } else { 0 },
+        siblings: vec![vec![sibling; shape.arity - 1]; shape.depth],
+    }
+}
+
+pub fn membership() -> BoundaryCheck {
+    let values = values::<Fr>();
+    let mut check = BoundaryCheck::new();
+    for shape in [TreeShape::binary(3), TreeShape::new(4, 2)] {
+        let hasher = PoseidonHasher::<Fr>::new(shape.arity);
+        for (name, leaf) in &values {
+            for sibling in [Fr::from(0u32), -Fr::from(1u32), *leaf] {
+                for last in [false, true] {
+                    let path = edge_path(shape, last, sibling);
+                    let honest = MembershipCircuit::new(hasher.clone(), *leaf, path);
+                    let mut wrong = honest.clone();
+                    wrong.root = wrong.root.map(|r| r + Fr::from(1u32));
+                    let case = format!("{shape:?}, leaf {name}, siblings {sibling}, last leaf {last}");
+                    check.case(format!("{case}, honest root"), true, honest);
+                    check.case(format!("{case}, root + 1"), false, wrong);
+                }
+            }
+        }
+    }
+    check
+}
+
+pub fn airdrop() -> BoundaryCheck {
+    let values = values::<Fr>();
+    let hasher = PoseidonHasher::<Fr>::new(2);
+    let shape = TreeShape::binary(2);
+    let mut check = BoundaryCheck::new();
+    for t in tuples(&values, 3) {
+        let (address, secret, id) = (t[0].1, t[1].1, t[2].1);
+        let case = describe(&["address", "secret", "airdrop id"], &t);
+        let honest = AirdropCircuit::new(hasher.clone(), id, address, secret, edge_path(shape, false, secret), address);
+        let mut wrong_nullifier = honest.clone();
+        wrong_nullifier.nullifier = Some(airdrop::nullifier(&hasher, address, id));
+        let holds = airdrop::nullifier(&hasher, address, id) == airdrop::nullifier(&hasher, secret, id);
+        check.case(format!("{case}, honest"), true, honest);
+        check.case(format!("{case}, the nullifier of the address"), holds, wrong_nullifier);
+    }
+    check
+}
+
+pub fn vss() -> BoundaryCheck {
+    let values = values::<Fr>();
+    let poseidon = poseidon_config::<Fr>(2);
+    let mut check = BoundaryCheck::new();
+    for t in tuples(&values, 2) {
+        let polynomial = Polynomial { coeffs: vec![t[0].1, t[1].1] };
+        // x = 0 is the secret itself, a share the dealer should never hand out, but
+        // it is an evaluation all the same
+        let shares: Vec<Share> = [Fr::from(0u32), Fr::from(1u32), -Fr::from(1u32)]
+            .iter()
+            .map(|&x| Share { x, y: polynomial.evaluate(x) })
+            .collect();
+        let case = describe(&["a0", "a1"], &t);
+        check.case(format!("{case}, honest shares"), true, VssCircuit::new(poseidon.clone(), polynomial.clone(), t[0].1, shares.clone()));
+        for i in 0..shares.len() {
+            let mut wrong = shares.clone();
+            wrong[i].y += Fr::from(1u32);
+            let circuit = VssCircuit::new(poseidon.clone(), polynomial.clone(), t[0].1, wrong);
+            check.case(format!("{case}, share {i} off by one"), false, circuit);
+        }
+    }
+    check
+}
+
+pub fn document() -> BoundaryCheck {
+    let poseidon = poseidon_config::<Fr>(2);
+    let layout = DocumentLayout {
+        document_len: 16,
+        field_offset: 4,
+        field_len: 4,
+    };
+    let mut check = BoundaryCheck::new();
+    let documents: [(&str, Vec<u8>); 3] = [("zeros", vec![0; 16]), ("0xff", vec![0xff; 16]), ("0, 1, 2, ...", (0..16).collect())];
+    for (name, bytes) in documents {
+        let field = document::field_value(&bytes[4..8]);
+        let commitment = document::commit(&poseidon, &bytes);
+        // 2^32 is what a fifth byte of the field would add
+        let mut disclosed = around("the field", field);
+        disclosed.push(("the field + 2^32".to_string(), field + Fr::from(1u64 << 32)));
+        disclosed.push(("p - 1".to_string(), -Fr::from(1u32)));
+        for (value_name, value) in disclosed {
+            for (tampered, commitment) in [(false, commitment), (true, commitment + Fr::from(1u32))] {
+                let circuit = DocumentCircuit {
+                    layout,
+                    poseidon: poseidon.clone(),
+                    document: Some(bytes.clone()),
+                    commitment: Some(commitment),
+                    field_value: Some(value),
+                };
+                let case = format!("document {name}, disclosed {value_name}, commitment tampered {tampered}");
+                check.case(case, value == field && !tampered, circuit);
+            }
+        }
+    }
+    check
+}
+
+pub fn bitcoin() -> BoundaryCheck {
+    let mut check = BoundaryCheck::new();
+    let top = Fr::from(2u32).pow([bitcoin::TARGET_BITS as u64]) - Fr::from(1u32);
+    for (name, header) in [("genesis", bitcoin::GENESIS), ("zeros", [0; 80]), ("0xff", [0xff; 80])] {
+        // the target is public, below 2^248 like every real one
+        let mut targets = vec![("0".to_string(), Fr::from(0u32)), ("1".to_string(), Fr::from(1u32)), ("2^248 - 1".to_string(), top)];
+        if let Some(hash) = bitcoin::hash_value(&bitcoin::block_hash(&header)) {
+            targets.extend(around("the hash", hash));
+        }
+        for (target_name, target) in targets {
+            check.case(format!("header {name}, target {target_name}"), bitcoin::meets_target(&header, target), PowCircuit::new(header, target));
+        }
+    }
+    check
+}
+
+pub fn eth_address() -> BoundaryCheck {
+    let mut check = BoundaryCheck::new();
+    let mut generator = [0u8; 64];
+    generator[31] = 1;
+    for (name, key) in [("zeros", [0u8; 64]), ("0xff", [0xff; 64]), ("x = 1", generator)] {
+        let key: PublicKey = key;
+        let address = eth_address::address_value::<Fr>(&eth_address::address_of(&key));
+        let mut addresses = around("its address", address);
+        addresses.push(("2^160".to_string(), Fr::from(2u32).pow([160])));
+        addresses.push(("p - 1".to_string(), -Fr::from(1u32)));
+        for (address_name, claimed) in addresses {
+            let circuit = AddressCircuit::<Fr> {
+                public_key: Some(key),
+                address: Some(claimed),
+            };
+            check.case(format!("key {name}, address {address_name}"), claimed == address, circuit);
+        }
+    }
+    check
+}
+
+pub fn trie() -> BoundaryCheck {
+    let mut check = BoundaryCheck::new();
+    // the first and the last child slot, the ends of the nibble selector
+    for slot in [0x00, 0xff] {
+        let mut storage = Trie::new(1);
+        let (key, value) = ([slot; 32], [slot; 32]);
+        storage.insert(key, value).expect("an empty trie");
+        storage.insert([0x77; 32], [0x01; 32]).expect("another path");
+        let proof = storage.prove(&key).expect("the key is in the trie");
+        check.case::<Fr, _>(format!("key {slot:#04x}.., honest"), true, StorageCircuit::new(key, value, proof.clone()));
+        let mut wrong = StorageCircuit::new(key, value, proof);
+        wrong.value = Some([slot ^ 1; 32]);
+        check.case::<Fr, _>(format!("key {slot:#04x}.., another value"), false, wrong);
+    }
+    check
+}
+
+pub fn bristol() -> BoundaryCheck {
+    // a 2 bit adder, so every input is a boundary
+    let adder = "6 10\n2 2 2\n1 2\n\n2 1 0 2 4 XOR\n2 1 0 2 5 AND\n2 1 1 3 6 XOR\n2 1 6 5 7 XOR\n1 1 4 8 EQW\n1 1 7 9 EQW\n";
+    let circuit = Bristol::parse(adder).expect("the adder parses");
+    let bits = |v: u32| vec![v & 1 == 1, v & 2 == 2];
+    let mut check = BoundaryCheck::new();
+    for a in 0..4 {
+        for b in 0..4 {
+            for claimed in 0..4 {
+                let mut bristol = BristolCircuit::<Fr>::new(circuit.clone(), vec![false, true], vec![bits(a), bits(b)]).expect("two 2 bit inputs");
+                bristol.outputs = Some(vec![bits(claimed)]);
+                check.case(format!("{a} + {b} = {claimed} (mod 4)"), (a + b) % 4 == claimed, bristol);
+            }
+        }
+    }
+    check
+}
+
+// each broken challenge circuit (challenges.rs) with the first boundary case that
+// gets a false statement through it, if any does
+pub fn broken() -> Vec<(Challenge, Option<String>)> {
+    let values = values::<Fr>();
+    Challenge::ALL
+        .into_iter()
+        .map(|challenge| {
+            let names: Vec<&str> = challenge.values().iter().map(|(name, _)| *name).collect();
+            let exploit = tuples(&values, names.len()).into_iter().find(|t| {
+                let values: Vec<Fr> = t.iter().map(|(_, v)| *v).collect();
+                // an error is a witness the prover can't even synthesize, 1 for a value != 1
+                matches!(challenges::judge(challenge, &values), Ok(Verdict::Exploit))
+            });
+            (challenge, exploit.map(|t| describe(&names, &t)))
+        })
+        .collect()
+}
+
+// range, sum and threshold from templates.rs (membership is the membership circuit),
+// built field by field so `new` doesn't refuse the witnesses first
+const TEMPLATE_BITS: usize = 32;
+
+fn template_witnesses() -> Vec<(String, Fr)> {
+    let mut witnesses = values::<Fr>();
+    witnesses.extend(bounded(TEMPLATE_BITS));
+    witnesses
+}
+
+fn small(x: &Fr) -> bool {
+    x.into_bigint().num_bits() as usize <= TEMPLATE_BITS
+}
+
+pub fn range() -> BoundaryCheck {
+    let int = |x: &Fr| x.into_bigint();
+    let mut check = BoundaryCheck::new();
+    let max = Fr::from(u32::MAX);
+    for (lo, hi) in [(Fr::from(0u32), max), (Fr::from(18u32), Fr::from(65u32)), (Fr::from(65u32), Fr::from(18u32))] {
+        let mut xs = template_witnesses();
+        xs.extend(around("lo", lo));
+        xs.extend(around("hi", hi));
+        for (name, x) in xs {
+            let holds = small(&x) && int(&lo) <= int(&x) && int(&x) <= int(&hi);
+            let circuit = RangeCircuit {
+                bits: TEMPLATE_BITS,
+                lo: Some(lo),
+                hi: Some(hi),
+                x: Some(x),
+            };
+            check.case(format!("x = {name}, lo = {lo}, hi = {hi}"), holds, circuit);
+        }
+    }
+    check
+}
+
+// the sum and the threshold templates, on the same pairs of values
+pub fn sum_and_threshold() -> (BoundaryCheck, BoundaryCheck) {
+    let int = |x: &Fr| x.into_bigint();
+    let (mut sums, mut thresholds) = (BoundaryCheck::new(), BoundaryCheck::new());
+    for t in tuples(&template_witnesses(), 2) {
+        let values = vec![t[0].1, t[1].1];
+        let sum = values[0] + values[1];
+        let valid = values.iter().all(small);
+        // the total the values add up to in the field, and its neighbours
+        for (name, total) in around("the sum", sum) {
+            let circuit = SumCircuit {
+                length: 2,
+                bits: TEMPLATE_BITS,
+                total: Some(total),
+                values: Some(values.clone()),
+            };
+            sums.case(format!("{}, total = {name}", describe(&["a", "b"], &t)), valid && total == sum, circuit);
+        }
+        // thresholds the verifier could pick, below 2^33 like a sum of two 32 bit values
+        let mut picks: Vec<(String, Fr)> = vec![("0".to_string(), Fr::from(0u32)), ("2^33 - 1".to_string(), Fr::from((1u64 << 33) - 1))];
+        if valid {
+            picks.extend(around("the sum", sum));
+        }
+        for (name, threshold) in picks.into_iter().filter(|(_, t)| t.into_bigint().num_bits() <= 33) {
+            let circuit = ThresholdCircuit {
+                length: 2,
+                bits: TEMPLATE_BITS,
+                threshold: Some(threshold),
+                values: Some(values.clone()),
+            };
+            let case = format!("{}, threshold = {name}", describe(&["a", "b"], &t));
+            thresholds.case(case, valid && int(&threshold) <= int(&sum), circuit);
+        }
+    }
+    (sums, thresholds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_agrees(check: BoundaryCheck) {
+        assert!(check.cases > 0);
+        assert!(check.agrees(), "{} of {} cases disagree: {:?}", check.disagreements.len(), check.cases, check.disagreements);
+    }
+
+    #[test]
+    fn values_are_distinct() {
+        let values = values::<Fr>();
+        for (i, (name, value)) in values.iter().enumerate() {
+            assert!(values[i + 1..].iter().all(|(_, other)| other != value), "{name} is there twice");
+        }
+    }
+
+    #[test]
+    fn tuples_cover_every_pair_and_the_same_everywhere() {
+        let values = bounded::<Fr>(8);
+        let pairs = tuples(&values, 2);
+        assert_eq!(pairs.len(), values.len() * values.len());
+        let triples = tuples(&values, 3);
+        assert!(values.iter().all(|v| triples.contains(&vec![v.clone(); 3])));
+    }
+
+    #[test]
+    fn a_disagreement_is_recorded() {
+        let mut check = BoundaryCheck::new();
+        let (x, y) = (Fr::from(1u32), Fr::from(2u32));
+        check.case("honest", true, AdditionCircuit { x: Some(x), y: Some(y), z: Some(x + y) });
+        check.case("wrong sum, said to hold", true, AdditionCircuit { x: Some(x), y: Some(y), z: Some(x) });
+        assert_eq!(check.cases, 2);
+        assert_eq!(check.disagreements.len(), 1);
+        assert!(check.disagreements[0].holds && !check.disagreements[0].satisfied);
+    }
+
+    #[test]
+    fn addition() {
+        assert_agrees(super::addition());
+    }
+
+    #[test]
+    fn age() {
+        assert_agrees(super::age());
+    }
+
+    #[test]
+    fn membership() {
+        assert_agrees(super::membership());
+    }
+
+    #[test]
+    fn airdrop() {
+        assert_agrees(super::airdrop());
+    }
+
+    #[test]
+    fn vss() {
+        assert_agrees(super::vss());
+    }
+
+    #[test]
+    fn document() {
+        assert_agrees(super::document());
+    }
+
+    #[test]
+    fn bristol() {
+        assert_agrees(super::bristol());
+    }
+
+    #[test]
+    fn bitcoin() {
+        assert_agrees(super::bitcoin());
+    }
+
+    #[test]
+    fn eth_address() {
+        assert_agrees(super::eth_address());
+    }
+
+    #[test]
+    fn trie() {
+        assert_agrees(super::trie());
+    }
+
+    #[test]
+    fn templates() {
+        assert_agrees(range());
+        let (sums, thresholds) = sum_and_threshold();
+        assert_agrees(sums);
+        assert_agrees(thresholds);
+    }
+
+    #[test]
+    fn the_boundaries_break_three_of_the_challenges() {
+        let broken: Vec<Challenge> = super::broken().into_iter().filter(|(_, exploit)| exploit.is_some()).map(|(c, _)| c).collect();
+        // the booleanity bug needs a non-bit balanced by the other bits, no single value does that
+        assert_eq!(broken, [Challenge::Output, Challenge::Division, Challenge::Wraparound]);
+    }
+}
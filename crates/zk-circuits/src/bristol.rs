@@ -0,0 +1,691 @@
+use std::fmt;
+use std::path::Path;
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/*
+Boolean circuits in Bristol Fashion, the text format the Bristol / Nigel Smart circuit
+collection (adder64.txt, aes_128.txt, sha256.txt, ...) and most MPC frameworks use:
+
+    <gates> <wires>
+    <number of inputs> <bits of input 1> <bits of input 2> ...
+    <number of outputs> <bits of output 1> ...
+
+    2 1 <a> <b> <out> XOR
+    2 1 <a> <b> <out> AND
+    1 1 <a> <out> INV
+    1 1 <a> <out> EQW       (a copy)
+    1 1 <0 or 1> <out> EQ   (a constant)
+    2n n <a1..an> <b1..bn> <out1..outn> MAND
+
+Inputs are the first wires, in order, and the outputs are the last ones. The gates
+come in an order where every wire is set before it is read, parse checks that.
+
+BristolCircuit proves "I know the secret inputs that, with these public inputs, give
+these outputs" for any such file. In R1CS an AND is one constraint, an XOR one too
+(a + b - 2ab), INV and the copies are free, so the cost is the AND and XOR count plus
+one constraint per input bit for its booleanity. Public inputs and the outputs are
+packed into field elements (as many bits per element as fit, lowest wire first) so a
+128 bit ciphertext is one public input and not 128.
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Gate {
+    Xor(usize, usize, usize),
+    And(usize, usize, usize),
+    Inv(usize, usize),
+    Copy(usize, usize),
+    Constant(bool, usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bristol {
+    pub wires: usize,
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+    pub gates: Vec<Gate>,
+}
+
+#[derive(Debug)]
+pub enum BristolError {
+    Io(std::io::Error),
+    // the line number is 1-based, like an editor's
+    Parse { line: usize, message: String },
+    WrongInputs { expected: Vec<usize>, got: Vec<usize> },
+}
+
+impl fmt::Display for BristolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BristolError::Io(e) => write!(f, "{e}"),
+            BristolError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            BristolError::WrongInputs { expected, got } => {
+                write!(f, "the circuit takes inputs of {expected:?} bits, got {got:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BristolError {}
+
+impl From<std::io::Error> for BristolError {
+    fn from(e: std::io::Error) -> Self {
+        BristolError::Io(e)
+    }
+}
+
+fn numbers(line: usize, words: &[&str]) -> Result<Vec<usize>, BristolError> {
+    words
+        .iter()
+        .map(|w| {
+            w.parse().map_err(|_| BristolError::Parse {
+                line,
+                message: format!("'{w}' is not a number"),
+            })
+        })
+        .collect()
+}
+
+// a count followed by that many numbers, the input and output lines of the header
+fn counted(line: usize, words: &[&str]) -> Result<Vec<usize>, BristolError> {
+    let all = numbers(line, words)?;
+    match all.split_first() {
+        Some((&n, rest)) if rest.len() == n => Ok(rest.to_vec()),
+        _ => Err(BristolError::Parse {
+            line,
+            message: "expected a count and that many bit widths".to_string(),
+        }),
+    }
+}
+
+impl Bristol {
+    pub fn parse(text: &str) -> Result<Self, BristolError> {
+        let mut lines = text.lines().enumerate().map(|(i, l)| (i + 1, l.split_whitespace().collect::<Vec<_>>()));
+        let mut header = lines.by_ref().filter(|(_, words)| !words.is_empty());
+        let missing = |what: &str| BristolError::Parse {
+            line: text.lines().count(),
+            message: format!("the file ends before the {what} line"),
+        };
+        let (line, sizes) = header.next().ok_or_else(|| missing("gates and wires"))?;
+        let sizes = numbers(line, &sizes)?;
+        let [gate_count, wires] = sizes[..] else {
+            return Err(BristolError::Parse {
+                line,
+                message: "expected the number of gates and of wires".to_string(),
+            });
+        };
+        let (line, words) = header.next().ok_or_else(|| missing("inputs"))?;
+        let inputs = counted(line, &words)?;
+        let (line, words) = header.next().ok_or_else(|| missing("outputs"))?;
+        let outputs = counted(line, &words)?;
+        let (input_bits, output_bits) = (inputs.iter().sum::<usize>(), outputs.iter().sum::<usize>());
+        if input_bits > wires || output_bits > wires {
+            return Err(BristolError::Parse {
+                line,
+                message: format!("{input_bits} input and {output_bits} output bits don't fit in {wires} wires"),
+            });
+        }
+
+        let mut set = vec![false; wires];
+        set[..input_bits].iter_mut().for_each(|s| *s = true);
+        let mut gates = Vec::new();
+        let (mut last, mut lines_of_gates) = (line, 0);
+        for (line, words) in header {
+            last = line;
+            lines_of_gates += 1;
+            let bad = |message: String| BristolError::Parse { line, message };
+            let (name, rest) = words.split_last().expect("blank lines are filtered out");
+            let wires_of = numbers(line, rest)?;
+            let (counts, wires_of) = wires_of.split_at(2.min(wires_of.len()));
+            let [ins, outs] = counts[..] else {
+                return Err(bad("expected the input and output counts".to_string()));
+            };
+            if wires_of.len() != ins + outs {
+                return Err(bad(format!("{ins} inputs and {outs} outputs but {} wires", wires_of.len())));
+            }
+            let (read, written) = wires_of.split_at(ins);
+            if let Some(&w) = wires_of.iter().find(|&&w| w >= wires) {
+                return Err(bad(format!("wire {w} is past the {wires} wires of the circuit")));
+            }
+            let constant = *name == "EQ";
+            if !constant && let Some(&w) = read.iter().find(|&&w| !set[w]) {
+                return Err(bad(format!("wire {w} is read before anything sets it")));
+            }
+            for &w in written {
+                if set[w] {
+                    return Err(bad(format!("wire {w} is set twice")));
+                }
+                set[w] = true;
+            }
+            match (*name, ins, outs) {
+                ("XOR", 2, 1) => gates.push(Gate::Xor(read[0], read[1], written[0])),
+                ("AND", 2, 1) => gates.push(Gate::And(read[0], read[1], written[0])),
+                ("INV", 1, 1) => gates.push(Gate::Inv(read[0], written[0])),
+                ("EQW", 1, 1) => gates.push(Gate::Copy(read[0], written[0])),
+                ("EQ", 1, 1) if read[0] <= 1 => gates.push(Gate::Constant(read[0] == 1, written[0])),
+                ("MAND", _, n) if ins == 2 * n => {
+                    for i in 0..n {
+                        gates.push(Gate::And(read[i], read[n + i], written[i]));
+                    }
+                }
+                _ => return Err(bad(format!("{name} with {ins} inputs and {outs} outputs isn't a gate this parser knows"))),
+            }
+        }
+        // a MAND is one gate in the header, however many ANDs it is
+        if lines_of_gates != gate_count {
+            return Err(BristolError::Parse {
+                line: last,
+                message: format!("the header says {gate_count} gates, the file has {lines_of_gates}"),
+            });
+        }
+        if let Some(w) = (wires - output_bits..wires).find(|&w| !set[w]) {
+            return Err(BristolError::Parse {
+                line: last,
+                message: format!("output wire {w} is never set"),
+            });
+        }
+        Ok(Self { wires, inputs, outputs, gates })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BristolError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn and_gates(&self) -> usize {
+        self.gates.iter().filter(|g| matches!(g, Gate::And(..))).count()
+    }
+
+    pub fn xor_gates(&self) -> usize {
+        self.gates.iter().filter(|g| matches!(g, Gate::Xor(..))).count()
+    }
+
+    fn check_inputs(&self, inputs: &[Vec<bool>]) -> Result<(), BristolError> {
+        let got: Vec<usize> = inputs.iter().map(Vec::len).collect();
+        if got != self.inputs {
+            return Err(BristolError::WrongInputs {
+                expected: self.inputs.clone(),
+                got,
+            });
+        }
+        Ok(())
+    }
+
+    // the outputs for these inputs, each a list of bits in wire order
+    pub fn evaluate(&self, inputs: &[Vec<bool>]) -> Result<Vec<Vec<bool>>, BristolError> {
+        self.check_inputs(inputs)?;
+        let mut wires = vec![false; self.wires];
+        for (w, bit) in inputs.iter().flatten().enumerate() {
+            wires[w] = *bit;
+        }
+        for gate in &self.gates {
+            match *gate {
+                Gate::Xor(a, b, c) => wires[c] = wires[a] ^ wires[b],
+                Gate::And(a, b, c) => wires[c] = wires[a] & wires[b],
+                Gate::Inv(a, c) => wires[c] = !wires[a],
+                Gate::Copy(a, c) => wires[c] = wires[a],
+                Gate::Constant(v, c) => wires[c] = v,
+            }
+        }
+        Ok(split(&wires[self.wires - self.outputs.iter().sum::<usize>()..], &self.outputs))
+    }
+}
+
+fn split<T: Clone>(items: &[T], sizes: &[usize]) -> Vec<Vec<T>> {
+    let mut rest = items;
+    sizes
+        .iter()
+        .map(|&n| {
+            let (group, tail) = rest.split_at(n);
+            rest = tail;
+            group.to_vec()
+        })
+        .collect()
+}
+
+// a + b mod 2^n in the format, inputs a and b, the sum on the last n wires
+pub fn ripple_adder(n: usize) -> String {
+    let mut gates = Vec::new();
+    let mut next = 2 * n;
+    let mut wire = || {
+        next += 1;
+        next - 1
+    };
+    let mut sums = Vec::new();
+    let mut carry = None;
+    for i in 0..n {
+        let (a, b) = (i, n + i);
+        let t = wire();
+        gates.push(format!("2 1 {a} {b} {t} XOR"));
+        let generate = wire();
+        gates.push(format!("2 1 {a} {b} {generate} AND"));
+        match carry {
+            None => {
+                sums.push(t);
+                carry = Some(generate);
+            }
+            Some(c) => {
+                let s = wire();
+                gates.push(format!("2 1 {t} {c} {s} XOR"));
+                sums.push(s);
+                // a carry is generated here or propagated, never both, so XOR is OR
+                let propagate = wire();
+                gates.push(format!("2 1 {t} {c} {propagate} AND"));
+                let out = wire();
+                gates.push(format!("2 1 {generate} {propagate} {out} XOR"));
+                carry = Some(out);
+            }
+        }
+    }
+    for s in sums {
+        let out = wire();
+        gates.push(format!("1 1 {s} {out} EQW"));
+    }
+    format!("{} {next}\n2 {n} {n}\n1 {n}\n\n{}\n", gates.len(), gates.join("\n"))
+}
+
+fn chunk_bits<F: PrimeField>() -> usize {
+    F::MODULUS_BIT_SIZE as usize - 1
+}
+
+// bits as field elements, lowest bit first, as many to an element as fit
+pub fn pack_bits<F: PrimeField>(bits: &[bool]) -> Vec<F> {
+    bits.chunks(chunk_bits::<F>())
+        .map(|chunk| chunk.iter().rev().fold(F::zero(), |acc, &b| acc.double() + F::from(b)))
+        .collect()
+}
+
+// the public input vector: the packed public inputs in order, then the packed outputs
+pub fn public_inputs<F: PrimeField>(public: &[bool], inputs: &[Vec<bool>], outputs: &[Vec<bool>]) -> Vec<F> {
+    let shown = inputs.iter().zip(public).filter(|(_, public)| **public).map(|(bits, _)| bits);
+    shown.chain(outputs).flat_map(|bits| pack_bits::<F>(bits)).collect()
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct BristolCircuit<F: PrimeField> {
+    #[zeroize(skip)]
+    pub circuit: Bristol,
+    // which of the inputs are public, the others are witnesses
+    #[zeroize(skip)]
+    pub public: Vec<bool>,
+    pub inputs: Option<Vec<Vec<bool>>>,
+    #[zeroize(skip)]
+    pub outputs: Option<Vec<Vec<bool>>>,
+    #[zeroize(skip)]
+    _field: std::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> BristolCircuit<F> {
+    pub fn empty(circuit: Bristol, public: Vec<bool>) -> Self {
+        assert_eq!(public.len(), circuit.inputs.len(), "say for every input whether it's public");
+        Self {
+            circuit,
+            public,
+            inputs: None,
+            outputs: None,
+            _field: std::marker::PhantomData,
+        }
+    }
+
+    pub fn new(circuit: Bristol, public: Vec<bool>, inputs: Vec<Vec<bool>>) -> Result<Self, BristolError> {
+        let outputs = circuit.evaluate(&inputs)?;
+        let mut c = Self::empty(circuit, public);
+        c.inputs = Some(inputs);
+        c.outputs = Some(outputs);
+        Ok(c)
+    }
+}
+
+// packs the bits and makes the result equal a fresh public input per element
+fn enforce_public<F: PrimeField>(cs: &ConstraintSystemRef<F>, bits: &[Boolean<F>], values: Option<&[bool]>) -> Result<(), SynthesisError> {
+    let packed = values.map(pack_bits::<F>);
+    for (i, chunk) in bits.chunks(chunk_bits::<F>()).enumerate() {
+        let input = FpVar::new_input(cs.clone(), || packed.as_ref().map(|p| p[i]).ok_or(SynthesisError::AssignmentMissing))?;
+        Boolean::le_bits_to_fp_var(chunk)?.enforce_equal(&input)?;
+    }
+    Ok(())
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for BristolCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let mut wires: Vec<Option<Boolean<F>>> = vec![None; self.circuit.wires];
+        let mut next = 0;
+        for (group, (&bits, &public)) in self.circuit.inputs.iter().zip(&self.public).enumerate() {
+            let values = self.inputs.as_ref().map(|inputs| &inputs[group]);
+            let allocated = (0..bits)
+                .map(|i| Boolean::new_witness(cs.clone(), || values.map(|v| v[i]).ok_or(SynthesisError::AssignmentMissing)))
+                .collect::<Result<Vec<_>, _>>()?;
+            if public {
+                enforce_public(&cs, &allocated, values.map(Vec::as_slice))?;
+            }
+            for bit in allocated {
+                wires[next] = Some(bit);
+                next += 1;
+            }
+        }
+        let wire = |wires: &[Option<Boolean<F>>], w: usize| wires[w].clone().ok_or(SynthesisError::Unsatisfiable);
+        for gate in &self.circuit.gates {
+            let (value, out) = match *gate {
+                Gate::Xor(a, b, c) => (wire(&wires, a)?.xor(&wire(&wires, b)?)?, c),
+                Gate::And(a, b, c) => (wire(&wires, a)?.and(&wire(&wires, b)?)?, c),
+                Gate::Inv(a, c) => (wire(&wires, a)?.not(), c),
+                Gate::Copy(a, c) => (wire(&wires, a)?, c),
+                Gate::Constant(v, c) => (Boolean::constant(v), c),
+            };
+            wires[out] = Some(value);
+        }
+        let output_bits = self.circuit.outputs.iter().sum::<usize>();
+        let outputs = wires[self.circuit.wires - output_bits..]
+            .iter()
+            .cloned()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(SynthesisError::Unsatisfiable)?;
+        for (group, bits) in split(&outputs, &self.circuit.outputs).iter().enumerate() {
+            enforce_public(&cs, bits, self.outputs.as_ref().map(|o| o[group].as_slice()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use super::*;
+
+    fn bits(value: u64, n: usize) -> Vec<bool> {
+        (0..n).map(|i| value >> i & 1 == 1).collect()
+    }
+
+    fn number(bits: &[bool]) -> u64 {
+        bits.iter().rev().fold(0, |acc, &b| acc << 1 | b as u64)
+    }
+
+    // bytes in order, each lowest bit first
+    fn hex_bits(hex: &str) -> Vec<bool> {
+        (0..hex.len()).step_by(2).flat_map(|i| bits(u64::from_str_radix(&hex[i..i + 2], 16).unwrap(), 8)).collect()
+    }
+
+    fn satisfied(circuit: BristolCircuit<Fr>) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    // writes a circuit gate by gate, a byte is 8 wires lowest bit first
+    struct Writer {
+        gates: Vec<String>,
+        next: usize,
+    }
+
+    type Byte = [usize; 8];
+
+    impl Writer {
+        fn new(input_bits: usize) -> Self {
+            Self { gates: Vec::new(), next: input_bits }
+        }
+
+        fn gate(&mut self, gate: String) -> usize {
+            self.gates.push(gate);
+            self.next += 1;
+            self.next - 1
+        }
+
+        fn xor(&mut self, a: usize, b: usize) -> usize {
+            let out = self.next;
+            self.gate(format!("2 1 {a} {b} {out} XOR"))
+        }
+
+        fn and(&mut self, a: usize, b: usize) -> usize {
+            let out = self.next;
+            self.gate(format!("2 1 {a} {b} {out} AND"))
+        }
+
+        fn inv(&mut self, a: usize) -> usize {
+            let out = self.next;
+            self.gate(format!("1 1 {a} {out} INV"))
+        }
+
+        // the xor of the wires, a wire that comes up twice cancels
+        fn sum(&mut self, wires: &[usize]) -> usize {
+            let mut odd: Vec<usize> = Vec::new();
+            for &w in wires {
+                if let Some(i) = odd.iter().position(|&v| v == w) {
+                    odd.remove(i);
+                } else {
+                    odd.push(w);
+                }
+            }
+            let (&first, rest) = odd.split_first().expect("never an empty sum here");
+            rest.iter().fold(first, |acc, &w| self.xor(acc, w))
+        }
+
+        // terms of x^0..x^14 reduced mod x^8 + x^4 + x^3 + x + 1, AES's polynomial
+        fn reduce(&mut self, mut terms: Vec<Vec<usize>>) -> Byte {
+            for k in (8..terms.len()).rev() {
+                let high = std::mem::take(&mut terms[k]);
+                for t in [4, 3, 1, 0] {
+                    terms[k - 8 + t].extend(&high);
+                }
+            }
+            std::array::from_fn(|i| self.sum(&terms[i]))
+        }
+
+        fn mul(&mut self, a: Byte, b: Byte) -> Byte {
+            let mut terms = vec![Vec::new(); 15];
+            for i in 0..8 {
+                for j in 0..8 {
+                    let product = self.and(a[i], b[j]);
+                    terms[i + j].push(product);
+                }
+            }
+            self.reduce(terms)
+        }
+
+        fn square(&mut self, a: Byte) -> Byte {
+            let mut terms = vec![Vec::new(); 15];
+            for i in 0..8 {
+                terms[2 * i].push(a[i]);
+            }
+            self.reduce(terms)
+        }
+
+        fn xor_bytes(&mut self, a: Byte, b: Byte) -> Byte {
+            std::array::from_fn(|i| self.xor(a[i], b[i]))
+        }
+
+        fn xor_constant(&mut self, a: Byte, c: u8) -> Byte {
+            std::array::from_fn(|i| if c >> i & 1 == 1 { self.inv(a[i]) } else { a[i] })
+        }
+
+        fn xtime(&mut self, a: Byte) -> Byte {
+            [a[7], self.xor(a[0], a[7]), a[1], self.xor(a[2], a[7]), self.xor(a[3], a[7]), a[4], a[5], a[6]]
+        }
+
+        // x^254, the inverse (and 0 for 0), then the affine map of FIPS-197 5.1.1
+        fn sbox(&mut self, x: Byte) -> Byte {
+            let x2 = self.square(x);
+            let x3 = self.mul(x2, x);
+            let x6 = self.square(x3);
+            let x12 = self.square(x6);
+            let x14 = self.mul(x12, x2);
+            let x15 = self.mul(x12, x3);
+            let mut x240 = x15;
+            for _ in 0..4 {
+                x240 = self.square(x240);
+            }
+            let inverse = self.mul(x240, x14);
+            let affine = std::array::from_fn(|i| self.sum(&[0, 4, 5, 6, 7].map(|k| inverse[(i + k) % 8])));
+            self.xor_constant(affine, 0x63)
+        }
+
+        // copies of the outputs on the last wires, and the file
+        fn finish(mut self, inputs: &[usize], outputs: &[Byte]) -> String {
+            for bit in outputs.iter().flatten() {
+                let out = self.next;
+                self.gate(format!("1 1 {bit} {out} EQW"));
+            }
+            let widths = inputs.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+            let (gates, wires, output_bits) = (self.gates.len(), self.next, outputs.len() * 8);
+            format!("{gates} {wires}\n{} {widths}\n1 {output_bits}\n\n{}\n", inputs.len(), self.gates.join("\n"))
+        }
+    }
+
+    fn bytes_at(start: usize, n: usize) -> Vec<Byte> {
+        (0..n).map(|i| std::array::from_fn(|j| start + 8 * i + j)).collect()
+    }
+
+    fn sbox_circuit() -> String {
+        let mut writer = Writer::new(8);
+        let out = writer.sbox(bytes_at(0, 1)[0]);
+        writer.finish(&[8], &[out])
+    }
+
+    // AES-128 of FIPS-197: the key, then the plaintext, bytes in the order of the
+    // standard's hex strings; the ciphertext in the same order
+    fn aes_128() -> String {
+        let mut w = Writer::new(256);
+        let mut words: Vec<[Byte; 4]> = bytes_at(0, 16).chunks(4).map(|c| [c[0], c[1], c[2], c[3]]).collect();
+        let mut rcon = 1u8;
+        for i in 4..44 {
+            let mut temp = words[i - 1];
+            if i % 4 == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|b| w.sbox(b));
+                temp[0] = w.xor_constant(temp[0], rcon);
+                rcon = if rcon & 0x80 != 0 { rcon << 1 ^ 0x1b } else { rcon << 1 };
+            }
+            let previous = words[i - 4];
+            words.push(std::array::from_fn(|j| w.xor_bytes(previous[j], temp[j])));
+        }
+        let round_key = |round: usize| -> Vec<Byte> { words[4 * round..4 * round + 4].iter().flatten().copied().collect() };
+        let add = |w: &mut Writer, state: &[Byte], key: &[Byte]| -> Vec<Byte> { state.iter().zip(key).map(|(&s, &k)| w.xor_bytes(s, k)).collect() };
+
+        // state[4c + r] is row r of column c
+        let mut state = add(&mut w, &bytes_at(128, 16), &round_key(0));
+        for round in 1..=10 {
+            let substituted: Vec<Byte> = state.iter().map(|&b| w.sbox(b)).collect();
+            let shifted: Vec<Byte> = (0..16).map(|i| substituted[4 * ((i / 4 + i % 4) % 4) + i % 4]).collect();
+            let mixed = if round == 10 {
+                shifted
+            } else {
+                shifted
+                    .chunks(4)
+                    .flat_map(|a| {
+                        let doubled: Vec<Byte> = a.iter().map(|&b| w.xtime(b)).collect();
+                        (0..4)
+                            .map(|r| {
+                                // 2 a_r + 3 a_r+1 + a_r+2 + a_r+3
+                                let (x, y, z, v) = (r, (r + 1) % 4, (r + 2) % 4, (r + 3) % 4);
+                                std::array::from_fn(|j| w.sum(&[doubled[x][j], doubled[y][j], a[y][j], a[z][j], a[v][j]]))
+                            })
+                            .collect::<Vec<Byte>>()
+                    })
+                    .collect()
+            };
+            state = add(&mut w, &mixed, &round_key(round));
+        }
+        w.finish(&[128, 128], &state)
+    }
+
+    #[test]
+    fn the_adder_adds() {
+        let adder = Bristol::parse(&ripple_adder(32)).unwrap();
+        assert_eq!((adder.inputs.clone(), adder.outputs.clone()), (vec![32, 32], vec![32]));
+        assert_eq!((adder.xor_gates(), adder.and_gates()), (3 * 32 - 2, 2 * 32 - 1));
+        for (a, b) in [(0, 0), (17, 2), (u32::MAX as u64, 1), (0xdead_beef, 0x1234_5678)] {
+            let sum = adder.evaluate(&[bits(a, 32), bits(b, 32)]).unwrap();
+            assert_eq!(number(&sum[0]), (a + b) % (1 << 32), "{a} + {b}");
+        }
+    }
+
+    #[test]
+    fn the_synthesized_adder_holds_only_for_the_right_sum() {
+        let adder = Bristol::parse(&ripple_adder(8)).unwrap();
+        let honest = BristolCircuit::<Fr>::new(adder.clone(), vec![false, true], vec![bits(200, 8), bits(100, 8)]).unwrap();
+        assert_eq!(number(&honest.outputs.clone().unwrap()[0]), 44);
+        assert!(satisfied(honest.clone()));
+        let mut lying = honest;
+        lying.outputs = Some(vec![bits(45, 8)]);
+        assert!(!satisfied(lying));
+    }
+
+    #[test]
+    fn public_bits_pack_lowest_wire_first() {
+        let packed = pack_bits::<Fr>(&bits(0b1011, 4));
+        assert_eq!(packed, vec![Fr::from(11u32)]);
+        assert_eq!(pack_bits::<Fr>(&vec![true; 300]).len(), 2);
+        let inputs = public_inputs::<Fr>(&[false, true], &[bits(5, 8), bits(6, 8)], &[bits(11, 8)]);
+        assert_eq!(inputs, vec![Fr::from(6u32), Fr::from(11u32)]);
+    }
+
+    #[test]
+    fn broken_files_are_refused_with_the_line() {
+        for (text, line, message) in [
+            ("1 4\n1 2\n1 1\n\n2 1 0 2 3 AND\n", 5, "wire 2 is read before anything sets it"),
+            ("2 4\n1 2\n1 1\n\n2 1 0 1 3 AND\n2 1 0 1 3 XOR\n", 6, "wire 3 is set twice"),
+            ("2 4\n1 2\n1 1\n\n2 1 0 1 3 AND\n", 5, "the header says 2 gates, the file has 1"),
+            ("1 4\n1 2\n1 1\n\n2 1 0 1 3 NAND\n", 5, "NAND with 2 inputs and 1 outputs isn't a gate this parser knows"),
+            ("1 5\n1 2\n1 2\n\n2 1 0 1 3 AND\n", 5, "output wire 4 is never set"),
+            ("1 4\n1 2\n1 1\n\n2 1 0 1 9 AND\n", 5, "wire 9 is past the 4 wires of the circuit"),
+            ("1 4\n1 2\n", 2, "the file ends before the outputs line"),
+            ("1 four\n", 1, "'four' is not a number"),
+        ] {
+            match Bristol::parse(text) {
+                Err(BristolError::Parse { line: at, message: said }) => assert_eq!((at, said.as_str()), (line, message)),
+                other => panic!("{text:?} gave {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn inputs_of_the_wrong_width_are_refused() {
+        let adder = Bristol::parse(&ripple_adder(8)).unwrap();
+        assert!(matches!(adder.evaluate(&[bits(1, 8)]), Err(BristolError::WrongInputs { .. })));
+        assert!(matches!(adder.evaluate(&[bits(1, 8), bits(1, 7)]), Err(BristolError::WrongInputs { .. })));
+    }
+
+    #[test]
+    fn the_sbox_gives_the_fips_197_values() {
+        let sbox = Bristol::parse(&sbox_circuit()).unwrap();
+        // FIPS-197 figure 7, and the 0x53 -> 0xed of 5.1.1
+        for (x, s) in [(0x00, 0x63), (0x01, 0x7c), (0x53, 0xed), (0xff, 0x16), (0x10, 0xca)] {
+            let circuit = BristolCircuit::<Fr>::new(sbox.clone(), vec![false], vec![bits(x, 8)]).unwrap();
+            assert_eq!(number(&circuit.outputs.clone().unwrap()[0]), s, "S({x:#04x})");
+            assert!(satisfied(circuit));
+        }
+    }
+
+    #[test]
+    fn aes_128_matches_the_fips_197_vectors() {
+        let aes = Bristol::parse(&aes_128()).unwrap();
+        for (key, plaintext, ciphertext) in [
+            // appendix B
+            ("2b7e151628aed2a6abf7158809cf4f3c", "3243f6a8885a308d313198a2e0370734", "3925841d02dc09fbdc118597196a0b32"),
+            // appendix C.1
+            ("000102030405060708090a0b0c0d0e0f", "00112233445566778899aabbccddeeff", "69c4e0d86a7b0430d8cdb78070b4c55a"),
+        ] {
+            let outputs = aes.evaluate(&[hex_bits(key), hex_bits(plaintext)]).unwrap();
+            assert_eq!(outputs, vec![hex_bits(ciphertext)], "the key {key}");
+        }
+    }
+
+    #[test]
+    fn the_synthesized_aes_holds_for_the_fips_197_ciphertext() {
+        let aes = Bristol::parse(&aes_128()).unwrap();
+        let (key, plaintext) = (hex_bits("000102030405060708090a0b0c0d0e0f"), hex_bits("00112233445566778899aabbccddeeff"));
+        // "I know a key that encrypts this plaintext to this ciphertext"
+        let circuit = BristolCircuit::<Fr>::new(aes, vec![false, true], vec![key, plaintext]).unwrap();
+        assert_eq!(circuit.outputs, Some(vec![hex_bits("69c4e0d86a7b0430d8cdb78070b4c55a")]));
+        assert!(satisfied(circuit.clone()));
+        let mut lying = circuit;
+        lying.outputs.as_mut().unwrap()[0][0] ^= true;
+        assert!(!satisfied(lying));
+    }
+}
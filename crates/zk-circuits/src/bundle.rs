@@ -0,0 +1,92 @@
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use num_bigint::BigUint;
+use rand::{CryptoRng, RngCore};
+use zk_core::artifact::{ArtifactError, deserialize_checked};
+use zk_core::bundle::{Bundle, BundledProof};
+
+use crate::curves::Curve;
+use crate::verifier::verify_constant_work;
+
+/*
+The groth16 side of proof bundles (zk-core/src/bundle.rs): adding a proof on a curve
+to a bundle, and checking one. A bundled proof names its curve, so each one is
+checked only against a verifying key of that curve, with the bundle's public inputs
+taken into that curve's scalar field. A proof on a curve this build doesn't know is
+reported as such rather than skipped, a bundle is only as convincing as the proofs
+somebody could check.
+*/
+
+pub const GROTH16: &str = "groth16";
+
+#[derive(Debug)]
+pub enum BundleError {
+    Artifact(ArtifactError),
+    Synthesis(SynthesisError),
+    WrongCurve { expected: String, found: String },
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Artifact(e) => write!(f, "{e}"),
+            BundleError::Synthesis(e) => write!(f, "proving failed: {e}"),
+            BundleError::WrongCurve { expected, found } => write!(f, "a {found} proof checked against a {expected} key"),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<ArtifactError> for BundleError {
+    fn from(e: ArtifactError) -> Self {
+        BundleError::Artifact(e)
+    }
+}
+
+impl From<SynthesisError> for BundleError {
+    fn from(e: SynthesisError) -> Self {
+        BundleError::Synthesis(e)
+    }
+}
+
+// field elements as the integers a bundle stores
+pub fn canonical<F: PrimeField>(inputs: &[F]) -> Vec<BigUint> {
+    inputs.iter().map(|x| (*x).into()).collect()
+}
+
+// proves with pk and adds the proof, after checking the circuit's public inputs are the bundle's
+pub fn prove<E: Curve, C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+    bundle: &mut Bundle,
+    pk: &ProvingKey<E>,
+    circuit: C,
+    public_inputs: &[E::ScalarField],
+    rng: &mut R,
+) -> Result<(), BundleError> {
+    if bundle.inputs_for::<E::ScalarField>()? != public_inputs {
+        return Err(ArtifactError::BadEncoding(format!("the {} proof would be for other public inputs than the bundle's", E::NAME)).into());
+    }
+    let proof = Groth16::<E>::prove(pk, circuit, rng)?;
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).map_err(ArtifactError::from)?;
+    bundle.push(GROTH16, E::NAME, bytes);
+    Ok(())
+}
+
+// whether one of the bundle's proofs is valid for its public inputs under vk
+pub fn verify<E: Curve>(bundle: &Bundle, member: &BundledProof, vk: &VerifyingKey<E>) -> Result<bool, BundleError> {
+    if member.backend != GROTH16 || member.curve != E::NAME {
+        return Err(BundleError::WrongCurve {
+            expected: format!("{GROTH16}/{}", E::NAME),
+            found: format!("{}/{}", member.backend, member.curve),
+        });
+    }
+    let inputs = bundle.inputs_for::<E::ScalarField>()?;
+    let proof: Proof<E> = deserialize_checked(&member.proof)?;
+    Ok(verify_constant_work(&prepare_verifying_key(vk), &inputs, &proof))
+}
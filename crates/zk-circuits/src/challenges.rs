@@ -0,0 +1,229 @@
+use ark_bls12_381::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use zk_gadgets::compare::enforce_bits;
+
+/*
+Prove it wrong. Every circuit here is broken on purpose, each in one of the ways real
+circuits have been broken: a bit that nobody checks is a bit, an output that is
+computed and then compared with nothing, a division that is fine until the divisor
+is zero, a comparison that trusts its inputs to be small.
+
+A challenge has a claim (what the verifier believes when the proof checks out) and a
+list of values, public and secret. An exploit is a set of values that satisfies every
+constraint while the claim is false. judge runs the circuit on the values and checks
+the claim natively, `zk-toy challenge` is the game around it.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Challenge {
+    Booleanity,
+    Output,
+    Division,
+    Wraparound,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    // the constraints aren't satisfied, the circuit caught it
+    Caught,
+    // satisfied, but the claim is true too: an honest witness, not an exploit
+    Honest,
+    // satisfied and the claim is false
+    Exploit,
+}
+
+// (name, public)
+pub type Value = (&'static str, bool);
+
+impl Challenge {
+    pub const ALL: [Challenge; 4] = [Challenge::Booleanity, Challenge::Output, Challenge::Division, Challenge::Wraparound];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Challenge::Booleanity => "booleanity",
+            Challenge::Output => "output",
+            Challenge::Division => "division",
+            Challenge::Wraparound => "wraparound",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    pub fn points(&self) -> u32 {
+        match self {
+            Challenge::Booleanity | Challenge::Output => 10,
+            Challenge::Division => 20,
+            Challenge::Wraparound => 30,
+        }
+    }
+
+    pub fn claim(&self) -> &'static str {
+        match self {
+            Challenge::Booleanity => "the public x fits in 4 bits (x < 16), the secret b0..b3 are its bits",
+            Challenge::Output => "the secret x and y are a factorization of the public z: x * y = z, neither is 1",
+            Challenge::Division => "the public q is the public a divided by a secret nonzero d: q * d = a",
+            Challenge::Wraparound => "the secret a is at most the public b, both smaller than 2^8",
+        }
+    }
+
+    // the circuit, in words, so there's something to go on without reading rust
+    pub fn constraints(&self) -> &'static str {
+        match self {
+            Challenge::Booleanity => "b0 + 2 b1 + 4 b2 + 8 b3 = x",
+            Challenge::Output => "x != 1, y != 1, and x * y is computed",
+            Challenge::Division => "q * d = a",
+            Challenge::Wraparound => "b - a is split into 8 bits (each checked to be 0 or 1)",
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Challenge::Booleanity => "who says a bit has to be 0 or 1?",
+            Challenge::Output => "computed, and then what happens to it?",
+            Challenge::Division => "what is q when d is 0?",
+            Challenge::Wraparound => "in a field, -1 is a very big number",
+        }
+    }
+
+    pub fn values(&self) -> &'static [Value] {
+        match self {
+            Challenge::Booleanity => &[("x", true), ("b0", false), ("b1", false), ("b2", false), ("b3", false)],
+            Challenge::Output => &[("z", true), ("x", false), ("y", false)],
+            Challenge::Division => &[("a", true), ("q", true), ("d", false)],
+            Challenge::Wraparound => &[("b", true), ("a", false)],
+        }
+    }
+
+    // a witness that satisfies the circuit honestly, in the order of values(), as a template
+    pub fn honest(&self) -> Vec<Fr> {
+        let v = |xs: &[u64]| xs.iter().map(|&x| Fr::from(x)).collect();
+        match self {
+            Challenge::Booleanity => v(&[13, 1, 0, 1, 1]),
+            Challenge::Output => v(&[15, 3, 5]),
+            Challenge::Division => v(&[42, 6, 7]),
+            Challenge::Wraparound => v(&[200, 17]),
+        }
+    }
+
+    // the claim, checked outside the circuit on the integers the values stand for
+    pub fn claim_holds(&self, values: &[Fr]) -> bool {
+        let small = |x: &Fr, bits: u32| x.into_bigint().num_bits() <= bits;
+        let one = Fr::from(1u32);
+        match self {
+            Challenge::Booleanity => {
+                let (x, bits) = (values[0], &values[1..]);
+                let is_bit = |b: &Fr| *b == Fr::from(0u32) || *b == one;
+                small(&x, 4) && bits.iter().all(is_bit)
+            }
+            Challenge::Output => values[1] * values[2] == values[0] && values[1] != one && values[2] != one,
+            Challenge::Division => values[2] != Fr::from(0u32) && values[1] * values[2] == values[0],
+            Challenge::Wraparound => {
+                let (b, a) = (values[0], values[1]);
+                small(&a, 8) && small(&b, 8) && a.into_bigint() <= b.into_bigint()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ChallengeCircuit {
+    pub challenge: Challenge,
+    pub values: Vec<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for ChallengeCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let mut vars = Vec::new();
+        for (&(_, public), value) in self.challenge.values().iter().zip(&self.values) {
+            let var = if public {
+                FpVar::new_input(cs.clone(), || Ok(*value))?
+            } else {
+                FpVar::new_witness(cs.clone(), || Ok(*value))?
+            };
+            vars.push(var);
+        }
+        let one = FpVar::constant(Fr::from(1u32));
+        match self.challenge {
+            // the flaw: no b * (b - 1) = 0 for any of the bits
+            Challenge::Booleanity => {
+                let mut sum = FpVar::constant(Fr::from(0u32));
+                for (i, bit) in vars[1..].iter().enumerate() {
+                    sum += bit * Fr::from(1u64 << i);
+                }
+                sum.enforce_equal(&vars[0])
+            }
+            // the flaw: the product is never compared with z
+            Challenge::Output => {
+                vars[1].enforce_not_equal(&one)?;
+                vars[2].enforce_not_equal(&one)?;
+                let _product = &vars[1] * &vars[2];
+                Ok(())
+            }
+            // the flaw: nothing says d != 0
+            Challenge::Division => (&vars[1] * &vars[2]).enforce_equal(&vars[0]),
+            // the flaw: a and b aren't range checked, see zk-gadgets/src/compare.rs
+            Challenge::Wraparound => enforce_bits(cs, &(&vars[0] - &vars[1]), 8),
+        }
+    }
+}
+
+pub fn judge(challenge: Challenge, values: &[Fr]) -> Result<Verdict, SynthesisError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let circuit = ChallengeCircuit {
+        challenge,
+        values: values.to_vec(),
+    };
+    circuit.generate_constraints(cs.clone())?;
+    Ok(match (cs.is_satisfied()?, challenge.claim_holds(values)) {
+        (false, _) => Verdict::Caught,
+        (true, true) => Verdict::Honest,
+        (true, false) => Verdict::Exploit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(xs: &[u64]) -> Vec<Fr> {
+        xs.iter().map(|&x| Fr::from(x)).collect()
+    }
+
+    #[test]
+    fn every_honest_witness_is_honest() {
+        for challenge in Challenge::ALL {
+            assert_eq!(judge(challenge, &challenge.honest()).unwrap(), Verdict::Honest, "{}", challenge.name());
+        }
+    }
+
+    #[test]
+    fn a_bit_that_isnt_a_bit_is_an_exploit() {
+        // 100 = 100 * 1, and 100 doesn't fit in 4 bits
+        assert_eq!(judge(Challenge::Booleanity, &values(&[100, 100, 0, 0, 0])).unwrap(), Verdict::Exploit);
+    }
+
+    #[test]
+    fn a_product_nobody_compares_is_an_exploit() {
+        assert_eq!(judge(Challenge::Output, &values(&[15, 2, 2])).unwrap(), Verdict::Exploit);
+    }
+
+    #[test]
+    fn dividing_zero_by_zero_is_an_exploit() {
+        assert_eq!(judge(Challenge::Division, &values(&[0, 5, 0])).unwrap(), Verdict::Exploit);
+        assert_eq!(judge(Challenge::Division, &values(&[42, 5, 7])).unwrap(), Verdict::Caught);
+    }
+
+    #[test]
+    fn minus_one_below_zero_is_an_exploit() {
+        // b - a = 0 - (-1) = 1 fits in 8 bits, a is p - 1
+        assert_eq!(judge(Challenge::Wraparound, &[Fr::from(0u32), -Fr::from(1u32)]).unwrap(), Verdict::Exploit);
+        assert_eq!(judge(Challenge::Wraparound, &values(&[17, 200])).unwrap(), Verdict::Caught);
+    }
+}
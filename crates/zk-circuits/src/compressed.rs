@@ -0,0 +1,86 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::{AllocatedFp, FpVar};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, LinearCombination, OptimizationGoal, SynthesisError,
+    SynthesisMode, Variable,
+};
+use zk_gadgets::hasher::CircuitHasher;
+
+/*
+Public input compression. A Groth16 verifier does a scalar multiplication per public
+input and, on chain, pays calldata for each one, so a statement with many inputs
+(a VSS with all its shares, a batch) costs more to check than the proof itself. The
+usual fix is to make one input, a hash of all of them: the circuit takes the old
+inputs as witnesses, recomputes the hash and exposes only that, and the verifier
+hashes the values it already has with the same function (compress) before checking.
+
+Compressed<C, H> does that to any circuit, unmodified: the inner circuit is
+synthesized into a constraint system of its own, and its constraints are copied into
+the real one with every inner variable, its public inputs included, turned into a
+witness. Then the inner inputs are hashed with H and the hash is the single public
+input. The same statement, as long as the hash is collision resistant, for the price
+of the hash in constraints: a few hundred for Poseidon, tens of thousands for SHA-256,
+which is the one a contract can recompute cheaply. src/bin/compressed_inputs.rs
+compares the layouts.
+*/
+
+#[derive(Clone)]
+pub struct Compressed<C, H> {
+    pub inner: C,
+    pub hasher: H,
+}
+
+impl<C, H> Compressed<C, H> {
+    pub fn new(inner: C, hasher: H) -> Self {
+        Self { inner, hasher }
+    }
+}
+
+// the verifier's side: the one public input of Compressed for the inner circuit's inputs
+pub fn compress<F: PrimeField, H: CircuitHasher<F>>(hasher: &H, public_inputs: &[F]) -> F {
+    hasher.hash(public_inputs)
+}
+
+// an inner matrix row as a linear combination of the outer variables
+fn row<F: PrimeField>(row: &[(F, usize)], variables: &[Variable]) -> LinearCombination<F> {
+    LinearCombination(row.iter().map(|(coefficient, i)| (*coefficient, variables[*i])).collect())
+}
+
+impl<F: PrimeField, C: ConstraintSynthesizer<F>, H: CircuitHasher<F>> ConstraintSynthesizer<F> for Compressed<C, H> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let setup = cs.is_in_setup_mode();
+        let inner = ConstraintSystem::<F>::new_ref();
+        inner.set_optimization_goal(OptimizationGoal::Constraints);
+        inner.set_mode(if setup { SynthesisMode::Setup } else { SynthesisMode::Prove { construct_matrices: true } });
+        self.inner.generate_constraints(inner.clone())?;
+        inner.finalize();
+        let matrices = inner.to_matrices().ok_or(SynthesisError::MissingCS)?;
+        let (instance, witness) = {
+            let inner = inner.borrow().ok_or(SynthesisError::MissingCS)?;
+            (inner.instance_assignment.clone(), inner.witness_assignment.clone())
+        };
+        let value = |values: &[F], i: usize| values.get(i).copied().ok_or(SynthesisError::AssignmentMissing);
+
+        // the inner variables in the inner order, instance first with the constant one
+        let mut variables = vec![Variable::One];
+        let mut inputs = Vec::with_capacity(matrices.num_instance_variables - 1);
+        for i in 1..matrices.num_instance_variables {
+            let variable = cs.new_witness_variable(|| value(&instance, i))?;
+            variables.push(variable);
+            inputs.push(FpVar::Var(AllocatedFp::new(instance.get(i).copied(), variable, cs.clone())));
+        }
+        for i in 0..matrices.num_witness_variables {
+            variables.push(cs.new_witness_variable(|| value(&witness, i))?);
+        }
+        for ((a, b), c) in matrices.a.iter().zip(&matrices.b).zip(&matrices.c) {
+            cs.enforce_constraint(row(a, &variables), row(b, &variables), row(c, &variables))?;
+        }
+
+        let digest = self.hasher.hash_var(cs.clone(), &inputs)?;
+        let public = FpVar::new_input(cs, || digest.value())?;
+        digest.enforce_equal(&public)
+    }
+}
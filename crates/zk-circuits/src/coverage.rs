@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, SynthesisError, SynthesisMode};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::Registry;
+
+use crate::assignment::ExportError;
+
+/*
+Constraint coverage for one witness, in the spirit of line coverage: which
+constraints did any checking with these values and which ones held for free. Every
+R1CS row is <a, z> * <b, z> = <c, z>, and evaluated on a satisfying z it is one of
+
+  tight      some side is nonzero, so the values had to line up: with b = 1 (an
+             enforce_equal) a is pinned to c, with a nonzero factor the other one
+             is pinned to zero (a boolean, a selected branch)
+  vacuous    a, b and c all zero: 0 * 0 = 0, this witness would have passed it
+             with plenty of other values in it too
+  empty      a row without terms, 0 = 0 whatever the witness
+
+by namespace (the gadget path ConstraintLayer records, as in diff.rs). Vacuous rows
+are normal for some witnesses, the branch a selector turned off say, so the telling
+part is a namespace that is never tight across the witnesses you try: the gadget is
+wired to something that is always zero, or its check cancels out. An empty row is
+dead for every witness.
+
+Like the assignment export this refuses a witness that doesn't satisfy the circuit,
+coverage of a failing run says nothing.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Hit {
+    Tight,
+    Vacuous,
+    Empty,
+}
+
+// how a row evaluated, given its terms and the three sides on the witness
+fn classify<F: PrimeField>(terms: usize, a: F, b: F, c: F) -> Hit {
+    if terms == 0 {
+        Hit::Empty
+    } else if a.is_zero() && b.is_zero() && c.is_zero() {
+        Hit::Vacuous
+    } else {
+        Hit::Tight
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counts {
+    pub tight: usize,
+    pub vacuous: usize,
+    pub empty: usize,
+}
+
+impl Counts {
+    fn add(&mut self, hit: Hit) {
+        match hit {
+            Hit::Tight => self.tight += 1,
+            Hit::Vacuous => self.vacuous += 1,
+            Hit::Empty => self.empty += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.tight + self.vacuous + self.empty
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coverage {
+    pub circuit: String,
+    pub namespaces: BTreeMap<String, Counts>,
+}
+
+impl Coverage {
+    pub fn totals(&self) -> Counts {
+        let mut totals = Counts::default();
+        for counts in self.namespaces.values() {
+            totals.tight += counts.tight;
+            totals.vacuous += counts.vacuous;
+            totals.empty += counts.empty;
+        }
+        totals
+    }
+
+    // namespaces where no constraint was tight with this witness
+    pub fn idle(&self) -> Vec<&str> {
+        self.namespaces.iter().filter(|(_, c)| c.tight == 0).map(|(path, _)| path.as_str()).collect()
+    }
+}
+
+// synthesizes the circuit with its witness and evaluates every constraint on it
+pub fn analyze<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: &str, synthesizer: C) -> Result<Coverage, ExportError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Prove { construct_matrices: true });
+    let subscriber = Registry::default().with(ConstraintLayer::default());
+    tracing::subscriber::with_default(subscriber, || synthesizer.generate_constraints(cs.clone()))?;
+    if let Some(at) = cs.which_is_unsatisfied()? {
+        return Err(ExportError::Unsatisfied(if at.is_empty() { "(top level)".to_string() } else { at }));
+    }
+    cs.finalize();
+    let names = cs.constraint_names().unwrap_or_default();
+    let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let z = {
+        let inner = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+        let mut z = inner.instance_assignment.clone();
+        z.extend_from_slice(&inner.witness_assignment);
+        z
+    };
+    let evaluate = |row: &[(F, usize)]| row.iter().map(|(coefficient, i)| *coefficient * z[*i]).sum::<F>();
+
+    let mut namespaces: BTreeMap<String, Counts> = BTreeMap::new();
+    for (i, ((a, b), c)) in matrices.a.iter().zip(&matrices.b).zip(&matrices.c).enumerate() {
+        let hit = classify(a.len() + b.len() + c.len(), evaluate(a), evaluate(b), evaluate(c));
+        let name = match names.get(i) {
+            Some(name) if !name.is_empty() => name.clone(),
+            Some(_) => "(top level)".to_string(),
+            None => "(untraced)".to_string(),
+        };
+        namespaces.entry(name).or_default().add(hit);
+    }
+    Ok(Coverage {
+        circuit: circuit.to_string(),
+        namespaces,
+    })
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let totals = self.totals();
+        writeln!(f, "=== Coverage of {} ({} constraints) ===", self.circuit, totals.total())?;
+        writeln!(f, "  {:>7} {:>7} {:>7}  namespace", "tight", "vacuous", "empty")?;
+        for (path, c) in &self.namespaces {
+            let idle = if c.tight == 0 { "  <- nothing tight" } else { "" };
+            writeln!(f, "  {:>7} {:>7} {:>7}  {path}{idle}", c.tight, c.vacuous, c.empty)?;
+        }
+        writeln!(f, "  {:>7} {:>7} {:>7}  (all)", totals.tight, totals.vacuous, totals.empty)?;
+        match self.idle().len() {
+            0 => write!(f, "\n  ✓ every namespace had a tight constraint with this witness"),
+            n => write!(
+                f,
+                "\n  {n} namespace{} only held trivially with this witness, try others before calling it dead",
+                if n == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+#[cfg(feature = "groth16")]
+use ark_bls12_381::Bls12_381;
+use ark_bls12_381::{Config as Bls12_381Config, Fr, G1Affine};
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ff::PrimeField;
+#[cfg(feature = "groth16")]
+use ark_groth16::{PreparedVerifyingKey, Proof};
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
+use blake2::{Blake2s256, Digest};
+use rand::Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use zk_gadgets::compare::{enforce_bits, enforce_less_or_equal};
+use zk_gadgets::poseidon;
+use zk_protocols::bls::{self, PublicKey, SecretKey, Signature};
+
+/*
+Anonymous-ish credentials, passport edition. Three parties:
+
+  issuer    looks at your passport, commits to its attributes with a random salt,
+            Poseidon(salt, name, nationality, birth date), and BLS signs the commitment
+  holder    keeps the attributes, the salt and the signature (the credential file)
+  verifier  wants to know you're at least 18 and nothing else
+
+The holder shows the verifier the commitment, the issuer's signature on it and a proof
+that the committed birth date is on or before a cutoff (today minus 18 years). The
+verifier checks the signature natively and the proof with groth16, and never sees the
+name, the nationality or the birth date.
+Same commitment every time though, so two verifiers can tell it's the same person.
+
+Dates are yyyymmdd numbers (2005-03-14 is 20050314), which compare the same way the
+dates do, so "born on or before the cutoff" is a plain <= in the circuit.
+*/
+
+pub type IssuerKey = SecretKey<Bls12_381Config>;
+pub type IssuerPublicKey = PublicKey<Bls12_381Config>;
+
+// yyyymmdd goes up to 99991231, which fits in 27 bits
+pub const DATE_BITS: usize = 27;
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize, Zeroize, ZeroizeOnDrop)]
+pub struct Attributes {
+    pub name: String,
+    pub nationality: String,
+    pub birth_date: u32,
+}
+
+impl Attributes {
+    // the field elements the commitment is over: the text ones go through blake2s first,
+    // only the birth date has to stay a number the circuit can compare
+    pub fn to_fields(&self) -> [Fr; 3] {
+        [text_field(&self.name), text_field(&self.nationality), Fr::from(self.birth_date)]
+    }
+}
+
+fn text_field(text: &str) -> Fr {
+    Fr::from_le_bytes_mod_order(&Blake2s256::digest(text.as_bytes()))
+}
+
+pub fn commit(config: &PoseidonConfig<Fr>, attributes: &Attributes, salt: Fr) -> Fr {
+    let mut inputs = vec![salt];
+    inputs.extend_from_slice(&attributes.to_fields());
+    poseidon::hash(config, &inputs)
+}
+
+// what the issuer signs: the commitment in its canonical encoding
+fn message(commitment: Fr) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    commitment.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    bytes
+}
+
+// the holder's file. The signature is public, the rest is not and is wiped on drop
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Zeroize, ZeroizeOnDrop)]
+pub struct Credential {
+    pub attributes: Attributes,
+    pub salt: Fr,
+    #[zeroize(skip)]
+    pub signature: G1Affine,
+}
+
+impl Credential {
+    pub fn commitment(&self, config: &PoseidonConfig<Fr>) -> Fr {
+        commit(config, &self.attributes, self.salt)
+    }
+
+    pub fn signature(&self) -> Signature<Bls12_381Config> {
+        Signature(self.signature)
+    }
+}
+
+pub fn issue<R: Rng>(key: &IssuerKey, config: &PoseidonConfig<Fr>, attributes: Attributes, rng: &mut R) -> Credential {
+    let salt = Fr::rand(rng);
+    let signature = key.sign(&message(commit(config, &attributes, salt)));
+    Credential {
+        attributes,
+        salt,
+        signature: signature.0,
+    }
+}
+
+pub fn verify_signature(issuer: &IssuerPublicKey, commitment: Fr, signature: &Signature<Bls12_381Config>) -> bool {
+    bls::verify(issuer, &message(commitment), signature)
+}
+
+// "at least min_age on `today`" is "born on or before today minus min_age years".
+// someone born on february 29 turns 18 on march 1 this way, which is what most laws say
+pub fn cutoff(today: u32, min_age: u32) -> u32 {
+    today.saturating_sub(min_age.saturating_mul(10000))
+}
+
+// "2005-03-14" -> 20050314
+pub fn parse_date(text: &str) -> Result<u32, String> {
+    let parts: Vec<&str> = text.trim().split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(format!("'{text}' is not a YYYY-MM-DD date"));
+    };
+    let number = |s: &str, len: usize| s.parse::<u32>().ok().filter(|_| s.len() == len);
+    match (number(year, 4), number(month, 2), number(day, 2)) {
+        (Some(y), Some(m), Some(d)) if (1..=12).contains(&m) && (1..=31).contains(&d) => Ok(y * 10000 + m * 100 + d),
+        _ => Err(format!("'{text}' is not a YYYY-MM-DD date")),
+    }
+}
+
+pub fn format_date(date: u32) -> String {
+    format!("{:04}-{:02}-{:02}", date / 10000, date / 100 % 100, date % 100)
+}
+
+/*
+The circuit. Public inputs: the commitment and the cutoff date.
+Witnesses: the salt and the three attribute fields.
+It checks the commitment opens to them and that birth date <= cutoff, with the birth
+date range checked so a huge "date" can't wrap around the comparison.
+*/
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct AgeCircuit {
+    #[zeroize(skip)]
+    pub poseidon: PoseidonConfig<Fr>,
+    pub attributes: Option<[Fr; 3]>,
+    pub salt: Option<Fr>,
+    #[zeroize(skip)]
+    pub commitment: Option<Fr>,
+    #[zeroize(skip)]
+    pub cutoff: Option<Fr>,
+}
+
+impl AgeCircuit {
+    pub fn empty(poseidon: PoseidonConfig<Fr>) -> Self {
+        Self {
+            poseidon,
+            attributes: None,
+            salt: None,
+            commitment: None,
+            cutoff: None,
+        }
+    }
+
+    pub fn new(poseidon: PoseidonConfig<Fr>, credential: &Credential, cutoff: u32) -> Self {
+        let commitment = credential.commitment(&poseidon);
+        Self {
+            poseidon,
+            attributes: Some(credential.attributes.to_fields()),
+            salt: Some(credential.salt),
+            commitment: Some(commitment),
+            cutoff: Some(Fr::from(cutoff)),
+        }
+    }
+}
+
+// the public input vector, in the order the circuit allocates it
+pub fn public_inputs(commitment: Fr, cutoff: u32) -> Vec<Fr> {
+    vec![commitment, Fr::from(cutoff)]
+}
+
+impl ConstraintSynthesizer<Fr> for AgeCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let commitment = FpVar::new_input(cs.clone(), || self.commitment.ok_or(SynthesisError::AssignmentMissing))?;
+        let cutoff = FpVar::new_input(cs.clone(), || self.cutoff.ok_or(SynthesisError::AssignmentMissing))?;
+
+        let salt = FpVar::new_witness(cs.clone(), || self.salt.ok_or(SynthesisError::AssignmentMissing))?;
+        let mut inputs = vec![salt];
+        for i in 0..3 {
+            let field = self.attributes.map(|a| a[i]);
+            inputs.push(FpVar::new_witness(cs.clone(), || field.ok_or(SynthesisError::AssignmentMissing))?);
+        }
+        poseidon::hash_var(cs.clone(), &self.poseidon, &inputs)?.enforce_equal(&commitment)?;
+
+        let birth_date = &inputs[3];
+        enforce_bits(cs.clone(), birth_date, DATE_BITS)?;
+        enforce_less_or_equal(cs, birth_date, &cutoff, DATE_BITS)
+    }
+}
+
+// what the holder hands the verifier. The cutoff isn't in here, the verifier picks it
+#[cfg(feature = "groth16")]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Presentation {
+    pub commitment: Fr,
+    pub signature: G1Affine,
+    pub proof: Proof<Bls12_381>,
+}
+
+// the issuer signed this commitment, and it opens to a birth date on or before the cutoff
+#[cfg(feature = "groth16")]
+pub fn verify_presentation(
+    pvk: &PreparedVerifyingKey<Bls12_381>,
+    issuer: &IssuerPublicKey,
+    presentation: &Presentation,
+    cutoff: u32,
+) -> bool {
+    let signed = verify_signature(issuer, presentation.commitment, &Signature(presentation.signature));
+    let proven = crate::verifier::verify_constant_work(pvk, &public_inputs(presentation.commitment, cutoff), &presentation.proof);
+    signed & proven
+}
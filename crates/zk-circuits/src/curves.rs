@@ -0,0 +1,48 @@
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+
+/*
+The pairing friendly curves the examples can prove on, with what you'd want to know
+before picking one. Groth16 itself doesn't care, any `Pairing` works, this trait only
+adds the name and the notes so examples can print them next to the numbers (and asks
+for a scalar field poseidon can absorb, which all of them have).
+
+The security numbers are rough, commonly quoted estimates, not guarantees. All three
+curves were designed for 128 bits, and the 2016 exTNFS attacks on the discrete log in
+the target field (Kim-Barbulescu) took them down: to around 100 bits for BN254 and
+around 120 for the two BLS12 curves, whose bigger base fields held up better.
+*/
+pub trait Curve: Pairing<ScalarField: Absorb> {
+    const NAME: &'static str;
+    const SECURITY_BITS: u32;
+    const NOTES: &'static str;
+
+    fn scalar_bits() -> u32 {
+        <Self::ScalarField as PrimeField>::MODULUS_BIT_SIZE
+    }
+
+    fn base_bits() -> u32 {
+        <Self::BaseField as PrimeField>::MODULUS_BIT_SIZE
+    }
+}
+
+impl Curve for ark_bls12_381::Bls12_381 {
+    const NAME: &'static str = "BLS12-381";
+    const SECURITY_BITS: u32 = 120;
+    const NOTES: &'static str = "zcash, ethereum consensus signatures; the default here";
+}
+
+#[cfg(feature = "evm")]
+impl Curve for ark_bn254::Bn254 {
+    const NAME: &'static str = "BN254";
+    const SECURITY_BITS: u32 = 100;
+    const NOTES: &'static str = "ethereum precompiles, circom and snarkjs; the fastest and the weakest";
+}
+
+#[cfg(feature = "bls")]
+impl Curve for ark_bls12_377::Bls12_377 {
+    const NAME: &'static str = "BLS12-377";
+    const SECURITY_BITS: u32 = 120;
+    const NOTES: &'static str = "zexe, aleo, celo; BW6-761 can verify its pairings inside a circuit";
+}
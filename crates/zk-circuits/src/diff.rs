@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::r1cs::{ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode};
+use blake2::{Blake2s256, Digest};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::Registry;
+
+/*
+What did my gadget change do to the circuit? A Shape is the summary of one synthesized
+constraint system: the variable and constraint counts, how many constraints came from
+each namespace path (the gadget call they were made in, as ark-relations'
+ConstraintLayer records it), and a hash of the R1CS matrices. diff puts two of them
+side by side, namespace by namespace.
+
+Shapes are plain json, so comparing two revisions of the code is: save the shape on
+one, check out the other, diff against the file (`zk-toy shape` / `zk-toy diff`).
+Equal counts with a different matrix hash means the same number of constraints, wired
+differently. Keys from a setup only fit circuits with the same matrices.
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shape {
+    pub circuit: String,
+    pub inputs: usize,
+    pub witnesses: usize,
+    pub constraints: usize,
+    pub namespaces: BTreeMap<String, usize>,
+    pub matrix_hash: String,
+}
+
+impl Shape {
+    // of a constraint system that was synthesized with a ConstraintLayer around, the
+    // namespaces are all "(untraced)" otherwise
+    pub fn of<F: PrimeField>(circuit: &str, cs: &ConstraintSystemRef<F>) -> Self {
+        let mut namespaces = BTreeMap::new();
+        let names = cs.constraint_names().unwrap_or_else(|| vec!["(untraced)".to_string(); cs.num_constraints()]);
+        for name in names {
+            let name = if name.is_empty() { "(top level)".to_string() } else { name };
+            *namespaces.entry(name).or_insert(0) += 1;
+        }
+        Self {
+            circuit: circuit.to_string(),
+            // the constant one isn't the circuit's
+            inputs: cs.num_instance_variables() - 1,
+            witnesses: cs.num_witness_variables(),
+            constraints: cs.num_constraints(),
+            namespaces,
+            matrix_hash: matrix_hash(cs),
+        }
+    }
+
+    // synthesizes the circuit in setup mode with a ConstraintLayer just for this call
+    pub fn synthesize<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: &str, synthesizer: C) -> Result<Self, SynthesisError> {
+        let cs = ConstraintSystem::<F>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let subscriber = Registry::default().with(ConstraintLayer::default());
+        tracing::subscriber::with_default(subscriber, || synthesizer.generate_constraints(cs.clone()))?;
+        cs.finalize();
+        Ok(Self::of(circuit, &cs))
+    }
+}
+
+fn matrix_hash<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> String {
+    let Some(matrices) = cs.to_matrices() else {
+        return "(no matrices)".to_string();
+    };
+    let mut hasher = Blake2s256::new();
+    for matrix in [&matrices.a, &matrices.b, &matrices.c] {
+        for row in matrix {
+            hasher.update((row.len() as u64).to_le_bytes());
+            for (coefficient, variable) in row {
+                hasher.update(coefficient.into_bigint().to_bytes_le());
+                hasher.update((*variable as u64).to_le_bytes());
+            }
+        }
+    }
+    hasher.finalize().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamespaceChange {
+    pub path: String,
+    pub before: usize,
+    pub after: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct ShapeDiff {
+    pub before: Shape,
+    pub after: Shape,
+    // only the namespaces whose count changed, added ones have before = 0
+    pub changes: Vec<NamespaceChange>,
+}
+
+impl ShapeDiff {
+    pub fn same_matrices(&self) -> bool {
+        self.before.matrix_hash == self.after.matrix_hash
+    }
+
+    pub fn added(&self) -> usize {
+        self.changes.iter().map(|c| c.after.saturating_sub(c.before)).sum()
+    }
+
+    pub fn removed(&self) -> usize {
+        self.changes.iter().map(|c| c.before.saturating_sub(c.after)).sum()
+    }
+}
+
+pub fn diff(before: &Shape, after: &Shape) -> ShapeDiff {
+    let paths: BTreeSet<&String> = before.namespaces.keys().chain(after.namespaces.keys()).collect();
+    let changes = paths
+        .into_iter()
+        .map(|path| NamespaceChange {
+            path: path.clone(),
+            before: before.namespaces.get(path).copied().unwrap_or(0),
+            after: after.namespaces.get(path).copied().unwrap_or(0),
+        })
+        .filter(|c| c.before != c.after)
+        .collect();
+    ShapeDiff {
+        before: before.clone(),
+        after: after.clone(),
+        changes,
+    }
+}
+
+fn signed(before: usize, after: usize) -> String {
+    match after as i64 - before as i64 {
+        0 => "=".to_string(),
+        d => format!("{d:+}"),
+    }
+}
+
+impl fmt::Display for ShapeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (b, a) = (&self.before, &self.after);
+        writeln!(f, "=== {} -> {} ===", b.circuit, a.circuit)?;
+        writeln!(f, "  {:<12} {:>9} {:>9} {:>8}", "", "before", "after", "change")?;
+        for (what, before, after) in [
+            ("inputs", b.inputs, a.inputs),
+            ("witnesses", b.witnesses, a.witnesses),
+            ("constraints", b.constraints, a.constraints),
+        ] {
+            writeln!(f, "  {what:<12} {before:>9} {after:>9} {:>8}", signed(before, after))?;
+        }
+        if self.changes.is_empty() {
+            writeln!(f, "\n  every namespace has the same number of constraints")?;
+        } else {
+            writeln!(f, "\n  {} constraints added, {} removed, by namespace:", self.added(), self.removed())?;
+            for c in &self.changes {
+                let tag = match (c.before, c.after) {
+                    (0, _) => "new",
+                    (_, 0) => "gone",
+                    _ => "",
+                };
+                writeln!(f, "  {:>9} {:>9} {:>8} {tag:<4} {}", c.before, c.after, signed(c.before, c.after), c.path)?;
+            }
+        }
+        if self.same_matrices() {
+            write!(f, "\n  ✓ identical matrices, keys from either setup fit both")
+        } else {
+            write!(f, "\n  the matrices differ, keys from one setup won't fit the other")
+        }
+    }
+}
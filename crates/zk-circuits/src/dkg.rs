@@ -0,0 +1,140 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ff::Zero;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use rand::{CryptoRng, Rng};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::vss::{self, Polynomial, Share, VssCircuit};
+
+/*
+A simulated distributed key generation in the style of Pedersen's DKG: every party
+acts as a VSS dealer for a random secret of its own, and the group secret is the sum
+of the secrets of all the dealers that behaved. Nobody ever learns the group secret,
+each party ends up with a share of it (the sum of the shares it received).
+
+What if a dealer cheats? Every share is handed over together with a VSS proof that
+it lies on the dealer's committed polynomial (vss.rs with a single share). A party
+that gets a bad share, or no valid proof, complains in public. The accused dealer then
+has to broadcast that share with a fresh proof, and everyone checks it. A dealer who
+can't do that is disqualified. A party can also complain about an honest dealer, the
+dealer just answers with a valid proof and the complaint is dismissed.
+(The broadcast does reveal the disputed share, same as in the original protocol.)
+*/
+
+pub type PartyId = u64;
+
+// the toxic waste of the ceremony: a dealer who kept its polynomial around after the
+// dkg could be forced (or hacked) to hand it over, so it is wiped when the party is dropped
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct Dealing {
+    #[zeroize(skip)]
+    pub commitment: Fr,
+    polynomial: Polynomial,
+    blinding: Fr,
+}
+
+// what a dealer sends one party over a private channel
+#[derive(Clone)]
+pub struct PrivateShare {
+    pub dealer: PartyId,
+    pub share: Share,
+    pub proof: Proof<Bls12_381>,
+}
+
+// a complaint answer is just the same thing, but broadcast
+pub type ComplaintResponse = PrivateShare;
+
+pub struct Party {
+    pub id: PartyId,
+    // cheating dealers hand out shares from some other polynomial
+    pub cheats_against: Option<PartyId>,
+    pub dealing: Option<Dealing>,
+    pub received: Vec<PrivateShare>,
+}
+
+pub struct DkgParams {
+    pub parties: u64,
+    pub threshold: usize,
+    pub poseidon: PoseidonConfig<Fr>,
+    pub pk: ProvingKey<Bls12_381>,
+    pub vk: VerifyingKey<Bls12_381>,
+}
+
+impl DkgParams {
+    // one setup for the single share vss circuit, shared by every dealer
+    pub fn setup<R: Rng + CryptoRng>(parties: u64, threshold: usize, poseidon: PoseidonConfig<Fr>, rng: &mut R) -> Self {
+        let empty = VssCircuit::empty(threshold, 1, poseidon.clone());
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(empty, rng).expect("vss setup failed");
+        Self {
+            parties,
+            threshold,
+            poseidon,
+            pk,
+            vk,
+        }
+    }
+
+    pub fn verify_share(&self, commitment: Fr, share: &PrivateShare) -> bool {
+        let inputs = vss::public_inputs(commitment, &[share.share]);
+        Groth16::<Bls12_381>::verify(&self.vk, &inputs, &share.proof).unwrap_or(false)
+    }
+}
+
+impl Party {
+    pub fn new(id: PartyId) -> Self {
+        Self {
+            id,
+            cheats_against: None,
+            dealing: None,
+            received: Vec::new(),
+        }
+    }
+
+    // round 1: pick a secret, commit to a sharing polynomial, publish the commitment
+    pub fn deal<R: Rng>(&mut self, params: &DkgParams, rng: &mut R) -> Fr {
+        let polynomial = Polynomial::random(Fr::rand(rng), params.threshold, rng);
+        let blinding = Fr::rand(rng);
+        let commitment = vss::commit(&params.poseidon, &polynomial, blinding);
+        self.dealing = Some(Dealing {
+            commitment,
+            polynomial,
+            blinding,
+        });
+        commitment
+    }
+
+    // the share for `to`, with a proof if the dealer is able to make one
+    pub fn share_for<R: Rng + CryptoRng>(&self, params: &DkgParams, to: PartyId, rng: &mut R) -> Option<PrivateShare> {
+        let dealing = self.dealing.as_ref()?;
+        let mut share = dealing.polynomial.share(to);
+        if self.cheats_against == Some(to) {
+            share.y += Fr::from(1u32);
+        }
+        let circuit = VssCircuit::new(params.poseidon.clone(), dealing.polynomial.clone(), dealing.blinding, vec![share]);
+        // for a bad share the circuit isn't satisfied, the prover still runs but the
+        // proof it spits out won't verify
+        let proof = Groth16::<Bls12_381>::prove(&params.pk, circuit, rng).ok()?;
+        Some(PrivateShare {
+            dealer: self.id,
+            share,
+            proof,
+        })
+    }
+
+    // the share of the group secret: sum of what we got from the qualified dealers
+    pub fn final_share(&self, qualified: &[PartyId]) -> Share {
+        let y = self
+            .received
+            .iter()
+            .filter(|s| qualified.contains(&s.dealer))
+            .fold(Fr::zero(), |acc, s| acc + s.share.y);
+        Share { x: Fr::from(self.id), y }
+    }
+
+    pub fn secret(&self) -> Option<Fr> {
+        self.dealing.as_ref().map(|d| d.polynomial.secret())
+    }
+}
@@ -0,0 +1,104 @@
+use ark_bls12_381::Fr;
+use ark_crypto_primitives::prf::blake2s::constraints::evaluate_blake2s;
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::ToBitsGadget;
+use ark_r1cs_std::bits::ToBytesGadget;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use blake2::{Blake2s256, Digest};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use zk_gadgets::packing::{pack_bytes, pack_bytes_var, pack_chunk, pack_chunk_var};
+use zk_gadgets::poseidon;
+
+/*
+A document commitment: the commitment to a document is
+
+    commitment = Poseidon(pack(blake2s(document)))
+
+blake2s turns a document of any length into 32 bytes, packing turns those bytes
+into two field elements (16 bytes each, since 32 bytes don't fit in one), and
+Poseidon turns the two field elements into the single public commitment.
+
+The circuit proves "I know a document with this commitment, and the bytes at
+position `offset` of it are this public value", without showing the rest of the
+document. Think of a dated certificate: you can show it was issued on a given
+date without revealing who it was issued to.
+The document length, offset and field length are fixed when the circuit is set
+up, so they are part of the circuit shape and not inputs.
+*/
+
+// how many digest bytes go into each packed field element
+const DIGEST_CHUNK: usize = 16;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DocumentLayout {
+    pub document_len: usize,
+    pub field_offset: usize,
+    pub field_len: usize,
+}
+
+// commitment to the document, computed outside the circuit
+pub fn commit(config: &PoseidonConfig<Fr>, document: &[u8]) -> Fr {
+    let digest = Blake2s256::digest(document);
+    poseidon::hash(config, &pack_bytes::<Fr>(&digest, DIGEST_CHUNK))
+}
+
+// the public value the verifier expects to find at the offset
+pub fn field_value(bytes: &[u8]) -> Fr {
+    pack_chunk(bytes)
+}
+
+// the document is the only witness, it is wiped when the circuit goes away
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct DocumentCircuit {
+    #[zeroize(skip)]
+    pub layout: DocumentLayout,
+    #[zeroize(skip)]
+    pub poseidon: PoseidonConfig<Fr>,
+    pub document: Option<Vec<u8>>,
+    #[zeroize(skip)]
+    pub commitment: Option<Fr>,
+    #[zeroize(skip)]
+    pub field_value: Option<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for DocumentCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let layout = self.layout;
+        assert!(layout.field_offset + layout.field_len <= layout.document_len);
+
+        // the public inputs: the commitment and the disclosed field
+        let commitment = FpVar::new_input(cs.clone(), || self.commitment.ok_or(SynthesisError::AssignmentMissing))?;
+        let disclosed = FpVar::new_input(cs.clone(), || self.field_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+        // the document itself is secret, one witness byte at a time
+        let document = match &self.document {
+            Some(bytes) => {
+                assert_eq!(bytes.len(), layout.document_len, "document has the wrong length");
+                UInt8::new_witness_vec(cs.clone(), bytes)?
+            }
+            None => UInt8::new_witness_vec(cs.clone(), &vec![0u8; layout.document_len])?,
+        };
+
+        // blake2s over the bits of the document, then the digest words back into bytes
+        let digest_words = evaluate_blake2s(&document.to_bits_le()?)?;
+        let mut digest = Vec::with_capacity(32);
+        for word in &digest_words {
+            digest.extend(word.to_bytes()?);
+        }
+
+        // pack the digest and hash it with poseidon, that has to be the commitment
+        let packed = pack_bytes_var(&digest, DIGEST_CHUNK)?;
+        poseidon::hash_var(cs.clone(), &self.poseidon, &packed)?.enforce_equal(&commitment)?;
+
+        // and the bytes at the offset have to be the disclosed value
+        let field = &document[layout.field_offset..layout.field_offset + layout.field_len];
+        pack_chunk_var(field)?.enforce_equal(&disclosed)?;
+
+        Ok(())
+    }
+}
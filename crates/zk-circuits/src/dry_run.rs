@@ -0,0 +1,174 @@
+use std::fmt;
+use std::time::Duration;
+
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode};
+use ark_serialize::CanonicalSerialize;
+use zk_core::report::timed;
+
+use crate::curves::Curve;
+
+/*
+What a circuit would cost before paying for a setup, for `zk-toy synth`: the circuit
+is synthesized in setup mode the way the Groth16 generator does it (no witness,
+linear combinations inlined for fewer constraints) and the sizes of what a setup
+would make are worked out from the counts, see key_info.rs for what the queries are:
+
+  proving key    2 + vars points in G1 for A and B, vars in G2, domain - 1 for H,
+                 witnesses for L, plus the verifying key and 8 bytes per list
+  verifying key  alpha, beta, gamma, delta and public inputs + 1 points
+  proof          two G1 points and one G2 point, whatever the circuit
+
+where vars counts the constant one. These are exact for arkworks' compressed
+encoding. The domain is constraints plus inputs rounded up to a power of two, so a
+circuit just past a power of two pays for almost twice the H query it uses.
+
+The density is the nonzero entries of the A, B and C matrices, a rough measure of
+the work in the multi-scalar multiplications besides their sizes.
+
+Proving time is only a guess: a straight line through the proving times and key sizes
+of earlier benchmark reports on a log-log scale (a power law, the sizes go from a kB
+to tens of MB), since the MSMs that dominate proving grow with the key. It only means
+something for the reports of one machine and one curve, within a factor of two or so.
+
+A Comparison is two dry runs side by side, the same statement arithmetized two ways
+(arithmetizations.rs) or one circuit with other parameters, for `zk-toy synth
+--versus`.
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DryRun {
+    pub constraints: usize,
+    // without the constant one
+    pub public_inputs: usize,
+    pub witnesses: usize,
+    // nonzero entries of A, B and C
+    pub nonzeros: [usize; 3],
+    pub domain_size: usize,
+    pub synthesis: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sizes {
+    pub proving_key: usize,
+    pub verifying_key: usize,
+    pub proof: usize,
+}
+
+impl DryRun {
+    pub fn synthesize<F: PrimeField, C: ConstraintSynthesizer<F>>(synthesizer: C) -> Result<Self, SynthesisError> {
+        let cs = ConstraintSystem::<F>::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        let (synthesized, synthesis) = timed(|| {
+            synthesizer.generate_constraints(cs.clone())?;
+            cs.finalize();
+            Ok::<_, SynthesisError>(())
+        });
+        synthesized?;
+        let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+        let public_inputs = cs.num_instance_variables() - 1;
+        Ok(Self {
+            constraints: cs.num_constraints(),
+            public_inputs,
+            witnesses: cs.num_witness_variables(),
+            nonzeros: [matrices.a_num_non_zero, matrices.b_num_non_zero, matrices.c_num_non_zero],
+            domain_size: (cs.num_constraints() + cs.num_instance_variables()).next_power_of_two(),
+            synthesis,
+        })
+    }
+
+    // nonzero entries per constraint, over all three matrices
+    pub fn density(&self) -> f64 {
+        self.nonzeros.iter().sum::<usize>() as f64 / self.constraints.max(1) as f64
+    }
+
+    pub fn sizes<E: Curve>(&self) -> Sizes {
+        let g1 = E::G1Affine::generator().compressed_size();
+        let g2 = E::G2Affine::generator().compressed_size();
+        let list = 8;
+        let variables = 1 + self.public_inputs + self.witnesses;
+        let verifying_key = g1 + 3 * g2 + list + (self.public_inputs + 1) * g1;
+        let proving_key = verifying_key
+            + 2 * g1
+            + 5 * list
+            + variables * (2 * g1 + g2)
+            + (self.domain_size - 1) * g1
+            + self.witnesses * g1;
+        Sizes {
+            proving_key,
+            verifying_key,
+            proof: 2 * g1 + g2,
+        }
+    }
+}
+
+// a least squares line through (proving key bytes, proving time) of earlier runs on a
+// log-log scale, evaluated at `proving_key_bytes`; None with fewer than two distinct sizes
+pub fn estimate_proving_time(calibration: &[(usize, Duration)], proving_key_bytes: usize) -> Option<Duration> {
+    let points: Vec<(f64, f64)> = calibration
+        .iter()
+        .filter(|(bytes, time)| *bytes > 0 && !time.is_zero())
+        .map(|(bytes, time)| ((*bytes as f64).ln(), time.as_secs_f64().ln()))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let spread: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if points.len() < 2 || spread == 0.0 {
+        return None;
+    }
+    let slope = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>() / spread;
+    let log_time = mean_y + slope * ((proving_key_bytes.max(1) as f64).ln() - mean_x);
+    Some(Duration::from_secs_f64(log_time.exp()))
+}
+
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub curve: &'static str,
+    // what each side is, "range bits=32" and "range bits=32 method=table"
+    pub labels: [String; 2],
+    pub runs: [DryRun; 2],
+    pub sizes: [Sizes; 2],
+}
+
+impl Comparison {
+    pub fn new<E: Curve>(before: (&str, DryRun), after: (&str, DryRun)) -> Self {
+        Self {
+            curve: E::NAME,
+            sizes: [before.1.sizes::<E>(), after.1.sizes::<E>()],
+            labels: [before.0.to_string(), after.0.to_string()],
+            runs: [before.1, after.1],
+        }
+    }
+}
+
+fn change(before: usize, after: usize) -> String {
+    if before == after {
+        return "=".to_string();
+    }
+    let ratio = if before == 0 { String::new() } else { format!(" (x{:.2})", after as f64 / before as f64) };
+    format!("{:+}{ratio}", after as i64 - before as i64)
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [b, a] = &self.runs;
+        let [sb, sa] = &self.sizes;
+        writeln!(f, "=== {} vs {} ===", self.labels[0], self.labels[1])?;
+        writeln!(f, "  {:<16} {:>10} {:>10}  change", "", "before", "after")?;
+        for (what, before, after) in [
+            ("constraints", b.constraints, a.constraints),
+            ("public inputs", b.public_inputs, a.public_inputs),
+            ("witnesses", b.witnesses, a.witnesses),
+            ("nonzero entries", b.nonzeros.iter().sum(), a.nonzeros.iter().sum()),
+            ("QAP domain", b.domain_size, a.domain_size),
+            ("proving key", sb.proving_key, sa.proving_key),
+            ("verifying key", sb.verifying_key, sa.verifying_key),
+        ] {
+            writeln!(f, "  {what:<16} {before:>10} {after:>10}  {}", change(before, after))?;
+        }
+        write!(f, "  (key sizes in bytes on {}, without a setup)", self.curve)
+    }
+}
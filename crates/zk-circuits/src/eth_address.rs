@@ -0,0 +1,72 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use zk_gadgets::keccak;
+use zk_gadgets::packing::pack_chunk_var;
+
+/*
+An ethereum address is the last 20 bytes of keccak256 of the uncompressed secp256k1
+public key, the 64 bytes x || y (big-endian, without the 0x04 prefix).
+
+The circuit proves "I know a public key with this address", that's the keccak part of
+proving account ownership: with an ECDSA (or secp256k1 scalar multiplication) circuit
+in front of it to get from the private key to the public key, it's "I own this
+account". Nearly all of its constraints are the one keccak permutation.
+
+The address is public as uint160(address), the way solidity casts it and what
+solidity::address_field computes on BN254.
+*/
+
+pub const PUBLIC_KEY_LEN: usize = 64;
+
+pub type PublicKey = [u8; PUBLIC_KEY_LEN];
+pub type Address = [u8; 20];
+
+pub fn address_of(public_key: &PublicKey) -> Address {
+    keccak::keccak256(public_key)[12..].try_into().expect("20 bytes")
+}
+
+pub fn address_value<F: PrimeField>(address: &Address) -> F {
+    F::from_be_bytes_mod_order(address)
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct AddressCircuit<F: PrimeField> {
+    pub public_key: Option<PublicKey>,
+    #[zeroize(skip)]
+    pub address: Option<F>,
+}
+
+impl<F: PrimeField> AddressCircuit<F> {
+    pub fn empty() -> Self {
+        Self {
+            public_key: None,
+            address: None,
+        }
+    }
+
+    pub fn new(public_key: PublicKey) -> Self {
+        Self {
+            address: Some(address_value(&address_of(&public_key))),
+            public_key: Some(public_key),
+        }
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for AddressCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let address = FpVar::new_input(cs.clone(), || self.address.ok_or(SynthesisError::AssignmentMissing))?;
+        let key = self.public_key.map(|k| k.map(Some)).unwrap_or([None; PUBLIC_KEY_LEN]);
+        let public_key = UInt8::new_witness_vec(cs, &key)?;
+        let digest = keccak::keccak256_var(&public_key)?;
+        // big-endian, so the last byte is the lowest: reversed it's a little-endian pack
+        let mut last = digest[12..].to_vec();
+        last.reverse();
+        pack_chunk_var(&last)?.enforce_equal(&address)
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ec::pairing::Pairing;
+use ark_ff::{BigInteger, PrimeField, Zero};
+#[cfg(feature = "groth16")]
+use ark_groth16::Proof;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/*
+Encoding things the way ethereum wants them. The precompiles for BN254 (EIP-196 for
+addition and scalar multiplication, EIP-197 for the pairing check) and the solidity
+verifiers built on them all speak in 32 byte big-endian words:
+
+  scalar / coordinate   one word, has to be smaller than the modulus
+  G1 point              x, y                 (the identity is 0, 0)
+  G2 point              x.c1, x.c0, y.c1, y.c0   imaginary part first!
+
+arkworks serializes little-endian, compressed, with flag bits mixed in, so none of
+this lines up by accident. The classic bugs are forgetting the byte order, getting
+the G2 coefficients the wrong way round, and passing a public input that is >= the
+scalar field modulus (the solidity verifier rejects it, while arkworks would have
+reduced it quietly). Decoding here is strict about all of those.
+*/
+
+pub type Word = [u8; 32];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvmEncodingError {
+    WrongLength { expected: usize, got: usize },
+    NotCanonical(&'static str),
+    NotOnCurve,
+    NotInSubgroup,
+}
+
+impl fmt::Display for EvmEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvmEncodingError::WrongLength { expected, got } => write!(f, "expected {expected} bytes, got {got}"),
+            EvmEncodingError::NotCanonical(what) => write!(f, "{what} is not smaller than the field modulus"),
+            EvmEncodingError::NotOnCurve => write!(f, "point is not on the curve"),
+            EvmEncodingError::NotInSubgroup => write!(f, "point is not in the prime order subgroup"),
+        }
+    }
+}
+
+impl std::error::Error for EvmEncodingError {}
+
+// works for both the scalar field (public inputs) and the base field (coordinates)
+pub fn encode_field<F: PrimeField>(x: &F) -> Word {
+    let mut word = [0u8; 32];
+    let bytes = x.into_bigint().to_bytes_be();
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+// reject instead of reducing: a word >= modulus doesn't round trip through encode
+pub fn decode_field<F: PrimeField>(word: &Word, what: &'static str) -> Result<F, EvmEncodingError> {
+    let x = F::from_be_bytes_mod_order(word);
+    if encode_field(&x) == *word { Ok(x) } else { Err(EvmEncodingError::NotCanonical(what)) }
+}
+
+pub fn encode_g1(p: &G1Affine) -> [Word; 2] {
+    match p.xy() {
+        Some((x, y)) => [encode_field(x), encode_field(y)],
+        None => [[0u8; 32], [0u8; 32]],
+    }
+}
+
+pub fn decode_g1(words: &[Word; 2]) -> Result<G1Affine, EvmEncodingError> {
+    let x: Fq = decode_field(&words[0], "G1 x coordinate")?;
+    let y: Fq = decode_field(&words[1], "G1 y coordinate")?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+    let p = G1Affine::new_unchecked(x, y);
+    // G1 of BN254 has cofactor 1, being on the curve is enough
+    if p.is_on_curve() { Ok(p) } else { Err(EvmEncodingError::NotOnCurve) }
+}
+
+pub fn encode_g2(p: &G2Affine) -> [Word; 4] {
+    match p.xy() {
+        Some((x, y)) => [encode_field(&x.c1), encode_field(&x.c0), encode_field(&y.c1), encode_field(&y.c0)],
+        None => [[0u8; 32]; 4],
+    }
+}
+
+pub fn decode_g2(words: &[Word; 4]) -> Result<G2Affine, EvmEncodingError> {
+    let x = Fq2::new(decode_field(&words[1], "G2 x.c0")?, decode_field(&words[0], "G2 x.c1")?);
+    let y = Fq2::new(decode_field(&words[3], "G2 y.c0")?, decode_field(&words[2], "G2 y.c1")?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() {
+        return Err(EvmEncodingError::NotOnCurve);
+    }
+    // G2 does have a cofactor, the precompile checks the subgroup so we do too
+    if !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(EvmEncodingError::NotInSubgroup);
+    }
+    Ok(p)
+}
+
+pub fn words_to_bytes(words: &[Word]) -> Vec<u8> {
+    words.iter().flatten().copied().collect()
+}
+
+pub fn bytes_to_words(bytes: &[u8]) -> Result<Vec<Word>, EvmEncodingError> {
+    if !bytes.len().is_multiple_of(32) {
+        return Err(EvmEncodingError::WrongLength {
+            expected: bytes.len().div_ceil(32) * 32,
+            got: bytes.len(),
+        });
+    }
+    Ok(bytes.chunks(32).map(|c| c.try_into().expect("chunks are 32 bytes")).collect())
+}
+
+pub fn encode_public_inputs(inputs: &[Fr]) -> Vec<Word> {
+    inputs.iter().map(encode_field).collect()
+}
+
+pub fn decode_public_inputs(words: &[Word]) -> Result<Vec<Fr>, EvmEncodingError> {
+    words.iter().map(|w| decode_field(w, "public input")).collect()
+}
+
+// the arguments of verifyProof(uint[2] a, uint[2][2] b, uint[2] c, uint[] input) in the
+// usual solidity groth16 verifier, flattened into words in that order
+#[cfg(feature = "groth16")]
+pub fn encode_proof_calldata(proof: &Proof<Bn254>, inputs: &[Fr]) -> Vec<Word> {
+    let mut words = Vec::new();
+    words.extend(encode_g1(&proof.a));
+    words.extend(encode_g2(&proof.b));
+    words.extend(encode_g1(&proof.c));
+    words.extend(encode_public_inputs(inputs));
+    words
+}
+
+#[cfg(feature = "groth16")]
+pub fn decode_proof_calldata(words: &[Word]) -> Result<(Proof<Bn254>, Vec<Fr>), EvmEncodingError> {
+    if words.len() < 8 {
+        return Err(EvmEncodingError::WrongLength {
+            expected: 8 * 32,
+            got: words.len() * 32,
+        });
+    }
+    let proof = Proof {
+        a: decode_g1(&[words[0], words[1]])?,
+        b: decode_g2(&[words[2], words[3], words[4], words[5]])?,
+        c: decode_g1(&[words[6], words[7]])?,
+    };
+    Ok((proof, decode_public_inputs(&words[8..])?))
+}
+
+// the input of the EIP-197 pairing precompile (address 0x08): a list of (G1, G2) pairs,
+// 192 bytes each, and it returns 1 if the product of the pairings is one
+pub fn encode_pairing_input(pairs: &[(G1Affine, G2Affine)]) -> Vec<u8> {
+    let mut words = Vec::new();
+    for (p, q) in pairs {
+        words.extend(encode_g1(p));
+        words.extend(encode_g2(q));
+    }
+    words_to_bytes(&words)
+}
+
+// what the precompile computes with that input, strict the same way: every point is
+// decoded and checked, then the product of the pairings is compared with one
+pub fn pairing_precompile(input: &[u8]) -> Result<bool, EvmEncodingError> {
+    if !input.len().is_multiple_of(192) {
+        return Err(EvmEncodingError::WrongLength {
+            expected: input.len().div_ceil(192) * 192,
+            got: input.len(),
+        });
+    }
+    let words = bytes_to_words(input)?;
+    let mut g1 = Vec::new();
+    let mut g2 = Vec::new();
+    for pair in words.chunks(6) {
+        g1.push(decode_g1(&[pair[0], pair[1]])?);
+        g2.push(decode_g2(&[pair[2], pair[3], pair[4], pair[5]])?);
+    }
+    Ok(Bn254::multi_pairing(g1, g2).is_zero())
+}
+
+// the first 4 bytes of keccak256 of the signature, e.g. "claim(uint256[2],uint256)"
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let digest = Keccak256::digest(signature.as_bytes());
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+// lots of contracts take one public input, the hash of the real ones:
+// uint256(sha256(abi.encodePacked(inputs))) % r in solidity. abi.encodePacked of uint256s
+// is just the words one after the other, so this matches it byte for byte
+pub fn hash_public_inputs(inputs: &[Fr]) -> Fr {
+    let digest = Sha256::digest(words_to_bytes(&encode_public_inputs(inputs)));
+    Fr::from_be_bytes_mod_order(&digest)
+}
+
+pub fn to_hex_word(word: &Word) -> String {
+    format!("0x{}", word.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::CurveGroup;
+    use ark_std::{UniformRand, test_rng};
+
+    use super::*;
+
+    fn word(hex: &str) -> Word {
+        let padded = format!("{hex:0>64}");
+        let mut word = [0u8; 32];
+        for (i, byte) in word.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&padded[2 * i..2 * i + 2], 16).unwrap();
+        }
+        word
+    }
+
+    const R: &str = "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+
+    #[test]
+    fn scalars_are_big_endian_words() {
+        assert_eq!(encode_field(&Fr::from(0x0102u32)), word("0102"));
+        assert_eq!(encode_field(&-Fr::from(1u32)), word("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000"));
+        let mut rng = test_rng();
+        let inputs: Vec<Fr> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        assert_eq!(decode_public_inputs(&encode_public_inputs(&inputs)).unwrap(), inputs);
+    }
+
+    #[test]
+    fn the_modulus_is_refused() {
+        assert_eq!(decode_field::<Fr>(&word(R), "public input"), Err(EvmEncodingError::NotCanonical("public input")));
+        assert_eq!(decode_field::<Fr>(&[0xff; 32], "public input"), Err(EvmEncodingError::NotCanonical("public input")));
+    }
+
+    #[test]
+    fn points_round_trip() {
+        let mut rng = test_rng();
+        for _ in 0..4 {
+            let p = G1Affine::rand(&mut rng);
+            let q = G2Affine::rand(&mut rng);
+            assert_eq!(decode_g1(&encode_g1(&p)).unwrap(), p);
+            assert_eq!(decode_g2(&encode_g2(&q)).unwrap(), q);
+        }
+        assert_eq!(encode_g1(&G1Affine::generator()), [word("1"), word("2")]);
+        assert_eq!(decode_g1(&[[0; 32]; 2]).unwrap(), G1Affine::identity());
+        assert_eq!(decode_g2(&[[0; 32]; 4]).unwrap(), G2Affine::identity());
+        assert_eq!(decode_g1(&[word("1"), word("3")]), Err(EvmEncodingError::NotOnCurve));
+    }
+
+    // the imaginary part goes first, the swap is the classic bug
+    #[test]
+    fn g2_puts_c1_first() {
+        let g = G2Affine::generator();
+        let (x, y) = g.xy().unwrap();
+        assert_eq!(encode_g2(&g), [encode_field(&x.c1), encode_field(&x.c0), encode_field(&y.c1), encode_field(&y.c0)]);
+        let mut swapped = encode_g2(&g);
+        swapped.swap(0, 1);
+        assert!(decode_g2(&swapped).is_err());
+    }
+
+    #[test]
+    fn pairing_input_checks_the_product() {
+        let mut rng = test_rng();
+        let (p, q) = (G1Affine::rand(&mut rng), G2Affine::rand(&mut rng));
+        let minus_p = (-p.into_group()).into_affine();
+        assert!(pairing_precompile(&encode_pairing_input(&[(p, q), (minus_p, q)])).unwrap());
+        assert!(!pairing_precompile(&encode_pairing_input(&[(p, q), (p, q)])).unwrap());
+        assert!(matches!(pairing_precompile(&[0; 100]), Err(EvmEncodingError::WrongLength { .. })));
+    }
+
+    #[test]
+    fn selectors_match_solidity() {
+        assert_eq!(function_selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+    }
+
+    #[cfg(feature = "groth16")]
+    #[test]
+    fn calldata_round_trips() {
+        let mut rng = test_rng();
+        let proof = Proof::<Bn254> { a: G1Affine::rand(&mut rng), b: G2Affine::rand(&mut rng), c: G1Affine::rand(&mut rng) };
+        let inputs = vec![Fr::from(17u32), Fr::from(19u32)];
+        let words = encode_proof_calldata(&proof, &inputs);
+        assert_eq!(words.len(), 10);
+        assert_eq!(decode_proof_calldata(&words).unwrap(), (proof, inputs));
+        assert!(decode_proof_calldata(&words[..7]).is_err());
+    }
+}
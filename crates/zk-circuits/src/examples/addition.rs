@@ -0,0 +1,40 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::addition::AdditionCircuit;
+
+// the addition example: prove we know x and y with x + y = z, z is public
+pub fn run<R: RngCore + CryptoRng>(rng: &mut R, x: Fr, y: Fr) -> Result<RunReport, ExampleError> {
+    // the setup only needs the shape of the circuit, not the values
+    let (keys, setup) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), rng));
+    let (pk, vk) = keys?;
+
+    let circuit = AdditionCircuit::new(x, y);
+    let z = x + y;
+    let constraints = count_constraints(&circuit)?;
+    let (proof, prove) = timed(|| Groth16::<Bls12_381>::prove(&pk, circuit, rng));
+    let proof = proof?;
+
+    let (verified, verify) = timed(|| Groth16::<Bls12_381>::verify(&vk, &[z], &proof));
+    let verified = verified?;
+    // the same proof for z + 1 has to fail
+    let wrong = Groth16::<Bls12_381>::verify(&vk, &[z + Fr::from(1u32)], &proof)?;
+
+    Ok(RunReport {
+        example: "addition".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&[z]),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
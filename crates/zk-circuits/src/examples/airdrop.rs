@@ -0,0 +1,185 @@
+use std::path::Path;
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Groth16;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use rand::{CryptoRng, Rng};
+use zk_core::report::{RunReport, timed};
+use zk_core::scenario::Scenario;
+use zk_gadgets::hasher::PoseidonHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::airdrop::{self, AirdropCircuit};
+use crate::solidity::{self, Address, AirdropContract, ClaimError};
+
+/*
+The private airdrop, start to finish. Sixteen addresses registered H(address, secret)
+before the snapshot, the airdrop commits to the tree of those, and a claimant proves
+they own one of the leaves and pays out to a fresh address nothing links to the old
+one. The claim goes through solidity::AirdropContract, the rust model of the exported
+contracts, as calldata bytes.
+The checks: the claim pays, a second claim with the same secret doesn't (not even to
+another address), a front-runner who swaps the recipient in the calldata gets a
+revert, and an address that never registered has no witness at all.
+
+BN254 because that's what the ethereum precompiles speak. With export_dir set,
+Verifier.sol, Airdrop.sol and the claim calldata are written there.
+The wrong statement in the report is the front-run.
+*/
+
+pub const MEMBERS: usize = 16;
+pub const AMOUNT: u64 = 100;
+
+fn random_address<R: Rng>(rng: &mut R) -> Address {
+    let mut address = [0u8; 20];
+    rng.fill(&mut address[..]);
+    address
+}
+
+pub fn run<R: Rng + CryptoRng>(rng: &mut R, quiet: bool, export_dir: Option<&Path>) -> Result<RunReport, ExampleError> {
+    let name = "private airdrop";
+    let mut scenario = if quiet { Scenario::quiet(name) } else { Scenario::new(name) };
+    let say = |line: String| {
+        if !quiet {
+            println!("    {line}");
+        }
+    };
+    let hasher = PoseidonHasher::<Fr>::new(2);
+    let shape = TreeShape::binary(4);
+    let airdrop_id = Fr::from(2026u32);
+
+    let (registered, tree) = scenario.step("eligible addresses register H(address, secret)", || {
+        let registered: Vec<(Fr, Fr)> = (0..MEMBERS)
+            .map(|_| (solidity::address_field(&random_address(rng)), Fr::rand(rng)))
+            .collect();
+        let leaves: Vec<Fr> = registered.iter().map(|(address, secret)| airdrop::leaf(&hasher, *address, *secret)).collect();
+        let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+        say(format!("{MEMBERS} leaves, root {}", tree.root()));
+        Ok::<_, String>((registered, tree))
+    })?;
+    let root = tree.root();
+
+    let ((pk, vk), setup) = scenario.step("setup for the claim circuit", || {
+        let (keys, setup) = timed(|| Groth16::<Bn254>::circuit_specific_setup(AirdropCircuit::empty(hasher.clone(), shape), rng));
+        keys.map(|keys| (keys, setup))
+    })?;
+    let constraints = count_constraints(&AirdropCircuit::empty(hasher.clone(), shape))?;
+    say(format!("{constraints} constraints"));
+    let mut contract = AirdropContract::new(vk.clone(), root, airdrop_id, AMOUNT);
+
+    let index = 5;
+    let (address, secret) = registered[index];
+    let recipient = random_address(rng);
+    let nullifier = airdrop::nullifier(&hasher, secret, airdrop_id);
+    let (proof, prove) = scenario.step("claimant proves membership for a fresh recipient", || {
+        say(format!("paying out to {}", solidity::address_hex(&recipient)));
+        let circuit = AirdropCircuit::new(
+            hasher.clone(),
+            airdrop_id,
+            address,
+            secret,
+            tree.path(index as u64),
+            solidity::address_field(&recipient),
+        );
+        let (proof, prove) = timed(|| Groth16::<Bn254>::prove(&pk, circuit, rng));
+        proof.map(|proof| (proof, prove))
+    })?;
+    let calldata = solidity::encode_claim_calldata(&proof, nullifier, &recipient);
+
+    if let Some(dir) = export_dir {
+        scenario.step("export the contracts and the calldata", || {
+            let verifier_sol = solidity::verifier_contract(&vk).map_err(std::io::Error::other)?;
+            std::fs::create_dir_all(dir)?;
+            std::fs::write(dir.join("Verifier.sol"), verifier_sol)?;
+            std::fs::write(dir.join("Airdrop.sol"), solidity::AIRDROP_CONTRACT)?;
+            let hex: String = calldata.iter().map(|b| format!("{b:02x}")).collect();
+            std::fs::write(dir.join("claim_calldata.txt"), format!("0x{hex}\n"))?;
+            say(format!("Verifier.sol, Airdrop.sol and claim_calldata.txt in {}", dir.display()));
+            Ok::<_, std::io::Error>(())
+        })?;
+    }
+
+    let (claimed, verify) = scenario.step("the contract checks the claim", || Ok::<_, String>(timed(|| contract.claim(&calldata))))?;
+    let verified = claimed == Ok(recipient) && contract.balance_of(&recipient) == AMOUNT;
+    scenario.check("the recipient is paid", verified)?;
+
+    let again = scenario.step("the claimant tries again, to another address", || {
+        let second = contract.claim(&calldata);
+        let elsewhere = random_address(rng);
+        let circuit = AirdropCircuit::new(
+            hasher.clone(),
+            airdrop_id,
+            address,
+            secret,
+            tree.path(index as u64),
+            solidity::address_field(&elsewhere),
+        );
+        let proof = Groth16::<Bn254>::prove(&pk, circuit, rng)?;
+        let third = contract.claim(&solidity::encode_claim_calldata(&proof, nullifier, &elsewhere));
+        Ok::<_, ark_relations::r1cs::SynthesisError>((second, third))
+    })?;
+    scenario.check(
+        "both are refused, the nullifier is spent",
+        again.0 == Err(ClaimError::AlreadyClaimed) && again.1 == Err(ClaimError::AlreadyClaimed),
+    )?;
+
+    // the front-runner sees a claim from another leaf in the mempool and resends it
+    // with their own address in the last word
+    let (other_address, other_secret) = registered[index + 1];
+    let other_nullifier = airdrop::nullifier(&hasher, other_secret, airdrop_id);
+    let front_run = scenario.step("a front-runner swaps the recipient of a pending claim", || {
+        let honest = random_address(rng);
+        let circuit = AirdropCircuit::new(
+            hasher.clone(),
+            airdrop_id,
+            other_address,
+            other_secret,
+            tree.path(index as u64 + 1),
+            solidity::address_field(&honest),
+        );
+        let proof = Groth16::<Bn254>::prove(&pk, circuit, rng)?;
+        let thief = random_address(rng);
+        let stolen = contract.claim(&solidity::encode_claim_calldata(&proof, other_nullifier, &thief));
+        let honest_claim = contract.claim(&solidity::encode_claim_calldata(&proof, other_nullifier, &honest));
+        Ok::<_, ark_relations::r1cs::SynthesisError>((stolen, honest_claim))
+    })?;
+    let rejects_wrong_statement = front_run.0 == Err(ClaimError::InvalidProof);
+    scenario.check("the swapped claim reverts", rejects_wrong_statement)?;
+    scenario.check("and the original still goes through", front_run.1.is_ok() && contract.claims() == 2)?;
+
+    let outsider = scenario.step("an address that never registered tries", || {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        // its own commitment, with a path from the tree: the root won't come out
+        let mut circuit = AirdropCircuit::new(
+            hasher.clone(),
+            airdrop_id,
+            solidity::address_field(&random_address(rng)),
+            Fr::rand(rng),
+            tree.path(0),
+            solidity::address_field(&recipient),
+        );
+        circuit.root = Some(root);
+        circuit.generate_constraints(cs.clone())?;
+        cs.is_satisfied()
+    })?;
+    scenario.check("no witness satisfies the circuit for the real root", !outsider)?;
+    scenario.finish();
+
+    let inputs = airdrop::public_inputs(root, nullifier, solidity::address_field(&recipient), airdrop_id);
+    Ok(RunReport {
+        example: "airdrop".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&inputs),
+        verified,
+        rejects_wrong_statement,
+    })
+}
@@ -0,0 +1,48 @@
+use ark_bls12_381::Bls12_381;
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+
+use super::{ExampleError, count_constraints, prove, size, to_strings};
+use crate::bitcoin::{self, Header, PowCircuit};
+use crate::matrix_cache::{CacheKey, MatrixCache};
+
+// the proof of work example: a secret header hashing below the target of its own bits
+// field. The wrong statement is a target equal to the block's hash, which it isn't below
+pub fn run<R: RngCore + CryptoRng>(rng: &mut R, header: &Header) -> Result<RunReport, ExampleError> {
+    run_cached(rng, header, None)
+}
+
+// the same, proving from cached constraint matrices when there is a cache
+pub fn run_cached<R: RngCore + CryptoRng>(rng: &mut R, header: &Header, cache: Option<&MatrixCache>) -> Result<RunReport, ExampleError> {
+    let target = bitcoin::target_from_bits(bitcoin::header_bits(header)).expect("the header's bits field is a valid target");
+    let (keys, setup) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(PowCircuit::empty(), rng));
+    let (pk, vk) = keys?;
+
+    let constraints = count_constraints(&PowCircuit::empty())?;
+    let cache = cache.map(|c| (c, CacheKey::of::<PowCircuit>("")));
+    let (proof, prove) = timed(|| prove(&pk, PowCircuit::new(*header, target), cache, rng));
+    let proof = proof?;
+    let (verified, verify) = timed(|| Groth16::<Bls12_381>::verify(&vk, &[target], &proof));
+    let verified = verified?;
+
+    let rejects_wrong_statement = match bitcoin::hash_value(&bitcoin::block_hash(header)) {
+        Some(hash) => !Groth16::<Bls12_381>::verify(&vk, &[hash], &proof)?,
+        None => true,
+    };
+
+    Ok(RunReport {
+        example: "bitcoin_pow".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&[target]),
+        verified,
+        rejects_wrong_statement,
+    })
+}
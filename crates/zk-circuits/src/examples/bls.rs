@@ -0,0 +1,51 @@
+use ark_bls12_377::Config as Bls12_377Config;
+use ark_bw6_761::BW6_761;
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+use zk_protocols::bls::{self, SecretKey};
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::bls_circuit::{self, BlsVerifyCircuit};
+
+// part 2 of the bls example: a BLS12-377 signature check proven with Groth16 over
+// BW6-761. Part 1 (plain aggregate signatures) has no snark in it and stays in the binary
+pub fn run<R: RngCore + CryptoRng>(rng: &mut R, message: &[u8]) -> Result<RunReport, ExampleError> {
+    let sk = SecretKey::<Bls12_377Config>::random(rng);
+    let pk = sk.public_key();
+    let sig = sk.sign(message);
+    let h = bls::hash_to_g1::<Bls12_377Config>(message);
+
+    let circuit = BlsVerifyCircuit {
+        public_key: Some(pk.0),
+        message_hash: Some(h),
+        signature: Some(sig.0),
+    };
+    let constraints = count_constraints(&circuit)?;
+    let (keys, setup) = timed(|| Groth16::<BW6_761>::circuit_specific_setup(BlsVerifyCircuit::empty(), rng));
+    let (pk_groth, vk_groth) = keys?;
+    let (proof, prove) = timed(|| Groth16::<BW6_761>::prove(&pk_groth, circuit, rng));
+    let proof = proof?;
+
+    let inputs = bls_circuit::public_inputs(&pk.0, &h);
+    let (verified, verify) = timed(|| Groth16::<BW6_761>::verify(&vk_groth, &inputs, &proof));
+    let verified = verified?;
+    // the same proof for another message has to fail
+    let other = bls::hash_to_g1::<Bls12_377Config>(&[message, b"!"].concat());
+    let wrong = Groth16::<BW6_761>::verify(&vk_groth, &bls_circuit::public_inputs(&pk.0, &other), &proof)?;
+
+    Ok(RunReport {
+        example: "bls".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk_groth),
+        verifying_key_bytes: size(&vk_groth),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&inputs),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
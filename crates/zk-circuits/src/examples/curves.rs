@@ -0,0 +1,48 @@
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+use zk_gadgets::hasher::PoseidonHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::curves::Curve;
+use crate::fixtures::{self, SEED};
+use crate::membership::MembershipCircuit;
+
+// the poseidon merkle membership example with the curve as the type parameter, so the
+// same statement (same tree shape, same hash, parameters generated for each field)
+// can be compared across curves. The wrong statement is the root of another tree
+pub fn run<E: Curve, R: RngCore + CryptoRng>(rng: &mut R, shape: TreeShape) -> Result<RunReport, ExampleError> {
+    let hasher = PoseidonHasher::<E::ScalarField>::new(2);
+    let (keys, setup) = timed(|| Groth16::<E>::circuit_specific_setup(MembershipCircuit::empty(hasher.clone(), shape), rng));
+    let (pk, vk) = keys?;
+
+    let members = shape.leaves().min(100);
+    let leaves: Vec<E::ScalarField> = fixtures::student_leaves(&hasher, &fixtures::students(SEED)[..members]);
+    let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+    let index = members / 2;
+    let circuit = MembershipCircuit::new(hasher, leaves[index], tree.path(index as u64));
+    let constraints = count_constraints(&circuit)?;
+    let (proof, prove) = timed(|| Groth16::<E>::prove(&pk, circuit, rng));
+    let proof = proof?;
+
+    let root = tree.root();
+    let (verified, verify) = timed(|| Groth16::<E>::verify(&vk, &[root], &proof));
+    let verified = verified?;
+    let wrong = Groth16::<E>::verify(&vk, &[root + E::ScalarField::from(1u32)], &proof)?;
+
+    Ok(RunReport {
+        example: format!("curve-{}", E::NAME.to_lowercase()),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&[root]),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
@@ -0,0 +1,134 @@
+use ark_bls12_381::Fr;
+use ark_ff::Zero;
+use rand::{CryptoRng, Rng};
+use zk_core::report::{RunReport, timed};
+use zk_core::scenario::Scenario;
+use zk_gadgets::poseidon::poseidon_config;
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::dkg::{DkgParams, Party, PartyId};
+use crate::vss::{self, VssCircuit};
+
+/*
+The dkg example, 5 parties with threshold 3 and two of them misbehaving:
+  - party 3 is a cheating dealer, it hands party 1 a share that is off by one
+  - party 4 is a troublemaker, it complains about party 2 even though party 2 is honest
+This runs through the scenario runner, so it prints every round as it goes (unless
+quiet is set, then it prints nothing). The prove and verify times are for one share
+proof, measured after the protocol is over. The public inputs in the report are the commitments broadcast in round 1, and the wrong
+statement that has to be refused is party 3's bad share.
+*/
+
+pub const PARTIES: u64 = 5;
+pub const THRESHOLD: usize = 3;
+
+pub fn run<R: Rng + CryptoRng>(rng: &mut R, quiet: bool) -> Result<RunReport, ExampleError> {
+    let name = "pedersen style dkg with complaint proofs";
+    let mut scenario = if quiet { Scenario::quiet(name) } else { Scenario::new(name) };
+    let say = |line: String| {
+        if !quiet {
+            println!("    {line}");
+        }
+    };
+
+    let params = scenario.step("setup of the single share vss circuit", || {
+        Ok::<_, String>(DkgParams::setup(PARTIES, THRESHOLD, poseidon_config::<Fr>(2), rng))
+    })?;
+    let constraints = count_constraints(&VssCircuit::empty(THRESHOLD, 1, params.poseidon.clone()))?;
+
+    let mut parties: Vec<Party> = (1..=PARTIES).map(Party::new).collect();
+    parties[2].cheats_against = Some(1);
+
+    // round 1: everybody deals and broadcasts a commitment to their polynomial
+    let commitments: Vec<Fr> = scenario.step("round 1: every party deals", || {
+        Ok::<_, String>(parties.iter_mut().map(|p| p.deal(&params, rng)).collect())
+    })?;
+
+    // round 2: private delivery of the shares, everybody checks what they got
+    let mut complaints: Vec<(PartyId, PartyId)> = Vec::new();
+    scenario.step("round 2: shares are sent and checked", || {
+        for dealer in 0..parties.len() {
+            for recipient in 1..=PARTIES {
+                let sent = parties[dealer].share_for(&params, recipient, rng);
+                let dealer_id = parties[dealer].id;
+                match sent {
+                    Some(share) if params.verify_share(commitments[dealer], &share) => {
+                        parties[recipient as usize - 1].received.push(share);
+                    }
+                    _ => {
+                        say(format!("party {recipient} got a bad share from party {dealer_id} and complains"));
+                        complaints.push((recipient, dealer_id));
+                    }
+                }
+            }
+        }
+        // party 4 lies about party 2
+        say("party 4 complains about party 2 (without a reason)".to_string());
+        complaints.push((4, 2));
+        Ok::<_, String>(())
+    })?;
+
+    // round 3: every accused dealer has to broadcast the disputed share with a proof
+    let mut disqualified: Vec<PartyId> = Vec::new();
+    scenario.step("round 3: accused dealers answer with proofs", || {
+        for &(complainer, accused) in &complaints {
+            let dealer = &parties[accused as usize - 1];
+            let response = dealer.share_for(&params, complainer, rng);
+            match response {
+                Some(share) if params.verify_share(commitments[accused as usize - 1], &share) => {
+                    say(format!("party {accused} proved the share for party {complainer}, complaint dismissed"));
+                    let recipient = &mut parties[complainer as usize - 1];
+                    if !recipient.received.iter().any(|s| s.dealer == accused) {
+                        recipient.received.push(share);
+                    }
+                }
+                _ => {
+                    say(format!("party {accused} could not prove the share for party {complainer}, disqualified"));
+                    disqualified.push(accused);
+                }
+            }
+        }
+        Ok::<_, String>(())
+    })?;
+
+    let qualified: Vec<PartyId> = (1..=PARTIES).filter(|id| !disqualified.contains(id)).collect();
+    scenario.check("party 3 was disqualified", disqualified == vec![3])?;
+    scenario.check("party 2 is still in", qualified.contains(&2))?;
+
+    // the result: everybody holds a share of the sum of the qualified secrets
+    scenario.step("final shares and a reconstruction check", || {
+        let group_secret = qualified
+            .iter()
+            .map(|&id| parties[id as usize - 1].secret().expect("every party dealt"))
+            .fold(Fr::zero(), |acc, s| acc + s);
+        let final_shares: Vec<_> = parties.iter().map(|p| p.final_share(&qualified)).collect();
+        say(format!("qualified dealers: {:?}", qualified));
+        // any threshold of them recover the group secret (nobody in the protocol ever
+        // computes this, we only do it here to check the simulation)
+        let ok = vss::reconstruct(&final_shares[..THRESHOLD]) == group_secret
+            && vss::reconstruct(&final_shares[PARTIES as usize - THRESHOLD..]) == group_secret;
+        if ok { Ok(()) } else { Err("final shares don't reconstruct the group secret") }
+    })?;
+
+    let steps = scenario.finish();
+
+    // one more honest share proof from party 1 to party 2, just to time it
+    let (share, prove) = timed(|| parties[0].share_for(&params, 2, rng));
+    let share = share.expect("party 1 is honest");
+    let (share_ok, verify) = timed(|| params.verify_share(commitments[0], &share));
+
+    // every failure above returns early, so getting here means both checks held
+    Ok(RunReport {
+        example: "dkg".to_string(),
+        constraints,
+        proving_key_bytes: size(&params.pk),
+        verifying_key_bytes: size(&params.vk),
+        proof_bytes: size(&share.proof),
+        setup: steps[0].duration,
+        prove,
+        verify,
+        public_inputs: to_strings(&commitments),
+        verified: share_ok,
+        rejects_wrong_statement: true,
+    })
+}
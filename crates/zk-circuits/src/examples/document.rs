@@ -0,0 +1,89 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+use zk_gadgets::poseidon::poseidon_config;
+
+use super::{ExampleError, count_constraints, prove, size, to_strings};
+use crate::document::{self, DocumentCircuit, DocumentLayout};
+use crate::matrix_cache::{CacheKey, MatrixCache};
+
+// a toy certificate, padded to 64 bytes so it is exactly one blake2s block. The issue
+// date is the field that gets disclosed
+pub const SAMPLE_DOCUMENT: &[u8; 64] = b"holder:Ada Lovelace;course:Analytical engines;issued:1843-10-05;";
+
+pub fn sample_layout() -> DocumentLayout {
+    DocumentLayout {
+        document_len: SAMPLE_DOCUMENT.len(),
+        field_offset: 53,
+        field_len: 10,
+    }
+}
+
+// the document example: disclose one field of a committed document and nothing else.
+// the document has to be layout.document_len bytes long
+pub fn run<R: RngCore + CryptoRng>(rng: &mut R, document: &[u8], layout: DocumentLayout) -> Result<RunReport, ExampleError> {
+    run_cached(rng, document, layout, None)
+}
+
+// the same, proving from cached constraint matrices when there is a cache. The layout
+// is the shape of the circuit, so it's the cache key's parameter
+pub fn run_cached<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    document: &[u8],
+    layout: DocumentLayout,
+    cache: Option<&MatrixCache>,
+) -> Result<RunReport, ExampleError> {
+    let poseidon = poseidon_config::<Fr>(2);
+
+    // the shape of the circuit only depends on the layout, not on the document
+    let empty = DocumentCircuit {
+        layout,
+        poseidon: poseidon.clone(),
+        document: None,
+        commitment: None,
+        field_value: None,
+    };
+    let (keys, setup) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(empty, rng));
+    let (pk, vk) = keys?;
+
+    // the commitment is what the holder published earlier
+    let field = &document[layout.field_offset..layout.field_offset + layout.field_len];
+    let commitment = document::commit(&poseidon, document);
+    let disclosed = document::field_value(field);
+    let circuit = DocumentCircuit {
+        layout,
+        poseidon,
+        document: Some(document.to_vec()),
+        commitment: Some(commitment),
+        field_value: Some(disclosed),
+    };
+    let constraints = count_constraints(&circuit)?;
+    let cache = cache.map(|c| (c, CacheKey::of::<DocumentCircuit>(format!("{layout:?}"))));
+    let (proof, prove) = timed(|| prove(&pk, circuit, cache, rng));
+    let proof = proof?;
+
+    let (verified, verify) = timed(|| Groth16::<Bls12_381>::verify(&vk, &[commitment, disclosed], &proof));
+    let verified = verified?;
+    // claim a field that differs in the last byte (1843-10-05 -> 1843-10-06)
+    let mut other = field.to_vec();
+    if let Some(last) = other.last_mut() {
+        *last = last.wrapping_add(1);
+    }
+    let wrong = Groth16::<Bls12_381>::verify(&vk, &[commitment, document::field_value(&other)], &proof)?;
+
+    Ok(RunReport {
+        example: "document_hash".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&[commitment, disclosed]),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
@@ -0,0 +1,47 @@
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::addition::AdditionCircuit;
+use crate::evm;
+
+// the evm example: an addition proof on BN254, sent through the calldata encoding
+// and verified from what comes out the other side
+pub fn run<R: RngCore + CryptoRng>(rng: &mut R, x: Fr, y: Fr) -> Result<RunReport, ExampleError> {
+    let (keys, setup) = timed(|| Groth16::<Bn254>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), rng));
+    let (pk, vk) = keys?;
+    let circuit = AdditionCircuit::new(x, y);
+    let z = x + y;
+    let constraints = count_constraints(&circuit)?;
+    let (proof, prove) = timed(|| Groth16::<Bn254>::prove(&pk, circuit, rng));
+    let proof = proof?;
+
+    // bytes -> words -> proof, like a contract would receive it
+    let bytes = evm::words_to_bytes(&evm::encode_proof_calldata(&proof, &[z]));
+    let (decoded, inputs) = evm::decode_proof_calldata(&evm::bytes_to_words(&bytes)?)?;
+    let (verified, verify) = timed(|| Groth16::<Bn254>::verify(&vk, &inputs, &decoded));
+    let verified = decoded == proof && verified?;
+
+    // calldata claiming z + 1 must not verify
+    let mut words = evm::encode_proof_calldata(&proof, &[z]);
+    words[8] = evm::encode_field(&(z + Fr::from(1u32)));
+    let (decoded, inputs) = evm::decode_proof_calldata(&words)?;
+    let wrong = Groth16::<Bn254>::verify(&vk, &inputs, &decoded)?;
+
+    Ok(RunReport {
+        example: "evm_encoding".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&[z]),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
@@ -0,0 +1,58 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+use zk_gadgets::hasher::CircuitHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::fixtures::{self, SEED};
+use crate::membership::MembershipCircuit;
+
+// the merkle membership example with the hash as a type parameter and the shape of
+// the tree picked at setup: a tree of the first `members` students of fixtures.rs, a
+// proof that one of them is in it, and the same proof checked against the root of a
+// tree with one leaf changed
+pub fn run<H: CircuitHasher<Fr>, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    hasher: H,
+    shape: TreeShape,
+    members: usize,
+) -> Result<RunReport, ExampleError> {
+    let (keys, setup) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(MembershipCircuit::empty(hasher.clone(), shape), rng));
+    let (pk, vk) = keys?;
+
+    let mut leaves: Vec<Fr> = fixtures::student_leaves(&hasher, &fixtures::students(SEED)[..members]);
+    let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+    let index = members / 2;
+    let circuit = MembershipCircuit::new(hasher.clone(), leaves[index], tree.path(index as u64));
+    let constraints = count_constraints(&circuit)?;
+    let (proof, prove) = timed(|| Groth16::<Bls12_381>::prove(&pk, circuit, rng));
+    let proof = proof?;
+
+    let root = tree.root();
+    let (verified, verify) = timed(|| Groth16::<Bls12_381>::verify(&vk, &[root], &proof));
+    let verified = verified?;
+    // somebody else's leaf changes, the old proof is no good for the new root
+    leaves[0] += Fr::from(1u32);
+    let other_root = MerkleTree::new(hasher, shape, &leaves).root();
+    let wrong = Groth16::<Bls12_381>::verify(&vk, &[other_root], &proof)?;
+
+    Ok(RunReport {
+        example: match shape.arity {
+            2 => format!("merkle-{}", H::NAME),
+            arity => format!("merkle-{}-{arity}ary", H::NAME),
+        },
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&[root]),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError, SynthesisMode};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::{CryptoRng, RngCore};
+use zk_core::scenario::ScenarioError;
+
+#[cfg(feature = "evm")]
+use crate::evm::EvmEncodingError;
+use crate::matrix_cache::{self, CacheError, CacheKey, MatrixCache};
+
+/*
+The flow of every example binary (setup, prove, verify, and the check that a wrong
+statement is refused) as a plain function returning a RunReport. The binaries in
+src/bin are thin wrappers that print the story around these, so the cli, benchmarks
+or anything else can run the same flows without copying them out of a main().
+*/
+
+pub mod addition;
+#[cfg(feature = "evm")]
+pub mod airdrop;
+pub mod bitcoin;
+#[cfg(feature = "bls")]
+pub mod bls;
+pub mod curves;
+pub mod dkg;
+pub mod document;
+#[cfg(feature = "evm")]
+pub mod evm;
+pub mod merkle;
+pub mod passport;
+pub mod vss;
+
+#[derive(Debug)]
+pub enum ExampleError {
+    Synthesis(SynthesisError),
+    Scenario(ScenarioError),
+    Cache(CacheError),
+    #[cfg(feature = "evm")]
+    Encoding(EvmEncodingError),
+}
+
+impl fmt::Display for ExampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExampleError::Synthesis(e) => write!(f, "synthesis error: {e}"),
+            ExampleError::Scenario(e) => write!(f, "{e}"),
+            ExampleError::Cache(e) => write!(f, "{e}"),
+            #[cfg(feature = "evm")]
+            ExampleError::Encoding(e) => write!(f, "encoding error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExampleError {}
+
+impl From<SynthesisError> for ExampleError {
+    fn from(e: SynthesisError) -> Self {
+        ExampleError::Synthesis(e)
+    }
+}
+
+impl From<ScenarioError> for ExampleError {
+    fn from(e: ScenarioError) -> Self {
+        ExampleError::Scenario(e)
+    }
+}
+
+impl From<CacheError> for ExampleError {
+    fn from(e: CacheError) -> Self {
+        ExampleError::Cache(e)
+    }
+}
+
+#[cfg(feature = "evm")]
+impl From<EvmEncodingError> for ExampleError {
+    fn from(e: EvmEncodingError) -> Self {
+        ExampleError::Encoding(e)
+    }
+}
+
+// synthesizes a copy of the circuit on its own, just to count the constraints. Setup
+// mode, so this works for empty circuits too (nothing gets assigned).
+// With the trace feature and RUST_LOG=trace this is where the x-ray happens (trace.rs)
+pub fn count_constraints<F: PrimeField, C: ConstraintSynthesizer<F> + Clone>(circuit: &C) -> Result<usize, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    #[cfg(feature = "trace")]
+    let _watch = crate::trace::watch(std::any::type_name::<C>(), &cs);
+    circuit.clone().generate_constraints(cs.clone())?;
+    Ok(cs.num_constraints())
+}
+
+// a groth16 proof, with the matrices from the cache when there is one (matrix_cache.rs)
+pub(crate) fn prove<E: Pairing, C: ConstraintSynthesizer<E::ScalarField> + Clone, R: RngCore + CryptoRng>(
+    pk: &ProvingKey<E>,
+    circuit: C,
+    cache: Option<(&MatrixCache, CacheKey)>,
+    rng: &mut R,
+) -> Result<Proof<E>, ExampleError> {
+    match cache {
+        Some((cache, key)) => {
+            let (matrices, _) = cache.matrices(&key, circuit.clone())?;
+            Ok(matrix_cache::prove(pk, &matrices, circuit, rng)?)
+        }
+        None => Ok(Groth16::<E>::prove(pk, circuit, rng)?),
+    }
+}
+
+pub(crate) fn to_strings<F: PrimeField>(inputs: &[F]) -> Vec<String> {
+    inputs.iter().map(|x| x.to_string()).collect()
+}
+
+// bytes of the compressed encoding, what a key or proof file would take
+pub(crate) fn size<T: CanonicalSerialize>(t: &T) -> usize {
+    t.compressed_size()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use rand::thread_rng;
+    use zk_gadgets::hasher::{MimcHasher, PoseidonHasher};
+    use zk_gadgets::merkle::TreeShape;
+
+    use super::*;
+
+    #[test]
+    fn addition_proves_and_refuses_z_plus_one() {
+        let report = addition::run(&mut thread_rng(), Fr::from(17u32), Fr::from(2u32)).expect("the example runs");
+        assert!(report.passed());
+        assert_eq!(report.example, "addition");
+        assert_eq!(report.public_inputs, ["19"]);
+        assert_eq!(report.proof_bytes, 192);
+    }
+
+    #[test]
+    fn vss_refuses_a_tampered_share() {
+        let report = vss::run(&mut thread_rng(), Fr::from(42u32), 3, 5).expect("the example runs");
+        assert!(report.passed());
+        // the commitment and an (x, y) per proven share
+        assert_eq!(report.public_inputs.len(), 1 + 2 * 3);
+    }
+
+    #[test]
+    fn merkle_runs_with_either_hash() {
+        let mut rng = thread_rng();
+        let poseidon = merkle::run(&mut rng, PoseidonHasher::<Fr>::new(2), TreeShape::binary(3), 5).expect("the example runs");
+        let mimc = merkle::run(&mut rng, MimcHasher::<Fr>::default(), TreeShape::binary(3), 5).expect("the example runs");
+        assert!(poseidon.passed() && mimc.passed());
+        assert_ne!(poseidon.constraints, mimc.constraints);
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn evm_proves_over_bn254() {
+        use ark_bn254::Fr;
+        let report = evm::run(&mut thread_rng(), Fr::from(17u32), Fr::from(2u32)).expect("the example runs");
+        assert!(report.passed());
+        assert_eq!(report.public_inputs, ["19"]);
+    }
+}
@@ -0,0 +1,170 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, prepare_verifying_key};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_snark::SNARK;
+use rand::{CryptoRng, Rng};
+use zk_core::artifact;
+use zk_core::report::{RunReport, timed};
+use zk_core::scenario::Scenario;
+use zk_gadgets::poseidon::poseidon_config;
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::credential::{self, AgeCircuit, Attributes, Credential, IssuerKey, Presentation};
+use crate::curves::Curve;
+
+/*
+The passport age check, start to finish: an issuer signs a credential, the holder
+keeps it in a file, and a verifier learns "18 or older" and nothing else.
+The checks along the way are the scenario's own test: the presentation verifies, it
+doesn't pass for 21, a 16 year old has no witness that satisfies the circuit and a
+holder who edits their birth date loses the issuer's signature.
+The date is fixed so the output doesn't change from one year to the next.
+
+Runs through the scenario runner like the dkg, quiet prints nothing. The public inputs
+in the report are the commitment and the cutoff, the wrong statement is "at least 21".
+*/
+
+pub const AS_OF: u32 = 20261014;
+pub const MIN_AGE: u32 = 18;
+
+pub fn holder() -> Attributes {
+    Attributes {
+        name: "Grace Hopper".to_string(),
+        nationality: "USA".to_string(),
+        birth_date: 20070502,
+    }
+}
+
+pub fn run<R: Rng + CryptoRng>(rng: &mut R, quiet: bool) -> Result<RunReport, ExampleError> {
+    let name = "passport age check";
+    let mut scenario = if quiet { Scenario::quiet(name) } else { Scenario::new(name) };
+    let say = |line: String| {
+        if !quiet {
+            println!("    {line}");
+        }
+    };
+    let poseidon = poseidon_config::<Fr>(2);
+    let issuer = IssuerKey::random(rng);
+    let issuer_pk = issuer.public_key();
+
+    let issued = scenario.step("issuer checks the passport and signs a credential", || {
+        let attributes = holder();
+        say(format!("{}, {}, born {}", attributes.name, attributes.nationality, credential::format_date(attributes.birth_date)));
+        Ok::<_, String>(credential::issue(&issuer, &poseidon, attributes, rng))
+    })?;
+    let commitment = issued.commitment(&poseidon);
+    scenario.check(
+        "the signature on the commitment verifies",
+        credential::verify_signature(&issuer_pk, commitment, &issued.signature()),
+    )?;
+
+    // the holder's wallet is a file, here a temporary one
+    let stored = scenario.step("holder stores the credential and loads it back", || {
+        let path = std::env::temp_dir().join(format!("zk-toy-credential-{}.bin", std::process::id()));
+        artifact::save(&issued, &crate::build_info(Bls12_381::NAME), &path)?;
+        let loaded: Result<Credential, _> = artifact::load(&path);
+        let _ = std::fs::remove_file(&path);
+        loaded
+    })?;
+    scenario.check("the stored credential opens to the same commitment", stored.commitment(&poseidon) == commitment)?;
+
+    let ((pk, vk), setup) = scenario.step("verifier sets up the age circuit", || {
+        let (keys, setup) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(AgeCircuit::empty(poseidon.clone()), rng));
+        keys.map(|keys| (keys, setup))
+    })?;
+    let constraints = count_constraints(&AgeCircuit::empty(poseidon.clone()))?;
+    say(format!("{constraints} constraints"));
+
+    let cutoff = credential::cutoff(AS_OF, MIN_AGE);
+    let (presentation, prove) = scenario.step("holder proves they are at least 18", || {
+        say(format!("as of {}: born on or before {}", credential::format_date(AS_OF), credential::format_date(cutoff)));
+        let circuit = AgeCircuit::new(poseidon.clone(), &stored, cutoff);
+        let (proof, prove) = timed(|| Groth16::<Bls12_381>::prove(&pk, circuit, rng));
+        proof.map(|proof| {
+            let presentation = Presentation {
+                commitment,
+                signature: stored.signature,
+                proof,
+            };
+            (presentation, prove)
+        })
+    })?;
+
+    let pvk = prepare_verifying_key(&vk);
+    let (verified, verify) = scenario.step("verifier checks the signature and the proof", || {
+        Ok::<_, String>(timed(|| credential::verify_presentation(&pvk, &issuer_pk, &presentation, cutoff)))
+    })?;
+    scenario.check("the holder is at least 18", verified)?;
+    let older = credential::cutoff(AS_OF, 21);
+    let wrong = credential::verify_presentation(&pvk, &issuer_pk, &presentation, older);
+    scenario.check("the same proof doesn't pass for at least 21", !wrong)?;
+
+    let minor = scenario.step("a 16 year old tries with their own credential", || {
+        let mut attributes = holder();
+        attributes.name = "Jamie".to_string();
+        attributes.birth_date = 20100601;
+        let minor = credential::issue(&issuer, &poseidon, attributes, rng);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        AgeCircuit::new(poseidon.clone(), &minor, cutoff).generate_constraints(cs.clone())?;
+        cs.is_satisfied()
+    })?;
+    scenario.check("no witness satisfies the circuit for them", !minor)?;
+
+    let forged = scenario.step("the holder edits the birth date in their file", || {
+        let mut forged = stored.clone();
+        forged.attributes.birth_date = 19700101;
+        Ok::<_, String>(credential::verify_signature(&issuer_pk, forged.commitment(&poseidon), &forged.signature()))
+    })?;
+    scenario.check("the issuer's signature doesn't cover the edited credential", !forged)?;
+    scenario.finish();
+
+    Ok(RunReport {
+        example: "passport".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&presentation.proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&credential::public_inputs(commitment, cutoff)),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+
+    fn satisfied(birth_date: u32) -> bool {
+        let mut rng = thread_rng();
+        let poseidon = poseidon_config::<Fr>(2);
+        let issuer = IssuerKey::random(&mut rng);
+        let mut attributes = holder();
+        attributes.birth_date = birth_date;
+        let issued = credential::issue(&issuer, &poseidon, attributes, &mut rng);
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        AgeCircuit::new(poseidon, &issued, credential::cutoff(AS_OF, MIN_AGE))
+            .generate_constraints(cs.clone())
+            .expect("the circuit synthesizes");
+        cs.is_satisfied().expect("the constraints evaluate")
+    }
+
+    #[test]
+    fn the_scenario_passes() {
+        let report = run(&mut thread_rng(), true).expect("every step and check passes");
+        assert!(report.passed());
+        let cutoff = credential::cutoff(AS_OF, MIN_AGE);
+        assert_eq!(report.public_inputs[1], cutoff.to_string());
+    }
+
+    #[test]
+    fn eighteen_today_is_old_enough_and_a_day_less_is_not() {
+        assert_eq!(credential::cutoff(AS_OF, MIN_AGE), 20081014);
+        assert!(satisfied(20081014));
+        assert!(!satisfied(20081015));
+    }
+}
@@ -0,0 +1,57 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use rand::{CryptoRng, RngCore};
+use zk_core::report::{RunReport, timed};
+use zk_gadgets::poseidon::poseidon_config;
+
+use super::{ExampleError, count_constraints, size, to_strings};
+use crate::vss::{self, Polynomial, VssCircuit};
+
+// the vss example: share the secret threshold-of-parties and prove the first
+// `threshold` shares lie on the committed polynomial. The public inputs in the
+// report are [commitment, x1, y1, x2, y2, ...]
+pub fn run<R: RngCore + CryptoRng>(rng: &mut R, secret: Fr, threshold: usize, parties: u64) -> Result<RunReport, ExampleError> {
+    let poseidon = poseidon_config::<Fr>(2);
+
+    // setup depends only on the threshold and on how many shares we prove about
+    let empty = VssCircuit::empty(threshold, threshold, poseidon.clone());
+    let (keys, setup) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(empty, rng));
+    let (pk, vk) = keys?;
+
+    let polynomial = Polynomial::random(secret, threshold, rng);
+    let blinding = Fr::rand(rng);
+    let commitment = vss::commit(&poseidon, &polynomial, blinding);
+    let shares = vss::deal(&polynomial, parties);
+    // sanity check: the last `threshold` shares give back the secret
+    assert_eq!(vss::reconstruct(&shares[shares.len() - threshold..]), secret);
+
+    let proven = shares[..threshold].to_vec();
+    let circuit = VssCircuit::new(poseidon, polynomial, blinding, proven.clone());
+    let constraints = count_constraints(&circuit)?;
+    let (proof, prove) = timed(|| Groth16::<Bls12_381>::prove(&pk, circuit, rng));
+    let proof = proof?;
+
+    let inputs = vss::public_inputs(commitment, &proven);
+    let (verified, verify) = timed(|| Groth16::<Bls12_381>::verify(&vk, &inputs, &proof));
+    let verified = verified?;
+    // a dealer who hands somebody a bad share can't get it past the verifier
+    let mut tampered = proven;
+    tampered[threshold - 1].y += Fr::from(1u32);
+    let wrong = Groth16::<Bls12_381>::verify(&vk, &vss::public_inputs(commitment, &tampered), &proof)?;
+
+    Ok(RunReport {
+        example: "vss".to_string(),
+        constraints,
+        proving_key_bytes: size(&pk),
+        verifying_key_bytes: size(&vk),
+        proof_bytes: size(&proof),
+        setup,
+        prove,
+        verify,
+        public_inputs: to_strings(&inputs),
+        verified,
+        rejects_wrong_statement: !wrong,
+    })
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use ark_ec::AffineRepr;
+use ark_ff::{PrimeField, Zero};
+use ark_groth16::{Groth16, VerifyingKey, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use zk_core::spec::InputSpec;
+
+use crate::Groth16Artifact;
+use crate::curves::Curve;
+use crate::verifier::verify_constant_work;
+
+/*
+A saved proof taken apart for `zk-toy explain`: what it claims, what it is made of,
+and the check a verifier does with it, one step at a time.
+
+A Groth16 proof is three points, A and C in G1 and B in G2, and the verifier checks
+
+  e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta)
+
+where alpha, beta, gamma and delta come from the verifying key and L is the public
+inputs folded into one G1 point, gamma_abc[0] + x_1 gamma_abc[1] + ... with the
+gamma_abc points of the key. The explanation computes both sides (and the verifier
+the cli uses, which has to agree) and reports the points' group membership on the
+way: a compressed point decodes to a point on the curve or not at all, but it can
+still be outside the prime order subgroup, which a checked load refuses (see
+artifact.rs, src/bin/validation.rs). The artifact is explained as read unchecked so
+that such a point is shown rather than an error.
+
+The names of the inputs come from the statement's InputSpec, without a verifying key
+the explanation stops before the equation.
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointCheck {
+    pub name: &'static str,
+    pub group: &'static str,
+    pub identity: bool,
+    pub in_subgroup: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verification {
+    NoKey,
+    // the key expects another number of public inputs, the pairings don't even start
+    InputCount { expected: usize, found: usize },
+    Equation { holds: bool, verifier: bool },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub statement: String,
+    pub curve: &'static str,
+    pub proof_bytes: usize,
+    // name and canonical decimal value
+    pub inputs: Vec<(String, String)>,
+    pub points: Vec<PointCheck>,
+    pub verification: Verification,
+}
+
+impl Explanation {
+    // what a checked load and the verifier would say together
+    pub fn valid(&self) -> bool {
+        self.points.iter().all(|p| p.in_subgroup) && matches!(self.verification, Verification::Equation { holds: true, verifier: true })
+    }
+}
+
+fn check<A: AffineRepr>(name: &'static str, group: &'static str, point: &A) -> PointCheck {
+    PointCheck {
+        name,
+        group,
+        identity: point.is_zero(),
+        in_subgroup: point.mul_bigint(A::ScalarField::MODULUS).is_zero(),
+    }
+}
+
+pub fn explain<E: Curve>(spec: &InputSpec, artifact: &Groth16Artifact<E>, vk: Option<&VerifyingKey<E>>) -> Explanation {
+    let proof = &artifact.proof;
+    let inputs = artifact
+        .public_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (spec.name(i), x.into_bigint().to_string()))
+        .collect();
+    let points = vec![check("A", "G1", &proof.a), check("B", "G2", &proof.b), check("C", "G1", &proof.c)];
+    let verification = match vk {
+        None => Verification::NoKey,
+        Some(vk) if vk.gamma_abc_g1.len() != artifact.public_inputs.len() + 1 => Verification::InputCount {
+            expected: vk.gamma_abc_g1.len().saturating_sub(1),
+            found: artifact.public_inputs.len(),
+        },
+        Some(vk) => {
+            let pvk = prepare_verifying_key(vk);
+            let folded = Groth16::<E>::prepare_inputs(&pvk, &artifact.public_inputs).expect("the input count was checked");
+            let left = E::pairing(proof.a, proof.b);
+            let right = E::pairing(vk.alpha_g1, vk.beta_g2) + E::pairing(folded, vk.gamma_g2) + E::pairing(proof.c, vk.delta_g2);
+            Verification::Equation {
+                holds: left == right,
+                verifier: verify_constant_work(&pvk, &artifact.public_inputs, proof),
+            }
+        }
+    };
+    Explanation {
+        statement: spec.statement.clone(),
+        curve: E::NAME,
+        proof_bytes: proof.compressed_size(),
+        inputs,
+        points,
+        verification,
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "=== A proof of {} ===", self.statement)?;
+        writeln!(f, "groth16 on {}, {} bytes: A and C in G1, B in G2", self.curve, self.proof_bytes)?;
+
+        writeln!(f, "\npublic inputs, all the verifier learns")?;
+        if self.inputs.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for (name, value) in &self.inputs {
+            writeln!(f, "  {name} = {value}")?;
+        }
+
+        writeln!(f, "\nproof elements")?;
+        for p in &self.points {
+            let verdict = match (p.in_subgroup, p.identity) {
+                (false, _) => "✗ not in the prime order subgroup, a checked load refuses it",
+                (true, true) => "  the point at infinity, in the group but never what an honest prover sends",
+                (true, false) => "✓ on the curve and in the prime order subgroup",
+            };
+            writeln!(f, "  {}  {}  {verdict}", p.name, p.group)?;
+        }
+
+        writeln!(f, "\nverification")?;
+        writeln!(f, "  e(A, B) = e(alpha, beta) * e(L, gamma) * e(C, delta)")?;
+        let folded: String = self.inputs.iter().enumerate().map(|(i, (name, _))| format!(" + {name} gamma_abc[{}]", i + 1)).collect();
+        writeln!(f, "  L = gamma_abc[0]{folded}")?;
+        match &self.verification {
+            Verification::NoKey => write!(f, "  - no verifying key to check it against"),
+            Verification::InputCount { expected, found } => {
+                write!(f, "  ✗ the key is for {expected} public inputs and the proof has {found}, it's a proof of another statement")
+            }
+            Verification::Equation { holds, verifier } => {
+                if *holds {
+                    write!(f, "  ✓ both sides are equal, the proof is valid for these inputs")?;
+                } else {
+                    write!(f, "  ✗ the sides differ, the proof isn't valid for these inputs under this key")?;
+                }
+                if holds != verifier {
+                    write!(f, "\n  ✗ and the verifier zk-toy uses says otherwise, which should never happen")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,658 @@
+use std::fmt;
+use std::path::Path;
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use blake2::{Blake2s256, Digest};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use zk_core::input::parse_field;
+use zk_core::spec::InputSpec;
+
+/*
+Arithmetic statements as text, compiled to R1CS at runtime, for proving things
+without writing a circuit in Rust:
+
+    public z
+    secret x, y
+    x*x + y*3 == z
+
+Statements end at a new line or a `;`, so the same thing fits on a command line as
+"public z; secret x, y; x*x + y*3 == z". `#` starts a comment. Every name has to be
+declared public or secret before an equation uses it: a typo that quietly became a
+fresh secret would let the prover pick it and make any equation hold.
+
+Expressions have + - * and ^ (to a constant power), unary minus and parentheses. A
+number is read by parse_field like every other input (zk-core/src/input.rs), so it
+has to be smaller than the modulus and can be decimal, 0x hex or 1e18, as long as it
+is one word: a '.' or the '-' of 1e-3 ends it, and -5 is the unary minus of 5, which
+comes to the same p - 5. The values of the names take every form parse_field does,
+2.5e3 and -5 included. All of it is arithmetic mod p:
+there is no division, no comparison and no overflow, 0 - 1 is p - 1. Expressions nest
+at most 256 deep, counting parentheses and each operator of a chain like x*x*x (use
+x^3, or split it into equations with a secret in between).
+
+ExprCircuit makes a public input per public name (in the order they were declared)
+and a witness per secret one, and compiles each equation with the FpVar operators.
+Additions and multiplying by a constant are free, a product of two unknowns is one
+constraint, x^n is a square and multiply each bit of n, and every equation is one
+more. Keys are per statement; the cli (zk-cli/src/expr.rs) names them by
+Program::fingerprint.
+
+Program::solve fills in the values that follow from the others, so only the
+independent ones have to be given: z from x and y above, or y from x and z. It goes
+over the equations until nothing changes, and whenever one has a single unknown
+that appears linearly (after putting in the known values) it solves for it, which
+derives chains like y = x + 1; z = y * y. What it won't do is solve a square or a
+system of several unknowns at once; those values have to be given and the error says
+which. An equation that's left without unknowns and doesn't hold is an error too, as
+is one that is false for every value of its unknown (y * x == 1 with y = 0).
+*/
+
+#[derive(Debug)]
+pub enum ExprError {
+    Io(std::io::Error),
+    // line and column are 1-based, like an editor's
+    Parse { line: usize, column: usize, message: String },
+    // a value for a name the statement doesn't declare
+    Unknown(String),
+    Missing(String),
+    // the values given don't make this equation hold
+    Unsatisfied(String),
+    // the values solve couldn't derive
+    Underdetermined(Vec<String>),
+    // the only unknown of an equation, but squared or worse
+    NotLinear { line: usize, name: String },
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::Io(e) => write!(f, "{e}"),
+            ExprError::Parse { line, column, message } => write!(f, "line {line}, column {column}: {message}"),
+            ExprError::Unknown(name) => write!(f, "the statement has no variable '{name}'"),
+            ExprError::Missing(name) => write!(f, "missing value for '{name}'"),
+            ExprError::Unsatisfied(equation) => write!(f, "{equation} doesn't hold for these values"),
+            ExprError::Underdetermined(names) => {
+                write!(f, "{} can't be derived from the values given, set more of them", names.join(", "))
+            }
+            ExprError::NotLinear { line, name } => {
+                write!(f, "line {line}: {name} is the only unknown but multiplied by itself, the solver only does linear, set {name}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+impl From<std::io::Error> for ExprError {
+    fn from(e: std::io::Error) -> Self {
+        ExprError::Io(e)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub public: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr<F: PrimeField> {
+    Constant(F),
+    // an index into Program::variables
+    Variable(usize),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+    Neg(Box<Expr<F>>),
+    Pow(Box<Expr<F>>, u64),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equation<F: PrimeField> {
+    pub line: usize,
+    pub lhs: Expr<F>,
+    pub rhs: Expr<F>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Program<F: PrimeField> {
+    pub variables: Vec<Variable>,
+    pub equations: Vec<Equation<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(String),
+    Name(String),
+    // one of + - * ^ ( ) ,
+    Op(char),
+    Equals,
+    // a new line or a ;
+    End,
+}
+
+struct Tokens {
+    tokens: Vec<(Token, usize, usize)>,
+    next: usize,
+}
+
+fn tokenize(text: &str) -> Result<Tokens, ExprError> {
+    let mut tokens = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut c = 0;
+        while c < chars.len() {
+            let (start, ch) = (c, chars[c]);
+            let token = match ch {
+                '#' => break,
+                _ if ch.is_whitespace() => {
+                    c += 1;
+                    continue;
+                }
+                ';' => Token::End,
+                '+' | '-' | '*' | '^' | '(' | ')' | ',' => Token::Op(ch),
+                '=' if chars.get(c + 1) == Some(&'=') => {
+                    c += 1;
+                    Token::Equals
+                }
+                _ if ch.is_ascii_digit() || ch.is_alphabetic() || ch == '_' => {
+                    while c + 1 < chars.len() && (chars[c + 1].is_alphanumeric() || chars[c + 1] == '_') {
+                        c += 1;
+                    }
+                    let word: String = chars[start..=c].iter().collect();
+                    if ch.is_ascii_digit() { Token::Number(word) } else { Token::Name(word) }
+                }
+                '=' => {
+                    return Err(ExprError::Parse {
+                        line: i + 1,
+                        column: start + 1,
+                        message: "a single '=', equations are written with '=='".to_string(),
+                    });
+                }
+                _ => {
+                    return Err(ExprError::Parse {
+                        line: i + 1,
+                        column: start + 1,
+                        message: format!("'{ch}' isn't part of the language"),
+                    });
+                }
+            };
+            tokens.push((token, i + 1, start + 1));
+            c += 1;
+        }
+        tokens.push((Token::End, i + 1, chars.len() + 1));
+    }
+    Ok(Tokens { tokens, next: 0 })
+}
+
+impl Tokens {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(t, _, _)| t)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).map(|(t, _, _)| t.clone());
+        self.next += 1;
+        token
+    }
+
+    // an error at the next token, or just past the last one
+    fn error(&self, message: impl Into<String>) -> ExprError {
+        let (line, column) = match self.tokens.get(self.next).or(self.tokens.last()) {
+            Some((_, line, column)) => (*line, *column),
+            None => (1, 1),
+        };
+        ExprError::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.next).map(|(_, line, _)| *line).unwrap_or(1)
+    }
+}
+
+struct Parser {
+    tokens: Tokens,
+    variables: Vec<Variable>,
+    // the parentheses and minus signs being parsed
+    opened: usize,
+}
+
+const KEYWORDS: [&str; 2] = ["public", "secret"];
+
+// expressions are evaluated and compiled recursively, so how deep they nest is limited
+// to what fits on a thread's stack with room to spare
+const MAX_NESTING: usize = 256;
+
+impl Parser {
+    fn declare(&mut self, public: bool) -> Result<(), ExprError> {
+        loop {
+            match self.tokens.peek() {
+                Some(Token::Name(name)) if KEYWORDS.contains(&name.as_str()) => {
+                    return Err(self.tokens.error(format!("'{name}' is a keyword, not a variable name")));
+                }
+                Some(Token::Name(name)) if self.variables.iter().any(|v| v.name == *name) => {
+                    return Err(self.tokens.error(format!("'{name}' is declared twice")));
+                }
+                Some(Token::Name(name)) => {
+                    let name = name.clone();
+                    self.tokens.advance();
+                    self.variables.push(Variable { name, public });
+                }
+                _ => return Err(self.tokens.error("expected a variable name")),
+            }
+            if self.tokens.peek() != Some(&Token::Op(',')) {
+                return Ok(());
+            }
+            self.tokens.advance();
+        }
+    }
+
+    // a node `depth` deep, refused past MAX_NESTING
+    fn nested(&self, depth: usize) -> Result<usize, ExprError> {
+        if depth > MAX_NESTING {
+            return Err(self.tokens.error(format!("the expression nests more than {MAX_NESTING} deep, split it into equations")));
+        }
+        Ok(depth)
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum<F: PrimeField>(&mut self) -> Result<(Expr<F>, usize), ExprError> {
+        let (mut expr, mut depth) = self.product()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.tokens.peek() {
+            let add = *op == '+';
+            self.tokens.advance();
+            let (rhs, rhs_depth) = self.product()?;
+            depth = self.nested(depth.max(rhs_depth) + 1)?;
+            expr = if add { Expr::Add(Box::new(expr), Box::new(rhs)) } else { Expr::Sub(Box::new(expr), Box::new(rhs)) };
+        }
+        Ok((expr, depth))
+    }
+
+    // product := unary ('*' unary)*
+    fn product<F: PrimeField>(&mut self) -> Result<(Expr<F>, usize), ExprError> {
+        let (mut expr, mut depth) = self.unary()?;
+        while self.tokens.peek() == Some(&Token::Op('*')) {
+            self.tokens.advance();
+            let (rhs, rhs_depth) = self.unary()?;
+            depth = self.nested(depth.max(rhs_depth) + 1)?;
+            expr = Expr::Mul(Box::new(expr), Box::new(rhs));
+        }
+        Ok((expr, depth))
+    }
+
+    // unary := '-' unary | power, so -x^2 is -(x^2)
+    fn unary<F: PrimeField>(&mut self) -> Result<(Expr<F>, usize), ExprError> {
+        if self.tokens.peek() == Some(&Token::Op('-')) {
+            self.tokens.advance();
+            self.opened = self.nested(self.opened + 1)?;
+            let (inner, depth) = self.unary()?;
+            self.opened -= 1;
+            return Ok((Expr::Neg(Box::new(inner)), self.nested(depth + 1)?));
+        }
+        self.power()
+    }
+
+    // power := atom ('^' exponent)*
+    fn power<F: PrimeField>(&mut self) -> Result<(Expr<F>, usize), ExprError> {
+        let (mut expr, mut depth) = self.atom()?;
+        while self.tokens.peek() == Some(&Token::Op('^')) {
+            self.tokens.advance();
+            let exponent = match self.tokens.peek() {
+                Some(Token::Number(n)) => n.parse::<u64>().map_err(|_| self.tokens.error(format!("the exponent {n} isn't a small decimal number"))),
+                _ => Err(self.tokens.error("'^' takes a constant exponent")),
+            }?;
+            self.tokens.advance();
+            depth = self.nested(depth + 1)?;
+            expr = Expr::Pow(Box::new(expr), exponent);
+        }
+        Ok((expr, depth))
+    }
+
+    fn atom<F: PrimeField>(&mut self) -> Result<(Expr<F>, usize), ExprError> {
+        let (expr, depth) = match self.tokens.peek() {
+            Some(Token::Number(n)) => (Expr::Constant(parse_field(n).map_err(|e| self.tokens.error(e.to_string()))?), 1),
+            Some(Token::Name(name)) => match self.variables.iter().position(|v| v.name == *name) {
+                Some(index) => (Expr::Variable(index), 1),
+                None => return Err(self.tokens.error(format!("'{name}' isn't declared, say `public {name}` or `secret {name}` before using it"))),
+            },
+            Some(Token::Op('(')) => {
+                self.tokens.advance();
+                // parentheses make no node, but parsing them recurses all the same
+                self.opened = self.nested(self.opened + 1)?;
+                let inner = self.sum()?;
+                self.opened -= 1;
+                if self.tokens.peek() != Some(&Token::Op(')')) {
+                    return Err(self.tokens.error("expected ')'"));
+                }
+                inner
+            }
+            _ => return Err(self.tokens.error("expected a number, a name or '('")),
+        };
+        self.tokens.advance();
+        Ok((expr, depth))
+    }
+
+    fn end_of_statement(&mut self) -> Result<(), ExprError> {
+        match self.tokens.advance() {
+            Some(Token::End) | None => Ok(()),
+            _ => {
+                self.tokens.next -= 1;
+                Err(self.tokens.error("expected the end of the statement"))
+            }
+        }
+    }
+}
+
+impl<F: PrimeField> Program<F> {
+    pub fn parse(text: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            variables: Vec::new(),
+            opened: 0,
+        };
+        let mut equations = Vec::new();
+        while let Some(token) = parser.tokens.peek() {
+            match token {
+                Token::End => {
+                    parser.tokens.advance();
+                    continue;
+                }
+                Token::Name(word) if KEYWORDS.contains(&word.as_str()) => {
+                    let public = word == "public";
+                    parser.tokens.advance();
+                    parser.declare(public)?;
+                }
+                _ => {
+                    let line = parser.tokens.line();
+                    let (lhs, _) = parser.sum()?;
+                    if parser.tokens.peek() != Some(&Token::Equals) {
+                        return Err(parser.tokens.error("expected '==', a statement is a declaration or an equation"));
+                    }
+                    parser.tokens.advance();
+                    let (rhs, _) = parser.sum()?;
+                    equations.push(Equation { line, lhs, rhs });
+                }
+            }
+            parser.end_of_statement()?;
+        }
+        if equations.is_empty() {
+            return Err(parser.tokens.error("no equations, there is nothing to prove"));
+        }
+        Ok(Self {
+            variables: parser.variables,
+            equations,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ExprError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.variables.iter().position(|v| v.name == name)
+    }
+
+    pub fn public_names(&self) -> Vec<&str> {
+        self.variables.iter().filter(|v| v.public).map(|v| v.name.as_str()).collect()
+    }
+
+    pub fn input_spec(&self) -> InputSpec {
+        InputSpec::new(self.to_string(), &self.public_names())
+    }
+
+    // the values given by name, in the order of Program::variables
+    fn known<'a>(&self, named: impl IntoIterator<Item = (&'a str, F)>) -> Result<Vec<Option<F>>, ExprError> {
+        let mut values: Vec<Option<F>> = vec![None; self.variables.len()];
+        for (name, value) in named {
+            let index = self.index(name).ok_or_else(|| ExprError::Unknown(name.to_string()))?;
+            values[index] = Some(value);
+        }
+        Ok(values)
+    }
+
+    // the values in the order of Program::variables, from (name, value) pairs for all of them
+    pub fn assign<'a>(&self, named: impl IntoIterator<Item = (&'a str, F)>) -> Result<Vec<F>, ExprError> {
+        self.known(named)?
+            .into_iter()
+            .zip(&self.variables)
+            .map(|(value, v)| value.ok_or_else(|| ExprError::Missing(v.name.clone())))
+            .collect()
+    }
+
+    // the values in the order of Program::variables, the ones not given derived from the
+    // equations (see solve below)
+    pub fn solve<'a>(&self, named: impl IntoIterator<Item = (&'a str, F)>) -> Result<Vec<F>, ExprError> {
+        let mut values = self.known(named)?;
+        let nonlinear = loop {
+            let (mut progress, mut nonlinear) = (false, None);
+            for eq in &self.equations {
+                let mut unknown = Vec::new();
+                unknowns(&eq.lhs, &values, &mut unknown);
+                unknowns(&eq.rhs, &values, &mut unknown);
+                let [x] = unknown[..] else { continue };
+                match self.residual(eq, &values, x) {
+                    // constant + coefficient * x = 0
+                    Some((constant, coefficient)) => match coefficient.inverse() {
+                        Some(inverse) => {
+                            values[x] = Some(-constant * inverse);
+                            progress = true;
+                        }
+                        // x drops out (a factor that is 0 for these values) and what's left is false
+                        None if !constant.is_zero() => return Err(self.unsatisfied(eq)),
+                        None => {}
+                    },
+                    None => nonlinear = Some((eq.line, x)),
+                }
+            }
+            if !progress {
+                break nonlinear;
+            }
+        };
+        // an equation without unknowns that doesn't hold says more than what's missing
+        if let Some(eq) = self.equations.iter().find(|eq| self.residual(eq, &values, usize::MAX).is_some_and(|(c, _)| !c.is_zero())) {
+            return Err(self.unsatisfied(eq));
+        }
+        let missing: Vec<String> = self.variables.iter().zip(&values).filter(|(_, v)| v.is_none()).map(|(v, _)| v.name.clone()).collect();
+        match nonlinear {
+            Some((line, x)) => Err(ExprError::NotLinear {
+                line,
+                name: self.variables[x].name.clone(),
+            }),
+            None if !missing.is_empty() => Err(ExprError::Underdetermined(missing)),
+            None => Ok(values.into_iter().flatten().collect()),
+        }
+    }
+
+    // lhs - rhs as constant + coefficient * values[x], when every other variable in the
+    // equation is known and x isn't multiplied by itself. With no unknowns at all (an x
+    // that isn't a variable) it's the difference of the two sides, None if one is missing
+    fn residual(&self, eq: &Equation<F>, values: &[Option<F>], x: usize) -> Option<(F, F)> {
+        let (lhs, rhs) = (affine(&eq.lhs, values, x)?, affine(&eq.rhs, values, x)?);
+        Some((lhs.0 - rhs.0, lhs.1 - rhs.1))
+    }
+
+    fn unsatisfied(&self, eq: &Equation<F>) -> ExprError {
+        ExprError::Unsatisfied(format!("line {}: {}", eq.line, self.show_equation(eq)))
+    }
+
+    pub fn evaluate(&self, expr: &Expr<F>, values: &[F]) -> F {
+        match expr {
+            Expr::Constant(c) => *c,
+            Expr::Variable(i) => values[*i],
+            Expr::Add(a, b) => self.evaluate(a, values) + self.evaluate(b, values),
+            Expr::Sub(a, b) => self.evaluate(a, values) - self.evaluate(b, values),
+            Expr::Mul(a, b) => self.evaluate(a, values) * self.evaluate(b, values),
+            Expr::Neg(a) => -self.evaluate(a, values),
+            Expr::Pow(a, e) => self.evaluate(a, values).pow([*e]),
+        }
+    }
+
+    // the first equation that doesn't hold, as an error
+    pub fn check(&self, values: &[F]) -> Result<(), ExprError> {
+        match self.equations.iter().find(|eq| self.evaluate(&eq.lhs, values) != self.evaluate(&eq.rhs, values)) {
+            Some(eq) => Err(self.unsatisfied(eq)),
+            None => Ok(()),
+        }
+    }
+
+    // the public inputs of the circuit: the public values in declaration order
+    pub fn public_inputs(&self, values: &[F]) -> Vec<F> {
+        self.variables.iter().zip(values).filter(|(v, _)| v.public).map(|(_, value)| *value).collect()
+    }
+
+    // 16 hex digits of a hash of the statement as Display prints it, so spacing and
+    // comments don't change it and anything else does
+    pub fn fingerprint(&self) -> String {
+        let digest = Blake2s256::digest(self.to_string().as_bytes());
+        digest[..8].iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn show_equation(&self, eq: &Equation<F>) -> String {
+        format!("{} == {}", self.show(&eq.lhs, 0), self.show(&eq.rhs, 0))
+    }
+
+    // the expression with as few parentheses as reparse to the same tree, inside an
+    // operator of precedence `outer`
+    fn show(&self, expr: &Expr<F>, outer: u8) -> String {
+        let (text, precedence) = match expr {
+            Expr::Constant(c) => (c.to_string(), 5),
+            Expr::Variable(i) => (self.variables[*i].name.clone(), 5),
+            Expr::Add(a, b) => (format!("{} + {}", self.show(a, 1), self.show(b, 2)), 1),
+            Expr::Sub(a, b) => (format!("{} - {}", self.show(a, 1), self.show(b, 2)), 1),
+            Expr::Mul(a, b) => (format!("{}*{}", self.show(a, 2), self.show(b, 3)), 2),
+            Expr::Neg(a) => (format!("-{}", self.show(a, 3)), 3),
+            Expr::Pow(a, e) => (format!("{}^{e}", self.show(a, 5)), 4),
+        };
+        if precedence < outer { format!("({text})") } else { text }
+    }
+}
+
+// the variables in expr without a value, each once
+fn unknowns<F: PrimeField>(expr: &Expr<F>, values: &[Option<F>], found: &mut Vec<usize>) {
+    match expr {
+        Expr::Constant(_) => {}
+        Expr::Variable(i) => {
+            if values[*i].is_none() && !found.contains(i) {
+                found.push(*i);
+            }
+        }
+        Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => {
+            unknowns(a, values, found);
+            unknowns(b, values, found);
+        }
+        Expr::Neg(a) | Expr::Pow(a, _) => unknowns(a, values, found),
+    }
+}
+
+// expr as constant + coefficient * x, None when x gets multiplied by itself or another
+// variable is unknown
+fn affine<F: PrimeField>(expr: &Expr<F>, values: &[Option<F>], x: usize) -> Option<(F, F)> {
+    Some(match expr {
+        Expr::Constant(c) => (*c, F::zero()),
+        Expr::Variable(i) if *i == x => (F::zero(), F::one()),
+        Expr::Variable(i) => (values[*i]?, F::zero()),
+        Expr::Add(a, b) => {
+            let (a, b) = (affine(a, values, x)?, affine(b, values, x)?);
+            (a.0 + b.0, a.1 + b.1)
+        }
+        Expr::Sub(a, b) => {
+            let (a, b) = (affine(a, values, x)?, affine(b, values, x)?);
+            (a.0 - b.0, a.1 - b.1)
+        }
+        Expr::Mul(a, b) => {
+            let (a, b) = (affine(a, values, x)?, affine(b, values, x)?);
+            if !a.1.is_zero() && !b.1.is_zero() {
+                return None;
+            }
+            (a.0 * b.0, a.0 * b.1 + a.1 * b.0)
+        }
+        Expr::Neg(a) => {
+            let a = affine(a, values, x)?;
+            (-a.0, -a.1)
+        }
+        Expr::Pow(a, e) => match (affine(a, values, x)?, e) {
+            (_, 0) => (F::one(), F::zero()),
+            (a, 1) => a,
+            ((c, coefficient), _) if coefficient.is_zero() => (c.pow([*e]), F::zero()),
+            _ => return None,
+        },
+    })
+}
+
+impl<F: PrimeField> fmt::Display for Program<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut statements = Vec::new();
+        for public in [true, false] {
+            let names: Vec<&str> = self.variables.iter().filter(|v| v.public == public).map(|v| v.name.as_str()).collect();
+            if !names.is_empty() {
+                statements.push(format!("{} {}", if public { "public" } else { "secret" }, names.join(", ")));
+            }
+        }
+        statements.extend(self.equations.iter().map(|eq| self.show_equation(eq)));
+        write!(f, "{}", statements.join("; "))
+    }
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ExprCircuit<F: PrimeField> {
+    #[zeroize(skip)]
+    pub program: Program<F>,
+    // in the order of program.variables, public ones too
+    pub values: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> ExprCircuit<F> {
+    pub fn empty(program: Program<F>) -> Self {
+        Self { program, values: None }
+    }
+
+    // refuses values the statement doesn't hold for, the proof would fail anyway
+    pub fn new(program: Program<F>, values: Vec<F>) -> Result<Self, ExprError> {
+        assert_eq!(values.len(), program.variables.len(), "a value for every variable, see Program::assign");
+        program.check(&values)?;
+        Ok(Self {
+            program,
+            values: Some(values),
+        })
+    }
+}
+
+fn compile<F: PrimeField>(expr: &Expr<F>, variables: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+    Ok(match expr {
+        Expr::Constant(c) => FpVar::constant(*c),
+        Expr::Variable(i) => variables[*i].clone(),
+        Expr::Add(a, b) => compile(a, variables)? + compile(b, variables)?,
+        Expr::Sub(a, b) => compile(a, variables)? - compile(b, variables)?,
+        Expr::Mul(a, b) => compile(a, variables)? * compile(b, variables)?,
+        Expr::Neg(a) => compile(a, variables)?.negate()?,
+        Expr::Pow(a, e) => compile(a, variables)?.pow_by_constant([*e])?,
+    })
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for ExprCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let variables = self
+            .program
+            .variables
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let value = || self.values.as_ref().map(|values| values[i]).ok_or(SynthesisError::AssignmentMissing);
+                if v.public { FpVar::new_input(cs.clone(), value) } else { FpVar::new_witness(cs.clone(), value) }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for eq in &self.program.equations {
+            compile(&eq.lhs, &variables)?.enforce_equal(&compile(&eq.rhs, &variables)?)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use ark_ff::PrimeField;
+use sha2::{Digest, Sha256};
+use zk_gadgets::hasher::CircuitHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+
+use crate::credential::{self, Attributes};
+
+/*
+One toy world for the examples to share, so the merkle tree of one binary and the
+balances of another are about the same people instead of 0..100 here and random
+field elements there. Everything is derived from a seed, SEED for the examples:
+
+  students   1024 of them, with an id, a name, a birth date and a secret; a leaf of
+             the student tree is H(id, secret), so a student proves membership with
+             the secret only they know (student_leaves, student_tree)
+  balances   accounts of 64 of the students, balances below 2^32 (the sum and
+             threshold templates' bits)
+  census     every student with a district and whether they can vote: 18 or over
+             on CENSUS_DATE, a yyyymmdd date like the age credential's
+
+The values come from SHA-256 of the seed, the dataset's name and an index, not from
+an rng: rand doesn't promise StdRng gives the same numbers across versions, and the
+point is that a number printed in a lesson is the same number next year. Change the
+seed for another world of the same shape.
+*/
+
+pub const SEED: u64 = 2024;
+pub const STUDENTS: usize = 1024;
+// the student tree has exactly one leaf per student
+pub const STUDENT_TREE_DEPTH: usize = 10;
+pub const ACCOUNTS: usize = 64;
+pub const BALANCE_BITS: usize = 32;
+pub const DISTRICTS: usize = 4;
+pub const CENSUS_DATE: u32 = 20260101;
+
+const FIRST_NAMES: [&str; 16] = [
+    "Ada", "Alan", "Barbara", "Claude", "Donald", "Edsger", "Emmy", "Grace", "Hedy", "John", "Katherine", "Leslie", "Margaret", "Niklaus",
+    "Radia", "Shafi",
+];
+const LAST_NAMES: [&str; 16] = [
+    "Allen", "Babbage", "Church", "Diffie", "Euler", "Fermat", "Goldwasser", "Hamming", "Kleene", "Lamport", "Merkle", "Noether", "Rivest",
+    "Shamir", "Turing", "Yao",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Student {
+    // also the student's leaf index in the tree
+    pub id: u64,
+    pub name: String,
+    // yyyymmdd, between 1990 and 2013
+    pub birth_date: u32,
+    // what only the student knows, as bytes so it goes into any field
+    pub secret: [u8; 32],
+}
+
+impl Student {
+    pub fn secret<F: PrimeField>(&self) -> F {
+        F::from_le_bytes_mod_order(&self.secret)
+    }
+
+    // what a credential for the student would commit to, for the age check
+    pub fn attributes(&self) -> Attributes {
+        Attributes {
+            name: self.name.clone(),
+            nationality: "Toyland".to_string(),
+            birth_date: self.birth_date,
+        }
+    }
+
+    // 18 or over on `date`, by the age credential's rule
+    pub fn adult_on(&self, date: u32) -> bool {
+        self.birth_date <= credential::cutoff(date, 18)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    // a Student id
+    pub owner: u64,
+    pub balance: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Voter {
+    // a Student id
+    pub student: u64,
+    pub district: usize,
+    pub eligible: bool,
+}
+
+// 32 bytes for entry `index` of `dataset` in the world of `seed`
+fn draw(seed: u64, dataset: &str, index: u64) -> [u8; 32] {
+    let mut hash = Sha256::new();
+    hash.update(b"zk-toy fixtures");
+    hash.update(seed.to_le_bytes());
+    hash.update(dataset.as_bytes());
+    hash.update(index.to_le_bytes());
+    hash.finalize().into()
+}
+
+// a number below `below` for entry `index` of `dataset`, close enough to uniform for
+// the small bounds here
+fn number(seed: u64, dataset: &str, index: u64, below: u64) -> u64 {
+    let bytes = draw(seed, dataset, index);
+    u64::from_le_bytes(bytes[..8].try_into().expect("8 bytes")) % below
+}
+
+pub fn students(seed: u64) -> Vec<Student> {
+    (0..STUDENTS as u64)
+        .map(|id| {
+            let first = FIRST_NAMES[number(seed, "first name", id, 16) as usize];
+            let last = LAST_NAMES[number(seed, "last name", id, 16) as usize];
+            let year = 1990 + number(seed, "birth year", id, 24) as u32;
+            let month = 1 + number(seed, "birth month", id, 12) as u32;
+            // every month has a 28th
+            let day = 1 + number(seed, "birth day", id, 28) as u32;
+            Student {
+                id,
+                name: format!("{first} {last}"),
+                birth_date: year * 10000 + month * 100 + day,
+                secret: draw(seed, "secret", id),
+            }
+        })
+        .collect()
+}
+
+// H(id, secret) for every student, in id order
+pub fn student_leaves<F: PrimeField, H: CircuitHasher<F>>(hasher: &H, students: &[Student]) -> Vec<F> {
+    students.iter().map(|s| hasher.hash(&[F::from(s.id), s.secret()])).collect()
+}
+
+// the binary tree of every student's leaf, depth STUDENT_TREE_DEPTH
+pub fn student_tree<F: PrimeField, H: CircuitHasher<F>>(hasher: H, seed: u64) -> MerkleTree<F, H> {
+    let leaves = student_leaves(&hasher, &students(seed));
+    MerkleTree::new(hasher, TreeShape::binary(STUDENT_TREE_DEPTH), &leaves)
+}
+
+// ACCOUNTS accounts of distinct students
+pub fn balances(seed: u64) -> Vec<Account> {
+    let mut owners: Vec<u64> = Vec::with_capacity(ACCOUNTS);
+    let mut draws = 0;
+    while owners.len() < ACCOUNTS {
+        let owner = number(seed, "account owner", draws, STUDENTS as u64);
+        draws += 1;
+        if !owners.contains(&owner) {
+            owners.push(owner);
+        }
+    }
+    owners
+        .into_iter()
+        .enumerate()
+        .map(|(i, owner)| Account {
+            owner,
+            balance: number(seed, "balance", i as u64, 1 << BALANCE_BITS),
+        })
+        .collect()
+}
+
+pub fn census(seed: u64) -> Vec<Voter> {
+    students(seed)
+        .iter()
+        .map(|s| Voter {
+            student: s.id,
+            district: number(seed, "district", s.id, DISTRICTS as u64) as usize,
+            eligible: s.adult_on(CENSUS_DATE),
+        })
+        .collect()
+}
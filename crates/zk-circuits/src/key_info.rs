@@ -0,0 +1,183 @@
+use std::fmt;
+
+use ark_ec::pairing::Pairing;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalSerialize;
+use blake2::{Blake2s256, Digest};
+
+use crate::curves::Curve;
+
+/*
+What a Groth16 proving key is made of, for `zk-toy inspect-key`. Every part is a list
+of curve points the prover multiplies its witness into (one multi-scalar
+multiplication each), and the sizes follow from the circuit:
+
+  A query     G1, one per variable (the constant one, the inputs, the witnesses)
+  B query     the same over G1 and again over G2, G2 points being twice the size
+  H query     G1, one per power of the QAP domain (constraints plus inputs, rounded
+              up to a power of two) less one, for the quotient polynomial
+  L query     G1, one per witness, which is where the secret part ends up
+  vk          the verifying key itself: alpha, beta, gamma, delta and one G1 point per
+              public input plus one
+
+So a key grows with variables and constraints together, its B query over G2 is
+usually the biggest part, and rounding the domain up can make one more constraint
+double the H query. The verifying key stays small whatever the circuit.
+
+The fingerprint is a hash of the verifying key inside, which a proving key and the
+verifying key from the same setup share: two files with the same fingerprint belong
+together, whatever their names. It says nothing about which circuit the setup was for
+(keys don't record that), the versioned header that setup writes does.
+*/
+
+// a verifying key with no gamma abc points: setup always writes one for the constant,
+// so it's a crafted or truncated file, and has no number of public inputs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MalformedKey;
+
+impl fmt::Display for MalformedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "malformed verifying key: no gamma abc points, not even the one for the constant")
+    }
+}
+
+impl std::error::Error for MalformedKey {}
+
+// one gamma abc point per public input, plus the one for the constant
+pub fn public_inputs<E: Pairing>(vk: &VerifyingKey<E>) -> Result<usize, MalformedKey> {
+    vk.gamma_abc_g1.len().checked_sub(1).ok_or(MalformedKey)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Component {
+    pub name: &'static str,
+    pub group: &'static str,
+    pub count: usize,
+    pub bytes: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub curve: &'static str,
+    pub components: Vec<Component>,
+    pub public_inputs: usize,
+    // None for a verifying key, which doesn't know them
+    pub witnesses: Option<usize>,
+    pub domain_size: Option<usize>,
+    pub fingerprint: String,
+}
+
+fn component<T: CanonicalSerialize>(name: &'static str, group: &'static str, points: &[T]) -> Component {
+    Component {
+        name,
+        group,
+        count: points.len(),
+        bytes: points.iter().map(|p| p.compressed_size()).sum(),
+    }
+}
+
+// the first 16 hex digits of blake2s over the compressed verifying key
+pub fn fingerprint<E: Curve>(vk: &VerifyingKey<E>) -> String {
+    let mut bytes = Vec::new();
+    vk.serialize_compressed(&mut bytes).expect("serializing to a vec doesn't fail");
+    Blake2s256::digest(&bytes).iter().take(8).map(|b| format!("{b:02x}")).collect()
+}
+
+fn verifying_components<E: Curve>(vk: &VerifyingKey<E>) -> Vec<Component> {
+    vec![
+        component("alpha", "G1", &[vk.alpha_g1]),
+        component("beta, gamma, delta", "G2", &[vk.beta_g2, vk.gamma_g2, vk.delta_g2]),
+        component("gamma abc (inputs)", "G1", &vk.gamma_abc_g1),
+    ]
+}
+
+impl KeyInfo {
+    pub fn of_proving_key<E: Curve>(pk: &ProvingKey<E>) -> Result<Self, MalformedKey> {
+        let mut components = verifying_components(&pk.vk);
+        components.extend([
+            component("beta, delta", "G1", &[pk.beta_g1, pk.delta_g1]),
+            component("A query", "G1", &pk.a_query),
+            component("B query", "G1", &pk.b_g1_query),
+            component("B query", "G2", &pk.b_g2_query),
+            component("H query", "G1", &pk.h_query),
+            component("L query", "G1", &pk.l_query),
+        ]);
+        Ok(Self {
+            curve: E::NAME,
+            components,
+            public_inputs: public_inputs(&pk.vk)?,
+            witnesses: Some(pk.l_query.len()),
+            domain_size: Some(pk.h_query.len() + 1),
+            fingerprint: fingerprint(&pk.vk),
+        })
+    }
+
+    pub fn of_verifying_key<E: Curve>(vk: &VerifyingKey<E>) -> Result<Self, MalformedKey> {
+        Ok(Self {
+            curve: E::NAME,
+            components: verifying_components(vk),
+            public_inputs: public_inputs(vk)?,
+            witnesses: None,
+            domain_size: None,
+            fingerprint: fingerprint(vk),
+        })
+    }
+
+    // the points alone, without the length prefixes of the serialized lists
+    pub fn total_bytes(&self) -> usize {
+        self.components.iter().map(|c| c.bytes).sum()
+    }
+}
+
+impl fmt::Display for KeyInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.witnesses.is_some() { "proving" } else { "verifying" };
+        writeln!(f, "Groth16 {kind} key on {}, fingerprint {}", self.curve, self.fingerprint)?;
+        write!(f, "{} public inputs", self.public_inputs)?;
+        if let (Some(witnesses), Some(domain)) = (self.witnesses, self.domain_size) {
+            write!(f, ", {witnesses} witnesses, a QAP domain of {domain}")?;
+        }
+        writeln!(f)?;
+        let total = self.total_bytes().max(1);
+        for c in &self.components {
+            writeln!(
+                f,
+                "  {:<20} {:>8} {} points {:>12} bytes {:>5.1}%",
+                c.name,
+                c.count,
+                c.group,
+                c.bytes,
+                100.0 * c.bytes as f64 / total as f64
+            )?;
+        }
+        write!(f, "  {:<20} {:>33} bytes", "total", self.total_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::Groth16;
+    use ark_snark::SNARK;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::addition::AdditionCircuit;
+
+    #[test]
+    fn the_addition_key_has_one_input() {
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut thread_rng()).unwrap();
+        let info = KeyInfo::of_proving_key(&pk).unwrap();
+        assert_eq!(info.public_inputs, 1);
+        assert_eq!(info.fingerprint, KeyInfo::of_verifying_key(&vk).unwrap().fingerprint);
+    }
+
+    #[test]
+    fn a_key_without_gamma_abc_points_is_malformed() {
+        let (mut pk, _) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut thread_rng()).unwrap();
+        pk.vk.gamma_abc_g1.clear();
+        assert_eq!(public_inputs(&pk.vk), Err(MalformedKey));
+        assert_eq!(KeyInfo::of_proving_key(&pk), Err(MalformedKey));
+        assert_eq!(KeyInfo::of_verifying_key(&pk.vk), Err(MalformedKey));
+    }
+}
@@ -0,0 +1,81 @@
+// the example circuits, plus the groth16 flows and export formats built around them.
+// the circuits themselves only need ark-relations, what else gets built depends on
+// the features (see Cargo.toml)
+#[cfg(feature = "groth16")]
+use ark_ec::pairing::Pairing;
+#[cfg(feature = "groth16")]
+use ark_groth16::Proof;
+#[cfg(feature = "groth16")]
+use zk_core::artifact::ProofArtifact;
+use zk_core::build_info::BuildInfo;
+
+pub mod addition;
+pub mod airdrop;
+pub mod arithmetizations;
+#[cfg(feature = "trace")]
+pub mod assignment;
+pub mod bitcoin;
+pub mod boundary;
+#[cfg(feature = "bls")]
+pub mod bls_circuit;
+pub mod bristol;
+#[cfg(feature = "groth16")]
+pub mod bundle;
+pub mod challenges;
+pub mod compressed;
+#[cfg(feature = "trace")]
+pub mod coverage;
+pub mod credential;
+#[cfg(feature = "groth16")]
+pub mod curves;
+#[cfg(feature = "groth16")]
+pub mod dkg;
+#[cfg(feature = "trace")]
+pub mod diff;
+pub mod document;
+#[cfg(feature = "groth16")]
+pub mod dry_run;
+pub mod eth_address;
+#[cfg(feature = "groth16")]
+pub mod explain;
+pub mod expr;
+#[cfg(feature = "evm")]
+pub mod evm;
+#[cfg(feature = "groth16")]
+pub mod examples;
+pub mod fixtures;
+#[cfg(feature = "groth16")]
+pub mod key_info;
+#[cfg(feature = "groth16")]
+pub mod matrix_cache;
+pub mod membership;
+#[cfg(all(feature = "evm", feature = "groth16"))]
+pub mod snarkjs;
+#[cfg(all(feature = "evm", feature = "groth16"))]
+pub mod solidity;
+#[cfg(feature = "trace")]
+pub mod trace;
+pub mod templates;
+pub mod trie;
+#[cfg(feature = "groth16")]
+pub mod verifier;
+pub mod versions;
+pub mod vss;
+
+// the stamp for artifacts of this build on `curve`, with the features that change what
+// the circuits are (chaos leaves constraints out, it lives in zk-gadgets)
+pub fn build_info(curve: &str) -> BuildInfo {
+    let features = [
+        ("groth16", cfg!(feature = "groth16")),
+        ("bls", cfg!(feature = "bls")),
+        ("evm", cfg!(feature = "evm")),
+        ("trace", cfg!(feature = "trace")),
+        ("chaos", zk_gadgets::chaos::ENABLED),
+    ];
+    let on: Vec<&str> = features.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect();
+    BuildInfo::new(curve, &on)
+}
+
+// what the cli writes to proof.bin
+#[cfg(feature = "groth16")]
+pub type Groth16Artifact<E> = ProofArtifact<Proof<E>, <E as Pairing>::ScalarField>;
@@ -0,0 +1,276 @@
+use std::fmt;
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{PrimeField, UniformRand};
+use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use blake2::{Blake2s256, Digest};
+use rand::{CryptoRng, RngCore};
+use zk_core::version::VersionedCircuit;
+
+/*
+A disk cache for the constraint matrices of a circuit. Proving normally synthesizes
+the whole constraint system again, every constraint turned into linear combinations
+and inlined into the A, B and C matrices, even though only the witness changed. With
+the matrices cached a proof only needs the assignment: synthesis runs with
+construct_matrices off (arkworks skips building constraints then) and the prover
+takes the cached matrices.
+
+An entry is keyed by the circuit's id and version (versions.rs) and a parameter
+string for whatever shapes the circuit (a layout, a tree shape, a threshold), one
+file per key. Changing a parameter is a different key and so a miss, changing the
+circuit means bumping its version, which is too. The key is stored in the file and
+checked on load, a file that doesn't match is synthesized again and overwritten.
+Setup still synthesizes, the keys it makes are cached on disk anyway (zk-toy setup).
+*/
+
+#[derive(Debug)]
+pub enum CacheError {
+    Io(std::io::Error),
+    Serialization(SerializationError),
+    Synthesis(SynthesisError),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Io(e) => write!(f, "matrix cache: {e}"),
+            CacheError::Serialization(e) => write!(f, "matrix cache: {e}"),
+            CacheError::Synthesis(e) => write!(f, "synthesis error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl From<std::io::Error> for CacheError {
+    fn from(e: std::io::Error) -> Self {
+        CacheError::Io(e)
+    }
+}
+
+impl From<SerializationError> for CacheError {
+    fn from(e: SerializationError) -> Self {
+        CacheError::Serialization(e)
+    }
+}
+
+impl From<SynthesisError> for CacheError {
+    fn from(e: SynthesisError) -> Self {
+        CacheError::Synthesis(e)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    pub fn of<C: VersionedCircuit>(parameters: impl fmt::Display) -> Self {
+        CacheKey(format!("{} {} {parameters}", C::ID, C::VERSION))
+    }
+
+    // the id and version to find it by, and a hash of the whole key
+    fn file_name(&self) -> String {
+        let digest = Blake2s256::digest(self.0.as_bytes());
+        let hash: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+        let prefix: String = self.0.split(' ').take(2).collect::<Vec<_>>().join("-");
+        format!("{prefix}-{hash}.r1cs")
+    }
+}
+
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lookup {
+    Hit,
+    Miss,
+    // there was a file for the key, but for another key or unreadable
+    Stale,
+}
+
+pub struct MatrixCache {
+    dir: PathBuf,
+}
+
+impl MatrixCache {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+
+    pub fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.file_name())
+    }
+
+    // the matrices for the key, synthesized (from a circuit of the right shape, an
+    // empty one will do) and written only when the cache doesn't have them
+    pub fn matrices<F: PrimeField, C: ConstraintSynthesizer<F>>(
+        &self,
+        key: &CacheKey,
+        circuit: C,
+    ) -> Result<(ConstraintMatrices<F>, Lookup), CacheError> {
+        let path = self.path(key);
+        let lookup = match fs::File::open(&path) {
+            Ok(file) => match read(BufReader::new(file)) {
+                Ok((stored, matrices)) if stored == key.0 => return Ok((matrices, Lookup::Hit)),
+                _ => Lookup::Stale,
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Lookup::Miss,
+            Err(e) => return Err(e.into()),
+        };
+        let matrices = synthesize(circuit)?;
+        fs::create_dir_all(&self.dir)?;
+        write(BufWriter::new(fs::File::create(&path)?), &key.0, &matrices)?;
+        Ok((matrices, lookup))
+    }
+}
+
+// the matrices exactly as groth16 sees them: optimized for constraints, LCs inlined
+pub fn synthesize<F: PrimeField, C: ConstraintSynthesizer<F>>(circuit: C) -> Result<ConstraintMatrices<F>, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.generate_constraints(cs.clone())?;
+    cs.finalize();
+    cs.to_matrices().ok_or(SynthesisError::MissingCS)
+}
+
+// a groth16 proof from cached matrices: synthesis only computes the assignment
+pub fn prove<E: Pairing, C: ConstraintSynthesizer<E::ScalarField>, R: RngCore + CryptoRng>(
+    pk: &ProvingKey<E>,
+    matrices: &ConstraintMatrices<E::ScalarField>,
+    circuit: C,
+    rng: &mut R,
+) -> Result<Proof<E>, SynthesisError> {
+    let cs = ConstraintSystem::<E::ScalarField>::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::Constraints);
+    cs.set_mode(SynthesisMode::Prove { construct_matrices: false });
+    circuit.generate_constraints(cs.clone())?;
+    if cs.num_constraints() != matrices.num_constraints
+        || cs.num_instance_variables() != matrices.num_instance_variables
+        || cs.num_witness_variables() != matrices.num_witness_variables
+    {
+        // the circuit changed and its version didn't
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let assignment = {
+        let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+        [cs.instance_assignment.as_slice(), cs.witness_assignment.as_slice()].concat()
+    };
+    let (r, s) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+    Groth16::<E>::create_proof_with_reduction_and_matrices(
+        pk,
+        r,
+        s,
+        matrices,
+        matrices.num_instance_variables,
+        matrices.num_constraints,
+        &assignment,
+    )
+}
+
+fn write<F: PrimeField>(mut out: impl std::io::Write, key: &str, m: &ConstraintMatrices<F>) -> Result<(), SerializationError> {
+    key.as_bytes().to_vec().serialize_uncompressed(&mut out)?;
+    let counts = [m.num_instance_variables, m.num_witness_variables, m.num_constraints, m.a_num_non_zero, m.b_num_non_zero, m.c_num_non_zero];
+    counts.to_vec().serialize_uncompressed(&mut out)?;
+    for matrix in [&m.a, &m.b, &m.c] {
+        matrix.serialize_uncompressed(&mut out)?;
+    }
+    Ok(())
+}
+
+fn read<F: PrimeField>(mut input: impl std::io::Read) -> Result<(String, ConstraintMatrices<F>), SerializationError> {
+    let key = String::from_utf8(Vec::<u8>::deserialize_uncompressed(&mut input)?).map_err(|_| SerializationError::InvalidData)?;
+    let counts = Vec::<usize>::deserialize_uncompressed(&mut input)?;
+    let [instance, witness, constraints, a_non_zero, b_non_zero, c_non_zero] = counts[..] else {
+        return Err(SerializationError::InvalidData);
+    };
+    let mut matrix = || Vec::<Vec<(F, usize)>>::deserialize_uncompressed(&mut input);
+    let (a, b, c) = (matrix()?, matrix()?, matrix()?);
+    Ok((
+        key,
+        ConstraintMatrices {
+            num_instance_variables: instance,
+            num_witness_variables: witness,
+            num_constraints: constraints,
+            a_num_non_zero: a_non_zero,
+            b_num_non_zero: b_non_zero,
+            c_num_non_zero: c_non_zero,
+            a,
+            b,
+            c,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::prepare_verifying_key;
+    use ark_snark::SNARK;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::addition::AdditionCircuit;
+
+    fn temp_dir() -> PathBuf {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let n = NEXT.fetch_add(1, Ordering::SeqCst);
+        std::env::temp_dir().join(format!("zk-toy-matrix-cache-test-{}-{n}", std::process::id()))
+    }
+
+    #[test]
+    fn a_changed_parameter_is_a_miss() {
+        let dir = temp_dir();
+        let cache = MatrixCache::new(&dir);
+        let (one, two) = (CacheKey::of::<AdditionCircuit<Fr>>("one"), CacheKey::of::<AdditionCircuit<Fr>>("two"));
+        let lookups: Vec<Lookup> = [&one, &one, &two, &two]
+            .into_iter()
+            .map(|key| cache.matrices(key, AdditionCircuit::<Fr>::empty()).unwrap().1)
+            .collect();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(lookups, [Lookup::Miss, Lookup::Hit, Lookup::Miss, Lookup::Hit]);
+        assert_ne!(cache.path(&one), cache.path(&two));
+    }
+
+    #[test]
+    fn a_file_for_another_key_is_stale_and_overwritten() {
+        let dir = temp_dir();
+        let cache = MatrixCache::new(&dir);
+        let (one, two) = (CacheKey::of::<AdditionCircuit<Fr>>("one"), CacheKey::of::<AdditionCircuit<Fr>>("two"));
+        cache.matrices(&two, AdditionCircuit::<Fr>::empty()).unwrap();
+        fs::copy(cache.path(&two), cache.path(&one)).unwrap();
+        let stale = cache.matrices(&one, AdditionCircuit::<Fr>::empty()).unwrap().1;
+        let after = cache.matrices(&one, AdditionCircuit::<Fr>::empty()).unwrap().1;
+        fs::write(cache.path(&two), b"not matrices").unwrap();
+        let garbage = cache.matrices(&two, AdditionCircuit::<Fr>::empty()).unwrap().1;
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!((stale, after, garbage), (Lookup::Stale, Lookup::Hit, Lookup::Stale));
+    }
+
+    #[test]
+    fn cached_matrices_prove_and_mismatched_ones_are_refused() {
+        let mut rng = thread_rng();
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng).unwrap();
+        let matrices = synthesize(AdditionCircuit::<Fr>::empty()).unwrap();
+        let circuit = || AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+        let proof = prove(&pk, &matrices, circuit(), &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&prepare_verifying_key(&vk), &[Fr::from(19u32)], &proof).unwrap());
+
+        // the same constraints and inputs, one witness more than the circuit has
+        let mut changed = matrices;
+        changed.num_witness_variables += 1;
+        assert!(matches!(prove(&pk, &changed, circuit(), &mut rng), Err(SynthesisError::Unsatisfiable)));
+    }
+}
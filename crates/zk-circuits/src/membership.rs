@@ -0,0 +1,63 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use zk_gadgets::hasher::CircuitHasher;
+use zk_gadgets::merkle::{self, MerklePath, TreeShape};
+
+/*
+Set membership: "my leaf is somewhere in the tree with this root", without saying
+which leaf it is or what it is. Public input: the root. Witnesses: the leaf, the
+siblings along its path and the bits of its index.
+
+The hash is a type parameter, MembershipCircuit<F, PoseidonHasher<F>> and
+MembershipCircuit<F, Sha256Hasher> are the same statement at very different prices.
+The shape of the tree (arity and depth) is fixed at setup.
+The leaf and its path are wiped on drop.
+*/
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct MembershipCircuit<F: PrimeField, H: CircuitHasher<F>> {
+    #[zeroize(skip)]
+    pub hasher: H,
+    #[zeroize(skip)]
+    pub shape: TreeShape,
+    #[zeroize(skip)]
+    pub root: Option<F>,
+    pub leaf: Option<F>,
+    pub path: Option<MerklePath<F>>,
+}
+
+impl<F: PrimeField, H: CircuitHasher<F>> MembershipCircuit<F, H> {
+    pub fn empty(hasher: H, shape: TreeShape) -> Self {
+        Self {
+            hasher,
+            shape,
+            root: None,
+            leaf: None,
+            path: None,
+        }
+    }
+
+    pub fn new(hasher: H, leaf: F, path: MerklePath<F>) -> Self {
+        let root = path.root(&hasher, leaf);
+        Self {
+            shape: path.shape,
+            hasher,
+            root: Some(root),
+            leaf: Some(leaf),
+            path: Some(path),
+        }
+    }
+}
+
+impl<F: PrimeField, H: CircuitHasher<F>> ConstraintSynthesizer<F> for MembershipCircuit<F, H> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let root = FpVar::new_input(cs.clone(), || self.root.ok_or(SynthesisError::AssignmentMissing))?;
+        let leaf = FpVar::new_witness(cs.clone(), || self.leaf.ok_or(SynthesisError::AssignmentMissing))?;
+
+        let (siblings, index_bits) = merkle::path_var(cs.clone(), self.shape, self.path.as_ref())?;
+        merkle::root_var(cs, &self.hasher, self.shape, &leaf, &siblings, &index_bits)?.enforce_equal(&root)
+    }
+}
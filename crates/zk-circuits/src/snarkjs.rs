@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_groth16::{Proof, VerifyingKey};
+use num_bigint::BigUint;
+use serde_json::{Value, json};
+use zk_core::artifact::ArtifactError;
+
+use crate::key_info::{self, MalformedKey};
+
+/*
+Writing BN254 Groth16 keys and proofs in the json format snarkjs uses
+(verification_key.json, proof.json, public.json), so another implementation can
+check that our proofs are really standard Groth16 proofs.
+
+snarkjs writes field elements as decimal strings and points in projective form with
+z = 1. Unlike the EIP-197 encoding in evm.rs, G2 coordinates are written real part
+first here, snarkjs only swaps them when it generates solidity calldata.
+
+snarkjs_verify runs `snarkjs groth16 verify` on such a fixture, the snarkjs version
+pinned below through npx, or the binary in SNARKJS (for example when you're offline).
+tests/snarkjs.rs does that, ignored by default since it needs node.
+*/
+
+pub const SNARKJS_VERSION: &str = "0.7.4";
+
+// not x.to_string(): arkworks' Display writes zero as an empty string
+fn decimal<F: PrimeField>(x: &F) -> String {
+    let x: BigUint = x.into_bigint().into();
+    x.to_string()
+}
+
+fn g1(p: &G1Affine) -> Value {
+    match p.xy() {
+        Some((x, y)) => json!([decimal(x), decimal(y), "1"]),
+        None => json!(["0", "1", "0"]),
+    }
+}
+
+fn g2(p: &G2Affine) -> Value {
+    match p.xy() {
+        Some((x, y)) => json!([
+            [decimal(&x.c0), decimal(&x.c1)],
+            [decimal(&y.c0), decimal(&y.c1)],
+            ["1", "0"]
+        ]),
+        None => json!([["0", "0"], ["1", "0"], ["0", "0"]]),
+    }
+}
+
+pub fn verifying_key_json(vk: &VerifyingKey<Bn254>) -> Result<Value, MalformedKey> {
+    Ok(json!({
+        "protocol": "groth16",
+        "curve": "bn128",
+        "nPublic": key_info::public_inputs(vk)?,
+        "vk_alpha_1": g1(&vk.alpha_g1),
+        "vk_beta_2": g2(&vk.beta_g2),
+        "vk_gamma_2": g2(&vk.gamma_g2),
+        "vk_delta_2": g2(&vk.delta_g2),
+        "IC": vk.gamma_abc_g1.iter().map(g1).collect::<Vec<_>>(),
+    }))
+}
+
+pub fn proof_json(proof: &Proof<Bn254>) -> Value {
+    json!({
+        "pi_a": g1(&proof.a),
+        "pi_b": g2(&proof.b),
+        "pi_c": g1(&proof.c),
+        "protocol": "groth16",
+        "curve": "bn128",
+    })
+}
+
+pub fn public_json(inputs: &[Fr]) -> Value {
+    json!(inputs.iter().map(decimal).collect::<Vec<_>>())
+}
+
+// writes the three files snarkjs groth16 verify expects into dir
+pub fn export_fixture(
+    dir: impl AsRef<Path>,
+    vk: &VerifyingKey<Bn254>,
+    proof: &Proof<Bn254>,
+    inputs: &[Fr],
+) -> Result<(), ArtifactError> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let write = |name: &str, value: Value| -> Result<(), ArtifactError> {
+        let text = serde_json::to_string_pretty(&value).expect("json values always serialize");
+        fs::write(dir.join(name), text)?;
+        Ok(())
+    };
+    let key = verifying_key_json(vk).map_err(|e| ArtifactError::BadEncoding(e.to_string()))?;
+    write("verification_key.json", key)?;
+    write("proof.json", proof_json(proof))?;
+    write("public.json", public_json(inputs))?;
+    Ok(())
+}
+
+// whether snarkjs accepts the fixture in dir, an error if snarkjs didn't get to say
+// (no node, npx offline): it prints "OK!" or "Invalid proof" when it does
+pub fn snarkjs_verify(dir: impl AsRef<Path>) -> std::io::Result<bool> {
+    let dir = dir.as_ref();
+    let mut command = match std::env::var("SNARKJS") {
+        Ok(binary) => Command::new(binary),
+        Err(_) => {
+            let mut npx = Command::new("npx");
+            npx.arg("--yes").arg(format!("snarkjs@{SNARKJS_VERSION}"));
+            npx
+        }
+    };
+    let output = command
+        .args(["groth16", "verify"])
+        .arg(dir.join("verification_key.json"))
+        .arg(dir.join("public.json"))
+        .arg(dir.join("proof.json"))
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if output.status.success() && stdout.contains("OK!") {
+        Ok(true)
+    } else if stdout.contains("Invalid proof") {
+        Ok(false)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(std::io::Error::other(format!("snarkjs didn't verify anything: {}{}", stdout.trim(), stderr.trim())))
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::Write as _;
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+
+use crate::evm::{self, Word};
+use crate::key_info::{self, MalformedKey};
+
+/*
+Solidity contracts for BN254 Groth16 proofs, written out as text, and a rust model of
+what they do so the examples can run the contract logic without an ethereum node.
+
+verifier_contract is the usual Groth16Verifier: the verifying key baked in as
+constants, verifyProof(a, b, c, input) folds the public inputs into vk_x with the
+ecAdd / ecMul precompiles (0x06, 0x07) and asks the pairing precompile (0x08) whether
+
+  e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+
+Inputs >= r and an A.y >= q are refused up front, otherwise (y, q + y) would be two
+encodings of the same proof. The encodings are the ones from evm.rs, G2 imaginary
+part first.
+
+AIRDROP_CONTRACT is the private airdrop from airdrop.rs: it keeps the nullifiers it
+has seen and builds the public inputs itself from its root, its airdrop id and the
+recipient in the call, so a proof only pays out to the address it was made for.
+
+verification_gas estimates what a verifyProof call costs from the precompile prices
+(EIP-1108) and the calldata (EIP-2028), the part that depends on the proof and its
+inputs; the 21000 of the transaction and the contract's own few thousand are left
+out. An ecMul and an ecAdd per public input, 6150 gas, is most of what an input
+costs, which is what compressing them into one (compressed.rs) is about.
+
+Nothing here has been through solc in this repo (there's no solc in the build), the
+model below is what the examples check. Keep the two in step when changing either.
+*/
+
+fn decimal<F: PrimeField>(x: &F) -> String {
+    x.into_bigint().to_string()
+}
+
+fn modulus<F: PrimeField>() -> String {
+    F::MODULUS.to_string()
+}
+
+// a G1 point as `NAME_X`, `NAME_Y` constants
+fn g1_constants(out: &mut String, name: &str, p: &G1Affine) {
+    let (x, y) = p.xy().map(|(x, y)| (*x, *y)).unwrap_or_default();
+    writeln!(out, "    uint256 constant {name}_X = {};", decimal(&x)).unwrap();
+    writeln!(out, "    uint256 constant {name}_Y = {};", decimal(&y)).unwrap();
+}
+
+// a G2 point as `NAME_X1`, `NAME_X0`, `NAME_Y1`, `NAME_Y0`, the order the precompile wants
+fn g2_constants(out: &mut String, name: &str, p: &ark_bn254::G2Affine) {
+    let words = evm::encode_g2(p);
+    for (suffix, word) in ["X1", "X0", "Y1", "Y0"].iter().zip(&words) {
+        let value = Fq::from_be_bytes_mod_order(word);
+        writeln!(out, "    uint256 constant {name}_{suffix} = {};", decimal(&value)).unwrap();
+    }
+}
+
+// an error for a key with no gamma abc points, which has no number of inputs
+pub fn verifier_contract(vk: &VerifyingKey<Bn254>) -> Result<String, MalformedKey> {
+    let inputs = key_info::public_inputs(vk)?;
+    let mut out = String::new();
+    out.push_str("// SPDX-License-Identifier: MIT\n");
+    out.push_str("// generated by zk-circuits/src/solidity.rs from a groth16 verifying key, don't edit\n");
+    out.push_str("pragma solidity ^0.8.20;\n\n");
+    out.push_str("contract Groth16Verifier {\n");
+    writeln!(out, "    uint256 constant Q = {};", modulus::<Fq>()).unwrap();
+    writeln!(out, "    uint256 constant R = {};\n", modulus::<Fr>()).unwrap();
+    g1_constants(&mut out, "ALPHA", &vk.alpha_g1);
+    g2_constants(&mut out, "BETA", &vk.beta_g2);
+    g2_constants(&mut out, "GAMMA", &vk.gamma_g2);
+    g2_constants(&mut out, "DELTA", &vk.delta_g2);
+    for (i, ic) in vk.gamma_abc_g1.iter().enumerate() {
+        g1_constants(&mut out, &format!("IC{i}"), ic);
+    }
+    out.push_str(
+        "
+    function ecAdd(uint256[2] memory p, uint256[2] memory q) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input;
+        input[0] = p[0];
+        input[1] = p[1];
+        input[2] = q[0];
+        input[3] = q[1];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x06, input, 0x80, r, 0x40)
+        }
+        require(ok, \"ecAdd failed\");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input;
+        input[0] = p[0];
+        input[1] = p[1];
+        input[2] = s;
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x07, input, 0x60, r, 0x40)
+        }
+        require(ok, \"ecMul failed\");
+    }
+
+",
+    );
+    writeln!(
+        out,
+        "    function verifyProof(uint256[2] calldata a, uint256[2][2] calldata b, uint256[2] calldata c, uint256[{inputs}] calldata input) public view returns (bool) {{"
+    )
+    .unwrap();
+    out.push_str("        if (a[1] >= Q) return false;\n");
+    out.push_str("        uint256[2] memory vkX;\n");
+    out.push_str("        vkX[0] = IC0_X;\n");
+    out.push_str("        vkX[1] = IC0_Y;\n");
+    for i in 0..inputs {
+        writeln!(out, "        if (input[{i}] >= R) return false;").unwrap();
+        writeln!(out, "        vkX = ecAdd(vkX, ecMul([IC{}_X, IC{}_Y], input[{i}]));", i + 1, i + 1).unwrap();
+    }
+    out.push_str("\n        uint256[24] memory p;\n");
+    let slots = [
+        "a[0]",
+        "a[1] == 0 ? 0 : Q - a[1]",
+        "b[0][0]",
+        "b[0][1]",
+        "b[1][0]",
+        "b[1][1]",
+        "ALPHA_X",
+        "ALPHA_Y",
+        "BETA_X1",
+        "BETA_X0",
+        "BETA_Y1",
+        "BETA_Y0",
+        "vkX[0]",
+        "vkX[1]",
+        "GAMMA_X1",
+        "GAMMA_X0",
+        "GAMMA_Y1",
+        "GAMMA_Y0",
+        "c[0]",
+        "c[1]",
+        "DELTA_X1",
+        "DELTA_X0",
+        "DELTA_Y1",
+        "DELTA_Y0",
+    ];
+    for (i, slot) in slots.iter().enumerate() {
+        writeln!(out, "        p[{i}] = {slot};").unwrap();
+    }
+    out.push_str(
+        "        uint256[1] memory result;
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x08, p, 0x300, result, 0x20)
+        }
+        return ok && result[0] == 1;
+    }
+}
+",
+    );
+    Ok(out)
+}
+
+pub const AIRDROP_CONTRACT: &str = r#"// SPDX-License-Identifier: MIT
+// the private airdrop from zk-circuits/src/airdrop.rs, modelled by solidity::AirdropContract
+pragma solidity ^0.8.20;
+
+interface IGroth16Verifier {
+    function verifyProof(uint256[2] calldata a, uint256[2][2] calldata b, uint256[2] calldata c, uint256[4] calldata input)
+        external view returns (bool);
+}
+
+contract Airdrop {
+    IGroth16Verifier public immutable verifier;
+    uint256 public immutable root;
+    uint256 public immutable airdropId;
+    uint256 public immutable amount;
+
+    mapping(uint256 => bool) public nullified;
+    // a toy ledger, a real airdrop would transfer an ERC-20 here
+    mapping(address => uint256) public balanceOf;
+
+    event Claimed(uint256 indexed nullifier, address indexed recipient, uint256 amount);
+
+    constructor(IGroth16Verifier verifier_, uint256 root_, uint256 airdropId_, uint256 amount_) {
+        verifier = verifier_;
+        root = root_;
+        airdropId = airdropId_;
+        amount = amount_;
+    }
+
+    // the recipient is a public input of the proof, changing it makes the proof invalid
+    function claim(uint256[2] calldata a, uint256[2][2] calldata b, uint256[2] calldata c, uint256 nullifier, address recipient)
+        external
+    {
+        require(!nullified[nullifier], "already claimed");
+        uint256[4] memory input;
+        input[0] = root;
+        input[1] = nullifier;
+        input[2] = uint256(uint160(recipient));
+        input[3] = airdropId;
+        require(verifier.verifyProof(a, b, c, input), "invalid proof");
+        nullified[nullifier] = true;
+        balanceOf[recipient] += amount;
+        emit Claimed(nullifier, recipient, amount);
+    }
+}
+"#;
+
+const EC_ADD_GAS: u64 = 150;
+const EC_MUL_GAS: u64 = 6000;
+const PAIRING_GAS: u64 = 45000;
+const PAIRING_GAS_PER_PAIR: u64 = 34000;
+const SHA256_GAS: u64 = 60;
+const SHA256_GAS_PER_WORD: u64 = 12;
+
+// 16 gas a non-zero byte, 4 a zero one
+pub fn calldata_gas(bytes: &[u8]) -> u64 {
+    bytes.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum()
+}
+
+// the precompiles verifyProof calls and its calldata
+pub fn verification_gas(proof: &Proof<Bn254>, inputs: &[Fr]) -> u64 {
+    let calldata = evm::words_to_bytes(&evm::encode_proof_calldata(proof, inputs));
+    inputs.len() as u64 * (EC_MUL_GAS + EC_ADD_GAS) + PAIRING_GAS + 4 * PAIRING_GAS_PER_PAIR + calldata_gas(&calldata)
+}
+
+// the sha256 precompile (0x02) on this many bytes
+pub fn sha256_gas(bytes: usize) -> u64 {
+    SHA256_GAS + SHA256_GAS_PER_WORD * bytes.div_ceil(32) as u64
+}
+
+pub const CLAIM_SIGNATURE: &str = "claim(uint256[2],uint256[2][2],uint256[2],uint256,address)";
+
+pub type Address = [u8; 20];
+
+// uint256(uint160(recipient)), the address as the circuit sees it
+pub fn address_field(address: &Address) -> Fr {
+    Fr::from_be_bytes_mod_order(address)
+}
+
+pub fn address_hex(address: &Address) -> String {
+    format!("0x{}", address.iter().map(|b| format!("{b:02x}")).collect::<String>())
+}
+
+// what verifyProof in the generated contract does, step by step. The public inputs
+// are checked before anything else, the pairing goes through the precompile model
+pub fn verify_proof(vk: &VerifyingKey<Bn254>, a: &[Word; 2], b: &[Word; 4], c: &[Word; 2], input: &[Word]) -> bool {
+    let q = Fq::MODULUS.to_bytes_be();
+    if input.len() + 1 != vk.gamma_abc_g1.len() || a[1].as_slice() >= q.as_slice() {
+        return false;
+    }
+    let mut vk_x: G1Projective = vk.gamma_abc_g1[0].into();
+    for (word, ic) in input.iter().zip(&vk.gamma_abc_g1[1..]) {
+        match evm::decode_field::<Fr>(word, "public input") {
+            Ok(x) => vk_x += ic.mul_bigint(x.into_bigint()),
+            Err(_) => return false,
+        }
+    }
+    // -A, with the same "0 stays 0" the contract has
+    let mut minus_a = *a;
+    if minus_a[1] != [0u8; 32] {
+        let y = Fq::from_be_bytes_mod_order(&a[1]);
+        minus_a[1] = evm::encode_field(&-y);
+    }
+    let mut words = Vec::with_capacity(24);
+    words.extend(minus_a);
+    words.extend(*b);
+    words.extend(evm::encode_g1(&vk.alpha_g1));
+    words.extend(evm::encode_g2(&vk.beta_g2));
+    words.extend(evm::encode_g1(&vk_x.into()));
+    words.extend(evm::encode_g2(&vk.gamma_g2));
+    words.extend(*c);
+    words.extend(evm::encode_g2(&vk.delta_g2));
+    // a point the precompile can't decode makes the staticcall fail, ok is false
+    evm::pairing_precompile(&evm::words_to_bytes(&words)).unwrap_or(false)
+}
+
+// calldata for Airdrop.claim: the selector, then a, b, c, the nullifier and the
+// recipient left-padded to a word
+pub fn encode_claim_calldata(proof: &Proof<Bn254>, nullifier: Fr, recipient: &Address) -> Vec<u8> {
+    let mut words = evm::encode_proof_calldata(proof, &[nullifier]);
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(recipient);
+    words.push(word);
+    let mut calldata = evm::function_selector(CLAIM_SIGNATURE).to_vec();
+    calldata.extend(evm::words_to_bytes(&words));
+    calldata
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClaimError {
+    BadCalldata(String),
+    AlreadyClaimed,
+    InvalidProof,
+}
+
+impl fmt::Display for ClaimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClaimError::BadCalldata(why) => write!(f, "bad calldata: {why}"),
+            ClaimError::AlreadyClaimed => write!(f, "already claimed"),
+            ClaimError::InvalidProof => write!(f, "invalid proof"),
+        }
+    }
+}
+
+impl std::error::Error for ClaimError {}
+
+// Airdrop.sol plus the verifier it points at, as rust. An Err is a revert: nothing
+// about the state changes
+pub struct AirdropContract {
+    vk: VerifyingKey<Bn254>,
+    root: Fr,
+    airdrop_id: Fr,
+    amount: u64,
+    nullified: HashSet<Word>,
+    balances: HashMap<Address, u64>,
+}
+
+impl AirdropContract {
+    pub fn new(vk: VerifyingKey<Bn254>, root: Fr, airdrop_id: Fr, amount: u64) -> Self {
+        Self {
+            vk,
+            root,
+            airdrop_id,
+            amount,
+            nullified: HashSet::new(),
+            balances: HashMap::new(),
+        }
+    }
+
+    pub fn claim(&mut self, calldata: &[u8]) -> Result<Address, ClaimError> {
+        let selector = evm::function_selector(CLAIM_SIGNATURE);
+        let Some(args) = calldata.strip_prefix(selector.as_slice()) else {
+            return Err(ClaimError::BadCalldata("not a call to claim".to_string()));
+        };
+        if args.len() != 10 * 32 {
+            return Err(ClaimError::BadCalldata(format!("expected 320 bytes of arguments, got {}", args.len())));
+        }
+        let words = evm::bytes_to_words(args).map_err(|e| ClaimError::BadCalldata(e.to_string()))?;
+        let (nullifier, recipient_word) = (words[8], words[9]);
+        // the abi decoder reverts on an address with anything in its top 12 bytes
+        if recipient_word[..12] != [0u8; 12] {
+            return Err(ClaimError::BadCalldata("recipient is not an address".to_string()));
+        }
+        let recipient: Address = recipient_word[12..].try_into().expect("20 bytes");
+
+        if self.nullified.contains(&nullifier) {
+            return Err(ClaimError::AlreadyClaimed);
+        }
+        let input = [
+            evm::encode_field(&self.root),
+            nullifier,
+            evm::encode_field(&address_field(&recipient)),
+            evm::encode_field(&self.airdrop_id),
+        ];
+        let a = [words[0], words[1]];
+        let b = [words[2], words[3], words[4], words[5]];
+        let c = [words[6], words[7]];
+        if !verify_proof(&self.vk, &a, &b, &c, &input) {
+            return Err(ClaimError::InvalidProof);
+        }
+        self.nullified.insert(nullifier);
+        *self.balances.entry(recipient).or_default() += self.amount;
+        Ok(recipient)
+    }
+
+    pub fn balance_of(&self, address: &Address) -> u64 {
+        self.balances.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn claims(&self) -> usize {
+        self.nullified.len()
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use zk_core::spec::InputSpec;
+use zk_gadgets::compare::enforce_bits;
+use zk_gadgets::hasher::PoseidonHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+
+use crate::membership::MembershipCircuit;
+
+/*
+The statements people keep asking for, with their sizes as parameters so they can
+be picked from a config file (zk-toy template, zk-cli/src/template.rs) instead of
+written in Rust:
+
+  range      { bits }            secret x with lo <= x <= hi, public lo and hi
+  membership { depth }           a secret leaf of a Poseidon tree, public root
+  sum        { length, bits }    secret values adding up to a public total
+  threshold  { length, bits }    secret values adding up to at least a public threshold
+
+Every secret value is range checked to `bits` bits, which is what makes the sums and
+comparisons mean anything: length * 2^bits stays far below p, so a sum can't wrap
+around to the total. The public values have to be in range too, but those are the
+verifier's to pick: new refuses them outside, the circuit doesn't check them again
+(see boundary.rs). Threshold compares with bits + log2(length) bits, the width a sum
+of `length` values can have.
+
+A membership template is the membership example's circuit with a binary Poseidon
+tree, the other three are here.
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    // a parameter the template can't be instantiated with
+    Parameter(String),
+    // an input the statement doesn't hold for, or one that doesn't fit the parameters
+    Input(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Parameter(msg) => write!(f, "template parameter: {msg}"),
+            TemplateError::Input(msg) => write!(f, "template input: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Template {
+    Range { bits: usize },
+    Membership { depth: usize },
+    Sum { length: usize, bits: usize },
+    Threshold { length: usize, bits: usize },
+}
+
+// the whole tree is built natively to get a path, at depth 20 that's a million leaves
+const MAX_DEPTH: usize = 20;
+
+impl Template {
+    // the statement and its parameters, like "sum-8x32"; the cli names key files by it
+    pub fn name(&self) -> String {
+        match self {
+            Template::Range { bits } => format!("range-{bits}"),
+            Template::Membership { depth } => format!("membership-{depth}"),
+            Template::Sum { length, bits } => format!("sum-{length}x{bits}"),
+            Template::Threshold { length, bits } => format!("threshold-{length}x{bits}"),
+        }
+    }
+
+    // the names of the public inputs, in order
+    pub fn public_names(&self) -> &'static [&'static str] {
+        match self {
+            Template::Range { .. } => &["lo", "hi"],
+            Template::Membership { .. } => &["root"],
+            Template::Sum { .. } => &["total"],
+            Template::Threshold { .. } => &["threshold"],
+        }
+    }
+
+    pub fn input_spec(&self) -> InputSpec {
+        InputSpec::new(self.to_string(), self.public_names())
+    }
+
+    // parameters a field F can hold the statement for
+    pub fn check<F: PrimeField>(&self) -> Result<(), TemplateError> {
+        let (length, bits) = match *self {
+            Template::Range { bits } => (1, bits),
+            Template::Membership { depth } if depth == 0 || depth > MAX_DEPTH => {
+                return Err(TemplateError::Parameter(format!("depth {depth}, a tree has 1 to {MAX_DEPTH} levels")));
+            }
+            Template::Membership { .. } => return Ok(()),
+            Template::Sum { length, bits } | Template::Threshold { length, bits } => (length, bits),
+        };
+        if length == 0 {
+            return Err(TemplateError::Parameter("length 0, there has to be a value to add up".to_string()));
+        }
+        // the widest comparison is threshold's, the sum of `length` values
+        let capacity = F::MODULUS_BIT_SIZE as usize - 2;
+        if bits == 0 || sum_bits(length, bits) > capacity {
+            return Err(TemplateError::Parameter(format!("{bits} bits for {length} values, the field has room for {capacity} bits of sum")));
+        }
+        Ok(())
+    }
+
+    pub fn empty<F: PrimeField + Absorb>(&self) -> Result<TemplateCircuit<F>, TemplateError> {
+        self.check::<F>()?;
+        Ok(match *self {
+            Template::Range { bits } => TemplateCircuit::Range(RangeCircuit::empty(bits)),
+            Template::Membership { depth } => TemplateCircuit::Membership(MembershipCircuit::empty(PoseidonHasher::new(2), TreeShape::binary(depth))),
+            Template::Sum { length, bits } => TemplateCircuit::Sum(SumCircuit::empty(length, bits)),
+            Template::Threshold { length, bits } => TemplateCircuit::Threshold(ThresholdCircuit::empty(length, bits)),
+        })
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Template::Range { bits } => write!(f, "lo <= x <= hi for a secret x of {bits} bits"),
+            Template::Membership { depth } => write!(f, "a secret leaf of a Poseidon tree of depth {depth}"),
+            Template::Sum { length, bits } => write!(f, "{length} secret values of {bits} bits add up to total"),
+            Template::Threshold { length, bits } => write!(f, "{length} secret values of {bits} bits add up to at least threshold"),
+        }
+    }
+}
+
+// the bits a sum of `length` values of `bits` bits fits in
+fn sum_bits(length: usize, bits: usize) -> usize {
+    bits + (usize::BITS - (length.max(1) - 1).leading_zeros()) as usize
+}
+
+fn fits<F: PrimeField>(name: &str, value: F, bits: usize) -> Result<(), TemplateError> {
+    if value.into_bigint().num_bits() as usize > bits {
+        return Err(TemplateError::Input(format!("{name} = {value} doesn't fit in {bits} bits")));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct RangeCircuit<F: PrimeField> {
+    #[zeroize(skip)]
+    pub bits: usize,
+    #[zeroize(skip)]
+    pub lo: Option<F>,
+    #[zeroize(skip)]
+    pub hi: Option<F>,
+    pub x: Option<F>,
+}
+
+impl<F: PrimeField> RangeCircuit<F> {
+    pub fn empty(bits: usize) -> Self {
+        Self { bits, lo: None, hi: None, x: None }
+    }
+
+    pub fn new(bits: usize, x: F, lo: F, hi: F) -> Result<Self, TemplateError> {
+        for (name, value) in [("x", x), ("lo", lo), ("hi", hi)] {
+            fits(name, value, bits)?;
+        }
+        if !(lo.into_bigint() <= x.into_bigint() && x.into_bigint() <= hi.into_bigint()) {
+            return Err(TemplateError::Input(format!("x = {x} isn't between lo = {lo} and hi = {hi}")));
+        }
+        Ok(Self {
+            bits,
+            lo: Some(lo),
+            hi: Some(hi),
+            x: Some(x),
+        })
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for RangeCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let lo = FpVar::new_input(cs.clone(), || self.lo.ok_or(SynthesisError::AssignmentMissing))?;
+        let hi = FpVar::new_input(cs.clone(), || self.hi.ok_or(SynthesisError::AssignmentMissing))?;
+        let x = FpVar::new_witness(cs.clone(), || self.x.ok_or(SynthesisError::AssignmentMissing))?;
+        enforce_bits(cs.clone(), &x, self.bits)?;
+        // x - lo and hi - x wrap around to nearly p when they are "negative"
+        enforce_bits(cs.clone(), &(&x - &lo), self.bits)?;
+        enforce_bits(cs, &(&hi - &x), self.bits)
+    }
+}
+
+// `length` secret values, each range checked, and their sum
+fn range_checked_sum<F: PrimeField>(cs: &ConstraintSystemRef<F>, length: usize, bits: usize, values: Option<&Vec<F>>) -> Result<FpVar<F>, SynthesisError> {
+    let mut sum = FpVar::zero();
+    for i in 0..length {
+        let value = FpVar::new_witness(cs.clone(), || values.map(|v| v[i]).ok_or(SynthesisError::AssignmentMissing))?;
+        enforce_bits(cs.clone(), &value, bits)?;
+        sum += value;
+    }
+    Ok(sum)
+}
+
+fn check_values<F: PrimeField>(length: usize, bits: usize, values: &[F]) -> Result<F, TemplateError> {
+    if values.len() != length {
+        return Err(TemplateError::Input(format!("{} values for a template of {length}", values.len())));
+    }
+    for (i, value) in values.iter().enumerate() {
+        fits(&format!("value {i}"), *value, bits)?;
+    }
+    Ok(values.iter().sum())
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SumCircuit<F: PrimeField> {
+    #[zeroize(skip)]
+    pub length: usize,
+    #[zeroize(skip)]
+    pub bits: usize,
+    #[zeroize(skip)]
+    pub total: Option<F>,
+    pub values: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> SumCircuit<F> {
+    pub fn empty(length: usize, bits: usize) -> Self {
+        Self {
+            length,
+            bits,
+            total: None,
+            values: None,
+        }
+    }
+
+    // the total is what the values add up to
+    pub fn new(bits: usize, values: Vec<F>) -> Result<Self, TemplateError> {
+        let total = check_values(values.len(), bits, &values)?;
+        Ok(Self {
+            length: values.len(),
+            bits,
+            total: Some(total),
+            values: Some(values),
+        })
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for SumCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let total = FpVar::new_input(cs.clone(), || self.total.ok_or(SynthesisError::AssignmentMissing))?;
+        range_checked_sum(&cs, self.length, self.bits, self.values.as_ref())?.enforce_equal(&total)
+    }
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ThresholdCircuit<F: PrimeField> {
+    #[zeroize(skip)]
+    pub length: usize,
+    #[zeroize(skip)]
+    pub bits: usize,
+    #[zeroize(skip)]
+    pub threshold: Option<F>,
+    pub values: Option<Vec<F>>,
+}
+
+impl<F: PrimeField> ThresholdCircuit<F> {
+    pub fn empty(length: usize, bits: usize) -> Self {
+        Self {
+            length,
+            bits,
+            threshold: None,
+            values: None,
+        }
+    }
+
+    pub fn new(bits: usize, values: Vec<F>, threshold: F) -> Result<Self, TemplateError> {
+        let sum = check_values(values.len(), bits, &values)?;
+        fits("threshold", threshold, sum_bits(values.len(), bits))?;
+        if sum.into_bigint() < threshold.into_bigint() {
+            return Err(TemplateError::Input(format!("the values add up to {sum}, less than the threshold {threshold}")));
+        }
+        Ok(Self {
+            length: values.len(),
+            bits,
+            threshold: Some(threshold),
+            values: Some(values),
+        })
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for ThresholdCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let threshold = FpVar::new_input(cs.clone(), || self.threshold.ok_or(SynthesisError::AssignmentMissing))?;
+        let sum = range_checked_sum(&cs, self.length, self.bits, self.values.as_ref())?;
+        enforce_bits(cs, &(sum - threshold), sum_bits(self.length, self.bits))
+    }
+}
+
+#[derive(Clone)]
+pub enum TemplateCircuit<F: PrimeField + Absorb> {
+    Range(RangeCircuit<F>),
+    Membership(MembershipCircuit<F, PoseidonHasher<F>>),
+    Sum(SumCircuit<F>),
+    Threshold(ThresholdCircuit<F>),
+}
+
+impl<F: PrimeField + Absorb> TemplateCircuit<F> {
+    // a membership circuit for leaf `index` of a binary Poseidon tree over these leaves
+    pub fn membership(depth: usize, leaves: &[F], index: usize) -> Result<Self, TemplateError> {
+        Template::Membership { depth }.check::<F>()?;
+        let shape = TreeShape::binary(depth);
+        if leaves.len() > shape.leaves() || index >= leaves.len() {
+            return Err(TemplateError::Input(format!(
+                "leaf {index} of {} leaves, a tree of depth {depth} has {}",
+                leaves.len(),
+                shape.leaves()
+            )));
+        }
+        let hasher = PoseidonHasher::new(2);
+        let tree = MerkleTree::new(hasher.clone(), shape, leaves);
+        Ok(TemplateCircuit::Membership(MembershipCircuit::new(hasher, leaves[index], tree.path(index as u64))))
+    }
+
+    // the public inputs, in the order of Template::public_names, None for an empty circuit
+    pub fn public_inputs(&self) -> Option<Vec<F>> {
+        match self {
+            TemplateCircuit::Range(c) => Some(vec![c.lo?, c.hi?]),
+            TemplateCircuit::Membership(c) => Some(vec![c.root?]),
+            TemplateCircuit::Sum(c) => Some(vec![c.total?]),
+            TemplateCircuit::Threshold(c) => Some(vec![c.threshold?]),
+        }
+    }
+}
+
+impl<F: PrimeField + Absorb> ConstraintSynthesizer<F> for TemplateCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        match self {
+            TemplateCircuit::Range(c) => c.generate_constraints(cs),
+            TemplateCircuit::Membership(c) => c.generate_constraints(cs),
+            TemplateCircuit::Sum(c) => c.generate_constraints(cs),
+            TemplateCircuit::Threshold(c) => c.generate_constraints(cs),
+        }
+    }
+}
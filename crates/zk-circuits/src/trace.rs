@@ -0,0 +1,168 @@
+use std::cell::RefCell;
+use std::sync::Once;
+
+use ark_ff::Field;
+use ark_relations::r1cs::{ConstraintLayer, ConstraintSystemRef};
+use tracing::span;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+/*
+An x-ray of synthesis. ark-r1cs-std wraps nearly every gadget call in a tracing span
+with target "r1cs" (new_variable, enforce_equal, mul, to_bits_le, ...). With the trace
+feature on and RUST_LOG=trace, the first count_constraints installs two layers:
+
+  ConstraintLayer   the one from ark-relations, it remembers the span path of every
+                    constraint so cs.constraint_names() can say where each came from
+  Xray              ours, it watches the constraint system count_constraints is
+                    filling and prints, whenever a span closes, what that span
+                    allocated and enforced itself (not counting the spans inside it)
+
+and at the end every constraint is listed with its namespace path. It only watches
+the count_constraints synthesis, which runs in setup mode: the shape is the same as
+when proving and no witness value ever gets printed. Expect a lot of output, the
+document circuit alone has 22034 constraints.
+*/
+
+#[derive(Clone, Copy, Default)]
+struct Counts {
+    inputs: usize,
+    witnesses: usize,
+    constraints: usize,
+}
+
+impl Counts {
+    fn minus(self, other: Counts) -> Counts {
+        Counts {
+            inputs: self.inputs - other.inputs,
+            witnesses: self.witnesses - other.witnesses,
+            constraints: self.constraints - other.constraints,
+        }
+    }
+
+    fn plus(self, other: Counts) -> Counts {
+        Counts {
+            inputs: self.inputs + other.inputs,
+            witnesses: self.witnesses + other.witnesses,
+            constraints: self.constraints + other.constraints,
+        }
+    }
+
+    fn describe(&self) -> Option<String> {
+        let parts: Vec<String> = [(self.inputs, "input"), (self.witnesses, "witness"), (self.constraints, "constraint")]
+            .iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, what)| format!("+{n} {what}{}", if *n == 1 { "" } else if *what == "witness" { "es" } else { "s" }))
+            .collect();
+        if parts.is_empty() { None } else { Some(parts.join(", ")) }
+    }
+}
+
+// one open span: its name, the counts when it was entered, what the spans inside took
+struct Open {
+    name: &'static str,
+    start: Counts,
+    children: Counts,
+}
+
+struct Watched {
+    counts: Box<dyn Fn() -> Counts>,
+    stack: Vec<Open>,
+}
+
+thread_local! {
+    static WATCHED: RefCell<Option<Watched>> = const { RefCell::new(None) };
+}
+
+struct Xray;
+
+impl<S: tracing::Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Xray {
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if span.metadata().target() != "r1cs" {
+            return;
+        }
+        WATCHED.with(|w| {
+            if let Some(watched) = w.borrow_mut().as_mut() {
+                let start = (watched.counts)();
+                watched.stack.push(Open {
+                    name: span.name(),
+                    start,
+                    children: Counts::default(),
+                });
+            }
+        });
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if span.metadata().target() != "r1cs" {
+            return;
+        }
+        WATCHED.with(|w| {
+            if let Some(watched) = w.borrow_mut().as_mut() {
+                let now = (watched.counts)();
+                let Some(open) = watched.stack.pop() else { return };
+                let total = now.minus(open.start);
+                if let Some(line) = total.minus(open.children).describe() {
+                    let path: Vec<&str> = watched.stack.iter().map(|o| o.name).chain([open.name]).collect();
+                    println!("[r1cs] {}: {line}", path.join(" > "));
+                }
+                if let Some(parent) = watched.stack.last_mut() {
+                    parent.children = parent.children.plus(total);
+                }
+            }
+        });
+    }
+}
+
+static INSTALL: Once = Once::new();
+
+// true if RUST_LOG asks for trace output, the layers are installed on the first call
+pub fn enabled() -> bool {
+    let wanted = std::env::var("RUST_LOG").map(|v| v.contains("trace")).unwrap_or(false);
+    if wanted {
+        INSTALL.call_once(|| {
+            let subscriber = Registry::default().with(ConstraintLayer::default()).with(Xray);
+            // somebody else's subscriber wins, then there's just no x-ray
+            let _ = tracing::subscriber::set_global_default(subscriber);
+        });
+    }
+    wanted
+}
+
+// prints what is synthesized into cs until the guard is dropped, then every constraint
+pub struct Watch<F: Field> {
+    cs: ConstraintSystemRef<F>,
+}
+
+pub fn watch<F: Field>(name: &str, cs: &ConstraintSystemRef<F>) -> Option<Watch<F>> {
+    if !enabled() {
+        return None;
+    }
+    println!("[r1cs] === synthesizing {name} ===");
+    let counted = cs.clone();
+    let counts = move || Counts {
+        inputs: counted.num_instance_variables(),
+        witnesses: counted.num_witness_variables(),
+        constraints: counted.num_constraints(),
+    };
+    WATCHED.with(|w| *w.borrow_mut() = Some(Watched { counts: Box::new(counts), stack: Vec::new() }));
+    Some(Watch { cs: cs.clone() })
+}
+
+impl<F: Field> Drop for Watch<F> {
+    fn drop(&mut self) {
+        WATCHED.with(|w| *w.borrow_mut() = None);
+        // the one variable is there from the start, it's not the circuit's
+        println!(
+            "[r1cs] {} public inputs (plus the constant one), {} witnesses, {} constraints:",
+            self.cs.num_instance_variables() - 1,
+            self.cs.num_witness_variables(),
+            self.cs.num_constraints()
+        );
+        for (i, name) in self.cs.constraint_names().unwrap_or_default().iter().enumerate() {
+            println!("[r1cs]   #{i:<6} {}", if name.is_empty() { "(top level)" } else { name });
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::ToBitsGadget;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use zk_gadgets::keccak;
+use zk_gadgets::packing::{pack_bytes, pack_bytes_var};
+
+/*
+A simplified Merkle-Patricia trie, the structure ethereum keeps its state and every
+contract's storage in, and a circuit proving "slot key holds value under this root".
+
+The real trie is hexary: the key is read a nibble (4 bits) at a time from the top, a
+branch node has 16 children and its hash is keccak256 of the node, so a child slot
+holds the hash of the node below. What's left out here:
+
+  no extension nodes   every level is a branch, the trie has a fixed depth
+  no RLP               a branch hashes as its 16 child hashes one after the other,
+                       an empty child is 32 zero bytes
+  a simpler leaf       keccak256(key || value), the full key, so two keys can't share
+                       a leaf even though only the first `depth` nibbles pick the path
+
+so the circuit is the shape of a storage proof (the nodes from eth_getProof, checked
+from the root down) without the parsing. A branch is 512 bytes, four keccak
+permutations, so a level costs about 570k constraints and the leaf 142k.
+
+Root, key and value are public, as two 16 byte halves each (32 bytes don't fit the
+field), the nodes are the witness.
+*/
+
+pub const HASH_LEN: usize = 32;
+pub const CHILDREN: usize = 16;
+const HALF: usize = HASH_LEN / 2;
+
+pub type Hash = [u8; HASH_LEN];
+pub type Key = [u8; HASH_LEN];
+pub type Value = [u8; HASH_LEN];
+pub type Branch = [Hash; CHILDREN];
+
+const EMPTY: Hash = [0; HASH_LEN];
+
+// nibble `level` of the key, the high half of a byte first
+pub fn nibble(key: &Key, level: usize) -> usize {
+    let byte = key[level / 2];
+    if level.is_multiple_of(2) { byte as usize >> 4 } else { byte as usize & 0x0f }
+}
+
+pub fn leaf_hash(key: &Key, value: &Value) -> Hash {
+    keccak::keccak256(&[key.as_slice(), value].concat())
+}
+
+pub fn branch_hash(branch: &Branch) -> Hash {
+    keccak::keccak256(&branch.concat())
+}
+
+// the public inputs of the circuit: root, key and value, each as two halves
+pub fn public_inputs<F: PrimeField>(root: &Hash, key: &Key, value: &Value) -> Vec<F> {
+    [root, key, value].iter().flat_map(|bytes| pack_bytes(bytes.as_slice(), HALF)).collect()
+}
+
+// the branch nodes from the root down to the leaf, each holding the hash of the next
+#[derive(Clone, Debug, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct TrieProof {
+    pub nodes: Vec<Branch>,
+}
+
+impl TrieProof {
+    pub fn root(&self, key: &Key, value: &Value) -> Hash {
+        // the root node's hash, or the leaf's for a trie of depth 0
+        self.nodes.first().map(branch_hash).unwrap_or_else(|| leaf_hash(key, value))
+    }
+
+    // what a verifier does natively: every node holds the hash of the one below it
+    pub fn verify(&self, root: &Hash, key: &Key, value: &Value) -> bool {
+        let mut below = leaf_hash(key, value);
+        for (level, node) in self.nodes.iter().enumerate().rev() {
+            if node[nibble(key, level)] != below {
+                return false;
+            }
+            below = branch_hash(node);
+        }
+        below == *root
+    }
+}
+
+// a trie of a fixed depth, as a map from keys to values
+#[derive(Clone, Debug)]
+pub struct Trie {
+    pub depth: usize,
+    pub entries: BTreeMap<Key, Value>,
+}
+
+impl Trie {
+    pub fn new(depth: usize) -> Self {
+        assert!(depth <= 2 * HASH_LEN, "a key has {} nibbles", 2 * HASH_LEN);
+        Self {
+            depth,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn path(&self, key: &Key) -> Vec<usize> {
+        (0..self.depth).map(|level| nibble(key, level)).collect()
+    }
+
+    // sets the value, or gives back the key already at the end of the same path
+    pub fn insert(&mut self, key: Key, value: Value) -> Result<(), Key> {
+        let path = self.path(&key);
+        if let Some(other) = self.entries.keys().find(|other| **other != key && self.path(other) == path) {
+            return Err(*other);
+        }
+        self.entries.insert(key, value);
+        Ok(())
+    }
+
+    // the subtrie below `prefix`, a path of `prefix.len()` nibbles
+    fn node(&self, prefix: &[usize]) -> Branch {
+        let mut branch = [EMPTY; CHILDREN];
+        for (slot, child) in branch.iter_mut().enumerate() {
+            let path = [prefix, &[slot]].concat();
+            *child = self.hash_at(&path);
+        }
+        branch
+    }
+
+    fn hash_at(&self, path: &[usize]) -> Hash {
+        let mut below = self.entries.iter().filter(|(key, _)| self.path(key).starts_with(path));
+        match below.next() {
+            None => EMPTY,
+            Some((key, value)) if path.len() == self.depth => leaf_hash(key, value),
+            Some(_) => branch_hash(&self.node(path)),
+        }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.hash_at(&[])
+    }
+
+    pub fn prove(&self, key: &Key) -> Option<TrieProof> {
+        self.entries.contains_key(key).then(|| {
+            let path = self.path(key);
+            TrieProof {
+                nodes: (0..self.depth).map(|level| self.node(&path[..level])).collect(),
+            }
+        })
+    }
+}
+
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct StorageCircuit {
+    #[zeroize(skip)]
+    pub depth: usize,
+    #[zeroize(skip)]
+    pub root: Option<Hash>,
+    #[zeroize(skip)]
+    pub key: Option<Key>,
+    #[zeroize(skip)]
+    pub value: Option<Value>,
+    pub proof: Option<TrieProof>,
+}
+
+impl StorageCircuit {
+    pub fn empty(depth: usize) -> Self {
+        Self {
+            depth,
+            root: None,
+            key: None,
+            value: None,
+            proof: None,
+        }
+    }
+
+    pub fn new(key: Key, value: Value, proof: TrieProof) -> Self {
+        Self {
+            depth: proof.nodes.len(),
+            root: Some(proof.root(&key, &value)),
+            key: Some(key),
+            value: Some(value),
+            proof: Some(proof),
+        }
+    }
+}
+
+// 32 public bytes: witnessed, and their two packed halves equal to two public inputs
+fn public_bytes<F: PrimeField>(cs: ConstraintSystemRef<F>, bytes: Option<Hash>) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    let halves: Vec<FpVar<F>> = (0..2)
+        .map(|half| FpVar::new_input(cs.clone(), || bytes.map(|b| pack_bytes::<F>(&b, HALF)[half]).ok_or(SynthesisError::AssignmentMissing)))
+        .collect::<Result<_, _>>()?;
+    let bytes = UInt8::new_witness_vec(cs, &bytes.map(|b| b.map(Some)).unwrap_or([None; HASH_LEN]))?;
+    for (packed, half) in pack_bytes_var(&bytes, HALF)?.iter().zip(&halves) {
+        packed.enforce_equal(half)?;
+    }
+    Ok(bytes)
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for StorageCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let root = public_bytes(cs.clone(), self.root)?;
+        let key = public_bytes(cs.clone(), self.key)?;
+        let value = public_bytes(cs.clone(), self.value)?;
+
+        let mut below = keccak::keccak256_var(&[key.as_slice(), &value].concat())?;
+        for level in (0..self.depth).rev() {
+            let node = self.proof.as_ref().map(|p| p.nodes[level]);
+            let children: Vec<Vec<UInt8<F>>> = (0..CHILDREN)
+                .map(|slot| UInt8::new_witness_vec(cs.clone(), &node.map(|n| n[slot].map(Some)).unwrap_or([None; HASH_LEN])))
+                .collect::<Result<_, _>>()?;
+
+            // the four bits of this level's nibble, lowest first
+            let bits = key[level / 2].to_bits_le()?;
+            let nibble = if level.is_multiple_of(2) { &bits[4..] } else { &bits[..4] };
+            let below_halves = pack_bytes_var(&below, HALF)?;
+            for (slot, child) in children.iter().enumerate() {
+                let literals: Vec<Boolean<F>> = nibble.iter().enumerate().map(|(i, bit)| if slot >> i & 1 == 1 { bit.clone() } else { bit.not() }).collect();
+                let selected: FpVar<F> = Boolean::kary_and(&literals)?.into();
+                // the slot the key points at holds the hash of the node below
+                for (half, below) in pack_bytes_var(child, HALF)?.iter().zip(&below_halves) {
+                    (half - below).mul_equals(&selected, &FpVar::zero())?;
+                }
+            }
+            below = keccak::keccak256_var(&children.concat())?;
+        }
+        below.enforce_equal(&root)
+    }
+}
@@ -0,0 +1,206 @@
+use ark_ec::VariableBaseMSM;
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof};
+use ark_relations::r1cs::SynthesisError;
+
+/*
+Side channels, verifier edition. Groth16::verify gives up early when the number of
+public inputs is wrong, and an error can come back before a single pairing is done.
+So how long the verifier takes tells whoever sent the proof *why* it was refused,
+and a service that verifies proofs for others hands out that oracle for free.
+For these toy circuits that leaks nothing worth having (the inputs are public after
+all), but it is the same habit that matters for the checks that do touch secrets.
+
+verify_constant_work does the same work for every proof it is given: the inputs are
+padded or cut to the length the key expects, prepare_inputs and the pairing check
+always run, and the checks are combined with `&` at the very end so nothing short
+circuits. It is "constant work", not constant time: the MSM and the pairing in
+arkworks aren't written to be constant time, their cost still depends a little on the
+values. src/bin/timing.rs measures how close to constant that gets.
+
+prepare_inputs_streaming is the other verifier path here, for statements with
+hundreds or thousands of public inputs, a rollup batch say. prepare_inputs wants them
+all in a slice, the streaming one takes an iterator and folds them into L in chunks
+of STREAM_CHUNK, one MSM per chunk, so only a chunk is ever in memory and the inputs
+can be computed or read as they're needed. It is quicker too, prepare_inputs does a
+scalar multiplication per input; src/bin/streaming_inputs.rs benchmarks the two.
+*/
+
+// scalars per MSM in prepare_inputs_streaming, big enough for the MSM to pay off
+pub const STREAM_CHUNK: usize = 256;
+
+pub fn verify_constant_work<E: Pairing>(pvk: &PreparedVerifyingKey<E>, public_inputs: &[E::ScalarField], proof: &Proof<E>) -> bool {
+    let expected = pvk.vk.gamma_abc_g1.len().saturating_sub(1);
+    let right_length = public_inputs.len() == expected;
+
+    // pad with -1, the scalar with the most bits, a zero would make the MSM cheaper
+    let mut inputs = public_inputs.to_vec();
+    inputs.resize(expected, -E::ScalarField::one());
+    let pairing_ok = match Groth16::<E>::prepare_inputs(pvk, &inputs) {
+        Ok(prepared) => Groth16::<E>::verify_proof_with_prepared_inputs(pvk, proof, &prepared).unwrap_or(false),
+        // only a malformed key gets here, burn the same pairing anyway
+        Err(_) => {
+            let _ = Groth16::<E>::verify_proof_with_prepared_inputs(pvk, proof, &E::G1::zero());
+            false
+        }
+    };
+    right_length & pairing_ok
+}
+
+// gamma_abc[0] + x_1 gamma_abc[1] + ..., the same as Groth16::prepare_inputs and the
+// same error when the count is wrong, without collecting the inputs first
+pub fn prepare_inputs_streaming<E: Pairing>(
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: impl IntoIterator<Item = E::ScalarField>,
+) -> Result<E::G1, SynthesisError> {
+    let (first, bases) = pvk.vk.gamma_abc_g1.split_first().ok_or(SynthesisError::MalformedVerifyingKey)?;
+    let mut folded = E::G1::from(*first);
+    let mut chunk = Vec::with_capacity(STREAM_CHUNK);
+    let mut done = 0;
+    let mut inputs = public_inputs.into_iter();
+    loop {
+        chunk.clear();
+        chunk.extend(inputs.by_ref().take(STREAM_CHUNK));
+        if chunk.is_empty() {
+            break;
+        }
+        let end = done + chunk.len();
+        if end > bases.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        folded += E::G1::msm_unchecked(&bases[done..end], &chunk);
+        done = end;
+    }
+    if done != bases.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    Ok(folded)
+}
+
+pub fn verify_streaming<E: Pairing>(
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: impl IntoIterator<Item = E::ScalarField>,
+    proof: &Proof<E>,
+) -> Result<bool, SynthesisError> {
+    let prepared = prepare_inputs_streaming(pvk, public_inputs)?;
+    Groth16::<E>::verify_proof_with_prepared_inputs(pvk, proof, &prepared)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+    use std::time::{Duration, Instant};
+
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use ark_groth16::{VerifyingKey, prepare_verifying_key};
+    use ark_snark::SNARK;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::addition::AdditionCircuit;
+
+    // the key of the addition circuit and a proof of 17 + 2 = 19
+    fn addition() -> (PreparedVerifyingKey<Bls12_381>, Proof<Bls12_381>) {
+        let mut rng = thread_rng();
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng).unwrap();
+        let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng).unwrap();
+        (prepare_verifying_key(&vk), proof)
+    }
+
+    #[test]
+    fn the_honest_proof_is_accepted() {
+        let (pvk, proof) = addition();
+        assert!(verify_constant_work(&pvk, &[Fr::from(19u32)], &proof));
+    }
+
+    #[test]
+    fn a_wrong_input_is_refused() {
+        let (pvk, proof) = addition();
+        assert!(!verify_constant_work(&pvk, &[Fr::from(20u32)], &proof));
+    }
+
+    #[test]
+    fn too_few_inputs_are_refused() {
+        let (pvk, proof) = addition();
+        assert!(!verify_constant_work(&pvk, &[], &proof));
+    }
+
+    #[test]
+    fn too_many_inputs_are_refused() {
+        let (pvk, proof) = addition();
+        let z = Fr::from(19u32);
+        assert!(!verify_constant_work(&pvk, &[z, z], &proof));
+        assert!(!verify_constant_work(&pvk, &[z, Fr::zero()], &proof));
+    }
+
+    // a key for `n` public inputs, random points: only gamma_abc_g1 matters to the inputs
+    fn key_for(n: usize) -> PreparedVerifyingKey<Bls12_381> {
+        let mut rng = thread_rng();
+        let gamma_abc_g1 = (0..=n).map(|_| G1Projective::rand(&mut rng).into_affine()).collect();
+        prepare_verifying_key(&VerifyingKey { gamma_abc_g1, ..Default::default() })
+    }
+
+    fn inputs(n: usize) -> Vec<Fr> {
+        let mut rng = thread_rng();
+        (0..n).map(|_| Fr::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn streaming_matches_prepare_inputs() {
+        for n in [0, 1, STREAM_CHUNK, STREAM_CHUNK + 1] {
+            let (pvk, inputs) = (key_for(n), inputs(n));
+            let expected = Groth16::<Bls12_381>::prepare_inputs(&pvk, &inputs).unwrap();
+            assert_eq!(prepare_inputs_streaming(&pvk, inputs).unwrap(), expected, "{n} inputs");
+        }
+    }
+
+    #[test]
+    fn the_wrong_number_of_inputs_is_a_malformed_key() {
+        for n in [1, STREAM_CHUNK, STREAM_CHUNK + 1] {
+            let pvk = key_for(n);
+            for given in [n - 1, n + 1] {
+                let result = prepare_inputs_streaming(&pvk, inputs(given));
+                assert!(matches!(result, Err(SynthesisError::MalformedVerifyingKey)), "{given} inputs for a key of {n}");
+            }
+        }
+        // and a key without any gamma abc points takes nothing at all
+        let empty = prepare_verifying_key(&VerifyingKey::<Bls12_381>::default());
+        assert!(matches!(prepare_inputs_streaming(&empty, []), Err(SynthesisError::MalformedVerifyingKey)));
+    }
+
+    fn time(f: impl Fn() -> bool) -> Duration {
+        let start = Instant::now();
+        black_box(f());
+        start.elapsed()
+    }
+
+    // noisy, so it only runs with `cargo test --release -- --ignored` on a quiet machine
+    #[test]
+    #[ignore]
+    fn refusing_takes_as_long_as_accepting() {
+        let (pvk, proof) = addition();
+        let z = Fr::from(19u32);
+        let kinds = [("the honest input", vec![z]), ("a wrong input", vec![z + Fr::one()]), ("a missing input", vec![]), ("an extra input", vec![z, z])];
+        // interleaved, so a slow patch of the machine hits every kind
+        let mut times = vec![Vec::new(); kinds.len()];
+        for _ in 0..101 {
+            for ((_, inputs), times) in kinds.iter().zip(&mut times) {
+                times.push(time(|| verify_constant_work(&pvk, inputs, &proof)));
+            }
+        }
+        let medians: Vec<f64> = times
+            .into_iter()
+            .map(|mut times| {
+                times.sort();
+                times[times.len() / 2].as_secs_f64()
+            })
+            .collect();
+        for ((what, _), median) in kinds.iter().zip(&medians).skip(1) {
+            let ratio = median / medians[0];
+            assert!((ratio - 1.0).abs() <= 0.2, "refusing {what} takes {ratio:.2}x the time of accepting");
+        }
+    }
+}
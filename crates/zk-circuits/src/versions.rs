@@ -0,0 +1,108 @@
+use zk_core::version::{CircuitVersion, VersionedCircuit};
+use zk_gadgets::hasher::CircuitHasher;
+
+use crate::addition::AdditionCircuit;
+use crate::airdrop::AirdropCircuit;
+use crate::bitcoin::PowCircuit;
+#[cfg(feature = "bls")]
+use crate::bls_circuit::BlsVerifyCircuit;
+use crate::credential::AgeCircuit;
+use crate::document::DocumentCircuit;
+use crate::eth_address::AddressCircuit;
+use crate::membership::MembershipCircuit;
+use crate::trie::StorageCircuit;
+use crate::vss::VssCircuit;
+
+// the version and changelog of every example circuit, see zk-core/src/version.rs for
+// what the version numbers mean. Bump these whenever a circuit changes!
+
+impl<F: ark_ff::PrimeField> VersionedCircuit for AdditionCircuit<F> {
+    const ID: &'static str = "addition";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] =
+        &[(CircuitVersion::new(1, 0, 0), "x + y = z with x, y secret and z public")];
+}
+
+impl<F: ark_ff::PrimeField, H: CircuitHasher<F>> VersionedCircuit for AirdropCircuit<F, H> {
+    const ID: &'static str = "airdrop";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "H(address, secret) in a merkle tree, nullifier H(secret, airdrop id), recipient bound",
+    )];
+}
+
+impl VersionedCircuit for PowCircuit {
+    const ID: &'static str = "bitcoin-pow";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "secret 80 byte header whose double sha256 is below a public target",
+    )];
+}
+
+impl VersionedCircuit for AgeCircuit {
+    const ID: &'static str = "age";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "committed credential attributes with birth date <= a public cutoff",
+    )];
+}
+
+impl VersionedCircuit for DocumentCircuit {
+    const ID: &'static str = "document";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "Poseidon(pack(blake2s(document))) commitment plus one disclosed field",
+    )];
+}
+
+impl<F: ark_ff::PrimeField> VersionedCircuit for AddressCircuit<F> {
+    const ID: &'static str = "eth-address";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "secret secp256k1 public key whose keccak256 ends in a public address",
+    )];
+}
+
+impl VersionedCircuit for StorageCircuit {
+    const ID: &'static str = "trie-storage";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "public key and value under a public root of a fixed depth hexary keccak trie",
+    )];
+}
+
+// the same id for every hash: keys for one hasher don't fit another, but the shape of
+// the tree doesn't show up in the id either, setup parameters are the caller's business
+impl<F: ark_ff::PrimeField, H: CircuitHasher<F>> VersionedCircuit for MembershipCircuit<F, H> {
+    const ID: &'static str = "membership";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 1, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[
+        (CircuitVersion::new(1, 0, 0), "binary merkle path from a secret leaf to a public root"),
+        (CircuitVersion::new(1, 1, 0), "power of two arities, binary trees synthesize exactly as before"),
+    ];
+}
+
+impl VersionedCircuit for VssCircuit {
+    const ID: &'static str = "vss";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "Poseidon commitment to the polynomial, public shares checked by evaluation",
+    )];
+}
+
+#[cfg(feature = "bls")]
+impl VersionedCircuit for BlsVerifyCircuit {
+    const ID: &'static str = "bls-verify";
+    const VERSION: CircuitVersion = CircuitVersion::new(1, 0, 0);
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)] = &[(
+        CircuitVersion::new(1, 0, 0),
+        "BLS12-377 signature check with the signature as witness",
+    )];
+}
@@ -0,0 +1,186 @@
+use ark_bls12_381::Fr;
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ff::{Field, One, Zero};
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::UniformRand;
+use rand::Rng;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use zk_gadgets::poseidon;
+
+/*
+Shamir secret sharing with a threshold k: the dealer picks a random polynomial
+p of degree k - 1 with p(0) = secret and hands party i the share (i, p(i)).
+Any k shares determine p (and so the secret) by interpolation, k - 1 shares say
+nothing about it.
+
+Plain Shamir has a problem: nobody can tell if the dealer handed out garbage.
+Verifiable secret sharing fixes that. Here the dealer publishes a commitment to
+the polynomial, Poseidon(blinding, a_0, ..., a_{k-1}), and proves in zero
+knowledge that a set of public shares are evaluations of the committed polynomial.
+The blinding is there so the commitment doesn't leak anything about the coefficients.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Share {
+    pub x: Fr,
+    pub y: Fr,
+}
+
+// coefficients from a_0 (the secret) up to a_{k-1}, all of them as secret as a_0
+// since any k shares give them back, so they're wiped on drop
+#[derive(Clone, Debug, Zeroize, ZeroizeOnDrop)]
+pub struct Polynomial {
+    pub coeffs: Vec<Fr>,
+}
+
+impl Polynomial {
+    pub fn random<R: Rng>(secret: Fr, threshold: usize, rng: &mut R) -> Self {
+        assert!(threshold >= 1, "threshold has to be at least 1");
+        let mut coeffs = vec![secret];
+        coeffs.extend((1..threshold).map(|_| Fr::rand(rng)));
+        Self { coeffs }
+    }
+
+    pub fn secret(&self) -> Fr {
+        self.coeffs[0]
+    }
+
+    // horner's rule
+    pub fn evaluate(&self, x: Fr) -> Fr {
+        self.coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+    }
+
+    pub fn share(&self, index: u64) -> Share {
+        let x = Fr::from(index);
+        Share { x, y: self.evaluate(x) }
+    }
+}
+
+// the shares for parties 1..=n (never 0, that would be the secret itself)
+pub fn deal(polynomial: &Polynomial, n: u64) -> Vec<Share> {
+    (1..=n).map(|i| polynomial.share(i)).collect()
+}
+
+// lagrange interpolation at 0, needs at least threshold shares with distinct x
+pub fn reconstruct(shares: &[Share]) -> Fr {
+    let mut secret = Fr::zero();
+    for (i, share_i) in shares.iter().enumerate() {
+        let mut basis = Fr::one();
+        for (j, share_j) in shares.iter().enumerate() {
+            if i != j {
+                let denominator = (share_j.x - share_i.x).inverse().expect("share x values must be distinct");
+                basis *= share_j.x * denominator;
+            }
+        }
+        secret += share_i.y * basis;
+    }
+    secret
+}
+
+// commitment to the whole polynomial
+pub fn commit(config: &PoseidonConfig<Fr>, polynomial: &Polynomial, blinding: Fr) -> Fr {
+    let mut inputs = vec![blinding];
+    inputs.extend_from_slice(&polynomial.coeffs);
+    poseidon::hash(config, &inputs)
+}
+
+/*
+The circuit. Public inputs: the commitment, then x and y of every share.
+Witnesses: the coefficients and the blinding.
+The threshold and the number of shares are fixed at setup.
+Dropping the circuit wipes the witnesses, the public parts are left alone.
+*/
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct VssCircuit {
+    #[zeroize(skip)]
+    pub threshold: usize,
+    #[zeroize(skip)]
+    pub num_shares: usize,
+    #[zeroize(skip)]
+    pub poseidon: PoseidonConfig<Fr>,
+    pub polynomial: Option<Polynomial>,
+    pub blinding: Option<Fr>,
+    #[zeroize(skip)]
+    pub commitment: Option<Fr>,
+    #[zeroize(skip)]
+    pub shares: Option<Vec<Share>>,
+}
+
+impl VssCircuit {
+    pub fn empty(threshold: usize, num_shares: usize, poseidon: PoseidonConfig<Fr>) -> Self {
+        Self {
+            threshold,
+            num_shares,
+            poseidon,
+            polynomial: None,
+            blinding: None,
+            commitment: None,
+            shares: None,
+        }
+    }
+
+    pub fn new(poseidon: PoseidonConfig<Fr>, polynomial: Polynomial, blinding: Fr, shares: Vec<Share>) -> Self {
+        let commitment = commit(&poseidon, &polynomial, blinding);
+        Self {
+            threshold: polynomial.coeffs.len(),
+            num_shares: shares.len(),
+            poseidon,
+            polynomial: Some(polynomial),
+            blinding: Some(blinding),
+            commitment: Some(commitment),
+            shares: Some(shares),
+        }
+    }
+}
+
+// the public input vector the verifier has to build, in the order the circuit allocates it
+pub fn public_inputs(commitment: Fr, shares: &[Share]) -> Vec<Fr> {
+    let mut inputs = vec![commitment];
+    for share in shares {
+        inputs.push(share.x);
+        inputs.push(share.y);
+    }
+    inputs
+}
+
+impl ConstraintSynthesizer<Fr> for VssCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let commitment = FpVar::new_input(cs.clone(), || self.commitment.ok_or(SynthesisError::AssignmentMissing))?;
+        let mut shares = Vec::with_capacity(self.num_shares);
+        for i in 0..self.num_shares {
+            let share = self.shares.as_ref().map(|s| s[i]);
+            let x = FpVar::new_input(cs.clone(), || share.map(|s| s.x).ok_or(SynthesisError::AssignmentMissing))?;
+            let y = FpVar::new_input(cs.clone(), || share.map(|s| s.y).ok_or(SynthesisError::AssignmentMissing))?;
+            shares.push((x, y));
+        }
+
+        let blinding = FpVar::new_witness(cs.clone(), || self.blinding.ok_or(SynthesisError::AssignmentMissing))?;
+        let mut coeffs = Vec::with_capacity(self.threshold);
+        for i in 0..self.threshold {
+            let c = self.polynomial.as_ref().map(|p| p.coeffs[i]);
+            coeffs.push(FpVar::new_witness(cs.clone(), || c.ok_or(SynthesisError::AssignmentMissing))?);
+        }
+
+        // the coefficients have to open the commitment
+        let mut inputs = vec![blinding];
+        inputs.extend(coeffs.iter().cloned());
+        poseidon::hash_var(cs.clone(), &self.poseidon, &inputs)?.enforce_equal(&commitment)?;
+
+        // and every share has to be an evaluation of that polynomial (horner again,
+        // one multiplication constraint per coefficient)
+        for (x, y) in &shares {
+            let mut acc = FpVar::zero();
+            for c in coeffs.iter().rev() {
+                acc = acc * x + c;
+            }
+            acc.enforce_equal(y)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{ProvingKey, VerifyingKey};
+use num_bigint::BigUint;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::bundle::{self as groth16_bundle, canonical};
+use zk_circuits::curves::Curve;
+use zk_core::artifact;
+use zk_core::bundle::{Bundle, BundledProof};
+use zk_core::version::VersionedCircuit;
+
+use crate::CliResult;
+
+/*
+zk-toy prove-bundle / verify-bundle: the addition statement proved on several curves
+at once, one groth16 proof each and the public z stated once (zk-core/src/bundle.rs):
+
+  zk-toy setup                     BLS12-381 keys, addition.pk / .vk
+  zk-toy setup --curve bn254       BN254 keys, addition-bn254.pk / .vk (evm feature)
+  zk-toy prove-bundle --x 17 --y 2 bundle.bin with a proof for every --curve
+  zk-toy verify-bundle             checks each proof against the key of its curve
+
+which is the shape of a system with a verifier on chain (BN254, what the ethereum
+precompiles do) and others off chain (BLS12-381). x and y have to be below the
+smaller of the scalar fields, and so does x + y: z has to be the same integer for
+every verifier, not just the same up to each curve's own reduction.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum CurveChoice {
+    #[value(name = "bls12-381")]
+    Bls12_381,
+    Bn254,
+}
+
+impl CurveChoice {
+    pub fn key_base(&self) -> &'static str {
+        match self {
+            CurveChoice::Bls12_381 => "addition",
+            CurveChoice::Bn254 => "addition-bn254",
+        }
+    }
+}
+
+fn statement() -> String {
+    format!("{} {}", AdditionCircuit::<Fr>::ID, AdditionCircuit::<Fr>::VERSION)
+}
+
+// the keys of the addition circuit on curve E, under base.pk and base.vk (the
+// BLS12-381 ones are plain zk-toy setup)
+#[cfg(feature = "evm")]
+fn setup_on<E: Curve>(keys_dir: &Path, base: &str) -> CliResult<()> {
+    use ark_groth16::Groth16;
+    use ark_snark::SNARK;
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(AdditionCircuit::<E::ScalarField>::empty(), &mut rng)?;
+    std::fs::create_dir_all(keys_dir)?;
+    let build = zk_circuits::build_info(E::NAME);
+    artifact::save_versioned::<AdditionCircuit<E::ScalarField>, _>(&pk, &build, keys_dir.join(format!("{base}.pk")))?;
+    artifact::save_versioned::<AdditionCircuit<E::ScalarField>, _>(&vk, &build, keys_dir.join(format!("{base}.vk")))?;
+    println!("✓ Setup complete: {} keys for {} written to {}", E::NAME, statement(), keys_dir.display());
+    Ok(())
+}
+
+#[cfg(feature = "evm")]
+pub fn setup_bn254(keys_dir: &Path) -> CliResult<()> {
+    setup_on::<ark_bn254::Bn254>(keys_dir, CurveChoice::Bn254.key_base())
+}
+
+#[cfg(not(feature = "evm"))]
+pub fn setup_bn254(_: &Path) -> CliResult<()> {
+    Err("zk-toy was built without the evm feature, which has the BN254 curve".into())
+}
+
+// x or y in the scalar field F of `curve`, refused when it doesn't fit as it is
+fn element<F: PrimeField>(name: &str, value: &BigUint, curve: &str) -> CliResult<F> {
+    if *value >= F::MODULUS.into() {
+        return Err(format!("{name} doesn't fit in the {curve} scalar field, a bundle needs values every curve can take").into());
+    }
+    Ok(F::from(value.clone()))
+}
+
+fn prove_on<E: Curve>(bundle: &mut Bundle, keys_dir: &Path, base: &str, x: &BigUint, y: &BigUint) -> CliResult<()> {
+    let pk: ProvingKey<E> = artifact::load_versioned::<AdditionCircuit<E::ScalarField>, _>(keys_dir.join(format!("{base}.pk")))?;
+    let circuit = AdditionCircuit::new(element("x", x, E::NAME)?, element("y", y, E::NAME)?);
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    if canonical(&[z]) != [x + y] {
+        return Err(format!("x + y wraps around in the {} scalar field, the curves would disagree about z", E::NAME).into());
+    }
+    Ok(groth16_bundle::prove(bundle, &pk, circuit, &[z], &mut thread_rng())?)
+}
+
+pub fn prove(keys_dir: &Path, x: Fr, y: Fr, curves: &[CurveChoice], out: &Path) -> CliResult<()> {
+    if curves.is_empty() {
+        return Err("a bundle needs at least one --curve".into());
+    }
+    let (x, y): (BigUint, BigUint) = (x.into(), y.into());
+    // z as an integer, the same for every curve or the bundle is refused
+    let z = &x + &y;
+    let mut bundle = Bundle::new(statement(), std::slice::from_ref(&z));
+    for curve in curves {
+        match curve {
+            CurveChoice::Bls12_381 => prove_on::<Bls12_381>(&mut bundle, keys_dir, curve.key_base(), &x, &y)?,
+            CurveChoice::Bn254 => prove_bn254(&mut bundle, keys_dir, &x, &y)?,
+        }
+    }
+    bundle.save(out)?;
+    let on = bundle.proofs.iter().map(|p| format!("{}/{}", p.backend, p.curve)).collect::<Vec<_>>().join(", ");
+    println!("✓ Bundle for public z = {z} written to {}: {on}", out.display());
+    Ok(())
+}
+
+#[cfg(feature = "evm")]
+fn prove_bn254(bundle: &mut Bundle, keys_dir: &Path, x: &BigUint, y: &BigUint) -> CliResult<()> {
+    prove_on::<ark_bn254::Bn254>(bundle, keys_dir, CurveChoice::Bn254.key_base(), x, y)
+}
+
+#[cfg(not(feature = "evm"))]
+fn prove_bn254(_: &mut Bundle, _: &Path, _: &BigUint, _: &BigUint) -> CliResult<()> {
+    Err("zk-toy was built without the evm feature, which has the BN254 curve".into())
+}
+
+fn verify_on<E: Curve>(bundle: &Bundle, member: &BundledProof, keys_dir: &Path, base: &str) -> CliResult<bool> {
+    let vk: VerifyingKey<E> = artifact::load_versioned::<AdditionCircuit<E::ScalarField>, _>(keys_dir.join(format!("{base}.vk")))?;
+    Ok(groth16_bundle::verify(bundle, member, &vk)?)
+}
+
+pub fn verify(keys_dir: &Path, path: PathBuf) -> CliResult<()> {
+    let bundle = Bundle::load(&path)?;
+    if bundle.statement != statement() {
+        return Err(format!("the bundle is for '{}', this zk-toy checks {}", bundle.statement, statement()).into());
+    }
+    if bundle.proofs.is_empty() {
+        return Err("the bundle has no proofs in it".into());
+    }
+    println!("bundle for {} with public inputs {:?}", bundle.statement, bundle.public_inputs);
+    let mut failed = 0;
+    for member in &bundle.proofs {
+        let checked = match member.curve.as_str() {
+            <Bls12_381 as Curve>::NAME => verify_on::<Bls12_381>(&bundle, member, keys_dir, CurveChoice::Bls12_381.key_base()),
+            #[cfg(feature = "evm")]
+            <ark_bn254::Bn254 as Curve>::NAME => verify_on::<ark_bn254::Bn254>(&bundle, member, keys_dir, CurveChoice::Bn254.key_base()),
+            other => Err(format!("no verifier for {other} in this build").into()),
+        };
+        match checked {
+            Ok(true) => println!("  ✓ {}/{}: valid", member.backend, member.curve),
+            Ok(false) => {
+                failed += 1;
+                println!("  ✗ {}/{}: invalid", member.backend, member.curve);
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {}/{}: {e}", member.backend, member.curve);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} of {} proofs in the bundle didn't verify", bundle.proofs.len()).into());
+    }
+    println!("✓ All {} proofs in the bundle are valid for z = {}", bundle.proofs.len(), bundle.public_inputs.join(", "));
+    Ok(())
+}
+
+// every curve this build can prove on, the default for prove-bundle
+pub fn built_curves() -> Vec<CurveChoice> {
+    let mut curves = vec![CurveChoice::Bls12_381];
+    if cfg!(feature = "evm") {
+        curves.push(CurveChoice::Bn254);
+    }
+    curves
+}
+
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use ark_bls12_381::Fr;
+use clap::Subcommand;
+use zk_circuits::challenges::{Challenge, Verdict, judge};
+use zk_core::witness::Witness;
+
+use crate::CliResult;
+
+/*
+Prove it wrong: the broken circuits from zk-circuits/src/challenges.rs as a game.
+
+  zk-toy challenge list                 the challenges and what they are worth
+  zk-toy challenge show booleanity      the claim, the constraints, an honest witness
+  zk-toy challenge try booleanity --witness exploit.json
+
+An exploit is a witness file like the one show prints (every value, public ones too,
+decimal or 0x hex) that satisfies the constraints while the claim is false. It scores
+the challenge's points, an honest witness or one the constraints catch scores nothing.
+*/
+
+#[derive(Subcommand)]
+pub enum ChallengeAction {
+    /// List the challenges
+    List,
+    /// Show what a challenge claims and how it is constrained, with an honest witness to start from
+    Show {
+        name: String,
+        /// Give a hint too
+        #[arg(long)]
+        hint: bool,
+    },
+    /// Check an exploit witness against a challenge and score it
+    Try {
+        name: String,
+        #[arg(long, default_value = "exploit.json")]
+        witness: std::path::PathBuf,
+    },
+}
+
+pub fn run(action: ChallengeAction) -> CliResult<()> {
+    match action {
+        ChallengeAction::List => {
+            list();
+            Ok(())
+        }
+        ChallengeAction::Show { name, hint } => show(&name, hint),
+        ChallengeAction::Try { name, witness } => attempt(&name, &witness),
+    }
+}
+
+fn find(name: &str) -> CliResult<Challenge> {
+    Challenge::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = Challenge::ALL.iter().map(|c| c.name()).collect();
+        format!("no challenge '{name}', there are {}", names.join(", ")).into()
+    })
+}
+
+fn list() {
+    for c in Challenge::ALL {
+        println!("{:<12} {:>3} points  {}", c.name(), c.points(), c.claim());
+    }
+    let total: u32 = Challenge::ALL.iter().map(|c| c.points()).sum();
+    println!("\n{total} points in all, `zk-toy challenge show <name>` to start");
+}
+
+fn show(name: &str, hint: bool) -> CliResult<()> {
+    let challenge = find(name)?;
+    println!("{} ({} points)", challenge.name(), challenge.points());
+    println!("  the verifier believes: {}", challenge.claim());
+    println!("  the circuit enforces:  {}", challenge.constraints());
+    let (public, secret): (Vec<_>, Vec<_>) = challenge.values().iter().partition(|(_, public)| *public);
+    let names = |values: Vec<&(&str, bool)>| values.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ");
+    println!("  public: {}   secret: {}", names(public), names(secret));
+    println!("  values live in the BLS12-381 scalar field, the largest is p - 1 = {}", -Fr::from(1u32));
+    let mut honest = Witness::new();
+    for ((name, _), value) in challenge.values().iter().zip(challenge.honest()) {
+        honest.insert(name, value);
+    }
+    println!("\nan honest witness, change it into an exploit:\n{}", honest.to_json());
+    if hint {
+        println!("\nhint: {}", challenge.hint());
+    }
+    Ok(())
+}
+
+fn attempt(name: &str, path: &Path) -> CliResult<()> {
+    let challenge = find(name)?;
+    let witness = Witness::load(path)?;
+    let values = challenge
+        .values()
+        .iter()
+        .map(|(name, _)| witness.get::<Fr>(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    match judge(challenge, &values)? {
+        Verdict::Exploit => {
+            println!("✓ Exploit! Every constraint holds and the claim is false.");
+            println!("  {}: {} points", challenge.name(), challenge.points());
+        }
+        Verdict::Honest => {
+            println!("✗ The constraints hold, but so does the claim: that's an honest witness.");
+            println!("  {}: 0 of {} points", challenge.name(), challenge.points());
+        }
+        Verdict::Caught => {
+            println!("✗ The circuit caught it, some constraint doesn't hold.");
+            println!("  {}: 0 of {} points", challenge.name(), challenge.points());
+        }
+    }
+    Ok(())
+}
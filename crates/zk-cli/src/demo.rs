@@ -0,0 +1,77 @@
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::CliResult;
+use crate::http::{self, Response};
+
+/*
+zk-toy demo: the addition example as a page for the classroom. Students type x and
+y, the browser proves x + y = z with the wasm build of crates/zk-wasm and shows the
+proof bytes and whether it verifies, and can change z or the proof to watch it fail.
+Nothing is sent anywhere, the server only hands out two static files:
+
+  index.html     the page (zk-cli/demo/index.html, built into zk-toy)
+  zk_wasm.wasm   the prover, built separately since cargo can't build it for us here:
+                   rustup target add wasm32-unknown-unknown
+                   cargo build -p zk-wasm --target wasm32-unknown-unknown --release
+
+`zk-toy demo --serve` serves them on --addr, without --serve they're written to --out
+for any static file server (browsers won't load wasm from a file:// page). The
+server is the std only HTTP of http.rs, GET and nothing else.
+*/
+
+const PAGE: &str = include_str!("../demo/index.html");
+const WASM_FILE: &str = "zk_wasm.wasm";
+const BUILD: &str = "cargo build -p zk-wasm --target wasm32-unknown-unknown --release";
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn run(out: &Path, wasm: &Path, serve: bool, addr: &str) -> CliResult<()> {
+    let prover = std::fs::read(wasm)
+        .map_err(|e| format!("can't read the wasm prover {}: {e}\nbuild it with: {BUILD}", wasm.display()))?;
+    if !prover.starts_with(b"\0asm") {
+        return Err(format!("{} isn't a wasm module, build it with: {BUILD}", wasm.display()).into());
+    }
+    if serve {
+        return serve_page(addr, Arc::new(prover));
+    }
+    std::fs::create_dir_all(out)?;
+    std::fs::write(out.join("index.html"), PAGE)?;
+    std::fs::write(out.join(WASM_FILE), &prover)?;
+    println!("✓ Demo written to {} ({} byte prover)", out.display(), prover.len());
+    println!("  serve the directory over http, e.g. python3 -m http.server -d {}", out.display());
+    Ok(())
+}
+
+fn serve_page(addr: &str, prover: Arc<Vec<u8>>) -> CliResult<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("demo on http://{}/ ({} byte prover)", listener.local_addr()?, prover.len());
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let prover = prover.clone();
+        thread::spawn(move || handle(stream, &prover));
+    }
+    Ok(())
+}
+
+fn handle(stream: TcpStream, prover: &[u8]) {
+    let response = match http::read_request(&stream, 0, READ_TIMEOUT) {
+        Ok(request) if request.method != "GET" || !request.body.is_empty() => {
+            Response::error(405, "method_not_allowed", "the demo only serves files, GET them")
+        }
+        Ok(request) => {
+            let response = match request.path.as_str() {
+                "/" | "/index.html" => Response::text("text/html; charset=utf-8", PAGE.to_string()),
+                // application/wasm is what WebAssembly.instantiateStreaming insists on
+                path if path.strip_prefix('/') == Some(WASM_FILE) => Response::bytes("application/wasm", prover.to_vec()),
+                path => Response::error(404, "not_found", format!("no file {path}")),
+            };
+            println!("GET {} {} {}", request.path, response.status, response.code.unwrap_or("ok"));
+            response
+        }
+        Err(response) => response,
+    };
+    let _ = http::write_response(&stream, &response);
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use ark_bls12_381::Fr;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::airdrop::AirdropCircuit;
+use zk_circuits::credential::AgeCircuit;
+use zk_circuits::diff::{self, Shape};
+use zk_circuits::document::DocumentCircuit;
+use zk_circuits::examples::document::sample_layout;
+use zk_circuits::membership::MembershipCircuit;
+use zk_circuits::vss::VssCircuit;
+use zk_gadgets::hasher::{MimcHasher, PoseidonHasher};
+use zk_gadgets::merkle::TreeShape;
+use zk_gadgets::poseidon::poseidon_config;
+
+use crate::CliResult;
+
+/*
+zk-toy shape / zk-toy diff, the cli side of zk-circuits/src/diff.rs. A side of a diff
+is either a circuit name, synthesized by this build, or a shape json saved earlier:
+
+  git checkout v1 && zk-toy shape membership --out before.json
+  git checkout my-branch && zk-toy diff --before before.json --after membership
+
+The circuits are synthesized the way the examples set them up (same tree shapes,
+same vss threshold, the sample document layout).
+*/
+
+pub const CIRCUITS: &[&str] = &[
+    "addition",
+    "document",
+    "vss",
+    "age",
+    "membership",
+    "membership-4ary",
+    "membership-mimc",
+    "airdrop",
+];
+
+pub fn circuit_shape(name: &str) -> CliResult<Shape> {
+    let poseidon = poseidon_config::<Fr>(2);
+    let shape = match name {
+        "addition" => Shape::synthesize(name, AdditionCircuit::<Fr>::empty())?,
+        "document" => Shape::synthesize(
+            name,
+            DocumentCircuit {
+                layout: sample_layout(),
+                poseidon,
+                document: None,
+                commitment: None,
+                field_value: None,
+            },
+        )?,
+        "vss" => Shape::synthesize(name, VssCircuit::empty(3, 5, poseidon))?,
+        "age" => Shape::synthesize(name, AgeCircuit::empty(poseidon))?,
+        "membership" => Shape::synthesize(name, MembershipCircuit::empty(PoseidonHasher::<Fr>::new(2), TreeShape::binary(8)))?,
+        "membership-4ary" => Shape::synthesize(name, MembershipCircuit::empty(PoseidonHasher::<Fr>::new(4), TreeShape::new(4, 4)))?,
+        "membership-mimc" => Shape::synthesize(name, MembershipCircuit::empty(MimcHasher::<Fr>::default(), TreeShape::binary(8)))?,
+        "airdrop" => Shape::synthesize(name, AirdropCircuit::empty(PoseidonHasher::<Fr>::new(2), TreeShape::binary(4)))?,
+        _ => return Err(format!("no circuit called '{name}', try one of {}", CIRCUITS.join(", ")).into()),
+    };
+    Ok(shape)
+}
+
+// a saved shape if there is a file by that name, the circuit otherwise
+fn side(what: &str) -> CliResult<Shape> {
+    if Path::new(what).is_file() {
+        Ok(serde_json::from_str(&std::fs::read_to_string(what)?)?)
+    } else {
+        circuit_shape(what)
+    }
+}
+
+pub fn save(circuit: &str, out: Option<&Path>) -> CliResult<()> {
+    let json = serde_json::to_string_pretty(&circuit_shape(circuit)?)?;
+    match out {
+        Some(path) => {
+            std::fs::write(path, json)?;
+            println!("✓ Shape of {circuit} written to {}", path.display());
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+pub fn run(before: &str, after: &str) -> CliResult<()> {
+    println!("{}", diff::diff(&side(before)?, &side(after)?));
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::VerifyingKey;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::explain::explain;
+use zk_circuits::expr::Program;
+use zk_core::artifact::{self, Validation};
+use zk_core::spec::InputSpec;
+
+use crate::{CliResult, ProofArtifact};
+
+/*
+zk-toy explain, a proof file taken apart for the classroom (zk-circuits/src/explain.rs):
+the statement, the curve, the public inputs by name, whether each proof point is in
+its group, and the verification equation with its result.
+
+  zk-toy explain proof.bin                                 a zk-toy prove proof, addition.vk
+  zk-toy explain proof.bin --statement "public z; ..."     an expr proof, expr-<fingerprint>.vk
+  zk-toy explain proof.bin --template templates/range.toml a template proof, range-32.vk
+
+A proof file is only the proof and the input values, it doesn't say what it proves,
+so the statement comes from the flags like it does for verify. The build stamps of
+the proof and the key come first, with what differs from the build explaining them. Without the key in
+--keys-dir everything but the equation is still explained.
+*/
+
+// the names and the verifying key of the statement the flags pick
+fn statement(keys_dir: &Path, expr: Option<&str>, template: Option<&Path>) -> CliResult<(InputSpec, PathBuf)> {
+    if let Some(text) = expr {
+        let program = Program::<Fr>::parse(text)?;
+        return Ok((program.input_spec(), crate::expr::key_base(&program, keys_dir).with_extension("vk")));
+    }
+    if let Some(descriptor) = template {
+        let template = crate::template::load_template(descriptor)?;
+        return Ok((template.input_spec(), keys_dir.join(format!("{}.vk", template.name()))));
+    }
+    Ok((AdditionCircuit::<Fr>::input_spec(), keys_dir.join("addition.vk")))
+}
+
+pub fn run(proof: &Path, keys_dir: &Path, expr: Option<&str>, template: Option<&Path>) -> CliResult<()> {
+    let (spec, key) = statement(keys_dir, expr, template)?;
+    // unchecked, so a point outside its subgroup is explained rather than refused
+    let artifact: ProofArtifact = artifact::load_with(proof, Validation::Unchecked)
+        .map_err(|e| format!("{} isn't a BLS12-381 groth16 proof file: {e}", proof.display()))?;
+    println!("proof {}", proof.display());
+    crate::print_stamp(artifact::read_stamp(proof)?.as_ref());
+    let vk = if key.exists() {
+        let bytes = std::fs::read(&key)?;
+        let (stamp, _) = artifact::split_stamp(&bytes)?;
+        let (header, rest) = artifact::split_header(&bytes)?;
+        let vk: VerifyingKey<Bls12_381> = artifact::deserialize_checked(rest)?;
+        match header {
+            Some(header) => println!("key {}, made for {} {}", key.display(), header.circuit_id, header.version),
+            None => println!("key {}", key.display()),
+        }
+        crate::print_stamp(stamp.as_ref());
+        Some(vk)
+    } else {
+        println!("no key at {}, the equation can't be checked", key.display());
+        None
+    };
+    println!("\n{}", explain(&spec, &artifact, vk.as_ref()));
+    Ok(())
+}
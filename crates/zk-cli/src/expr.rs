@@ -0,0 +1,244 @@
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_snark::SNARK;
+use clap::Subcommand;
+use rand::thread_rng;
+use zk_circuits::examples::count_constraints;
+use zk_circuits::expr::{ExprCircuit, Program};
+use zk_circuits::verifier::verify_constant_work;
+use zk_core::artifact;
+use zk_core::input::{InputError, Reduction, parse_field_with};
+
+use crate::{CliResult, ProofArtifact};
+
+/*
+Ad-hoc statements in the expression language of zk-circuits/src/expr.rs, proved
+without writing any Rust:
+
+  zk-toy expr setup  "public z; secret x, y; x*x + y*3 == z"
+  zk-toy expr prove  "public z; secret x, y; x*x + y*3 == z" --set x=3 --set y=4
+  zk-toy expr verify "public z; secret x, y; x*x + y*3 == z"
+  zk-toy expr solve  "public z; secret x, y; x*x + y*3 == z" --set x=3 --set z=21
+
+The statement is part of every step, the verifier has to know what was proved. Keys
+are circuit specific, so setup writes expr-<fingerprint>.pk and .vk, the fingerprint
+a hash of the statement (spacing and comments aside), and prove and verify pick the
+keys of their statement or say there are none. Values come from --set or from a
+json witness file like the ones zk-toy witness writes ({ "x": "3", "y": "4" }), --set
+wins when both have one. Whatever follows from those is derived (Program::solve, z =
+21 above), solve prints all of them without proving. With the trace feature, prove and
+solve take --assignment FILE to write every variable of the circuit as json, and
+--coverage to print which constraints were tight or trivially satisfied for the values.
+*/
+
+#[derive(Subcommand)]
+pub enum ExprAction {
+    /// Generate the keys for a statement
+    Setup {
+        statement: String,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+    },
+    /// Prove the statement holds for secret values
+    Prove {
+        statement: String,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        /// name=value (decimal, 0x hex, -5 or 1e18) for every variable that can't be derived
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        values: Vec<String>,
+        /// Read values from a json witness file as well
+        #[arg(long)]
+        witness: Option<PathBuf>,
+        /// Take numbers mod the field modulus instead of refusing the ones that are too big
+        #[arg(long)]
+        reduce: bool,
+        #[arg(long, default_value = "proof.bin")]
+        out: PathBuf,
+        /// Also write every variable with its value and namespace as json (needs the trace feature)
+        #[arg(long)]
+        assignment: Option<PathBuf>,
+        /// Print which constraints these values exercise, by namespace (needs the trace feature)
+        #[arg(long)]
+        coverage: bool,
+    },
+    /// Print the values that follow from the ones given, without proving
+    Solve {
+        statement: String,
+        /// name=value (decimal, 0x hex, -5 or 1e18) for the values to derive the others from
+        #[arg(long = "set", value_name = "NAME=VALUE")]
+        values: Vec<String>,
+        #[arg(long)]
+        witness: Option<PathBuf>,
+        /// Take numbers mod the field modulus instead of refusing the ones that are too big
+        #[arg(long)]
+        reduce: bool,
+        /// Write every variable of the circuit with its value and namespace as json (needs the trace feature)
+        #[arg(long)]
+        assignment: Option<PathBuf>,
+        /// Print which constraints these values exercise, by namespace (needs the trace feature)
+        #[arg(long)]
+        coverage: bool,
+    },
+    /// Verify a proof of the statement
+    Verify {
+        statement: String,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value = "proof.bin")]
+        proof: PathBuf,
+    },
+}
+
+pub fn run(action: ExprAction) -> CliResult<()> {
+    match action {
+        ExprAction::Setup { statement, keys_dir } => setup(&Program::parse(&statement)?, &keys_dir),
+        ExprAction::Prove {
+            statement,
+            keys_dir,
+            values,
+            witness,
+            reduce,
+            out,
+            assignment,
+            coverage,
+        } => {
+            let program = Program::parse(&statement)?;
+            let (values, derived) = read_values(&program, &values, witness.as_deref(), crate::reduction(reduce))?;
+            if !derived.is_empty() {
+                println!("derived {} from the other values", derived.join(", "));
+            }
+            if let Some(path) = assignment {
+                write_assignment(&program, &values, &path)?;
+            }
+            if coverage {
+                print_coverage(&program, &values)?;
+            }
+            prove(program, values, &keys_dir, &out)
+        }
+        ExprAction::Solve {
+            statement,
+            values,
+            witness,
+            reduce,
+            assignment,
+            coverage,
+        } => {
+            let program = Program::parse(&statement)?;
+            let (values, derived) = read_values(&program, &values, witness.as_deref(), crate::reduction(reduce))?;
+            for (v, value) in program.variables.iter().zip(&values) {
+                let how = if derived.contains(&v.name) { "derived" } else { "given" };
+                println!("{} {} = {value} ({how})", if v.public { "public" } else { "secret" }, v.name);
+            }
+            if coverage {
+                println!();
+                print_coverage(&program, &values)?;
+            }
+            match assignment {
+                Some(path) => write_assignment(&program, &values, &path),
+                None => Ok(()),
+            }
+        }
+        ExprAction::Verify { statement, keys_dir, proof } => verify(&Program::parse(&statement)?, &keys_dir, &proof),
+    }
+}
+
+// expr-<fingerprint>, the .pk and .vk of the statement
+pub fn key_base(program: &Program<Fr>, keys_dir: &Path) -> PathBuf {
+    keys_dir.join(format!("expr-{}", program.fingerprint()))
+}
+
+fn existing_key(program: &Program<Fr>, keys_dir: &Path, extension: &str) -> CliResult<PathBuf> {
+    let path = key_base(program, keys_dir).with_extension(extension);
+    if !path.exists() {
+        return Err(format!("no keys for `{program}` in {}, run zk-toy expr setup first", keys_dir.display()).into());
+    }
+    Ok(path)
+}
+
+fn setup(program: &Program<Fr>, keys_dir: &Path) -> CliResult<()> {
+    let empty = ExprCircuit::empty(program.clone());
+    let constraints = count_constraints(&empty)?;
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(empty, &mut thread_rng())?;
+    std::fs::create_dir_all(keys_dir)?;
+    let path = key_base(program, keys_dir);
+    artifact::save(&pk, &crate::build_info(), path.with_extension("pk"))?;
+    artifact::save(&vk, &crate::build_info(), path.with_extension("vk"))?;
+    println!("✓ Keys for `{program}` ({constraints} constraints) written to {}.pk and .vk", path.display());
+    Ok(())
+}
+
+// --set over the witness file and the rest solved for, in the order of the program's
+// variables, and the names of the solved ones
+fn read_values(
+    program: &Program<Fr>,
+    set: &[String],
+    witness: Option<&Path>,
+    reduction: Reduction,
+) -> CliResult<(Vec<Fr>, Vec<String>)> {
+    let mut named: Vec<(String, Fr)> = Vec::new();
+    if let Some(path) = witness {
+        let witness = crate::load_witness(path)?;
+        for v in &program.variables {
+            match witness.get_with(&v.name, reduction) {
+                Ok(value) => named.push((v.name.clone(), value)),
+                Err(InputError::Missing(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    for assignment in set {
+        let (name, value) = assignment.split_once('=').ok_or_else(|| format!("--set {assignment}: expected name=value"))?;
+        let value = parse_field_with(value, reduction).map_err(|e| format!("--set {name}: {e}"))?;
+        named.push((name.trim().to_string(), value));
+    }
+    let values = program.solve(named.iter().map(|(name, value)| (name.as_str(), *value)))?;
+    let derived = program.variables.iter().filter(|v| named.iter().all(|(name, _)| *name != v.name)).map(|v| v.name.clone()).collect();
+    Ok((values, derived))
+}
+
+fn write_assignment(program: &Program<Fr>, values: &[Fr], path: &Path) -> CliResult<()> {
+    let circuit = ExprCircuit::new(program.clone(), values.to_vec())?;
+    crate::write_assignment(&program.to_string(), circuit, path)
+}
+
+fn print_coverage(program: &Program<Fr>, values: &[Fr]) -> CliResult<()> {
+    let circuit = ExprCircuit::new(program.clone(), values.to_vec())?;
+    crate::print_coverage(&program.to_string(), circuit)
+}
+
+fn prove(program: Program<Fr>, values: Vec<Fr>, keys_dir: &Path, out: &Path) -> CliResult<()> {
+    let pk: ProvingKey<Bls12_381> = artifact::load(existing_key(&program, keys_dir, "pk")?)?;
+    let public_inputs = program.public_inputs(&values);
+    let shown = named_inputs(&program, &public_inputs);
+    let circuit = ExprCircuit::new(program, values)?;
+    let artifact = ProofArtifact {
+        proof: Groth16::<Bls12_381>::prove(&pk, circuit, &mut thread_rng())?,
+        public_inputs,
+    };
+    artifact::save(&artifact, &crate::build_info(), out)?;
+    println!("✓ Proof for {shown} written to {}", out.display());
+    Ok(())
+}
+
+fn verify(program: &Program<Fr>, keys_dir: &Path, proof: &Path) -> CliResult<()> {
+    let vk: VerifyingKey<Bls12_381> = artifact::load(existing_key(program, keys_dir, "vk")?)?;
+    let artifact: ProofArtifact = artifact::load(proof)?;
+    if verify_constant_work(&prepare_verifying_key(&vk), &artifact.public_inputs, &artifact.proof) {
+        println!("✓ The proof is valid: `{program}` holds for {}", named_inputs(program, &artifact.public_inputs));
+        Ok(())
+    } else {
+        Err("proof is invalid".into())
+    }
+}
+
+// "z = 21, w = 5", or "no public values"
+fn named_inputs(program: &Program<Fr>, inputs: &[Fr]) -> String {
+    let names = program.public_names();
+    if names.is_empty() {
+        return "no public values".to_string();
+    }
+    names.iter().zip(crate::display_inputs(inputs)).map(|(name, value)| format!("{name} = {value}")).collect::<Vec<_>>().join(", ")
+}
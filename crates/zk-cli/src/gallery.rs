@@ -0,0 +1,164 @@
+use std::path::Path;
+use std::time::Duration;
+
+use zk_core::report::RunReport;
+
+use crate::CliResult;
+
+/*
+The examples as a table of contents: every example binary in src/bin, grouped by
+track and ordered by difficulty within it, with what it takes as input and how long
+it runs. The times come from benchmark reports, bench/baseline.json as it was when
+zk-toy was built or a fresh `zk-toy bench --json` with --bench. Examples the bench
+doesn't run (the ones that are about checking rather than proving one statement)
+have no time.
+
+  zk-toy gallery                   everything
+  zk-toy gallery --track hashes    one track
+
+When an example binary is added, it gets a line here too.
+*/
+
+const BASELINE: &str = include_str!("../../../bench/baseline.json");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Track {
+    Arithmetics,
+    Hashes,
+    Merkle,
+    Protocols,
+    Applications,
+    // the binaries that check the verifier and the circuits rather than prove something
+    Checks,
+}
+
+impl Track {
+    const ALL: [Track; 6] = [Track::Arithmetics, Track::Hashes, Track::Merkle, Track::Protocols, Track::Applications, Track::Checks];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Track::Arithmetics => "ARITHMETICS",
+            Track::Hashes => "HASHES",
+            Track::Merkle => "MERKLE",
+            Track::Protocols => "PROTOCOLS",
+            Track::Applications => "APPLICATIONS",
+            Track::Checks => "CHECKS",
+        }
+    }
+}
+
+pub struct Example {
+    pub name: &'static str,
+    pub track: Track,
+    // 1 to 3: start here, needs the earlier ones, needs some reading around
+    pub difficulty: u8,
+    pub inputs: &'static str,
+    // the features it needs that a default build doesn't have
+    pub features: &'static [&'static str],
+    // the reports of `zk-toy bench` its time is the sum of
+    pub bench: &'static [&'static str],
+}
+
+const fn example(
+    name: &'static str,
+    track: Track,
+    difficulty: u8,
+    inputs: &'static str,
+    features: &'static [&'static str],
+    bench: &'static [&'static str],
+) -> Example {
+    Example {
+        name,
+        track,
+        difficulty,
+        inputs,
+        features,
+        bench,
+    }
+}
+
+pub const EXAMPLES: &[Example] = &[
+    example("addition", Track::Arithmetics, 1, "secret x, y; public z = x + y", &[], &["addition"]),
+    example("sorted", Track::Arithmetics, 2, "secret list of 40 values below 2^32", &[], &[]),
+    example("arithmetizations", Track::Arithmetics, 2, "none, one statement as two circuits, side by side", &[], &[]),
+    example("expression", Track::Arithmetics, 1, "secret and public values of a statement like x*x + y*3 == z", &[], &[]),
+    example("bristol", Track::Arithmetics, 3, "secret first input of a boolean circuit; public other inputs, outputs", &[], &[]),
+    example("document_hash", Track::Hashes, 1, "secret 64 byte document; public commitment, disclosed field", &[], &["document_hash"]),
+    example("poseidon_padded", Track::Hashes, 2, "secret list of up to 8 elements and its length", &[], &[]),
+    example("bitcoin", Track::Hashes, 2, "secret 80 byte block header; public target", &[], &["bitcoin_pow"]),
+    example("eth_address", Track::Hashes, 3, "secret 64 byte secp256k1 public key; public address", &[], &[]),
+    example(
+        "merkle",
+        Track::Merkle,
+        1,
+        "secret leaf and path; public root",
+        &[],
+        &["merkle-poseidon", "merkle-poseidon-4ary", "merkle-mimc"],
+    ),
+    example("curves", Track::Merkle, 2, "secret leaf and path; public root, on three curves", &[], &[]),
+    example("storage_proof", Track::Merkle, 3, "secret trie nodes; public root, key, value", &[], &[]),
+    example("transcripts", Track::Protocols, 1, "none, one schnorr proof under three transcript hashes", &[], &[]),
+    example("weak_fiat_shamir", Track::Protocols, 2, "none, a schnorr proof forged for a key nobody holds", &[], &[]),
+    example("vss", Track::Protocols, 2, "secret polynomial, blinding; public commitment, shares", &[], &["vss"]),
+    example("dkg", Track::Protocols, 3, "secret dealings of 5 parties; public commitments, shares", &[], &["dkg"]),
+    example("bls", Track::Protocols, 3, "secret signature; public key, message", &[], &["bls"]),
+    example("tally", Track::Applications, 2, "secret votes, blindings; public ballot commitments, total", &[], &[]),
+    example("passport", Track::Applications, 2, "secret attributes, salt; public commitment, cutoff date", &[], &["passport"]),
+    example("airdrop", Track::Applications, 2, "secret address, secret, path; public root, nullifier, recipient", &[], &["airdrop"]),
+    example("evm_encoding", Track::Applications, 2, "a BN254 proof and public inputs to encode", &[], &["evm_encoding"]),
+    example("bundle", Track::Applications, 2, "secret x and y; public z, proved on BN254 and BLS12-381", &[], &[]),
+    example("cross_check", Track::Applications, 3, "a BN254 proof for snarkjs (needs node)", &["cross-check"], &[]),
+    example("edge_cases", Track::Checks, 1, "none, crafted proofs", &[], &[]),
+    example("unlinkability", Track::Checks, 2, "none, proofs of the same statements", &[], &[]),
+    example("timing", Track::Checks, 2, "none, right and wrong proofs", &[], &[]),
+    example("compressed_inputs", Track::Checks, 3, "the airdrop claim and 32 squares, with one hashed public input", &[], &[]),
+    example("streaming_inputs", Track::Checks, 2, "secret square roots; public squares, up to 4096 of them", &[], &[]),
+    example("validation", Track::Checks, 2, "none, points outside the subgroup", &[], &[]),
+    example("boundaries", Track::Checks, 2, "none, boundary values for every circuit", &[], &[]),
+    example("inputs", Track::Checks, 1, "none, numbers written every way zk-toy reads them", &[], &[]),
+    example("fixtures", Track::Checks, 1, "none, the students, balances and census the examples share", &[], &[]),
+    example("chaos", Track::Checks, 2, "none, false statements with a constraint left out", &["chaos"], &[]),
+    example("compat", Track::Checks, 2, "the files in compat/", &[], &[]),
+    example("build_info", Track::Checks, 1, "none, a key and proof saved and read back, and compat/", &[], &[]),
+];
+
+// setup, prove and verify of every report the example is made of, None if one is missing
+fn run_time(example: &Example, reports: &[RunReport]) -> Option<Duration> {
+    if example.bench.is_empty() {
+        return None;
+    }
+    example
+        .bench
+        .iter()
+        .map(|name| reports.iter().find(|r| r.example == *name).map(|r| r.setup + r.prove + r.verify))
+        .sum()
+}
+
+// the reports in `bench`, or the baseline zk-toy was built with
+pub fn reports(bench: Option<&Path>) -> CliResult<Vec<RunReport>> {
+    Ok(match bench {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        None => serde_json::from_str(BASELINE)?,
+    })
+}
+
+fn stars(difficulty: u8) -> String {
+    (1..=3).map(|i| if i <= difficulty { '★' } else { '☆' }).collect()
+}
+
+pub fn run(track: Option<Track>, bench: Option<&Path>) -> CliResult<()> {
+    let reports = reports(bench)?;
+    for track in Track::ALL.into_iter().filter(|t| track.is_none_or(|only| only == *t)) {
+        println!("{}", track.title());
+        let mut examples: Vec<&Example> = EXAMPLES.iter().filter(|e| e.track == track).collect();
+        examples.sort_by_key(|e| e.difficulty);
+        for e in examples {
+            let time = run_time(e, &reports).map(|t| format!("~{t:.1?}")).unwrap_or_else(|| "-".to_string());
+            println!("  {} {:<16} {:>9}  {}", stars(e.difficulty), e.name, time, e.inputs);
+            let features: String = e.features.iter().map(|f| format!("--features {f} ")).collect();
+            println!("      cargo run --release {features}--bin {}", e.name);
+        }
+        println!();
+    }
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/*
+Just enough HTTP/1.1 for server.rs and demo.rs, on std's TcpStream: one request per
+connection, a body only with Content-Length (no chunked uploads), json responses
+(text for the metrics, the demo's page and its wasm), the connection closed after
+each. It's a toy server for demos on a trusted network, not something to put on the
+internet; a real one would sit behind a proper HTTP server anyway.
+
+A request has one deadline for all of it. A read timeout alone would only bound each
+read, and a client sending a byte every few seconds could hold its connection open
+for as long as it liked.
+*/
+
+const MAX_HEAD: usize = 8 * 1024;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+    // the error code of an error response, for the metrics
+    pub code: Option<&'static str>,
+}
+
+impl Response {
+    pub fn json(status: u16, value: &serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+            code: None,
+        }
+    }
+
+    pub fn text(content_type: &'static str, body: String) -> Self {
+        Self::bytes(content_type, body.into_bytes())
+    }
+
+    pub fn bytes(content_type: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body,
+            code: None,
+        }
+    }
+
+    // {"error": code, "message": ...}, the shape of every error the server returns
+    pub fn error(status: u16, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code: Some(code),
+            ..Self::json(status, &serde_json::json!({ "error": code, "message": message.into() }))
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    }
+}
+
+// the stream, with every read waiting at most until the deadline
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+fn timed_out(e: &std::io::Error) -> bool {
+    matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut)
+}
+
+fn too_slow() -> Response {
+    Response::error(408, "request_timeout", "the request took too long to arrive")
+}
+
+// reads one request, refusing heads over 8 KiB, bodies over `max_body` bytes and
+// requests that take longer than `timeout` to arrive with the response to send instead
+pub fn read_request(stream: &TcpStream, max_body: usize, timeout: Duration) -> Result<Request, Response> {
+    let deadline = Deadline { stream, deadline: Instant::now() + timeout };
+    let mut reader = BufReader::new(deadline.take((MAX_HEAD + max_body) as u64));
+    let mut head = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return Err(Response::error(400, "bad_request", "the connection closed before the request ended")),
+            Ok(_) => {}
+            Err(e) if timed_out(&e) => return Err(too_slow()),
+            Err(_) => return Err(Response::error(400, "bad_request", "the request isn't text")),
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push(line.trim_end().to_string());
+        if head.iter().map(String::len).sum::<usize>() > MAX_HEAD {
+            return Err(Response::error(413, "head_too_large", "the request head is over 8 KiB"));
+        }
+    }
+
+    let mut request_line = head.first().map(|l| l.split_whitespace()).ok_or_else(|| Response::error(400, "bad_request", "no request line"))?;
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(Response::error(400, "bad_request", "the request line should be METHOD PATH VERSION"));
+    };
+    let length = head[1..]
+        .iter()
+        .filter_map(|h| h.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>())
+        .transpose()
+        .map_err(|_| Response::error(400, "bad_request", "Content-Length isn't a number"))?
+        .unwrap_or(0);
+    if length > max_body {
+        return Err(Response::error(413, "body_too_large", format!("the body is {length} bytes, the limit is {max_body}")));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| {
+        if timed_out(&e) { too_slow() } else { Response::error(400, "bad_request", "the body is shorter than Content-Length") }
+    })?;
+    Ok(Request {
+        method: method.to_string(),
+        // the query string isn't used by any endpoint
+        path: path.split('?').next().unwrap_or(path).to_string(),
+        body,
+    })
+}
+
+pub fn write_response(mut stream: &TcpStream, response: &Response) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+    )?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    // a connection whose client runs `client`, and the server's end of it
+    fn connection(client: impl FnOnce(TcpStream) + Send + 'static) -> TcpStream {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || client(TcpStream::connect(address).unwrap()));
+        listener.accept().unwrap().0
+    }
+
+    #[test]
+    fn a_request_reads() {
+        let stream = connection(|mut client| {
+            client.write_all(b"POST /expr/prove?x=1 HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}").unwrap();
+        });
+        let request = read_request(&stream, 64, Duration::from_secs(5)).unwrap_or_else(|e| panic!("{}", e.status));
+        assert_eq!((request.method.as_str(), request.path.as_str(), request.body.as_slice()), ("POST", "/expr/prove", b"{}".as_slice()));
+    }
+
+    #[test]
+    fn a_trickle_of_bytes_runs_into_the_deadline() {
+        // every byte well within the timeout, the whole request far past it
+        let stream = connection(|mut client| {
+            for byte in b"GET /health HTTP/1.1\r\n\r\n" {
+                if client.write_all(&[*byte]).is_err() {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        });
+        let start = Instant::now();
+        let refused = read_request(&stream, 0, Duration::from_millis(300)).err().expect("the request is too slow");
+        assert_eq!((refused.status, refused.code), (408, Some("request_timeout")));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn a_body_that_stops_coming_runs_into_the_deadline() {
+        let stream = connection(|mut client| {
+            client.write_all(b"POST /expr/prove HTTP/1.1\r\nContent-Length: 10\r\n\r\n{").unwrap();
+            thread::sleep(Duration::from_secs(2));
+        });
+        let refused = read_request(&stream, 64, Duration::from_millis(200)).err().expect("the body never ends");
+        assert_eq!(refused.status, 408);
+    }
+}
@@ -0,0 +1,907 @@
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey, prepare_verifying_key};
+#[cfg(feature = "trace")]
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_snark::SNARK;
+use clap::{Args, Parser, Subcommand};
+use num_bigint::BigUint;
+use rand::thread_rng;
+use zeroize::Zeroize;
+#[cfg(feature = "encryption")]
+use zeroize::Zeroizing;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::curves::Curve;
+use zk_circuits::examples;
+use zk_circuits::key_info::KeyInfo;
+use zk_circuits::matrix_cache::MatrixCache;
+use zk_circuits::verifier::verify_constant_work;
+use zk_core::artifact;
+use zk_core::build_info::BuildInfo;
+use zk_core::input::{InputError, Reduction, parse_field_with};
+use zk_core::report::{self, RunReport};
+use zk_core::version::{self, VersionedCircuit};
+use zk_core::witness::Witness;
+use zk_gadgets::hasher::{MimcHasher, PoseidonHasher};
+use zk_gadgets::merkle::TreeShape;
+
+mod bundle;
+mod challenge;
+#[cfg(feature = "demo")]
+mod demo;
+#[cfg(feature = "trace")]
+mod diff;
+mod explain;
+mod expr;
+mod gallery;
+#[cfg(any(feature = "server", feature = "demo"))]
+mod http;
+#[cfg(feature = "server")]
+mod metrics;
+#[cfg(feature = "params")]
+mod params;
+mod passport;
+#[cfg(feature = "qr")]
+mod qr;
+#[cfg(feature = "server")]
+mod registry;
+mod repl;
+#[cfg(feature = "server")]
+mod server;
+mod synth;
+mod template;
+
+type ProofArtifact = Groth16Artifact<Bls12_381>;
+
+// the stamp on everything the cli saves (zk-core/src/build_info.rs), all of it BLS12-381
+fn build_info() -> BuildInfo {
+    zk_circuits::build_info(Bls12_381::NAME)
+}
+
+/*
+A small command line tool for the addition example, split into the three steps
+of the protocol so they can run at different times (or on different machines):
+
+  zk-toy setup              writes addition.pk and addition.vk (--curve bn254 for BN254 keys)
+  zk-toy prove --x 17 --y 2 writes proof.bin (or --witness witness.json)
+  zk-toy witness --x 17 --y 2  saves x and y for later, --encrypt to encrypt them
+  zk-toy verify             checks proof.bin against addition.vk
+  zk-toy prove-bundle       one proof per curve of the same x + y = z in bundle.bin,
+                            verify-bundle checks them all (bundle.rs)
+  zk-toy migrate            says whether old keys still fit the circuit
+  zk-toy inspect-key K      what a proving (or verifying) key is made of and why it's big
+  zk-toy explain proof.bin  the proof taken apart: named inputs, its points, the pairing check
+  zk-toy bench              runs every example and prints sizes and timings,
+                            --compare bench/baseline.json flags regressions,
+                            --cache DIR proves the big ones from cached matrices
+  zk-toy params fetch       downloads pinned powers of tau for universal setups
+  zk-toy passport ...       the age check from src/bin/passport.rs, step by step
+  zk-toy synth membership depth=20  a circuit's counts and estimated key sizes and
+                            proving time, without a setup
+                            (--versus method=table: two arithmetizations side by side)
+  zk-toy serve              the addition and expr steps over HTTP, with limits (server.rs)
+  zk-toy demo --serve       a page that proves the addition example in the browser
+  zk-toy shape / diff       what a change did to a circuit, namespace by namespace
+  zk-toy challenge ...      broken circuits to find exploit witnesses for (challenge.rs)
+  zk-toy gallery            every example by track and difficulty, with run times
+  zk-toy expr ...           setup, prove and verify for "x*x + y*3 == z" and the like
+  zk-toy template ...       the same for range, membership, sum and threshold statements
+                            from a descriptor file (templates/)
+
+Encrypted witnesses (the `encryption` feature) take their passphrase from the
+ZK_TOY_PASSPHRASE environment variable, for saving and for proving. The secrets are
+wiped from memory once the proof is written, as far as we can reach them (the
+prover makes its own copies we can't get at).
+
+The `params` feature is for the universal setup backends: it fetches a pinned and
+hash checked powers of tau file from the Hermez ceremony (see params.rs), or with
+--insecure-local makes a toy one in the same format.
+
+The `trace` feature adds shape and diff (see diff.rs): the constraint counts of a
+circuit by namespace, saved as json, and the difference between two of those. It
+also lets expr prove, expr solve and template prove write the full assignment of the
+circuit with --assignment FILE, every variable with its value and namespace, for
+looking at in a notebook (zk-circuits/src/assignment.rs), and print with --coverage
+which constraints that witness actually exercised, by namespace (coverage.rs).
+
+The `server` feature adds serve, a small HTTP service proving and verifying addition
+and expr statements, with limits on body sizes, connections, concurrent jobs, job
+time and circuit size so it can run on a shared machine (see server.rs), and
+Prometheus metrics at /metrics (metrics.rs), and a registry of verifying keys for
+other people's circuits to verify proofs against (registry.rs).
+
+The `demo` feature adds demo, the addition example as a web page for the classroom:
+x and y are typed in, the browser proves with the wasm build of zk-wasm and shows the
+proof and whether it verifies (see demo.rs for building the wasm).
+
+With the `qr` feature the proof can also be shown as a qr code and verified
+from a png of it, which is nice for live demos.
+*/
+
+#[derive(Parser)]
+#[command(name = "zk-toy", about = "toy zk-proof examples")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the proving and verifying keys for the addition circuit
+    Setup {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        /// The curve to make keys for, bn254 needs the evm feature
+        #[arg(long, value_enum, default_value = "bls12-381")]
+        curve: bundle::CurveChoice,
+    },
+    /// Prove that you know x and y adding up to the public z = x + y
+    Prove {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        /// Secret x: decimal, 0x hex, -5 or 1e18, has to be smaller than the field modulus
+        #[arg(long, required_unless_present = "witness", conflicts_with = "witness", allow_hyphen_values = true)]
+        x: Option<String>,
+        /// Secret y, same rules as x
+        #[arg(long, required_unless_present = "witness", conflicts_with = "witness", allow_hyphen_values = true)]
+        y: Option<String>,
+        /// Read x and y from a json witness file instead
+        #[arg(long)]
+        witness: Option<PathBuf>,
+        /// Take numbers mod the field modulus instead of refusing the ones that are too big
+        #[arg(long)]
+        reduce: bool,
+        #[arg(long, default_value = "proof.bin")]
+        out: PathBuf,
+        /// Print the proof as a qr code in the terminal (needs the qr feature)
+        #[arg(long)]
+        qr: bool,
+        /// Also save the proof as a qr code png (needs the qr feature)
+        #[arg(long)]
+        qr_png: Option<PathBuf>,
+    },
+    /// Save x and y to a witness file to prove later
+    Witness {
+        /// Secret x: decimal, 0x hex, -5 or 1e18, has to be smaller than the field modulus
+        #[arg(long, allow_hyphen_values = true)]
+        x: String,
+        /// Secret y, same rules as x
+        #[arg(long, allow_hyphen_values = true)]
+        y: String,
+        /// Take numbers mod the field modulus instead of refusing the ones that are too big
+        #[arg(long)]
+        reduce: bool,
+        #[arg(long, default_value = "witness.json")]
+        out: PathBuf,
+        /// Encrypt the file with the passphrase in ZK_TOY_PASSPHRASE (needs the encryption feature)
+        #[arg(long)]
+        encrypt: bool,
+    },
+    /// Prove x + y = z on several curves at once, one bundle with a proof for each
+    ProveBundle {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        /// Secret x: decimal, 0x hex, -5 or 1e18, has to be smaller than the scalar field modulus of every curve in the bundle
+        #[arg(long, required_unless_present = "witness", conflicts_with = "witness", allow_hyphen_values = true)]
+        x: Option<String>,
+        /// Secret y, same rules as x
+        #[arg(long, required_unless_present = "witness", conflicts_with = "witness", allow_hyphen_values = true)]
+        y: Option<String>,
+        /// Read x and y from a json witness file instead
+        #[arg(long)]
+        witness: Option<PathBuf>,
+        /// Take numbers mod the BLS12-381 field modulus instead of refusing the ones that are too big
+        #[arg(long)]
+        reduce: bool,
+        /// The curves to prove on, every one this build has when left out
+        #[arg(long = "curve", value_enum)]
+        curves: Vec<bundle::CurveChoice>,
+        #[arg(long, default_value = "bundle.bin")]
+        out: PathBuf,
+    },
+    /// Verify every proof in a bundle against the verifying key of its curve
+    VerifyBundle {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value = "bundle.bin")]
+        bundle: PathBuf,
+    },
+    /// Verify a proof against the verifying key
+    Verify {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value = "proof.bin")]
+        proof: PathBuf,
+        /// Read the proof from a qr code png instead of a proof file (needs the qr feature)
+        #[arg(long)]
+        from_qr: Option<PathBuf>,
+    },
+    /// Explain whether the keys in a directory still fit the current circuit version
+    Migrate {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        /// Add a version header to unversioned 0.1.0 keys in place (no new setup needed)
+        #[arg(long)]
+        upgrade: bool,
+    },
+    /// Show what a Groth16 key file holds: points per query, sizes, curve and fingerprint
+    InspectKey {
+        /// A .pk (or .vk) written by any of the setup commands
+        key: PathBuf,
+    },
+    /// Take a proof file apart: statement, curve, named public inputs, point checks and the pairing equation
+    Explain {
+        /// A proof written by prove, expr prove or template prove
+        proof: PathBuf,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        /// The expr statement the proof is for, like "public z; secret x, y; x*x + y == z"
+        #[arg(long, conflicts_with = "template")]
+        statement: Option<String>,
+        /// The template descriptor the proof is for (templates/range.toml)
+        #[arg(long)]
+        template: Option<PathBuf>,
+    },
+    /// Run every example flow once and report constraints, sizes and timings
+    Bench {
+        /// Print the reports as a json array instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Flag regressions against the reports in this file (an earlier `bench --json`)
+        #[arg(long)]
+        compare: Option<PathBuf>,
+        /// How much slower proving or verifying may get before it's flagged, in percent
+        #[arg(long, default_value_t = 50.0)]
+        threshold: f64,
+        /// Keep the constraint matrices of the bigger examples here and prove from them
+        #[arg(long, value_name = "DIR")]
+        cache: Option<PathBuf>,
+    },
+    /// The passport age check: issue a credential, prove an age from it, verify that
+    Passport {
+        #[command(subcommand)]
+        action: passport::PassportAction,
+    },
+    /// Prove it wrong: find witnesses that fool deliberately broken circuits
+    Challenge {
+        #[command(subcommand)]
+        action: challenge::ChallengeAction,
+    },
+    /// Prove arithmetic statements written as text, like "public z; secret x, y; x*x + y*3 == z"
+    Expr {
+        #[command(subcommand)]
+        action: expr::ExprAction,
+    },
+    /// Prove a range, membership, sum or threshold statement described in a toml or json file
+    Template {
+        #[command(subcommand)]
+        action: template::TemplateAction,
+    },
+    /// List the examples by track and difficulty, with their inputs and run times
+    Gallery {
+        /// Only this track
+        #[arg(long, value_enum)]
+        track: Option<gallery::Track>,
+        /// Take the run times from these reports (a `bench --json`) instead of bench/baseline.json
+        #[arg(long)]
+        bench: Option<PathBuf>,
+    },
+    /// Field and curve arithmetic line by line: scalars, points, pairings, hashes and commitments
+    Repl,
+    /// Synthesize a circuit without a setup and estimate its key sizes and proving time
+    Synth {
+        /// addition, document, vss, age, bitcoin, membership, airdrop, range, sum or threshold
+        circuit: String,
+        /// name=value, like depth=16 or bits=64 (the defaults are what the examples use)
+        params: Vec<String>,
+        /// Synthesize again with these name=value changed and compare the two, like method=table or arity=4
+        #[arg(long, num_args = 1..)]
+        versus: Vec<String>,
+        /// Calibrate the proving time on these reports (a `bench --json`) instead of bench/baseline.json
+        #[arg(long)]
+        bench: Option<PathBuf>,
+    },
+    /// Save the shape of a circuit (counts by namespace, matrix hash) as json (needs the trace feature)
+    Shape {
+        /// addition, document, vss, age, membership, membership-4ary, membership-mimc or airdrop
+        circuit: String,
+        /// Write to a file instead of printing
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Compare two circuit shapes, each a circuit name or a saved shape json (needs the trace feature)
+    Diff {
+        #[arg(long)]
+        before: String,
+        #[arg(long)]
+        after: String,
+    },
+    /// Prove and verify over HTTP (needs the server feature)
+    Serve(ServeArgs),
+    /// The addition example as a web page that proves in the browser (needs the demo feature)
+    Demo {
+        /// Where to write the page and the prover
+        #[arg(long, default_value = "demo")]
+        out: PathBuf,
+        /// The wasm prover, from cargo build -p zk-wasm --target wasm32-unknown-unknown --release
+        #[arg(long, default_value = "target/wasm32-unknown-unknown/release/zk_wasm.wasm")]
+        wasm: PathBuf,
+        /// Serve the page on --addr instead of writing it to --out
+        #[arg(long)]
+        serve: bool,
+        #[arg(long, default_value = "127.0.0.1:8000")]
+        addr: String,
+    },
+    /// Public parameters (powers of tau) for universal setups (needs the params feature)
+    Params {
+        #[command(subcommand)]
+        action: ParamsAction,
+    },
+}
+
+// the address and the limits of zk-toy serve
+#[derive(Args)]
+struct ServeArgs {
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: String,
+    /// Where to look for the addition keys of zk-toy setup
+    #[arg(long, default_value = ".")]
+    keys_dir: PathBuf,
+    /// Seconds a request waits for its setup or proof before it gets a timeout error
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+    /// Setups and proofs running at the same time, more are refused as busy
+    #[arg(long, default_value_t = 2)]
+    max_jobs: usize,
+    /// Requests handled at the same time
+    #[arg(long, default_value_t = 16)]
+    max_connections: usize,
+    /// The biggest statement, in constraints, the server sets up and proves
+    #[arg(long, default_value_t = 100_000)]
+    max_constraints: usize,
+    /// The biggest request body, in bytes
+    #[arg(long, default_value_t = 64 * 1024)]
+    max_body: usize,
+    /// Where registered verifying keys are kept
+    #[arg(long, default_value = "registry")]
+    registry: PathBuf,
+}
+
+#[derive(Subcommand)]
+enum ParamsAction {
+    /// Download the Hermez powers of tau file for 2^power constraints and check its hash
+    Fetch {
+        #[arg(long, default_value_t = 12)]
+        power: u32,
+        #[arg(long, default_value = "params")]
+        dir: PathBuf,
+        /// blake2b-512 of the file, for powers that have no pin built in
+        #[arg(long)]
+        blake2b: Option<String>,
+        /// Don't download, make a local file from a tau we know (insecure, for testing only)
+        #[arg(long)]
+        insecure_local: bool,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Setup { keys_dir, curve } => match curve {
+            bundle::CurveChoice::Bls12_381 => setup(keys_dir),
+            bundle::CurveChoice::Bn254 => bundle::setup_bn254(&keys_dir),
+        },
+        Command::Prove {
+            keys_dir,
+            x,
+            y,
+            witness,
+            reduce,
+            out,
+            qr,
+            qr_png,
+        } => read_secrets(x, y, witness, reduction(reduce))
+            .and_then(|(x, y)| prove(keys_dir, x, y, out))
+            .and_then(|artifact| show_qr(&artifact, qr, qr_png)),
+        Command::Witness { x, y, reduce, out, encrypt } => save_witness(x, y, reduction(reduce), out, encrypt),
+        Command::ProveBundle {
+            keys_dir,
+            x,
+            y,
+            witness,
+            reduce,
+            curves,
+            out,
+        } => read_secrets(x, y, witness, reduction(reduce)).and_then(|(x, y)| {
+            let curves = if curves.is_empty() { bundle::built_curves() } else { curves };
+            bundle::prove(&keys_dir, x, y, &curves, &out)
+        }),
+        Command::VerifyBundle { keys_dir, bundle } => bundle::verify(&keys_dir, bundle),
+        Command::Verify { keys_dir, proof, from_qr } => {
+            let artifact = match from_qr {
+                Some(png) => read_qr(png),
+                None => artifact::load(&proof).map_err(Into::into),
+            };
+            artifact.and_then(|artifact| verify(keys_dir, artifact))
+        }
+        Command::Migrate { keys_dir, upgrade } => migrate(keys_dir, upgrade),
+        Command::InspectKey { key } => inspect_key(&key),
+        Command::Explain {
+            proof,
+            keys_dir,
+            statement,
+            template,
+        } => explain::run(&proof, &keys_dir, statement.as_deref(), template.as_deref()),
+        Command::Bench {
+            json,
+            compare,
+            threshold,
+            cache,
+        } => bench(json, compare.as_deref(), threshold, cache.map(MatrixCache::new).as_ref()),
+        Command::Passport { action } => passport::run(action),
+        Command::Challenge { action } => challenge::run(action),
+        Command::Expr { action } => expr::run(action),
+        Command::Template { action } => template::run(action),
+        Command::Gallery { track, bench } => gallery::run(track, bench.as_deref()),
+        Command::Repl => repl::run(),
+        Command::Serve(args) => serve(args),
+        Command::Demo { out, wasm, serve, addr } => demo(&out, &wasm, serve, &addr),
+        Command::Synth {
+            circuit,
+            params,
+            versus,
+            bench,
+        } => synth::run(&circuit, &params, &versus, bench.as_deref()),
+        Command::Shape { circuit, out } => save_shape(&circuit, out.as_deref()),
+        Command::Diff { before, after } => diff_shapes(&before, &after),
+        Command::Params {
+            action: ParamsAction::Fetch {
+                power,
+                dir,
+                blake2b,
+                insecure_local,
+            },
+        } => fetch_params(power, dir, blake2b, insecure_local),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+fn setup(keys_dir: PathBuf) -> CliResult<()> {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut rng)?;
+    std::fs::create_dir_all(&keys_dir)?;
+    artifact::save_versioned::<AdditionCircuit, _>(&pk, &build_info(), keys_dir.join("addition.pk"))?;
+    artifact::save_versioned::<AdditionCircuit, _>(&vk, &build_info(), keys_dir.join("addition.vk"))?;
+    println!(
+        "✓ Setup complete: keys for {} {} written to {}",
+        AdditionCircuit::<Fr>::ID,
+        AdditionCircuit::<Fr>::VERSION,
+        keys_dir.display()
+    );
+    Ok(())
+}
+
+// --reduce, for the commands that read numbers
+fn reduction(reduce: bool) -> Reduction {
+    if reduce { Reduction::Reduce } else { Reduction::Reject }
+}
+
+// x and y either from the flags or from a witness file, canonical field elements either way
+fn read_secrets(x: Option<String>, y: Option<String>, witness: Option<PathBuf>, reduction: Reduction) -> CliResult<(Fr, Fr)> {
+    match (x, y, witness) {
+        (Some(x), Some(y), None) => Ok((parse_field_with(&x, reduction)?, parse_field_with(&y, reduction)?)),
+        (None, None, Some(path)) => {
+            let witness = load_witness(&path)?;
+            Ok((witness.get_with("x", reduction)?, witness.get_with("y", reduction)?))
+        }
+        _ => Err("give either --x and --y or --witness".into()),
+    }
+}
+
+fn prove(keys_dir: PathBuf, mut x: Fr, mut y: Fr, out: PathBuf) -> CliResult<ProofArtifact> {
+    let mut rng = thread_rng();
+    let pk: ProvingKey<Bls12_381> = artifact::load_versioned::<AdditionCircuit, _>(keys_dir.join("addition.pk"))?;
+    let circuit = AdditionCircuit::new(x, y);
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng);
+    // the circuit was moved into the prover, our own copies of the secrets we can wipe
+    x.zeroize();
+    y.zeroize();
+    let artifact = ProofArtifact { proof: proof?, public_inputs: vec![z] };
+    artifact::save(&artifact, &build_info(), &out)?;
+    println!("✓ Proof for public z = {} written to {}", decimal(&z), out.display());
+    Ok(artifact)
+}
+
+#[cfg(feature = "encryption")]
+const PASSPHRASE_VAR: &str = "ZK_TOY_PASSPHRASE";
+
+fn save_witness(mut x: String, mut y: String, reduction: Reduction, out: PathBuf, encrypt: bool) -> CliResult<()> {
+    let parsed = (parse_field_with::<Fr>(&x, reduction), parse_field_with::<Fr>(&y, reduction));
+    x.zeroize();
+    y.zeroize();
+    let (mut x, mut y) = (parsed.0?, parsed.1?);
+    let mut witness = Witness::new();
+    witness.insert("x", x);
+    witness.insert("y", y);
+    x.zeroize();
+    y.zeroize();
+    if encrypt {
+        save_encrypted(&witness, &out)?;
+        println!("✓ Encrypted witness written to {}", out.display());
+    } else {
+        witness.save(&out)?;
+        println!("✓ Witness written to {} (in the clear, --encrypt to encrypt it)", out.display());
+    }
+    Ok(())
+}
+
+fn load_witness(path: &Path) -> CliResult<Witness> {
+    match Witness::load(path) {
+        Err(InputError::Encrypted(_)) => load_encrypted(path),
+        other => Ok(other?),
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn passphrase() -> CliResult<Zeroizing<String>> {
+    match std::env::var(PASSPHRASE_VAR) {
+        Ok(p) if !p.is_empty() => Ok(Zeroizing::new(p)),
+        _ => Err(format!("set {PASSPHRASE_VAR} to the witness passphrase").into()),
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn save_encrypted(witness: &Witness, out: &Path) -> CliResult<()> {
+    Ok(witness.save_encrypted(out, &passphrase()?)?)
+}
+
+#[cfg(not(feature = "encryption"))]
+fn save_encrypted(_: &Witness, _: &Path) -> CliResult<()> {
+    Err("zk-toy was built without the encryption feature".into())
+}
+
+#[cfg(feature = "encryption")]
+fn load_encrypted(path: &Path) -> CliResult<Witness> {
+    Ok(Witness::load_encrypted(path, &passphrase()?)?)
+}
+
+#[cfg(not(feature = "encryption"))]
+fn load_encrypted(path: &Path) -> CliResult<Witness> {
+    Err(format!("{} is encrypted and zk-toy was built without the encryption feature", path.display()).into())
+}
+
+fn verify(keys_dir: PathBuf, artifact: ProofArtifact) -> CliResult<()> {
+    let vk: VerifyingKey<Bls12_381> = artifact::load_versioned::<AdditionCircuit, _>(keys_dir.join("addition.vk"))?;
+    // same amount of work whatever is wrong with the proof, see zk-circuits/src/verifier.rs
+    let is_valid = verify_constant_work(&prepare_verifying_key(&vk), &artifact.public_inputs, &artifact.proof);
+    if is_valid {
+        println!("✓ The proof is valid for public inputs {:?}", display_inputs(&artifact.public_inputs));
+        Ok(())
+    } else {
+        Err("proof is invalid".into())
+    }
+}
+
+fn migrate(keys_dir: PathBuf, upgrade: bool) -> CliResult<()> {
+    for name in ["addition.pk", "addition.vk"] {
+        let path = keys_dir.join(name);
+        if !path.exists() {
+            println!("{name}: not found, nothing to migrate");
+            continue;
+        }
+        match artifact::read_header(&path)? {
+            Some(header) if header.circuit_id == AdditionCircuit::<Fr>::ID => {
+                println!("{name}: {}", version::migration_advice::<AdditionCircuit>(&header.version));
+            }
+            Some(header) => println!("{name}: a key for '{}', not a circuit this cli knows", header.circuit_id),
+            // 0.1.0 keys are addition 1.0.0 keys without the header
+            None if upgrade => {
+                if name.ends_with(".pk") {
+                    let pk: ProvingKey<Bls12_381> = artifact::load(&path)?;
+                    artifact::save_versioned::<AdditionCircuit, _>(&pk, &build_info(), &path)?;
+                } else {
+                    let vk: VerifyingKey<Bls12_381> = artifact::load(&path)?;
+                    artifact::save_versioned::<AdditionCircuit, _>(&vk, &build_info(), &path)?;
+                }
+                println!("{name}: ✓ upgraded to a versioned addition 1.0.0 key");
+            }
+            None => println!(
+                "{name}: written before keys were versioned (zk-toy 0.1.0). The addition circuit hasn't \
+                 changed since, so no new setup is needed: run `zk-toy migrate --upgrade` to add the header"
+            ),
+        }
+    }
+    Ok(())
+}
+
+// where a saved file was made, and what this build does differently
+fn print_stamp(stamp: Option<&BuildInfo>) {
+    match stamp {
+        Some(stamp) => {
+            println!("written by {stamp}");
+            for difference in build_info().differences(stamp) {
+                println!("  differs   {difference}");
+            }
+        }
+        None => println!("no build stamp, written before zk-toy stamped its files"),
+    }
+}
+
+// keys are read unchecked: nothing gets proved or verified with them here, and checking
+// every point of a big proving key takes longer than the rest
+fn inspect_key(path: &Path) -> CliResult<()> {
+    let bytes = std::fs::read(path)?;
+    let (stamp, _) = artifact::split_stamp(&bytes)?;
+    let (header, key) = artifact::split_header(&bytes)?;
+    let info = match artifact::deserialize_unchecked::<ProvingKey<Bls12_381>>(key) {
+        Ok(pk) => KeyInfo::of_proving_key(&pk)?,
+        Err(_) => match artifact::deserialize_unchecked::<VerifyingKey<Bls12_381>>(key) {
+            Ok(vk) => KeyInfo::of_verifying_key(&vk)?,
+            Err(_) => return Err(format!("{} is not a BLS12-381 Groth16 proving or verifying key", path.display()).into()),
+        },
+    };
+    println!("{}: {} bytes", path.display(), bytes.len());
+    match header {
+        Some(header) => println!("made for {} {}", header.circuit_id, header.version),
+        None => println!("no version header, so which circuit it's for is only in the file name"),
+    }
+    print_stamp(stamp.as_ref());
+    println!("{info}");
+    if let (Some(b_g2), Some(largest)) = (
+        info.components.iter().find(|c| c.group == "G2" && c.name == "B query"),
+        info.components.iter().max_by_key(|c| c.bytes),
+    ) && largest == b_g2
+    {
+        println!("The B query over G2 is the largest part: a point per variable, each twice the size of a G1 point");
+    }
+    Ok(())
+}
+
+// the examples this build has, with the same inputs the example binaries use
+fn run_examples(cache: Option<&MatrixCache>) -> CliResult<Vec<RunReport>> {
+    let mut rng = thread_rng();
+    #[allow(unused_mut)] // only pushed to with the bls or evm feature
+    let mut reports = vec![
+        examples::addition::run(&mut rng, Fr::from(17u32), Fr::from(2u32))?,
+        examples::document::run_cached(&mut rng, examples::document::SAMPLE_DOCUMENT, examples::document::sample_layout(), cache)?,
+        examples::vss::run(&mut rng, Fr::from(424242u32), 3, 5)?,
+        examples::dkg::run(&mut rng, true)?,
+        examples::passport::run(&mut rng, true)?,
+        examples::bitcoin::run_cached(&mut rng, &zk_circuits::bitcoin::GENESIS, cache)?,
+        examples::merkle::run(&mut rng, PoseidonHasher::new(2), TreeShape::binary(8), 100)?,
+        examples::merkle::run(&mut rng, PoseidonHasher::new(4), TreeShape::new(4, 4), 100)?,
+        examples::merkle::run(&mut rng, MimcHasher::default(), TreeShape::binary(8), 100)?,
+    ];
+    #[cfg(feature = "bls")]
+    reports.push(examples::bls::run(&mut rng, b"transfer 5 toy coins to bob")?);
+    #[cfg(feature = "evm")]
+    reports.push(examples::evm::run(&mut rng, ark_bn254::Fr::from(17u32), ark_bn254::Fr::from(2u32))?);
+    #[cfg(feature = "evm")]
+    reports.push(examples::airdrop::run(&mut rng, true, None)?);
+    Ok(reports)
+}
+
+fn bench(json: bool, baseline: Option<&Path>, threshold: f64, cache: Option<&MatrixCache>) -> CliResult<()> {
+    let reports = run_examples(cache)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        println!(
+            "{:<14} {:>11} {:>10} {:>10} {:>10} {:>7} {:>10} {:>7}  ok",
+            "example", "constraints", "setup", "prove", "verify", "proof", "pk", "vk"
+        );
+        for r in &reports {
+            println!(
+                "{:<14} {:>11} {:>10.2?} {:>10.2?} {:>10.2?} {:>7} {:>10} {:>7}  {}",
+                r.example,
+                r.constraints,
+                r.setup,
+                r.prove,
+                r.verify,
+                r.proof_bytes,
+                r.proving_key_bytes,
+                r.verifying_key_bytes,
+                if r.passed() { "✓" } else { "✗" }
+            );
+        }
+    }
+    if !reports.iter().all(RunReport::passed) {
+        return Err("some example did not pass".into());
+    }
+    match baseline {
+        Some(path) => compare_to_baseline(&reports, path, threshold),
+        None => Ok(()),
+    }
+}
+
+fn compare_to_baseline(reports: &[RunReport], path: &Path, threshold: f64) -> CliResult<()> {
+    let baseline: Vec<RunReport> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let comparison = report::compare(&baseline, reports, threshold / 100.0);
+    // the table goes to stdout, so with --json the comparison has to go somewhere else
+    for example in &comparison.new {
+        eprintln!("  {example} is not in {}", path.display());
+    }
+    for example in &comparison.missing {
+        eprintln!("  {example} is in {} but wasn't run", path.display());
+    }
+    for regression in &comparison.regressions {
+        eprintln!("✗ {regression}");
+    }
+    if comparison.passed() {
+        eprintln!("✓ no regressions against {} (threshold {threshold}%)", path.display());
+        Ok(())
+    } else {
+        Err(format!("{} regressions against {}", comparison.regressions.len(), path.display()).into())
+    }
+}
+
+// in decimal, not x.to_string(): arkworks' Display writes zero as an empty string
+fn decimal(x: &Fr) -> String {
+    BigUint::from(x.into_bigint()).to_string()
+}
+
+fn display_inputs(inputs: &[Fr]) -> Vec<String> {
+    inputs.iter().map(decimal).collect()
+}
+
+#[cfg(feature = "qr")]
+fn show_qr(artifact: &ProofArtifact, terminal: bool, png: Option<PathBuf>) -> CliResult<()> {
+    if terminal {
+        println!("{}", qr::render_terminal(artifact)?);
+    }
+    if let Some(path) = png {
+        qr::save_png(artifact, &path)?;
+        println!("✓ QR code written to {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "qr"))]
+fn show_qr(_: &ProofArtifact, terminal: bool, png: Option<PathBuf>) -> CliResult<()> {
+    if terminal || png.is_some() {
+        return Err("zk-toy was built without the qr feature".into());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "qr")]
+fn read_qr(png: PathBuf) -> CliResult<ProofArtifact> {
+    Ok(qr::read_png(png)?)
+}
+
+#[cfg(not(feature = "qr"))]
+fn read_qr(_: PathBuf) -> CliResult<ProofArtifact> {
+    Err("zk-toy was built without the qr feature".into())
+}
+
+#[cfg(feature = "trace")]
+fn save_shape(circuit: &str, out: Option<&Path>) -> CliResult<()> {
+    diff::save(circuit, out)
+}
+
+#[cfg(not(feature = "trace"))]
+fn save_shape(_: &str, _: Option<&Path>) -> CliResult<()> {
+    Err("zk-toy was built without the trace feature".into())
+}
+
+#[cfg(feature = "trace")]
+fn diff_shapes(before: &str, after: &str) -> CliResult<()> {
+    diff::run(before, after)
+}
+
+#[cfg(not(feature = "trace"))]
+fn diff_shapes(_: &str, _: &str) -> CliResult<()> {
+    Err("zk-toy was built without the trace feature".into())
+}
+
+// the circuit's variables with their values and namespaces as json, for debugging outside
+// Rust (see zk-circuits/src/assignment.rs)
+#[cfg(feature = "trace")]
+fn write_assignment<C: ConstraintSynthesizer<Fr>>(circuit: &str, synthesizer: C, out: &Path) -> CliResult<()> {
+    let assignment = zk_circuits::assignment::export(circuit, synthesizer)?;
+    std::fs::write(out, serde_json::to_string_pretty(&assignment)?)?;
+    println!(
+        "✓ Assignment of {circuit} ({} inputs, {} witnesses) written to {}, it holds the secret values",
+        assignment.inputs.len(),
+        assignment.witnesses.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "trace"))]
+fn write_assignment<C>(_: &str, _: C, _: &Path) -> CliResult<()> {
+    Err("zk-toy was built without the trace feature".into())
+}
+
+// which constraints did any checking with this witness, by namespace (see
+// zk-circuits/src/coverage.rs)
+#[cfg(feature = "trace")]
+fn print_coverage<C: ConstraintSynthesizer<Fr>>(circuit: &str, synthesizer: C) -> CliResult<()> {
+    println!("{}\n", zk_circuits::coverage::analyze(circuit, synthesizer)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "trace"))]
+fn print_coverage<C>(_: &str, _: C) -> CliResult<()> {
+    Err("zk-toy was built without the trace feature".into())
+}
+
+#[cfg(feature = "server")]
+fn serve(args: ServeArgs) -> CliResult<()> {
+    server::serve(args)
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_: ServeArgs) -> CliResult<()> {
+    Err("zk-toy was built without the server feature".into())
+}
+
+#[cfg(feature = "demo")]
+fn demo(out: &Path, wasm: &Path, serve: bool, addr: &str) -> CliResult<()> {
+    demo::run(out, wasm, serve, addr)
+}
+
+#[cfg(not(feature = "demo"))]
+fn demo(_: &Path, _: &Path, _: bool, _: &str) -> CliResult<()> {
+    Err("zk-toy was built without the demo feature".into())
+}
+
+#[cfg(feature = "params")]
+fn fetch_params(power: u32, dir: PathBuf, blake2b: Option<String>, insecure_local: bool) -> CliResult<()> {
+    if insecure_local {
+        let path = params::generate_local(&dir, power)?;
+        println!("✓ Local powers of tau for 2^{power} written to {}", path.display());
+        println!("  INSECURE: this process knew tau, anyone who got hold of it can forge proofs");
+        return Ok(());
+    }
+    let path = params::fetch(&dir, power, blake2b.as_deref())?;
+    println!("✓ {} is there, its blake2b matches the pin and its powers check out", path.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "params"))]
+fn fetch_params(_: u32, _: PathBuf, _: Option<String>, _: bool) -> CliResult<()> {
+    Err("zk-toy was built without the params feature".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secrets(args: &[&str]) -> CliResult<(Fr, Fr)> {
+        let cli = Cli::try_parse_from(args).expect("the arguments parse");
+        match cli.command {
+            Command::Prove { x, y, witness, reduce, .. } | Command::ProveBundle { x, y, witness, reduce, .. } => {
+                read_secrets(x, y, witness, reduction(reduce))
+            }
+            _ => unreachable!("not a proving command"),
+        }
+    }
+
+    #[test]
+    fn zero_is_displayed_as_a_number() {
+        let p_minus_one = BigUint::from(Fr::MODULUS) - 1u32;
+        assert_eq!(display_inputs(&[Fr::from(0u32), Fr::from(19u32), -Fr::from(1u32)]), ["0".to_string(), "19".to_string(), p_minus_one.to_string()]);
+    }
+
+    #[test]
+    fn prove_bundle_takes_the_numbers_prove_takes() {
+        for command in ["prove", "prove-bundle"] {
+            assert_eq!(secrets(&["zk-toy", command, "--x", "-5", "--y", "5"]).unwrap(), (-Fr::from(5u32), Fr::from(5u32)));
+            let modulus = Fr::MODULUS.to_string();
+            assert!(secrets(&["zk-toy", command, "--x", &modulus, "--y", "1"]).is_err());
+            assert_eq!(secrets(&["zk-toy", command, "--x", &modulus, "--y", "1", "--reduce"]).unwrap(), (Fr::from(0u32), Fr::from(1u32)));
+        }
+    }
+}
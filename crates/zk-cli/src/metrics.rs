@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/*
+What zk-toy serve has done, in the Prometheus text format at GET /metrics, so a demo
+can be watched with Prometheus and Grafana (or curl):
+
+  zk_toy_proofs_total{circuit}            proofs made, including ones whose request
+                                          timed out (they still finish)
+  zk_toy_verifications_total{circuit,result}   result is valid or invalid
+  zk_toy_errors_total{code}               error responses, by the code in their json
+  zk_toy_setup_seconds{circuit}           histograms of how long each step took
+  zk_toy_prove_seconds{circuit}
+  zk_toy_verify_seconds{circuit}
+  zk_toy_jobs_running                     setups and proofs running now, out of --max-jobs
+  zk_toy_connections_open                 requests being handled now
+  zk_toy_statements                       expr statements with keys
+  zk_toy_registered_keys                  verifying keys in the registry
+
+Proofs verified against registered keys count as circuit="registry", not by id: ids
+are made up by whoever registers them and there can be a thousand.
+
+There is no queue: a request over --max-jobs is refused as busy right away, so how
+close to full the server runs is jobs_running against its maximum (also a gauge,
+zk_toy_jobs_max), and how often it's over, errors_total{code="busy"}.
+
+Counters live as long as the process, like Prometheus expects (it handles resets).
+Hand written rather than with the prometheus crate, the format is simple and the
+server is std only.
+*/
+
+// upper bounds in seconds, from a small circuit's proof to a big one's setup
+const BUCKETS: [f64; 12] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+#[derive(Default)]
+struct Histogram {
+    // observations at or below each bucket's bound, the +Inf one is `count`
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, took: Duration) {
+        let seconds = took.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+#[derive(Default)]
+struct Recorded {
+    proofs: BTreeMap<&'static str, u64>,
+    verifications: BTreeMap<(&'static str, bool), u64>,
+    errors: BTreeMap<&'static str, u64>,
+    setup: BTreeMap<&'static str, Histogram>,
+    prove: BTreeMap<&'static str, Histogram>,
+    verify: BTreeMap<&'static str, Histogram>,
+}
+
+// the gauges, read off the server's state when /metrics is asked for
+pub struct Gauges {
+    pub jobs_running: usize,
+    pub jobs_max: usize,
+    pub connections_open: usize,
+    pub statements: usize,
+    pub registered_keys: usize,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    recorded: Mutex<Recorded>,
+}
+
+impl Metrics {
+    fn record(&self, f: impl FnOnce(&mut Recorded)) {
+        f(&mut self.recorded.lock().expect("no panics holding the lock"));
+    }
+
+    pub fn set_up(&self, circuit: &'static str, took: Duration) {
+        self.record(|r| r.setup.entry(circuit).or_default().observe(took));
+    }
+
+    pub fn proved(&self, circuit: &'static str, took: Duration) {
+        self.record(|r| {
+            *r.proofs.entry(circuit).or_default() += 1;
+            r.prove.entry(circuit).or_default().observe(took);
+        });
+    }
+
+    pub fn verified(&self, circuit: &'static str, valid: bool, took: Duration) {
+        self.record(|r| {
+            *r.verifications.entry((circuit, valid)).or_default() += 1;
+            r.verify.entry(circuit).or_default().observe(took);
+        });
+    }
+
+    pub fn error(&self, code: &'static str) {
+        self.record(|r| *r.errors.entry(code).or_default() += 1);
+    }
+
+    pub fn render(&self, gauges: &Gauges) -> String {
+        let r = self.recorded.lock().expect("no panics holding the lock");
+        let mut out = String::new();
+        header(&mut out, "zk_toy_proofs_total", "counter", "Proofs made");
+        for (circuit, n) in &r.proofs {
+            let _ = writeln!(out, "zk_toy_proofs_total{{circuit=\"{circuit}\"}} {n}");
+        }
+        header(&mut out, "zk_toy_verifications_total", "counter", "Proofs verified, by result");
+        for ((circuit, valid), n) in &r.verifications {
+            let result = if *valid { "valid" } else { "invalid" };
+            let _ = writeln!(out, "zk_toy_verifications_total{{circuit=\"{circuit}\",result=\"{result}\"}} {n}");
+        }
+        header(&mut out, "zk_toy_errors_total", "counter", "Error responses, by error code");
+        for (code, n) in &r.errors {
+            let _ = writeln!(out, "zk_toy_errors_total{{code=\"{code}\"}} {n}");
+        }
+        histograms(&mut out, "zk_toy_setup_seconds", "Time to set up a statement", &r.setup);
+        histograms(&mut out, "zk_toy_prove_seconds", "Time to make a proof", &r.prove);
+        histograms(&mut out, "zk_toy_verify_seconds", "Time to verify a proof", &r.verify);
+        for (name, help, value) in [
+            ("zk_toy_jobs_running", "Setups and proofs running", gauges.jobs_running),
+            ("zk_toy_jobs_max", "Setups and proofs allowed at once", gauges.jobs_max),
+            ("zk_toy_connections_open", "Requests being handled", gauges.connections_open),
+            ("zk_toy_statements", "Expr statements with keys", gauges.statements),
+            ("zk_toy_registered_keys", "Verifying keys in the registry", gauges.registered_keys),
+        ] {
+            header(&mut out, name, "gauge", help);
+            let _ = writeln!(out, "{name} {value}");
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+fn histograms(out: &mut String, name: &str, help: &str, by_circuit: &BTreeMap<&'static str, Histogram>) {
+    header(out, name, "histogram", help);
+    for (circuit, h) in by_circuit {
+        for (bound, n) in BUCKETS.iter().zip(h.buckets) {
+            let _ = writeln!(out, "{name}_bucket{{circuit=\"{circuit}\",le=\"{bound}\"}} {n}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{circuit=\"{circuit}\",le=\"+Inf\"}} {}", h.count);
+        let _ = writeln!(out, "{name}_sum{{circuit=\"{circuit}\"}} {}", h.sum);
+        let _ = writeln!(out, "{name}_count{{circuit=\"{circuit}\"}} {}", h.count);
+    }
+}
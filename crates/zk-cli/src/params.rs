@@ -0,0 +1,380 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInt, Field, PrimeField, UniformRand};
+use blake2::{Blake2b512, Digest};
+use rand::thread_rng;
+use zeroize::Zeroize;
+
+/*
+Public parameters for universal setups. Plonk and marlin style backends don't need a
+setup per circuit, they need one structured reference string (powers of a secret tau
+in G1 and G2) that works for every circuit up to some size. Whoever knows tau can fake
+proofs, so a real one comes out of a ceremony where lots of people mix in randomness
+and it's enough that one of them threw theirs away. The Hermez (polygon) perpetual
+powers of tau on BN254 is the usual one, distributed as snarkjs .ptau files, one per
+power: powersOfTau28_hez_final_NN.ptau holds 2^NN powers.
+
+  zk-toy params fetch --power 12   downloads that file with curl, checks its blake2b-512
+                                   against the pin and that the points fit together
+  zk-toy params fetch --power 12 --insecure-local
+                                   makes a file in the same format from a tau this
+                                   process picked, fine for playing, useless for security
+
+A pin is the blake2b hash published next to the file in the snarkjs README. Powers 8
+to 16 are pinned in PINS below, for any other power pass the hash with --blake2b.
+*/
+
+const PTAU_URL: &str = "https://hermez.s3-eu-west-1.amazonaws.com";
+
+// (power, blake2b-512 of powersOfTau28_hez_final_NN.ptau), copy new lines from the
+// table in the snarkjs README (github.com/iden3/snarkjs), never from a download
+const PINS: &[(u32, &str)] = &[
+    (8, "d6a8fb3a04feb600096c3b791f936a578c4e664d262e4aa24beed1b7a9a96aa5eb72864d628db247e9293384b74b36ffb52ca8d148d6e1b8b51e279fdf57b583"),
+    (9, "94f108a80e81b5d932d8e8c9e8fd7f46cf32457e31462deeeef37af1b71c2c1b3c71fb0d9b59c654ec266b042735f50311f9fd1d4cadce47ab234ad163157cb5"),
+    (10, "6cfeb8cda92453099d20120bdd0e8a5c4e7706c2da9a8f09ccc157ed2464d921fd0437fb70db42104769efd7d6f3c1f964bcf448c455eab6f6c7d863e88a5849"),
+    (11, "47c282116b892e5ac92ca238578006e31a47e7c7e70f0baa8b687f0a5203e28ea07bbbec765a98dcd654bad618475d4661bfaec3bd9ad2ed12e7abc251d94d33"),
+    (12, "ded2694169b7b08e898f736d5de95af87c3f1a64594013351b1a796dbee393bd825f88f9468c84505ddd11eb0b1465ac9b43b9064aa8ec97f2b73e04758b8a4a"),
+    (13, "58efc8bf2834d04768a3d7ffcd8e1e23d461561729beaac4e3e7a47829a1c9066d5320241e124a1a8e8aa6c75be0ba66f65bc8239a0542ed38e11276f6fdb4d9"),
+    (14, "eeefbcf7c3803b523c94112023c7ff89558f9b8e0cf5d6cdcba3ade60f168af4a181c9c21774b94fbae6c90411995f7d854d02ebd93fb66043dbb06f17a831c1"),
+    (15, "982372c867d229c236091f767e703253249a9b432c1710b4f326306bfa2428a17b06240359606cfe4d580b10a5a1f63fbed499527069c18ae17060472969ae6e"),
+    (16, "6a6277a2f74e1073601b4f9ed6e1e55226c9f1780fee80cea7fc9b7a8fb8a5f8a2b9d6b048faf20dc7a4f2e6b15c72fe8cc297cf1e9d2a887f89ee1060a2ea6c"),
+];
+
+// the biggest ceremony file, and a limit for local files so they stay quick to make
+const MAX_POWER: u32 = 28;
+const MAX_LOCAL_POWER: u32 = 16;
+
+// ptau files are a "ptau" magic, a version, then numbered sections of (id, size, data)
+const MAGIC: &[u8; 4] = b"ptau";
+const HEADER: u32 = 1;
+const TAU_G1: u32 = 2;
+const TAU_G2: u32 = 3;
+// bytes per base field element, points are stored uncompressed in montgomery form
+const N8: usize = 32;
+
+#[derive(Debug)]
+pub enum ParamsError {
+    Io(io::Error),
+    Download(String),
+    Hash { expected: String, actual: String },
+    Format(String),
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamsError::Io(e) => write!(f, "io error: {e}"),
+            ParamsError::Download(msg) => write!(f, "download failed: {msg}"),
+            ParamsError::Hash { expected, actual } => write!(f, "blake2b mismatch: pinned {expected}, got {actual}"),
+            ParamsError::Format(msg) => write!(f, "bad ptau file: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+impl From<io::Error> for ParamsError {
+    fn from(e: io::Error) -> Self {
+        ParamsError::Io(e)
+    }
+}
+
+pub fn ceremony_file(power: u32) -> String {
+    format!("powersOfTau28_hez_final_{power:02}.ptau")
+}
+
+pub fn local_file(power: u32) -> String {
+    format!("insecure_local_{power:02}.ptau")
+}
+
+pub fn pinned_hash(power: u32) -> Option<&'static str> {
+    PINS.iter().find(|(p, _)| *p == power).map(|(_, hash)| *hash)
+}
+
+// downloads the ceremony file for `power` into dir, unless a verified copy is already there
+pub fn fetch(dir: &Path, power: u32, blake2b: Option<&str>) -> Result<PathBuf, ParamsError> {
+    if !(1..=MAX_POWER).contains(&power) {
+        return Err(ParamsError::Format(format!("the ceremony goes from power 1 to {MAX_POWER}, not {power}")));
+    }
+    let expected = match (blake2b, pinned_hash(power)) {
+        (Some(given), Some(pinned)) if !given.eq_ignore_ascii_case(pinned) => {
+            return Err(ParamsError::Hash {
+                expected: pinned.to_string(),
+                actual: given.to_string(),
+            });
+        }
+        (Some(given), _) => given.to_ascii_lowercase(),
+        (None, Some(pinned)) => pinned.to_string(),
+        (None, None) => {
+            return Err(ParamsError::Download(format!(
+                "no pinned hash for power {power}, pass --blake2b with the hash from the snarkjs README \
+                 (or --insecure-local for a local toy file)"
+            )));
+        }
+    };
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(ceremony_file(power));
+    if path.exists() && blake2b_hex(&path)? == expected {
+        check(&path, power)?;
+        return Ok(path);
+    }
+
+    // curl does the https, same as cross_check leans on npx for snarkjs
+    let partial = path.with_extension("ptau.part");
+    let url = format!("{PTAU_URL}/{}", ceremony_file(power));
+    let status = Command::new("curl")
+        .args(["--fail", "--location", "--silent", "--show-error", "--output"])
+        .arg(&partial)
+        .arg(&url)
+        .status()
+        .map_err(|e| ParamsError::Download(format!("could not run curl: {e}")))?;
+    if !status.success() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(ParamsError::Download(format!("curl {url} exited with {status}")));
+    }
+
+    let actual = blake2b_hex(&partial)?;
+    if actual != expected {
+        let _ = std::fs::remove_file(&partial);
+        return Err(ParamsError::Hash { expected, actual });
+    }
+    std::fs::rename(&partial, &path)?;
+    check(&path, power)?;
+    Ok(path)
+}
+
+// a file in the ptau layout (header, tau G1, tau G2 and nothing else) from a tau we
+// pick ourselves. tau is wiped afterwards, but it was in memory, so trust it as much
+// as you trust this machine
+pub fn generate_local(dir: &Path, power: u32) -> Result<PathBuf, ParamsError> {
+    if !(1..=MAX_LOCAL_POWER).contains(&power) {
+        return Err(ParamsError::Format(format!("local files go up to power {MAX_LOCAL_POWER}, not {power}")));
+    }
+    let n = 1usize << power;
+    let mut tau = Fr::rand(&mut thread_rng());
+    let mut g1 = Vec::with_capacity(2 * n - 1);
+    let mut g2 = Vec::with_capacity(n);
+    let mut power_of_tau = Fr::ONE;
+    for i in 0..2 * n - 1 {
+        g1.push((G1Affine::generator() * power_of_tau).into_affine());
+        if i < n {
+            g2.push((G2Affine::generator() * power_of_tau).into_affine());
+        }
+        power_of_tau *= tau;
+    }
+    tau.zeroize();
+    power_of_tau.zeroize();
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(local_file(power));
+    let mut out = BufWriter::new(File::create(&path)?);
+    out.write_all(MAGIC)?;
+    out.write_all(&1u32.to_le_bytes())?;
+    out.write_all(&3u32.to_le_bytes())?;
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(N8 as u32).to_le_bytes());
+    write_bigint(&mut header, Fq::MODULUS);
+    header.extend_from_slice(&power.to_le_bytes());
+    header.extend_from_slice(&power.to_le_bytes());
+    write_section(&mut out, HEADER, &header)?;
+
+    let mut section = Vec::with_capacity(g1.len() * 2 * N8);
+    for p in &g1 {
+        write_fq(&mut section, p.x);
+        write_fq(&mut section, p.y);
+    }
+    write_section(&mut out, TAU_G1, &section)?;
+
+    let mut section = Vec::with_capacity(g2.len() * 4 * N8);
+    for p in &g2 {
+        for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+            write_fq(&mut section, c);
+        }
+    }
+    write_section(&mut out, TAU_G2, &section)?;
+    out.flush()?;
+    drop(out);
+
+    check(&path, power)?;
+    Ok(path)
+}
+
+pub fn blake2b_hex(path: &Path) -> Result<String, ParamsError> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut hasher = Blake2b512::new();
+    let mut buf = vec![0u8; 1 << 16];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/*
+A hash only says it's the file we pinned. This also checks that it is a BN254 ptau
+with enough powers, that the first points are on the curve, start at the generators
+and really are successive powers of one tau:
+
+    e(tau^(i+1) g1, g2) == e(tau^i g1, tau g2)
+*/
+pub fn check(path: &Path, power: u32) -> Result<(), ParamsError> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(ParamsError::Format("not a ptau file".to_string()));
+    }
+    let _version = read_u32(&mut file)?;
+    let sections = read_u32(&mut file)?;
+
+    // where each section starts, they can come in any order
+    let mut offsets = Vec::new();
+    for _ in 0..sections {
+        let id = read_u32(&mut file)?;
+        let size = read_u64(&mut file)?;
+        offsets.push((id, file.stream_position()?));
+        file.seek(SeekFrom::Current(size as i64))?;
+    }
+
+    seek_section(&mut file, &offsets, HEADER)?;
+    let n8 = read_u32(&mut file)? as usize;
+    if n8 != N8 || read_fq_raw(&mut file)? != Fq::MODULUS {
+        return Err(ParamsError::Format("not over the BN254 base field".to_string()));
+    }
+    let file_power = read_u32(&mut file)?;
+    if file_power < power {
+        return Err(ParamsError::Format(format!("holds 2^{file_power} powers, asked for 2^{power}")));
+    }
+
+    // a handful of points is enough to catch a wrong curve, layout or tau
+    seek_section(&mut file, &offsets, TAU_G1)?;
+    let count = 4.min((2usize << file_power) - 1);
+    let g1: Vec<G1Affine> = (0..count).map(|_| read_g1(&mut file)).collect::<Result<_, _>>()?;
+    seek_section(&mut file, &offsets, TAU_G2)?;
+    let g2: Vec<G2Affine> = (0..2).map(|_| read_g2(&mut file)).collect::<Result<_, _>>()?;
+
+    if g1[0] != G1Affine::generator() || g2[0] != G2Affine::generator() {
+        return Err(ParamsError::Format("the powers don't start at the generators".to_string()));
+    }
+    for pair in g1.windows(2) {
+        if Bn254::pairing(pair[1], g2[0]) != Bn254::pairing(pair[0], g2[1]) {
+            return Err(ParamsError::Format("the G1 points are not successive powers of tau".to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn seek_section(file: &mut impl Seek, offsets: &[(u32, u64)], id: u32) -> Result<(), ParamsError> {
+    let (_, at) = offsets
+        .iter()
+        .find(|(s, _)| *s == id)
+        .ok_or_else(|| ParamsError::Format(format!("section {id} is missing")))?;
+    file.seek(SeekFrom::Start(*at))?;
+    Ok(())
+}
+
+fn write_section(out: &mut impl Write, id: u32, data: &[u8]) -> io::Result<()> {
+    out.write_all(&id.to_le_bytes())?;
+    out.write_all(&(data.len() as u64).to_le_bytes())?;
+    out.write_all(data)
+}
+
+fn write_bigint(out: &mut Vec<u8>, x: BigInt<4>) {
+    for limb in x.0 {
+        out.extend_from_slice(&limb.to_le_bytes());
+    }
+}
+
+// snarkjs keeps field elements in montgomery form, which is also what arkworks has inside
+fn write_fq(out: &mut Vec<u8>, x: Fq) {
+    write_bigint(out, x.0);
+}
+
+fn read_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_fq_raw(r: &mut impl Read) -> io::Result<BigInt<4>> {
+    let mut limbs = [0u64; 4];
+    for limb in &mut limbs {
+        *limb = read_u64(r)?;
+    }
+    Ok(BigInt(limbs))
+}
+
+fn read_fq(r: &mut impl Read) -> Result<Fq, ParamsError> {
+    let raw = read_fq_raw(r)?;
+    if raw >= Fq::MODULUS {
+        return Err(ParamsError::Format("field element out of range".to_string()));
+    }
+    Ok(Fq::new_unchecked(raw))
+}
+
+fn read_g1(r: &mut impl Read) -> Result<G1Affine, ParamsError> {
+    let point = G1Affine::new_unchecked(read_fq(r)?, read_fq(r)?);
+    if !point.is_on_curve() {
+        return Err(ParamsError::Format("a G1 point is not on the curve".to_string()));
+    }
+    Ok(point)
+}
+
+fn read_g2(r: &mut impl Read) -> Result<G2Affine, ParamsError> {
+    let x = Fq2::new(read_fq(r)?, read_fq(r)?);
+    let y = Fq2::new(read_fq(r)?, read_fq(r)?);
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(ParamsError::Format("a G2 point is not in the group".to_string()));
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_default_power_is_pinned() {
+        assert!(pinned_hash(12).is_some());
+        for (power, hash) in PINS {
+            assert_eq!(hash.len(), 128, "power {power}");
+            assert!(hash.bytes().all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase()), "power {power}");
+        }
+    }
+
+    #[test]
+    fn a_hash_that_disagrees_with_the_pin_is_refused_before_downloading() {
+        let dir = std::env::temp_dir().join(format!("zk-toy-params-{}", std::process::id()));
+        let err = fetch(&dir, 12, Some(&"0".repeat(128))).expect_err("the pin wins");
+        assert!(matches!(err, ParamsError::Hash { .. }));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn local_files_pass_the_check() {
+        let dir = std::env::temp_dir().join(format!("zk-toy-params-local-{}", std::process::id()));
+        let path = generate_local(&dir, 3).expect("a small file");
+        check(&path, 3).expect("the powers fit together");
+        assert!(check(&path, 4).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_snark::SNARK;
+use clap::Subcommand;
+use rand::thread_rng;
+use zk_circuits::credential::{self, AgeCircuit, Attributes, Credential, IssuerKey, IssuerPublicKey, Presentation};
+use zk_core::artifact;
+use zk_gadgets::poseidon::poseidon_config;
+use zk_protocols::bls::{PublicKey, SecretKey};
+
+use crate::CliResult;
+
+/*
+The passport age check from src/bin/passport.rs, split by who does what, with files
+in between:
+
+  zk-toy passport issue --name "Grace Hopper" --nationality USA --birth-date 2007-05-02
+      the issuer signs credential.bin (and makes issuer.sk / issuer.pk the first time)
+  zk-toy passport setup       the verifier makes age.pk and age.vk
+  zk-toy passport prove       the holder turns credential.bin into presentation.bin
+  zk-toy passport verify      the verifier checks presentation.bin against issuer.pk
+
+"today" is the system date (utc) unless --today says otherwise. The cutoff date is a
+public input, so the holder and the verifier have to agree on the day: a proof made
+yesterday is still true today but won't verify against today's cutoff.
+*/
+
+#[derive(Subcommand)]
+pub enum PassportAction {
+    /// As the issuer: sign a credential for the holder's attributes
+    Issue {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        nationality: String,
+        /// YYYY-MM-DD
+        #[arg(long)]
+        birth_date: String,
+        /// Where issuer.sk and issuer.pk live, they are created if missing
+        #[arg(long, default_value = ".")]
+        issuer_dir: PathBuf,
+        #[arg(long, default_value = "credential.bin")]
+        out: PathBuf,
+    },
+    /// As the verifier: generate the keys for the age circuit
+    Setup {
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+    },
+    /// As the holder: prove the birth date in a credential is at least --min-age years ago
+    Prove {
+        #[arg(long, default_value = "credential.bin")]
+        credential: PathBuf,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value_t = 18)]
+        min_age: u32,
+        /// YYYY-MM-DD, defaults to today
+        #[arg(long)]
+        today: Option<String>,
+        #[arg(long, default_value = "presentation.bin")]
+        out: PathBuf,
+    },
+    /// As the verifier: check a presentation against the issuer's public key
+    Verify {
+        #[arg(long, default_value = "presentation.bin")]
+        presentation: PathBuf,
+        #[arg(long, default_value = "issuer.pk")]
+        issuer_pk: PathBuf,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value_t = 18)]
+        min_age: u32,
+        /// YYYY-MM-DD, defaults to today
+        #[arg(long)]
+        today: Option<String>,
+    },
+}
+
+pub fn run(action: PassportAction) -> CliResult<()> {
+    match action {
+        PassportAction::Issue {
+            name,
+            nationality,
+            birth_date,
+            issuer_dir,
+            out,
+        } => issue(name, nationality, &birth_date, &issuer_dir, &out),
+        PassportAction::Setup { keys_dir } => setup(&keys_dir),
+        PassportAction::Prove {
+            credential,
+            keys_dir,
+            min_age,
+            today,
+            out,
+        } => prove(&credential, &keys_dir, min_age, today, &out),
+        PassportAction::Verify {
+            presentation,
+            issuer_pk,
+            keys_dir,
+            min_age,
+            today,
+        } => verify(&presentation, &issuer_pk, &keys_dir, min_age, today),
+    }
+}
+
+fn issue(name: String, nationality: String, birth_date: &str, issuer_dir: &Path, out: &Path) -> CliResult<()> {
+    let attributes = Attributes {
+        name,
+        nationality,
+        birth_date: credential::parse_date(birth_date)?,
+    };
+    let key = issuer_key(issuer_dir)?;
+    let issued = credential::issue(&key, &poseidon_config::<Fr>(2), attributes, &mut thread_rng());
+    artifact::save(&issued, &crate::build_info(), out)?;
+    println!("✓ Credential for {} written to {}", issued.attributes.name, out.display());
+    Ok(())
+}
+
+// the issuer's key pair, made on first use. The secret key file is as secret as it gets
+fn issuer_key(dir: &Path) -> CliResult<IssuerKey> {
+    let (sk_path, pk_path) = (dir.join("issuer.sk"), dir.join("issuer.pk"));
+    if sk_path.exists() {
+        return Ok(SecretKey(artifact::load(&sk_path)?));
+    }
+    std::fs::create_dir_all(dir)?;
+    let key = IssuerKey::random(&mut thread_rng());
+    artifact::save(&key.0, &crate::build_info(), &sk_path)?;
+    artifact::save(&key.public_key().0, &crate::build_info(), &pk_path)?;
+    println!("✓ New issuer key, the public half is {}", pk_path.display());
+    Ok(key)
+}
+
+fn setup(keys_dir: &Path) -> CliResult<()> {
+    let empty = AgeCircuit::empty(poseidon_config::<Fr>(2));
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(empty, &mut thread_rng())?;
+    std::fs::create_dir_all(keys_dir)?;
+    artifact::save_versioned::<AgeCircuit, _>(&pk, &crate::build_info(), keys_dir.join("age.pk"))?;
+    artifact::save_versioned::<AgeCircuit, _>(&vk, &crate::build_info(), keys_dir.join("age.vk"))?;
+    println!("✓ Age circuit keys written to {}", keys_dir.display());
+    Ok(())
+}
+
+fn prove(credential_path: &Path, keys_dir: &Path, min_age: u32, today: Option<String>, out: &Path) -> CliResult<()> {
+    let held: Credential = artifact::load(credential_path)?;
+    let cutoff = credential::cutoff(date_or_today(today)?, min_age);
+    if held.attributes.birth_date > cutoff {
+        return Err(format!("the credential says you are under {min_age}, there is nothing to prove").into());
+    }
+    let pk: ProvingKey<Bls12_381> = artifact::load_versioned::<AgeCircuit, _>(keys_dir.join("age.pk"))?;
+    let poseidon = poseidon_config::<Fr>(2);
+    let circuit = AgeCircuit::new(poseidon.clone(), &held, cutoff);
+    let presentation = Presentation {
+        commitment: held.commitment(&poseidon),
+        signature: held.signature,
+        proof: Groth16::<Bls12_381>::prove(&pk, circuit, &mut thread_rng())?,
+    };
+    artifact::save(&presentation, &crate::build_info(), out)?;
+    println!("✓ Proof of being born on or before {} written to {}", credential::format_date(cutoff), out.display());
+    Ok(())
+}
+
+fn verify(presentation: &Path, issuer_pk: &Path, keys_dir: &Path, min_age: u32, today: Option<String>) -> CliResult<()> {
+    let presentation: Presentation = artifact::load(presentation)?;
+    let issuer: IssuerPublicKey = PublicKey(artifact::load(issuer_pk)?);
+    let vk: VerifyingKey<Bls12_381> = artifact::load_versioned::<AgeCircuit, _>(keys_dir.join("age.vk"))?;
+    let cutoff = credential::cutoff(date_or_today(today)?, min_age);
+    if credential::verify_presentation(&prepare_verifying_key(&vk), &issuer, &presentation, cutoff) {
+        println!("✓ Signed by the issuer and born on or before {}: at least {min_age}", credential::format_date(cutoff));
+        Ok(())
+    } else {
+        Err(format!("not convinced the holder is at least {min_age}").into())
+    }
+}
+
+fn date_or_today(date: Option<String>) -> CliResult<u32> {
+    match date {
+        Some(text) => Ok(credential::parse_date(&text)?),
+        None => Ok(today()),
+    }
+}
+
+// yyyymmdd in utc, from days since 1970 (Howard Hinnant's civil_from_days)
+fn today() -> u32 {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86400).unwrap_or(0) as i64;
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year * 10000 + month * 100 + day) as u32
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use image::Luma;
+use qrcode::QrCode;
+use qrcode::render::unicode;
+
+use ark_bls12_381::Bls12_381;
+use zk_circuits::Groth16Artifact;
+use zk_core::artifact::{ArtifactError, from_hex, to_hex};
+
+type ProofArtifact = Groth16Artifact<Bls12_381>;
+
+/*
+Turning a proof into a qr code, so you can put it on a screen and have someone
+else scan it and verify it. The payload is the serialized ProofArtifact (proof +
+public inputs) written as uppercase hex with a small prefix. Uppercase hex only
+uses characters from the qr "alphanumeric" mode, which packs them more tightly
+than plain bytes would. A BLS12-381 proof with one public input fits comfortably.
+*/
+
+const PREFIX: &str = "ZKTOY1:";
+
+pub fn encode_payload(artifact: &ProofArtifact) -> Result<String, ArtifactError> {
+    Ok(format!("{PREFIX}{}", to_hex(&artifact.to_bytes()?)))
+}
+
+pub fn decode_payload(text: &str) -> Result<ProofArtifact, ArtifactError> {
+    let hex = text
+        .trim()
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| ArtifactError::BadEncoding(format!("qr payload does not start with {PREFIX}")))?;
+    ProofArtifact::from_bytes(&from_hex(hex)?)
+}
+
+fn qr_code(artifact: &ProofArtifact) -> Result<QrCode, ArtifactError> {
+    QrCode::new(encode_payload(artifact)?.as_bytes())
+        .map_err(|e| ArtifactError::BadEncoding(format!("payload does not fit in a qr code: {e}")))
+}
+
+// a qr code made of unicode half blocks, so it can be printed straight to the terminal
+pub fn render_terminal(artifact: &ProofArtifact) -> Result<String, ArtifactError> {
+    Ok(qr_code(artifact)?
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .build())
+}
+
+pub fn save_png(artifact: &ProofArtifact, path: impl AsRef<Path>) -> Result<(), ArtifactError> {
+    let image = qr_code(artifact)?.render::<Luma<u8>>().min_dimensions(400, 400).build();
+    image
+        .save(path)
+        .map_err(|e| ArtifactError::BadEncoding(format!("could not write png: {e}")))
+}
+
+// the "scan" side: find a qr code in the image and decode the proof out of it
+pub fn read_png(path: impl AsRef<Path>) -> Result<ProofArtifact, ArtifactError> {
+    let image = image::open(path)
+        .map_err(|e| ArtifactError::BadEncoding(format!("could not read image: {e}")))?
+        .to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare(image);
+    let grid = prepared
+        .detect_grids()
+        .into_iter()
+        .next()
+        .ok_or_else(|| ArtifactError::BadEncoding("no qr code found in image".into()))?;
+    let (_, content) = grid
+        .decode()
+        .map_err(|e| ArtifactError::BadEncoding(format!("could not decode qr code: {e}")))?;
+    decode_payload(&content)
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ark_bls12_381::Bls12_381;
+use ark_groth16::{PreparedVerifyingKey, VerifyingKey, prepare_verifying_key};
+use zk_circuits::key_info::{self, MalformedKey};
+use zk_core::artifact::{self, ArtifactError};
+
+/*
+Verifying keys other people registered with zk-toy serve, so the server can verify
+proofs of arkworks circuits it has never seen: a team registers the verifying key of
+their BLS12-381 Groth16 circuit under an id once, then anyone posts proofs against
+that id. Each key is a file <id>.vk in the registry directory (ark-serialize,
+compressed, what artifact::save writes), read back when the server starts. A file
+that doesn't hold a usable key (truncated, for another curve, no gamma abc points) is
+skipped with a warning, so one bad file doesn't keep every other key from serving.
+
+An id is taken by whoever registers it first and can't be replaced or removed over
+HTTP: there are no accounts, so replacing would let anybody swap a key for one of
+their own and make their proofs verify under another team's name. Registering the
+same key again is fine. To let an id go, delete its file and restart the server.
+
+Keys come from strangers, so they are read with every subgroup check (artifact.rs
+says why), and ids are short and plain since they become file names.
+*/
+
+pub const MAX_KEYS: usize = 1024;
+
+#[derive(Debug)]
+pub enum RegistryError {
+    BadId(String),
+    Taken(String),
+    Full,
+    Malformed(MalformedKey),
+    Artifact(ArtifactError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::BadId(id) => write!(f, "'{id}' isn't an id: 1 to 64 of a-z, 0-9, '-' and '_'"),
+            RegistryError::Taken(id) => write!(f, "'{id}' is registered with another key already"),
+            RegistryError::Full => write!(f, "the registry has {MAX_KEYS} keys already"),
+            RegistryError::Malformed(e) => write!(f, "{e}"),
+            RegistryError::Artifact(e) => write!(f, "{e}"),
+            RegistryError::Io(e) => write!(f, "io error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl From<ArtifactError> for RegistryError {
+    fn from(e: ArtifactError) -> Self {
+        RegistryError::Artifact(e)
+    }
+}
+
+impl From<MalformedKey> for RegistryError {
+    fn from(e: MalformedKey) -> Self {
+        RegistryError::Malformed(e)
+    }
+}
+
+impl From<std::io::Error> for RegistryError {
+    fn from(e: std::io::Error) -> Self {
+        RegistryError::Io(e)
+    }
+}
+
+pub struct Registered {
+    pub vk: VerifyingKey<Bls12_381>,
+    pub pvk: PreparedVerifyingKey<Bls12_381>,
+    pub fingerprint: String,
+    pub public_inputs: usize,
+}
+
+impl Registered {
+    // a key without gamma abc points is refused here, whether it came over HTTP or
+    // from a file somebody put in the directory
+    fn new(vk: VerifyingKey<Bls12_381>) -> Result<Self, RegistryError> {
+        Ok(Self {
+            public_inputs: key_info::public_inputs(&vk)?,
+            pvk: prepare_verifying_key(&vk),
+            fingerprint: key_info::fingerprint(&vk),
+            vk,
+        })
+    }
+}
+
+pub struct Registry {
+    dir: PathBuf,
+    keys: Mutex<BTreeMap<String, Arc<Registered>>>,
+}
+
+fn check_id(id: &str) -> Result<(), RegistryError> {
+    let plain = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_';
+    if id.is_empty() || id.len() > 64 || !id.chars().all(plain) {
+        return Err(RegistryError::BadId(id.to_string()));
+    }
+    Ok(())
+}
+
+impl Registry {
+    // the keys in `dir`, which is made if it isn't there
+    pub fn open(dir: &Path) -> Result<Self, RegistryError> {
+        std::fs::create_dir_all(dir)?;
+        let mut keys = BTreeMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+            if path.extension().and_then(|e| e.to_str()) != Some("vk") || check_id(id).is_err() {
+                continue;
+            }
+            match artifact::load(&path).map_err(RegistryError::from).and_then(Registered::new) {
+                Ok(registered) => keys.insert(id.to_string(), Arc::new(registered)),
+                Err(e) => {
+                    eprintln!("warning: skipping {}: {e}", path.display());
+                    continue;
+                }
+            };
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            keys: Mutex::new(keys),
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Registered>> {
+        self.keys.lock().expect("no panics holding the lock").get(id).cloned()
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.keys.lock().expect("no panics holding the lock").keys().cloned().collect()
+    }
+
+    pub fn count(&self) -> usize {
+        self.keys.lock().expect("no panics holding the lock").len()
+    }
+
+    // registers the key under `id`, true if it's new and false if it was there already
+    pub fn register(&self, id: &str, bytes: &[u8]) -> Result<(Arc<Registered>, bool), RegistryError> {
+        check_id(id)?;
+        // a key file zk-toy saved starts with a header, which says nothing to the registry
+        let (_, bytes) = artifact::split_header(bytes)?;
+        let vk: VerifyingKey<Bls12_381> = artifact::deserialize_checked(bytes)?;
+        let registered = Registered::new(vk)?;
+        let mut keys = self.keys.lock().expect("no panics holding the lock");
+        if let Some(existing) = keys.get(id) {
+            return if existing.vk == registered.vk { Ok((existing.clone(), false)) } else { Err(RegistryError::Taken(id.to_string())) };
+        }
+        if keys.len() >= MAX_KEYS {
+            return Err(RegistryError::Full);
+        }
+        // written next to it and renamed, so a crash never leaves half a key behind
+        let path = self.dir.join(format!("{id}.vk"));
+        let partial = self.dir.join(format!("{id}.vk.partial"));
+        artifact::save(&registered.vk, &crate::build_info(), &partial)?;
+        std::fs::rename(&partial, &path)?;
+        let registered = Arc::new(registered);
+        keys.insert(id.to_string(), registered.clone());
+        Ok((registered, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_groth16::Groth16;
+    use ark_snark::SNARK;
+    use rand::thread_rng;
+    use zk_circuits::addition::AdditionCircuit;
+
+    use super::*;
+
+    fn key() -> VerifyingKey<Bls12_381> {
+        Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut thread_rng()).unwrap().1
+    }
+
+    fn bytes(vk: &VerifyingKey<Bls12_381>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ark_serialize::CanonicalSerialize::serialize_compressed(vk, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("zk-toy-registry-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn keys_survive_a_restart() {
+        let dir = dir("restart");
+        let vk = key();
+        let registry = Registry::open(&dir).unwrap();
+        assert!(registry.register("sum", &bytes(&vk)).unwrap().1);
+        assert!(!registry.register("sum", &bytes(&vk)).unwrap().1);
+        assert!(matches!(registry.register("sum", &bytes(&key())), Err(RegistryError::Taken(_))));
+        let reopened = Registry::open(&dir).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(reopened.get("sum").unwrap().vk, vk);
+        assert_eq!(reopened.get("sum").unwrap().public_inputs, 1);
+    }
+
+    #[test]
+    fn a_key_without_gamma_abc_points_is_refused() {
+        let dir = dir("malformed");
+        let mut vk = key();
+        vk.gamma_abc_g1.clear();
+        let registry = Registry::open(&dir).unwrap();
+        let registered = registry.register("empty", &bytes(&vk));
+        // and one put in the directory by hand is skipped
+        artifact::save(&vk, &crate::build_info(), dir.join("by-hand.vk")).unwrap();
+        let reopened = Registry::open(&dir).unwrap();
+        let again = reopened.register("by-hand", &bytes(&vk));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(matches!(registered, Err(RegistryError::Malformed(_))));
+        assert!(reopened.get("by-hand").is_none());
+        assert!(matches!(again, Err(RegistryError::Malformed(_))));
+    }
+
+    #[test]
+    fn a_bad_file_is_skipped_and_the_others_still_load() {
+        let dir = dir("skipped");
+        let vk = key();
+        let registry = Registry::open(&dir).unwrap();
+        registry.register("sum", &bytes(&vk)).unwrap();
+        std::fs::write(dir.join("garbage.vk"), b"not a key at all").unwrap();
+        let mut truncated = std::fs::read(dir.join("sum.vk")).unwrap();
+        truncated.truncate(truncated.len() / 2);
+        std::fs::write(dir.join("truncated.vk"), truncated).unwrap();
+        let reopened = Registry::open(&dir).unwrap();
+        let garbage = reopened.register("garbage", b"not a key at all");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(reopened.ids(), ["sum"]);
+        assert_eq!(reopened.get("sum").unwrap().vk, vk);
+        assert!(matches!(garbage, Err(RegistryError::Artifact(_))));
+    }
+}
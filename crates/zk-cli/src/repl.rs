@@ -0,0 +1,577 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal, Write};
+
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_ec::Group;
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use num_bigint::BigUint;
+use rand::rngs::ThreadRng;
+use rand::thread_rng;
+use zk_circuits::curves::Curve;
+use zk_core::input::{Reduction, parse_field_with};
+use zk_gadgets::hasher::{CircuitHasher, MimcHasher, PoseidonHasher, Sha256Hasher};
+use zk_protocols::pedersen::{Opening, PedersenParams};
+
+use crate::CliResult;
+
+/*
+zk-toy repl, the arithmetic every circuit is made of, one line at a time: scalars of
+BLS12-381, points of G1 and G2, pairings, the three hashes of zk-gadgets and Pedersen
+commitments (zk-protocols/src/pedersen.rs).
+
+  [1]> x = 3
+  x = 3
+  [2]> x / 2 * 2 == x
+  _2 = true
+  [3]> commit(x, r = rand()) - r * h == x * g1
+  _3 = true
+
+Every result is kept as _1, _2, ... (and _ for the last one), names are bound with
+`name = ...` and :history lists the session. Division is by the inverse, so 1/2 is
+the number that gives 1 when doubled, and -1 is r - 1; a scalar close to r prints as
+the negative number it is. The pairing's group is written additively like arkworks
+does, pair(a * g1, g2) == a * pair(g1, g2). Piped in, the lines are echoed, so a
+script gives a transcript:
+
+  printf 'x = 5\nsqrt(x * x) == x\n' | zk-toy repl
+*/
+
+const HELP: &str = "\
+numbers    17, 0x11, -5, 1e18: scalars mod the BLS12-381 group order r
+operators  + - * / ^ (x^-1 is the inverse), == and !=, parentheses
+points     g1, g2 and h (the second Pedersen generator, in G1), times scalars
+functions  inv(x)  sqrt(x)  rand()  pair(P in G1, Q in G2)  commit(value, blinding)
+           poseidon(x, ...)  mimc(x, ...)  sha256(x, ...)
+names      name = expr binds one, _1, _2, ... are the results and _ the last
+commands   :vars  :history  :help  :quit";
+
+const SYMBOLS: [&str; 11] = ["==", "!=", "+", "-", "*", "/", "^", "(", ")", ",", "="];
+const CONSTANTS: &[&str] = &["g1", "g2", "h"];
+const FUNCTIONS: &[&str] = &["inv", "sqrt", "rand", "pair", "commit", "poseidon", "mimc", "sha256"];
+
+#[derive(Clone, PartialEq)]
+enum Value {
+    Scalar(Fr),
+    G1(G1Projective),
+    G2(G2Projective),
+    // an element of Fq12, twelve times a base field element, so boxed
+    Gt(Box<PairingOutput<Bls12_381>>),
+    Bool(bool),
+}
+
+// points are shown by the start and end of their compressed encoding
+fn abbreviated<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing to memory can't fail");
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{}…{}", &hex[..16], &hex[hex.len() - 8..])
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Scalar(x) => {
+                let (x, minus) = (BigUint::from(x.into_bigint()), BigUint::from((-*x).into_bigint()));
+                // -5 rather than the 77 digit number it is mod r
+                if minus < x && minus.bits() <= 64 { write!(f, "-{minus}") } else { write!(f, "{x}") }
+            }
+            Value::G1(p) if p.is_zero() => write!(f, "G1 infinity"),
+            Value::G1(p) => write!(f, "G1 {}", abbreviated(p)),
+            Value::G2(p) if p.is_zero() => write!(f, "G2 infinity"),
+            Value::G2(p) => write!(f, "G2 {}", abbreviated(p)),
+            Value::Gt(p) if p.is_zero() => write!(f, "Gt one"),
+            Value::Gt(p) => write!(f, "Gt {}", abbreviated(&p.0)),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Scalar(_) => "a scalar",
+            Value::G1(_) => "a G1 point",
+            Value::G2(_) => "a G2 point",
+            Value::Gt(_) => "a pairing",
+            Value::Bool(_) => "a boolean",
+        }
+    }
+
+    fn scalar(&self) -> Result<Fr, String> {
+        match self {
+            Value::Scalar(x) => Ok(*x),
+            other => Err(format!("expected a scalar, got {}", other.kind())),
+        }
+    }
+}
+
+fn add(a: Value, b: Value) -> Result<Value, String> {
+    match (a, b) {
+        (Value::Scalar(a), Value::Scalar(b)) => Ok(Value::Scalar(a + b)),
+        (Value::G1(a), Value::G1(b)) => Ok(Value::G1(a + b)),
+        (Value::G2(a), Value::G2(b)) => Ok(Value::G2(a + b)),
+        (Value::Gt(a), Value::Gt(b)) => Ok(Value::Gt(Box::new(*a + *b))),
+        (a, b) => Err(format!("can't add {} and {}", a.kind(), b.kind())),
+    }
+}
+
+fn negate(a: Value) -> Result<Value, String> {
+    match a {
+        Value::Scalar(a) => Ok(Value::Scalar(-a)),
+        Value::G1(a) => Ok(Value::G1(-a)),
+        Value::G2(a) => Ok(Value::G2(-a)),
+        Value::Gt(a) => Ok(Value::Gt(Box::new(-*a))),
+        Value::Bool(_) => Err("can't negate a boolean".to_string()),
+    }
+}
+
+fn multiply(a: Value, b: Value) -> Result<Value, String> {
+    match (a, b) {
+        (Value::Scalar(a), Value::Scalar(b)) => Ok(Value::Scalar(a * b)),
+        (Value::Scalar(k), Value::G1(p)) | (Value::G1(p), Value::Scalar(k)) => Ok(Value::G1(p * k)),
+        (Value::Scalar(k), Value::G2(p)) | (Value::G2(p), Value::Scalar(k)) => Ok(Value::G2(p * k)),
+        (Value::Scalar(k), Value::Gt(p)) | (Value::Gt(p), Value::Scalar(k)) => Ok(Value::Gt(Box::new(*p * k))),
+        (a, b) => Err(format!("can't multiply {} by {}, only scalars multiply", a.kind(), b.kind())),
+    }
+}
+
+fn inverse(x: Fr) -> Result<Fr, String> {
+    x.inverse().ok_or_else(|| "0 has no inverse".to_string())
+}
+
+fn power(base: Value, exponent: Fr) -> Result<Value, String> {
+    let base = base.scalar()?;
+    let minus = BigUint::from((-exponent).into_bigint());
+    // a small negative exponent is a power of the inverse, not of r minus something
+    if minus.bits() <= 64 && !exponent.is_zero() && minus < BigUint::from(exponent.into_bigint()) {
+        return Ok(Value::Scalar(inverse(base)?.pow((-exponent).into_bigint())));
+    }
+    Ok(Value::Scalar(base.pow(exponent.into_bigint())))
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Name(String),
+    Symbol(&'static str),
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let word = line[start..end].to_string();
+            tokens.push(if c.is_ascii_digit() { Token::Number(word) } else { Token::Name(word) });
+        } else {
+            let symbol = SYMBOLS
+                .into_iter()
+                .find(|s| line[start..].starts_with(s))
+                .ok_or_else(|| format!("unexpected '{c}'"))?;
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Session {
+    vars: BTreeMap<String, Value>,
+    // (input, result) of every line that worked
+    history: Vec<(String, String)>,
+    pedersen: PedersenParams<G1Projective>,
+    rng: ThreadRng,
+}
+
+// a line being evaluated: the tokens, where it's at and the session it reads and binds in
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    at: usize,
+    session: &'a mut Session,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn eat(&mut self, symbol: &'static str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<(), String> {
+        if self.eat(symbol) { Ok(()) } else { Err(format!("expected '{symbol}'")) }
+    }
+
+    // comparison := sum (("==" | "!=") sum)?
+    fn comparison(&mut self) -> Result<Value, String> {
+        let left = self.sum()?;
+        if self.eat("==") {
+            return Ok(Value::Bool(left == self.sum()?));
+        }
+        if self.eat("!=") {
+            return Ok(Value::Bool(left != self.sum()?));
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Value, String> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat("+") {
+                value = add(value, self.product()?)?;
+            } else if self.eat("-") {
+                value = add(value, negate(self.product()?)?)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Value, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat("*") {
+                value = multiply(value, self.unary()?)?;
+            } else if self.eat("/") {
+                let divisor = self.unary()?;
+                value = multiply(value, Value::Scalar(inverse(divisor.scalar()?)?))?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // -x^2 is -(x^2), and 2^-1 works
+    fn unary(&mut self) -> Result<Value, String> {
+        if self.eat("-") {
+            return negate(self.unary()?);
+        }
+        let base = self.atom()?;
+        if self.eat("^") {
+            let exponent = self.unary()?.scalar()?;
+            return power(base, exponent);
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Value, String> {
+        let token = self.peek().cloned().ok_or("the line ends too early")?;
+        self.at += 1;
+        match token {
+            Token::Number(text) => parse_field_with::<Fr>(&text, Reduction::Reject)
+                .map(Value::Scalar)
+                .map_err(|e| e.to_string()),
+            Token::Symbol("(") => {
+                let value = self.comparison()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Token::Name(name) if self.eat("(") => {
+                let mut args = Vec::new();
+                if !self.eat(")") {
+                    loop {
+                        args.push(self.argument()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                self.session.call(&name, args)
+            }
+            Token::Name(name) => self.session.lookup(&name),
+            Token::Symbol(symbol) => Err(format!("unexpected '{symbol}'")),
+        }
+    }
+
+    // an argument can bind a name on the way, commit(v, r = rand())
+    fn argument(&mut self) -> Result<Value, String> {
+        if let (Some(Token::Name(name)), Some(Token::Symbol("="))) = (self.peek().cloned(), self.tokens.get(self.at + 1)) {
+            self.at += 2;
+            let value = self.comparison()?;
+            self.session.bind(&name, value.clone())?;
+            return Ok(value);
+        }
+        self.comparison()
+    }
+}
+
+fn hash_args<H: CircuitHasher<Fr>>(hasher: H, args: &[Value]) -> Result<Value, String> {
+    let inputs = args.iter().map(Value::scalar).collect::<Result<Vec<_>, _>>()?;
+    Ok(Value::Scalar(hasher.hash(&inputs)))
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            vars: BTreeMap::new(),
+            history: Vec::new(),
+            pedersen: PedersenParams::new(b"zk-toy pedersen h"),
+            rng: thread_rng(),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Value, String> {
+        match name {
+            "g1" => Ok(Value::G1(G1Projective::generator())),
+            "g2" => Ok(Value::G2(G2Projective::generator())),
+            "h" => Ok(Value::G1(self.pedersen.h.into())),
+            "_" => self.vars.get(&format!("_{}", self.history.len())).cloned().ok_or_else(|| "no results yet".to_string()),
+            _ if FUNCTIONS.contains(&name) => Err(format!("{name} is a function, call it with {name}(...)")),
+            _ => self.vars.get(name).cloned().ok_or_else(|| format!("nothing is called '{name}', :vars lists the names")),
+        }
+    }
+
+    fn bind(&mut self, name: &str, value: Value) -> Result<(), String> {
+        if CONSTANTS.contains(&name) || FUNCTIONS.contains(&name) {
+            return Err(format!("'{name}' is built in, pick another name"));
+        }
+        if name.starts_with('_') {
+            return Err("the names starting with _ are the results".to_string());
+        }
+        self.vars.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let arity = |n: usize| {
+            if args.len() == n { Ok(()) } else { Err(format!("{name} takes {n} arguments, not {}", args.len())) }
+        };
+        match name {
+            "inv" => {
+                arity(1)?;
+                Ok(Value::Scalar(inverse(args[0].scalar()?)?))
+            }
+            "sqrt" => {
+                arity(1)?;
+                let x = args[0].scalar()?;
+                x.sqrt().map(Value::Scalar).ok_or_else(|| format!("{} isn't a square mod r", Value::Scalar(x)))
+            }
+            "rand" => {
+                arity(0)?;
+                Ok(Value::Scalar(Fr::rand(&mut self.rng)))
+            }
+            "pair" => match args.as_slice() {
+                [Value::G1(p), Value::G2(q)] => Ok(Value::Gt(Box::new(Bls12_381::pairing(*p, *q)))),
+                _ => Err("pair takes a G1 point and a G2 point".to_string()),
+            },
+            "commit" => {
+                arity(2)?;
+                let opening = Opening { value: args[0].scalar()?, blinding: args[1].scalar()? };
+                Ok(Value::G1(self.pedersen.commit(&opening).into()))
+            }
+            "poseidon" => hash_args(PoseidonHasher::<Fr>::new(2), &args),
+            "mimc" => hash_args(MimcHasher::<Fr>::default(), &args),
+            "sha256" => hash_args(Sha256Hasher, &args),
+            _ => Err(format!("no function '{name}', :help lists them")),
+        }
+    }
+
+    // what the line prints, None for a blank one
+    fn eval(&mut self, line: &str) -> Result<Option<String>, String> {
+        let line = line.trim();
+        match line {
+            "" => return Ok(None),
+            ":help" => return Ok(Some(HELP.to_string())),
+            ":vars" => {
+                let mut names: Vec<String> = self.vars.iter().filter(|(name, _)| !name.starts_with('_')).map(|(name, value)| format!("{name} = {value}")).collect();
+                names.extend(CONSTANTS.iter().map(|name| format!("{name} = {}", self.lookup(name).expect("constants are there"))));
+                return Ok(Some(names.join("\n")));
+            }
+            ":history" => {
+                let lines: Vec<String> =
+                    self.history.iter().enumerate().map(|(i, (input, result))| format!("[{}] {input}\n    {result}", i + 1)).collect();
+                return Ok(Some(if lines.is_empty() { "nothing yet".to_string() } else { lines.join("\n") }));
+            }
+            _ if line.starts_with(':') => return Err(format!("no command {line}, :help lists them")),
+            _ => {}
+        }
+
+        let tokens = tokenize(line)?;
+        let assigned = match tokens.as_slice() {
+            [Token::Name(name), Token::Symbol("="), ..] => Some(name.clone()),
+            _ => None,
+        };
+        let start = if assigned.is_some() { 2 } else { 0 };
+        // the names a line binds on the way only stay if all of it works
+        let before = self.vars.clone();
+        let mut parser = Parser { tokens, at: start, session: self };
+        let result = parser.comparison().and_then(|value| match parser.peek() {
+            None => Ok(value),
+            Some(Token::Number(text) | Token::Name(text)) => Err(format!("unexpected '{text}' after the expression")),
+            Some(Token::Symbol(symbol)) => Err(format!("unexpected '{symbol}' after the expression")),
+        });
+        let value = match result {
+            Ok(value) => value,
+            Err(e) => {
+                self.vars = before;
+                return Err(e);
+            }
+        };
+        if let Some(name) = &assigned
+            && let Err(e) = self.bind(name, value.clone())
+        {
+            self.vars = before;
+            return Err(e);
+        }
+        let number = self.history.len() + 1;
+        self.vars.insert(format!("_{number}"), value.clone());
+        let shown = format!("{} = {value}", assigned.unwrap_or(format!("_{number}")));
+        self.history.push((line.to_string(), shown.clone()));
+        Ok(Some(shown))
+    }
+}
+
+pub fn run() -> CliResult<()> {
+    let mut session = Session::new();
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        println!("zk-toy repl, BLS12-381 ({} scalars, G1, G2 and pairings). :help for what it knows", Bls12_381::NAME);
+    }
+    let mut lines = stdin.lock().lines();
+    loop {
+        let prompt = format!("[{}]> ", session.history.len() + 1);
+        if interactive {
+            print!("{prompt}");
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next().transpose()? else { break };
+        if !interactive {
+            println!("{prompt}{line}");
+        }
+        if matches!(line.trim(), ":quit" | ":q") {
+            break;
+        }
+        match session.eval(&line) {
+            Ok(Some(output)) => println!("{output}"),
+            Ok(None) => {}
+            Err(e) => println!("error: {e}"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // what each line prints, panicking on the first one that fails
+    fn run(lines: &[&str]) -> Vec<String> {
+        let mut session = Session::new();
+        lines.iter().map(|line| session.eval(line).unwrap_or_else(|e| panic!("{line}: {e}")).unwrap_or_default()).collect()
+    }
+
+    // the value of the line, without its name
+    fn value(line: &str) -> String {
+        let shown = run(&[line]).remove(0);
+        shown.split_once(" = ").expect("a result").1.to_string()
+    }
+
+    fn error(line: &str) -> String {
+        Session::new().eval(line).err().unwrap_or_else(|| panic!("{line} should fail"))
+    }
+
+    #[test]
+    fn precedence_is_the_usual_one() {
+        assert_eq!(value("1 + 2 * 3"), "7");
+        assert_eq!(value("(1 + 2) * 3"), "9");
+        assert_eq!(value("10 - 3 - 2"), "5");
+        assert_eq!(value("12 / 2 / 3"), "2");
+        assert_eq!(value("2 * 3^2"), "18");
+        assert_eq!(value("-2^2"), "-4");
+        assert_eq!(value("2^3^2"), "512");
+        assert_eq!(value("1 + 2 == 3"), "true");
+        assert_eq!(value("2 * 2 != 4"), "false");
+        assert_eq!(value("0 - 5"), "-5");
+    }
+
+    #[test]
+    fn a_negative_exponent_is_the_inverse() {
+        assert_eq!(value("2^-1 == inv(2)"), "true");
+        assert_eq!(value("2^-1 * 2"), "1");
+        assert_eq!(value("1 / 2 == 2^-1"), "true");
+        assert_eq!(value("3^-2 * 9"), "1");
+        assert_eq!(value("-1 == 0 - 1"), "true");
+        assert_eq!(error("0^-1"), "0 has no inverse");
+    }
+
+    #[test]
+    fn names_and_results_are_kept() {
+        assert_eq!(run(&["x = 3", "x * x", "_ + 1", "_1 + _2", "x = x + 1", "x"]), ["x = 3", "_2 = 9", "_3 = 10", "_4 = 12", "x = 4", "_6 = 4"]);
+        // a line that fails binds nothing and takes no number
+        let mut session = Session::new();
+        assert!(session.eval("commit(1, r = 2) + 1").is_err());
+        assert_eq!(session.eval("r"), Err("nothing is called 'r', :vars lists the names".to_string()));
+        assert_eq!(session.eval("commit(1, r = 2) == g1 + 2 * h"), Ok(Some("_1 = true".to_string())));
+        assert_eq!(session.eval("r"), Ok(Some("_2 = 2".to_string())));
+    }
+
+    #[test]
+    fn points_add_multiply_and_pair() {
+        assert_eq!(value("2 * g1 == g1 + g1"), "true");
+        assert_eq!(value("g2 * 3 == g2 + g2 + g2"), "true");
+        assert_eq!(value("g1 / 2 * 2 == g1"), "true");
+        assert_eq!(value("g1 - g1"), "G1 infinity");
+        assert_eq!(value("0 * g2"), "G2 infinity");
+        assert_eq!(value("pair(3 * g1, g2) == 3 * pair(g1, g2)"), "true");
+        assert_eq!(value("pair(g1, 5 * g2) == pair(5 * g1, g2)"), "true");
+        assert_eq!(value("pair(g1, g2) - pair(g1, g2)"), "Gt one");
+        assert_eq!(value("commit(5, 7) == 5 * g1 + 7 * h"), "true");
+        assert!(value("g1").starts_with("G1 "));
+    }
+
+    #[test]
+    fn mistakes_say_what_is_wrong() {
+        let cases = [
+            ("g1 * g2", "can't multiply a G1 point by a G2 point, only scalars multiply"),
+            ("g1 + 1", "can't add a G1 point and a scalar"),
+            ("-(1 == 1)", "can't negate a boolean"),
+            ("2 / g1", "expected a scalar, got a G1 point"),
+            ("g1 ^ 2", "expected a scalar, got a G1 point"),
+            ("1 / 0", "0 has no inverse"),
+            ("inv(0)", "0 has no inverse"),
+            ("inv(1, 2)", "inv takes 1 arguments, not 2"),
+            ("pair(g2, g1)", "pair takes a G1 point and a G2 point"),
+            ("sha256(g1)", "expected a scalar, got a G1 point"),
+            ("foo(1)", "no function 'foo', :help lists them"),
+            ("inv", "inv is a function, call it with inv(...)"),
+            ("y", "nothing is called 'y', :vars lists the names"),
+            ("_", "no results yet"),
+            ("g1 = 5", "'g1' is built in, pick another name"),
+            ("_1 = 5", "the names starting with _ are the results"),
+            ("1 +", "the line ends too early"),
+            ("(1 + 2", "expected ')'"),
+            ("1 2", "unexpected '2' after the expression"),
+            ("1 )", "unexpected ')' after the expression"),
+            ("* 2", "unexpected '*'"),
+            ("1 $ 2", "unexpected '$'"),
+            ("1.5", "unexpected '.'"),
+            (":nope", "no command :nope, :help lists them"),
+        ];
+        for (line, message) in cases {
+            assert_eq!(error(line), message, "{line}");
+        }
+        assert!(error("0x").contains("0x"));
+    }
+}
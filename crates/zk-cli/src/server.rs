@@ -0,0 +1,601 @@
+use std::collections::HashMap;
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, PreparedVerifyingKey, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use serde_json::{Value, json};
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::examples::count_constraints;
+use zk_circuits::expr::{ExprCircuit, Program};
+use zk_circuits::verifier::verify_constant_work;
+use zk_core::artifact;
+use zk_core::input::parse_field;
+use zk_core::report::timed;
+
+use crate::http::{self, Request, Response};
+use crate::metrics::{Gauges, Metrics};
+use crate::registry::{Registered, Registry, RegistryError};
+use crate::{CliResult, ProofArtifact, ServeArgs};
+
+/*
+zk-toy serve, proving and verifying over HTTP for demos and classrooms (the `server`
+feature). Bodies and answers are json, proofs travel as the hex of the proof file's
+bytes (ProofArtifact, the proof and its public inputs):
+
+  GET  /health
+  POST /addition/prove   { "x": "17", "y": "2" }         -> { "proof": "...", "public_inputs": ["19"] }
+  POST /addition/verify  { "proof": "..." }              -> { "valid": true, "public_inputs": ["19"] }
+  POST /expr/prove       { "statement": "public z; secret x, y; x*x + y*3 == z",
+                           "values": { "x": "3", "y": "4" } }
+  POST /expr/verify      { "statement": "...", "proof": "..." }
+  GET  /metrics          counters, histograms and gauges for Prometheus (metrics.rs)
+
+and a registry of verifying keys for circuits the server doesn't know (registry.rs),
+kept in --registry (registry/ by default):
+
+  PUT  /keys/<id>        { "verifying_key": "..." }      -> { "id": ..., "fingerprint": ..., "public_inputs": 2 }
+  GET  /keys             the ids
+  GET  /keys/<id>        the key and what it's for
+  POST /keys/<id>/verify { "proof": "...", "public_inputs": ["3", "0x2a"] }
+
+where the key is the hex of a compressed arkworks VerifyingKey<Bls12_381> and the
+proof of a compressed Proof<Bls12_381>, which is what `serialize_compressed` writes
+on the team's side. Without public_inputs the proof is a whole proof file, like for
+the other endpoints.
+
+The addition keys come from --keys-dir when zk-toy setup put them there (so proofs
+from the server verify with zk-toy verify), from a setup at startup otherwise. Expr
+keys are made the first time a statement is proved and kept in memory, so a restart
+forgets them.
+
+A shared machine needs limits, or one request for a big statement keeps every core
+busy. Each is a flag, and going over one is an error like any other, with a status
+and a code to match on:
+
+  --max-body         bytes of a request body                 413 body_too_large
+  --max-connections  requests being handled at once          503 too_many_connections
+  --max-jobs         setups and proofs running at once       503 busy
+  --timeout          seconds to wait for a job               504 timeout
+  --max-constraints  size of a statement's circuit           413 circuit_too_large
+
+A job that times out can't be stopped (arkworks has no way to cancel a proof), so it
+keeps its slot until it finishes: the timeout bounds how long a client waits, and
+--max-jobs bounds how much work there is. Slow clients get 10 seconds to send the
+whole request, however they spread it out (408 request_timeout). The number of
+statements with keys is capped as well (503 too_many_statements), they'd grow the
+memory otherwise.
+*/
+
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_STATEMENTS: usize = 64;
+
+struct Keys {
+    pk: ProvingKey<Bls12_381>,
+    pvk: PreparedVerifyingKey<Bls12_381>,
+}
+
+impl Keys {
+    fn new(pk: ProvingKey<Bls12_381>, vk: &VerifyingKey<Bls12_381>) -> Self {
+        Self {
+            pvk: prepare_verifying_key(vk),
+            pk,
+        }
+    }
+}
+
+// a counter with a maximum, handing out slots that give themselves back when dropped
+struct Slots {
+    used: Arc<AtomicUsize>,
+    max: usize,
+}
+
+struct Slot(Arc<AtomicUsize>);
+
+impl Slots {
+    fn new(max: usize) -> Self {
+        Self {
+            used: Arc::new(AtomicUsize::new(0)),
+            max,
+        }
+    }
+
+    fn take(&self) -> Option<Slot> {
+        self.used.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| (used < self.max).then_some(used + 1)).ok()?;
+        Some(Slot(self.used.clone()))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct State {
+    limits: ServeArgs,
+    addition: Arc<Keys>,
+    // by the statement's fingerprint
+    statements: Mutex<HashMap<String, Arc<Keys>>>,
+    jobs: Slots,
+    connections: Slots,
+    metrics: Arc<Metrics>,
+    registry: Registry,
+}
+
+fn addition_keys(keys_dir: &Path) -> CliResult<Keys> {
+    let (pk_path, vk_path) = (keys_dir.join("addition.pk"), keys_dir.join("addition.vk"));
+    if pk_path.exists() && vk_path.exists() {
+        let pk: ProvingKey<Bls12_381> = artifact::load_versioned::<AdditionCircuit<Fr>, _>(&pk_path)?;
+        let vk: VerifyingKey<Bls12_381> = artifact::load_versioned::<AdditionCircuit<Fr>, _>(&vk_path)?;
+        println!("addition keys from {}", keys_dir.display());
+        return Ok(Keys::new(pk, &vk));
+    }
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut thread_rng())?;
+    println!("no addition keys in {}, made new ones (zk-toy verify won't know them)", keys_dir.display());
+    Ok(Keys::new(pk, &vk))
+}
+
+pub fn serve(args: ServeArgs) -> CliResult<()> {
+    let listener = TcpListener::bind(&args.addr)?;
+    let state = Arc::new(State {
+        addition: Arc::new(addition_keys(&args.keys_dir)?),
+        statements: Mutex::new(HashMap::new()),
+        jobs: Slots::new(args.max_jobs),
+        connections: Slots::new(args.max_connections),
+        metrics: Arc::new(Metrics::default()),
+        registry: Registry::open(&args.registry)?,
+        limits: args,
+    });
+    let limits = &state.limits;
+    println!("{} registered verifying keys in {}", state.registry.count(), limits.registry.display());
+    println!(
+        "listening on http://{} (timeout {}s, {} jobs, {} connections, {} constraints, {} byte bodies)",
+        listener.local_addr()?,
+        limits.timeout,
+        limits.max_jobs,
+        limits.max_connections,
+        limits.max_constraints,
+        limits.max_body
+    );
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let Some(slot) = state.connections.take() else {
+            let busy = Response::error(503, "too_many_connections", "too many requests at once, try again later");
+            state.metrics.error("too_many_connections");
+            let _ = http::write_response(&stream, &busy);
+            continue;
+        };
+        let state = state.clone();
+        thread::spawn(move || {
+            let _slot = slot;
+            handle(&state, stream);
+        });
+    }
+    Ok(())
+}
+
+fn handle(state: &State, stream: TcpStream) {
+    let response = match http::read_request(&stream, state.limits.max_body, READ_TIMEOUT) {
+        Ok(request) => route(state, &request).unwrap_or_else(|error| error),
+        Err(error) => error,
+    };
+    if let Some(code) = response.code {
+        state.metrics.error(code);
+    }
+    let _ = http::write_response(&stream, &response);
+}
+
+fn route(state: &State, request: &Request) -> Result<Response, Response> {
+    let post = |handler: fn(&State, &Value) -> Result<Response, Response>| post_with(request, |body| handler(state, body));
+    let get = |handler: &dyn Fn() -> Result<Response, Response>| {
+        if request.method != "GET" {
+            return Err(Response::error(405, "method_not_allowed", format!("{} takes GET", request.path)));
+        }
+        handler()
+    };
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["health"] => Ok(Response::json(200, &json!({ "status": "ok" }))),
+        ["metrics"] => Ok(Response::text("text/plain; version=0.0.4", state.metrics.render(&gauges(state)))),
+        ["addition", "prove"] => post(prove_addition),
+        ["addition", "verify"] => post(verify_addition),
+        ["expr", "prove"] => post(prove_expr),
+        ["expr", "verify"] => post(verify_expr),
+        ["keys"] => get(&|| Ok(Response::json(200, &json!({ "ids": state.registry.ids() })))),
+        ["keys", id] if request.method == "PUT" => register_key(state, id, &request.body),
+        ["keys", id] => get(&|| show_key(state, id)),
+        ["keys", id, "verify"] => post_with(request, |body| verify_registered(state, id, body)),
+        _ => Err(Response::error(404, "not_found", format!("no endpoint {}", request.path))),
+    }
+}
+
+fn post_with(request: &Request, handler: impl FnOnce(&Value) -> Result<Response, Response>) -> Result<Response, Response> {
+    if request.method != "POST" {
+        return Err(Response::error(405, "method_not_allowed", format!("{} takes POST", request.path)));
+    }
+    let body = serde_json::from_slice(&request.body).map_err(|e| Response::error(400, "bad_json", e.to_string()))?;
+    handler(&body)
+}
+
+fn gauges(state: &State) -> Gauges {
+    Gauges {
+        jobs_running: state.jobs.used.load(Ordering::SeqCst),
+        jobs_max: state.jobs.max,
+        connections_open: state.connections.used.load(Ordering::SeqCst),
+        statements: state.statements.lock().expect("no panics holding the lock").len(),
+        registered_keys: state.registry.count(),
+    }
+}
+
+// setups and proofs run on their own thread, within the job limit and the timeout
+fn run_job<T: Send + 'static>(state: &State, work: impl FnOnce() -> Result<T, Response> + Send + 'static) -> Result<T, Response> {
+    let slot = state
+        .jobs
+        .take()
+        .ok_or_else(|| Response::error(503, "busy", format!("{} jobs are running already, try again later", state.limits.max_jobs)))?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _slot = slot;
+        let _ = sender.send(work());
+    });
+    match receiver.recv_timeout(Duration::from_secs(state.limits.timeout)) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(Response::error(504, "timeout", format!("the job took over {}s", state.limits.timeout))),
+        Err(RecvTimeoutError::Disconnected) => Err(Response::error(500, "internal", "the job failed")),
+    }
+}
+
+fn field(body: &Value, name: &str) -> Result<String, Response> {
+    match body.get(name) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(Value::Number(n)) => Ok(n.to_string()),
+        _ => Err(Response::error(422, "bad_input", format!("the body needs a string {name}"))),
+    }
+}
+
+fn element(text: &str, name: &str) -> Result<Fr, Response> {
+    parse_field(text).map_err(|e| Response::error(422, "bad_input", format!("{name}: {e}")))
+}
+
+fn proof_json(artifact: &ProofArtifact) -> Result<Response, Response> {
+    let bytes = artifact.to_bytes().map_err(|e| Response::error(500, "internal", e.to_string()))?;
+    Ok(Response::json(200, &json!({
+        "proof": artifact::to_hex(&bytes),
+        "public_inputs": crate::display_inputs(&artifact.public_inputs),
+    })))
+}
+
+// the proof in a body, checked like any proof from somebody else
+fn read_proof(body: &Value) -> Result<ProofArtifact, Response> {
+    let bad = |e: artifact::ArtifactError| Response::error(422, "bad_proof", e.to_string());
+    ProofArtifact::from_bytes(&artifact::from_hex(&field(body, "proof")?).map_err(bad)?).map_err(bad)
+}
+
+fn verified(state: &State, circuit: &'static str, pvk: &PreparedVerifyingKey<Bls12_381>, artifact: &ProofArtifact) -> Response {
+    let (valid, took) = timed(|| verify_constant_work(pvk, &artifact.public_inputs, &artifact.proof));
+    state.metrics.verified(circuit, valid, took);
+    Response::json(200, &json!({ "valid": valid, "public_inputs": crate::display_inputs(&artifact.public_inputs) }))
+}
+
+fn prove_addition(state: &State, body: &Value) -> Result<Response, Response> {
+    let circuit = AdditionCircuit::new(element(&field(body, "x")?, "x")?, element(&field(body, "y")?, "y")?);
+    let public_inputs = vec![circuit.z.expect("new sets z")];
+    let (keys, metrics) = (state.addition.clone(), state.metrics.clone());
+    let proof = run_job(state, move || {
+        let (proof, took) = timed(|| Groth16::<Bls12_381>::prove(&keys.pk, circuit, &mut thread_rng()));
+        metrics.proved("addition", took);
+        proof.map_err(|e| Response::error(500, "internal", e.to_string()))
+    })?;
+    proof_json(&ProofArtifact { proof, public_inputs })
+}
+
+fn verify_addition(state: &State, body: &Value) -> Result<Response, Response> {
+    Ok(verified(state, "addition", &state.addition.pvk, &read_proof(body)?))
+}
+
+fn statement(body: &Value) -> Result<Program<Fr>, Response> {
+    Program::parse(&field(body, "statement")?).map_err(|e| Response::error(422, "bad_statement", e.to_string()))
+}
+
+fn prove_expr(state: &State, body: &Value) -> Result<Response, Response> {
+    let program = statement(body)?;
+    let mut named = Vec::new();
+    if let Some(values) = body.get("values").and_then(Value::as_object) {
+        for name in values.keys() {
+            named.push((name.clone(), element(&field(&body["values"], name)?, name)?));
+        }
+    }
+    let fingerprint = program.fingerprint();
+    let too_many = || Response::error(503, "too_many_statements", format!("the server has keys for {MAX_STATEMENTS} statements already"));
+    let keys = {
+        let statements = state.statements.lock().expect("no panics holding the lock");
+        let keys = statements.get(&fingerprint).cloned();
+        if keys.is_none() && statements.len() >= MAX_STATEMENTS {
+            return Err(too_many());
+        }
+        keys
+    };
+
+    // counting the constraints and solving synthesize the statement, which takes as long
+    // as the statement is big, so they are part of the job too
+    let max_constraints = state.limits.max_constraints;
+    let internal = |e: ark_relations::r1cs::SynthesisError| Response::error(500, "internal", e.to_string());
+    let metrics = state.metrics.clone();
+    let made = run_job(state, move || {
+        let constraints = count_constraints(&ExprCircuit::empty(program.clone())).map_err(|e| Response::error(422, "bad_statement", e.to_string()))?;
+        if constraints > max_constraints {
+            let message = format!("the statement is {constraints} constraints, the limit is {max_constraints}");
+            return Err(Response::error(413, "circuit_too_large", message));
+        }
+        let values = program
+            .solve(named.iter().map(|(name, value)| (name.as_str(), *value)))
+            .map_err(|e| Response::error(422, "bad_input", e.to_string()))?;
+        let public_inputs = program.public_inputs(&values);
+        let circuit = ExprCircuit::new(program.clone(), values).map_err(|e| Response::error(422, "bad_input", e.to_string()))?;
+        let keys = match keys {
+            Some(keys) => keys,
+            None => {
+                let (made, took) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(ExprCircuit::empty(program), &mut thread_rng()));
+                metrics.set_up("expr", took);
+                let (pk, vk) = made.map_err(internal)?;
+                Arc::new(Keys::new(pk, &vk))
+            }
+        };
+        let (proof, took) = timed(|| Groth16::<Bls12_381>::prove(&keys.pk, circuit, &mut thread_rng()));
+        metrics.proved("expr", took);
+        Ok((keys, proof.map_err(internal)?, public_inputs))
+    })?;
+    let (keys, proof, public_inputs) = made;
+    // two first proofs of a statement can race, the keys that got in first are kept and
+    // a proof made with the others wouldn't verify with them. The limit is checked again
+    // under the same lock as the insert, other statements may have come in meanwhile
+    let kept = {
+        let mut statements = state.statements.lock().expect("no panics holding the lock");
+        if !statements.contains_key(&fingerprint) && statements.len() >= MAX_STATEMENTS {
+            return Err(too_many());
+        }
+        statements.entry(fingerprint).or_insert_with(|| keys.clone()).clone()
+    };
+    let artifact = ProofArtifact { proof, public_inputs };
+    if !Arc::ptr_eq(&kept, &keys) {
+        return Err(Response::error(503, "busy", "the statement was set up by another request at the same time, try again"));
+    }
+    proof_json(&artifact)
+}
+
+fn verify_expr(state: &State, body: &Value) -> Result<Response, Response> {
+    let program = statement(body)?;
+    let keys = state.statements.lock().expect("no panics holding the lock").get(&program.fingerprint()).cloned();
+    let keys = keys.ok_or_else(|| Response::error(404, "unknown_statement", format!("no keys for `{program}`, prove it here first")))?;
+    Ok(verified(state, "expr", &keys.pvk, &read_proof(body)?))
+}
+
+fn register_key(state: &State, id: &str, body: &[u8]) -> Result<Response, Response> {
+    let body: Value = serde_json::from_slice(body).map_err(|e| Response::error(400, "bad_json", e.to_string()))?;
+    let bytes = artifact::from_hex(&field(&body, "verifying_key")?).map_err(|e| Response::error(422, "bad_key", e.to_string()))?;
+    let (key, new) = state.registry.register(id, &bytes).map_err(|e| match e {
+        RegistryError::BadId(_) => Response::error(422, "bad_id", e.to_string()),
+        RegistryError::Taken(_) => Response::error(409, "id_taken", e.to_string()),
+        RegistryError::Full => Response::error(503, "registry_full", e.to_string()),
+        RegistryError::Malformed(_) | RegistryError::Artifact(_) => Response::error(422, "bad_key", e.to_string()),
+        RegistryError::Io(_) => Response::error(500, "internal", e.to_string()),
+    })?;
+    let status = if new { 201 } else { 200 };
+    Ok(Response::json(status, &json!({ "id": id, "fingerprint": key.fingerprint, "public_inputs": key.public_inputs })))
+}
+
+fn registered(state: &State, id: &str) -> Result<Arc<Registered>, Response> {
+    state.registry.get(id).ok_or_else(|| Response::error(404, "unknown_key", format!("no verifying key registered as '{id}'")))
+}
+
+fn show_key(state: &State, id: &str) -> Result<Response, Response> {
+    let key = registered(state, id)?;
+    let mut bytes = Vec::new();
+    key.vk.serialize_compressed(&mut bytes).map_err(|e| Response::error(500, "internal", e.to_string()))?;
+    Ok(Response::json(200, &json!({
+        "id": id,
+        "curve": "BLS12-381",
+        "fingerprint": key.fingerprint,
+        "public_inputs": key.public_inputs,
+        "verifying_key": artifact::to_hex(&bytes),
+    })))
+}
+
+fn verify_registered(state: &State, id: &str, body: &Value) -> Result<Response, Response> {
+    let key = registered(state, id)?;
+    let artifact = match body.get("public_inputs") {
+        None => read_proof(body)?,
+        Some(Value::Array(items)) => {
+            let bad = |e: artifact::ArtifactError| Response::error(422, "bad_proof", e.to_string());
+            let bytes = artifact::from_hex(&field(body, "proof")?).map_err(bad)?;
+            let (_, bytes) = artifact::split_stamp(&bytes).map_err(bad)?;
+            let proof = artifact::deserialize_checked(bytes).map_err(bad)?;
+            let public_inputs = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| match item {
+                    Value::String(text) => element(text, &format!("public_inputs[{i}]")),
+                    _ => Err(Response::error(422, "bad_input", format!("public_inputs[{i}] should be a string"))),
+                })
+                .collect::<Result<_, _>>()?;
+            ProofArtifact { proof, public_inputs }
+        }
+        Some(_) => return Err(Response::error(422, "bad_input", "public_inputs should be a list of strings")),
+    };
+    Ok(verified(state, "registry", &key.pvk, &artifact))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+    use crate::metrics::Metrics;
+
+    const STATEMENT: &str = "public z; secret x, y; x*x + y*3 == z";
+
+    fn temp_dir() -> PathBuf {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let n = NEXT.fetch_add(1, Ordering::SeqCst);
+        std::env::temp_dir().join(format!("zk-toy-server-test-{}-{n}", std::process::id()))
+    }
+
+    fn state(max_jobs: usize, max_constraints: usize) -> State {
+        let dir = temp_dir();
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut thread_rng()).unwrap();
+        State {
+            limits: ServeArgs {
+                addr: "127.0.0.1:0".to_string(),
+                keys_dir: dir.clone(),
+                timeout: 60,
+                max_jobs,
+                max_connections: 4,
+                max_constraints,
+                max_body: 64 * 1024,
+                registry: dir.join("registry"),
+            },
+            addition: Arc::new(Keys::new(pk, &vk)),
+            statements: Mutex::new(HashMap::new()),
+            jobs: Slots::new(max_jobs),
+            connections: Slots::new(4),
+            metrics: Arc::new(Metrics::default()),
+            registry: Registry::open(&dir.join("registry")).unwrap(),
+        }
+    }
+
+    // the status and the json of the answer
+    fn call(state: &State, method: &str, path: &str, body: Value) -> (u16, Value) {
+        let request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: serde_json::to_vec(&body).unwrap(),
+        };
+        let response = route(state, &request).unwrap_or_else(|error| error);
+        (response.status, serde_json::from_slice(&response.body).unwrap())
+    }
+
+    #[test]
+    fn a_statement_proves_and_verifies() {
+        let state = state(2, 1000);
+        let (status, proved) = call(&state, "POST", "/expr/prove", json!({ "statement": STATEMENT, "values": { "x": "3", "y": "4" } }));
+        assert_eq!(status, 200, "{proved}");
+        assert_eq!(proved["public_inputs"], json!(["21"]));
+        let (status, answer) = call(&state, "POST", "/expr/verify", json!({ "statement": STATEMENT, "proof": proved["proof"] }));
+        assert_eq!((status, &answer["valid"]), (200, &json!(true)));
+    }
+
+    #[test]
+    fn solving_and_counting_wait_for_a_job_slot() {
+        // no slots at all: even values that don't solve get busy, not bad_input
+        let state = state(0, 1000);
+        let (status, answer) = call(&state, "POST", "/expr/prove", json!({ "statement": STATEMENT, "values": { "x": "3" } }));
+        assert_eq!((status, &answer["error"]), (503, &json!("busy")));
+    }
+
+    #[test]
+    fn a_statement_over_the_limit_is_too_large() {
+        let state = state(2, 1);
+        let (status, answer) = call(&state, "POST", "/expr/prove", json!({ "statement": STATEMENT, "values": { "x": "3", "y": "4" } }));
+        assert_eq!((status, &answer["error"]), (413, &json!("circuit_too_large")));
+    }
+
+    #[test]
+    fn new_statements_stop_at_the_limit() {
+        let state = state(2, 1000);
+        {
+            let mut statements = state.statements.lock().unwrap();
+            for i in 0..MAX_STATEMENTS {
+                statements.insert(format!("taken-{i}"), state.addition.clone());
+            }
+        }
+        let (status, answer) = call(&state, "POST", "/expr/prove", json!({ "statement": STATEMENT, "values": { "x": "3", "y": "4" } }));
+        assert_eq!((status, &answer["error"]), (503, &json!("too_many_statements")));
+        assert_eq!(state.statements.lock().unwrap().len(), MAX_STATEMENTS);
+    }
+
+    fn key_hex(vk: &VerifyingKey<Bls12_381>) -> String {
+        let mut bytes = Vec::new();
+        vk.serialize_compressed(&mut bytes).unwrap();
+        artifact::to_hex(&bytes)
+    }
+
+    #[test]
+    fn a_key_registers_and_shows() {
+        let state = state(2, 1000);
+        let (_, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut thread_rng()).unwrap();
+        let (status, answer) = call(&state, "PUT", "/keys/sum", json!({ "verifying_key": key_hex(&vk) }));
+        assert_eq!((status, &answer["public_inputs"]), (201, &json!(1)));
+        let (status, shown) = call(&state, "GET", "/keys/sum", json!({}));
+        assert_eq!((status, &shown["verifying_key"]), (200, &json!(key_hex(&vk))));
+    }
+
+    #[test]
+    fn a_key_without_gamma_abc_points_is_refused() {
+        let state = state(2, 1000);
+        let (_, mut vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut thread_rng()).unwrap();
+        vk.gamma_abc_g1.clear();
+        let (status, answer) = call(&state, "PUT", "/keys/empty", json!({ "verifying_key": key_hex(&vk) }));
+        assert_eq!((status, &answer["error"]), (422, &json!("bad_key")));
+        assert!(!state.limits.registry.join("empty.vk").exists());
+        assert_eq!(call(&state, "GET", "/keys/empty", json!({})).0, 404);
+    }
+
+    #[test]
+    fn a_zero_input_comes_back_as_a_number_that_reads_back_in() {
+        let mut state = state(2, 1000);
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut thread_rng()).unwrap();
+        state.addition = Arc::new(Keys::new(pk, &vk));
+        call(&state, "PUT", "/keys/sum", json!({ "verifying_key": key_hex(&vk) }));
+        let (status, proved) = call(&state, "POST", "/addition/prove", json!({ "x": "0", "y": "0" }));
+        assert_eq!((status, &proved["public_inputs"]), (200, &json!(["0"])), "{proved}");
+        // the proof on its own, with the inputs as the answer gave them
+        let artifact = ProofArtifact::from_bytes(&artifact::from_hex(proved["proof"].as_str().unwrap()).unwrap()).unwrap();
+        let mut proof = Vec::new();
+        artifact.proof.serialize_compressed(&mut proof).unwrap();
+        let body = json!({ "proof": artifact::to_hex(&proof), "public_inputs": proved["public_inputs"] });
+        let (status, answer) = call(&state, "POST", "/keys/sum/verify", body);
+        assert_eq!((status, &answer["valid"]), (200, &json!(true)), "{answer}");
+        let (_, answer) = call(&state, "POST", "/addition/verify", json!({ "proof": proved["proof"] }));
+        assert_eq!(answer["public_inputs"], json!(["0"]));
+    }
+
+    // what `zk-toy prove` writes, stamp and all, hexed as it is
+    fn saved_hex<T: CanonicalSerialize>(value: &T) -> String {
+        let path = temp_dir().with_extension("bin");
+        artifact::save(value, &crate::build_info(), &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(bytes.starts_with(b"ZKTS"));
+        artifact::to_hex(&bytes)
+    }
+
+    #[test]
+    fn a_saved_proof_file_verifies_as_it_is() {
+        let state = state(2, 1000);
+        let circuit = AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+        let proof = Groth16::<Bls12_381>::prove(&state.addition.pk, circuit, &mut thread_rng()).unwrap();
+        let saved = ProofArtifact { proof, public_inputs: vec![Fr::from(19u32)] };
+        let (status, answer) = call(&state, "POST", "/addition/verify", json!({ "proof": saved_hex(&saved) }));
+        assert_eq!((status, &answer["valid"]), (200, &json!(true)), "{answer}");
+
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut thread_rng()).unwrap();
+        call(&state, "PUT", "/keys/sum", json!({ "verifying_key": key_hex(&vk) }));
+        let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut thread_rng()).unwrap();
+        let saved = ProofArtifact { proof, public_inputs: vec![Fr::from(19u32)] };
+        let (status, answer) = call(&state, "POST", "/keys/sum/verify", json!({ "proof": saved_hex(&saved) }));
+        assert_eq!((status, &answer["valid"]), (200, &json!(true)), "{answer}");
+        let body = json!({ "proof": saved_hex(&saved.proof), "public_inputs": ["19"] });
+        let (status, answer) = call(&state, "POST", "/keys/sum/verify", body);
+        assert_eq!((status, &answer["valid"]), (200, &json!(true)), "{answer}");
+    }
+}
@@ -0,0 +1,227 @@
+use std::path::Path;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::arithmetizations::{RangeCheckCircuit, RangeMethod};
+use zk_circuits::bitcoin::PowCircuit;
+use zk_circuits::credential::AgeCircuit;
+use zk_circuits::document::DocumentCircuit;
+use zk_circuits::dry_run::{self, Comparison, DryRun};
+use zk_circuits::examples::document::sample_layout;
+use zk_circuits::membership::MembershipCircuit;
+use zk_circuits::templates::Template;
+use zk_circuits::vss::VssCircuit;
+use zk_gadgets::hasher::{MimcHasher, PoseidonHasher};
+use zk_gadgets::merkle::TreeShape;
+use zk_gadgets::poseidon::poseidon_config;
+
+use crate::CliResult;
+
+/*
+zk-toy synth, a dry run of a circuit: synthesized without a witness and without a
+setup, so trying parameters takes as long as building the constraints (see
+zk-circuits/src/dry_run.rs for what is estimated and how):
+
+  zk-toy synth membership depth=20
+  zk-toy synth membership depth=10 arity=4
+  zk-toy synth range bits=64
+  zk-toy synth range bits=64 --versus method=table limb=4
+
+Parameters are name=value, the ones left out are what the examples use. --versus
+synthesizes the circuit a second time with some of them changed and prints the two
+side by side instead, the way to see what another arithmetization of the same
+statement costs (zk-circuits/src/arithmetizations.rs): range checks by bits or by a
+table of limbs, a binary tree or a 4-ary one.
+*/
+
+pub const CIRCUITS: &str = "addition, document, vss [threshold shares], age, bitcoin, membership [depth arity hasher], \
+     airdrop [depth], range [bits method limb], sum [length bits], threshold [length bits]";
+
+// the bench reports on BLS12-381, the curve synth estimates for
+const CALIBRATION: &[&str] = &[
+    "addition",
+    "document_hash",
+    "vss",
+    "dkg",
+    "passport",
+    "bitcoin_pow",
+    "merkle-poseidon",
+    "merkle-poseidon-4ary",
+    "merkle-mimc",
+];
+
+// name=value parameters, each to be taken once, so a typo is an error and not a default
+struct Params {
+    given: Vec<(String, String)>,
+}
+
+impl Params {
+    fn parse(params: &[String]) -> CliResult<Self> {
+        let given = params
+            .iter()
+            .map(|p| {
+                p.split_once('=')
+                    .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| format!("{p}: expected name=value"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { given })
+    }
+
+    fn take(&mut self, name: &str) -> Option<String> {
+        let at = self.given.iter().position(|(n, _)| n == name)?;
+        Some(self.given.remove(at).1)
+    }
+
+    fn number(&mut self, name: &str, default: usize) -> CliResult<usize> {
+        match self.take(name) {
+            Some(value) => Ok(value.parse().map_err(|_| format!("{name}={value}: expected a number"))?),
+            None => Ok(default),
+        }
+    }
+
+    fn done(self) -> CliResult<()> {
+        match self.given.first() {
+            Some((name, _)) => Err(format!("no parameter {name} for this circuit").into()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn dry_run<C: ConstraintSynthesizer<Fr>>(circuit: C) -> CliResult<DryRun> {
+    Ok(DryRun::synthesize(circuit)?)
+}
+
+fn synthesize(circuit: &str, params: &mut Params) -> CliResult<DryRun> {
+    let poseidon = poseidon_config::<Fr>(2);
+    match circuit {
+        "addition" => dry_run(AdditionCircuit::<Fr>::empty()),
+        "document" => dry_run(DocumentCircuit {
+            layout: sample_layout(),
+            poseidon,
+            document: None,
+            commitment: None,
+            field_value: None,
+        }),
+        "vss" => {
+            let threshold = params.number("threshold", 3)?;
+            let shares = params.number("shares", 5)?;
+            if threshold == 0 || threshold > shares {
+                return Err("vss needs 1 <= threshold <= shares".into());
+            }
+            dry_run(VssCircuit::empty(threshold, shares, poseidon))
+        }
+        "age" => dry_run(AgeCircuit::empty(poseidon)),
+        "bitcoin" => dry_run(PowCircuit::empty()),
+        "membership" => {
+            let depth = params.number("depth", 8)?;
+            let arity = params.number("arity", 2)?;
+            if depth == 0 || arity < 2 {
+                return Err("membership needs depth >= 1 and arity >= 2".into());
+            }
+            let shape = TreeShape::new(arity, depth);
+            match params.take("hasher").as_deref() {
+                None | Some("poseidon") => dry_run(MembershipCircuit::empty(PoseidonHasher::<Fr>::new(arity), shape)),
+                Some("mimc") => dry_run(MembershipCircuit::empty(MimcHasher::<Fr>::default(), shape)),
+                Some(other) => Err(format!("hasher={other}: poseidon or mimc").into()),
+            }
+        }
+        "airdrop" => {
+            let depth = params.number("depth", 4)?;
+            if depth == 0 {
+                return Err("airdrop needs depth >= 1".into());
+            }
+            dry_run(zk_circuits::airdrop::AirdropCircuit::empty(PoseidonHasher::<Fr>::new(2), TreeShape::binary(depth)))
+        }
+        "range" => {
+            let bits = params.number("bits", 32)?;
+            let template = Template::Range { bits };
+            template.check::<Fr>()?;
+            match params.take("method").as_deref() {
+                None | Some("bits") => dry_run(template.empty::<Fr>()?),
+                Some("table") => {
+                    let limb_bits = params.number("limb", 4)?;
+                    if !(1..=8).contains(&limb_bits) {
+                        return Err("range method=table needs 1 <= limb <= 8, the table has 2^limb rows".into());
+                    }
+                    dry_run(RangeCheckCircuit::<Fr>::empty(RangeMethod::Table { limb_bits }, bits))
+                }
+                Some(other) => Err(format!("method={other}: bits or table").into()),
+            }
+        }
+        "sum" | "threshold" => {
+            let template = match circuit {
+                "sum" => Template::Sum {
+                    length: params.number("length", 4)?,
+                    bits: params.number("bits", 32)?,
+                },
+                _ => Template::Threshold {
+                    length: params.number("length", 3)?,
+                    bits: params.number("bits", 64)?,
+                },
+            };
+            template.check::<Fr>()?;
+            dry_run(template.empty::<Fr>()?)
+        }
+        _ => Err(format!("no circuit called '{circuit}', try one of {CIRCUITS}").into()),
+    }
+}
+
+fn synthesize_all(circuit: &str, params: &[String]) -> CliResult<DryRun> {
+    let mut parsed = Params::parse(params)?;
+    let run = synthesize(circuit, &mut parsed)?;
+    parsed.done()?;
+    Ok(run)
+}
+
+// params with the ones named in `versus` replaced, or added when they weren't given
+fn overridden(params: &[String], versus: &[String]) -> CliResult<Vec<String>> {
+    let overrides = Params::parse(versus)?.given;
+    let mut merged: Vec<String> = Params::parse(params)?
+        .given
+        .into_iter()
+        .filter(|(name, _)| !overrides.iter().any(|(n, _)| n == name))
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    merged.extend(overrides.into_iter().map(|(name, value)| format!("{name}={value}")));
+    Ok(merged)
+}
+
+fn label(circuit: &str, params: &[String]) -> String {
+    if params.is_empty() { circuit.to_string() } else { format!("{circuit} {}", params.join(" ")) }
+}
+
+pub fn run(circuit: &str, params: &[String], versus: &[String], bench: Option<&Path>) -> CliResult<()> {
+    let run = synthesize_all(circuit, params)?;
+    if !versus.is_empty() {
+        let other = overridden(params, versus)?;
+        let comparison = Comparison::new::<Bls12_381>((&label(circuit, params), run), (&label(circuit, &other), synthesize_all(circuit, &other)?));
+        println!("{comparison}");
+        return Ok(());
+    }
+    let sizes = run.sizes::<Bls12_381>();
+
+    let shown = if params.is_empty() { String::new() } else { format!(" ({})", params.join(", ")) };
+    println!("{circuit}{shown}, synthesized in setup mode in {:.1?}", run.synthesis);
+    println!("  constraints      {}", run.constraints);
+    println!("  public inputs    {}", run.public_inputs);
+    println!("  witnesses        {}", run.witnesses);
+    let [a, b, c] = run.nonzeros;
+    println!("  nonzero entries  A {a}, B {b}, C {c}, {:.1} per constraint", run.density());
+    let used = run.constraints + run.public_inputs + 1;
+    println!("  QAP domain       {} ({used} used, {} more before it doubles)", run.domain_size, run.domain_size - used);
+    println!("estimated on BLS12-381, without a setup");
+    println!("  proving key      {} bytes", sizes.proving_key);
+    println!("  verifying key    {} bytes", sizes.verifying_key);
+    println!("  proof            {} bytes", sizes.proof);
+
+    let reports = crate::gallery::reports(bench)?;
+    let calibration: Vec<_> =
+        reports.iter().filter(|r| CALIBRATION.contains(&r.example.as_str())).map(|r| (r.proving_key_bytes, r.prove)).collect();
+    match dry_run::estimate_proving_time(&calibration, sizes.proving_key) {
+        Some(time) => println!("  proving time     ~{time:.1?} (a line through {} bench reports on this curve)", calibration.len()),
+        None => println!("  proving time     - (not enough bench reports to estimate from)"),
+    }
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_snark::SNARK;
+use clap::Subcommand;
+use rand::thread_rng;
+use serde::Deserialize;
+use serde_json::Value;
+use zk_circuits::examples::count_constraints;
+use zk_circuits::templates::{RangeCircuit, SumCircuit, Template, TemplateCircuit, ThresholdCircuit};
+use zk_circuits::verifier::verify_constant_work;
+use zk_core::artifact;
+use zk_core::input::{Reduction, parse_field_with};
+
+use crate::{CliResult, ProofArtifact};
+
+/*
+The statement templates of zk-circuits/src/templates.rs from a descriptor file, toml
+or json (by the extension), so a statement is a config file and not Rust:
+
+    # range.toml                     { "template": "sum", "length": 4, "bits": 32 }
+    template = "range"
+    bits = 32
+
+  zk-toy template setup templates/range.toml     writes range-32.pk and range-32.vk
+  zk-toy template prove templates/range.toml --inputs templates/range-inputs.json
+  zk-toy template verify templates/range.toml
+
+The keys are named after the template and its parameters, so one directory holds
+the keys of as many statements as you like. The inputs are a json (or toml) object,
+numbers as strings or plain numbers, lists for the list ones:
+
+  range       { "x": "42", "lo": "18", "hi": "65" }
+  membership  { "leaves": ["11", "22", ...], "index": 2 }   the root is computed
+  sum         { "values": ["10", "20", ...] }               the total is computed
+  threshold   { "values": ["10", "20", ...], "threshold": "25" }
+
+templates/ has a descriptor and inputs for each. With the trace feature prove also
+takes --assignment FILE, the circuit's variables with their values as json, and
+--coverage, which of its constraints the inputs exercise.
+*/
+
+#[derive(Subcommand)]
+pub enum TemplateAction {
+    /// Generate the keys for the statement a descriptor describes
+    Setup {
+        descriptor: PathBuf,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+    },
+    /// Prove the statement for the inputs in a json or toml file
+    Prove {
+        descriptor: PathBuf,
+        #[arg(long)]
+        inputs: PathBuf,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value = "proof.bin")]
+        out: PathBuf,
+        /// Take numbers mod the field modulus instead of refusing the ones that are too big
+        #[arg(long)]
+        reduce: bool,
+        /// Also write every variable with its value and namespace as json (needs the trace feature)
+        #[arg(long)]
+        assignment: Option<PathBuf>,
+        /// Print which constraints the inputs exercise, by namespace (needs the trace feature)
+        #[arg(long)]
+        coverage: bool,
+    },
+    /// Verify a proof of the statement
+    Verify {
+        descriptor: PathBuf,
+        #[arg(long, default_value = ".")]
+        keys_dir: PathBuf,
+        #[arg(long, default_value = "proof.bin")]
+        proof: PathBuf,
+    },
+}
+
+// what a descriptor file holds, Template with names for the config file
+#[derive(Deserialize)]
+#[serde(tag = "template", rename_all = "lowercase", deny_unknown_fields)]
+enum Descriptor {
+    Range { bits: usize },
+    Membership { depth: usize },
+    Sum { length: usize, bits: usize },
+    Threshold { length: usize, bits: usize },
+}
+
+impl From<Descriptor> for Template {
+    fn from(d: Descriptor) -> Self {
+        match d {
+            Descriptor::Range { bits } => Template::Range { bits },
+            Descriptor::Membership { depth } => Template::Membership { depth },
+            Descriptor::Sum { length, bits } => Template::Sum { length, bits },
+            Descriptor::Threshold { length, bits } => Template::Threshold { length, bits },
+        }
+    }
+}
+
+pub fn run(action: TemplateAction) -> CliResult<()> {
+    match action {
+        TemplateAction::Setup { descriptor, keys_dir } => setup(load_template(&descriptor)?, &keys_dir),
+        TemplateAction::Prove {
+            descriptor,
+            inputs,
+            keys_dir,
+            out,
+            reduce,
+            assignment,
+            coverage,
+        } => {
+            let template = load_template(&descriptor)?;
+            let circuit = instantiate(template, &read(&inputs)?, crate::reduction(reduce))?;
+            if let Some(path) = assignment {
+                crate::write_assignment(&template.name(), circuit.clone(), &path)?;
+            }
+            if coverage {
+                crate::print_coverage(&template.name(), circuit.clone())?;
+            }
+            prove(template, circuit, &keys_dir, &out)
+        }
+        TemplateAction::Verify { descriptor, keys_dir, proof } => verify(load_template(&descriptor)?, &keys_dir, &proof),
+    }
+}
+
+// a toml or json file, as json either way
+fn read<T: for<'de> Deserialize<'de>>(path: &Path) -> CliResult<T> {
+    let text = std::fs::read_to_string(path)?;
+    let parsed = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+        _ => serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?,
+    };
+    Ok(parsed)
+}
+
+pub fn load_template(path: &Path) -> CliResult<Template> {
+    let template: Template = read::<Descriptor>(path)?.into();
+    template.check::<Fr>()?;
+    Ok(template)
+}
+
+fn existing_key(template: Template, keys_dir: &Path, extension: &str) -> CliResult<PathBuf> {
+    let path = keys_dir.join(format!("{}.{extension}", template.name()));
+    if !path.exists() {
+        return Err(format!("no keys for {} in {}, run zk-toy template setup first", template.name(), keys_dir.display()).into());
+    }
+    Ok(path)
+}
+
+fn setup(template: Template, keys_dir: &Path) -> CliResult<()> {
+    let empty = template.empty::<Fr>()?;
+    let constraints = count_constraints(&empty)?;
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(empty, &mut thread_rng())?;
+    std::fs::create_dir_all(keys_dir)?;
+    let path = keys_dir.join(template.name());
+    artifact::save(&pk, &crate::build_info(), path.with_extension("pk"))?;
+    artifact::save(&vk, &crate::build_info(), path.with_extension("vk"))?;
+    println!("✓ Keys for {template} ({constraints} constraints) written to {}.pk and .vk", path.display());
+    Ok(())
+}
+
+// a field element from a string or a plain number, `what` for the errors
+fn element(value: &Value, what: &str, reduction: Reduction) -> CliResult<Fr> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        // json numbers past u64 arrive as floats, whose 53 bits may already have lost digits
+        Value::Number(n) if n.is_f64() => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f.abs() < 2f64.powi(53) => (f as i64).to_string(),
+            _ => return Err(format!("{what}: {n} can't be held exactly by a json number, write it as a string").into()),
+        },
+        Value::Number(n) => n.to_string(),
+        _ => return Err(format!("{what} should be a number or a string of one").into()),
+    };
+    Ok(parse_field_with(&text, reduction).map_err(|e| format!("{what}: {e}"))?)
+}
+
+fn number(inputs: &Value, name: &str, reduction: Reduction) -> CliResult<Fr> {
+    element(inputs.get(name).ok_or_else(|| format!("the inputs have no {name}"))?, name, reduction)
+}
+
+fn list(inputs: &Value, name: &str, reduction: Reduction) -> CliResult<Vec<Fr>> {
+    let Some(Value::Array(items)) = inputs.get(name) else {
+        return Err(format!("the inputs need a list {name}").into());
+    };
+    items.iter().enumerate().map(|(i, item)| element(item, &format!("{name}[{i}]"), reduction)).collect()
+}
+
+fn instantiate(template: Template, inputs: &Value, reduction: Reduction) -> CliResult<TemplateCircuit<Fr>> {
+    let number = |name| number(inputs, name, reduction);
+    let list = |name| list(inputs, name, reduction);
+    Ok(match template {
+        Template::Range { bits } => TemplateCircuit::Range(RangeCircuit::new(bits, number("x")?, number("lo")?, number("hi")?)?),
+        Template::Membership { depth } => {
+            let index = match inputs.get("index").and_then(Value::as_u64) {
+                Some(index) => index as usize,
+                None => return Err("the inputs need the index of the leaf, a number".into()),
+            };
+            TemplateCircuit::membership(depth, &list("leaves")?, index)?
+        }
+        Template::Sum { length, bits } => {
+            let values = list("values")?;
+            if values.len() != length {
+                return Err(format!("{} values for a sum of {length}", values.len()).into());
+            }
+            TemplateCircuit::Sum(SumCircuit::new(bits, values)?)
+        }
+        Template::Threshold { length, bits } => {
+            let values = list("values")?;
+            if values.len() != length {
+                return Err(format!("{} values for a threshold over {length}", values.len()).into());
+            }
+            TemplateCircuit::Threshold(ThresholdCircuit::new(bits, values, number("threshold")?)?)
+        }
+    })
+}
+
+fn prove(template: Template, circuit: TemplateCircuit<Fr>, keys_dir: &Path, out: &Path) -> CliResult<()> {
+    let pk: ProvingKey<Bls12_381> = artifact::load(existing_key(template, keys_dir, "pk")?)?;
+    let public_inputs = circuit.public_inputs().expect("an instantiated template has its public inputs");
+    let artifact = ProofArtifact {
+        proof: Groth16::<Bls12_381>::prove(&pk, circuit, &mut thread_rng())?,
+        public_inputs,
+    };
+    artifact::save(&artifact, &crate::build_info(), out)?;
+    println!("✓ Proof for {} written to {}", named_inputs(template, &artifact.public_inputs), out.display());
+    Ok(())
+}
+
+fn verify(template: Template, keys_dir: &Path, proof: &Path) -> CliResult<()> {
+    let vk: VerifyingKey<Bls12_381> = artifact::load(existing_key(template, keys_dir, "vk")?)?;
+    let artifact: ProofArtifact = artifact::load(proof)?;
+    if verify_constant_work(&prepare_verifying_key(&vk), &artifact.public_inputs, &artifact.proof) {
+        println!("✓ The proof is valid: {template}, for {}", named_inputs(template, &artifact.public_inputs));
+        Ok(())
+    } else {
+        Err("proof is invalid".into())
+    }
+}
+
+// "lo = 18, hi = 65"
+fn named_inputs(template: Template, inputs: &[Fr]) -> String {
+    let values = crate::display_inputs(inputs);
+    template.public_names().iter().zip(values).map(|(name, value)| format!("{name} = {value}")).collect::<Vec<_>>().join(", ")
+}
@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::Path;
+
+/*
+What the build-info stamp (src/build_info.rs) can't find out at run time: the target
+triple, and the arkworks versions cargo resolved, read from the workspace's
+Cargo.lock. Built from anywhere else there is no lock file to read, and the stamp
+says "unknown".
+*/
+
+fn arkworks(lock: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut name = None;
+    for line in lock.lines() {
+        if let Some(value) = line.strip_prefix("name = ") {
+            name = Some(value.trim_matches('"').to_string());
+        } else if let (Some(value), Some(crate_name)) = (line.strip_prefix("version = "), name.take()) {
+            // the proc macro crates don't end up in anything that's saved
+            let macros = ["-asm", "-macros", "-derive"].iter().any(|suffix| crate_name.ends_with(suffix));
+            if crate_name.starts_with("ark-") && !macros {
+                found.push(format!("{crate_name} {}", value.trim_matches('"')));
+            }
+        }
+    }
+    found
+}
+
+fn main() {
+    let lock = Path::new(&std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets it")).join("../../Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock.display());
+    let versions = fs::read_to_string(&lock).map(|text| arkworks(&text)).unwrap_or_default();
+    let versions = if versions.is_empty() { "unknown".to_string() } else { versions.join(", ") };
+    println!("cargo:rustc-env=ZK_TOY_ARKWORKS={versions}");
+    println!("cargo:rustc-env=ZK_TOY_TARGET={}", std::env::var("TARGET").expect("cargo sets it"));
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate};
+
+use crate::build_info::BuildInfo;
+use crate::version::{CircuitVersion, VersionedCircuit};
+
+/*
+Saving and loading the stuff a proof system produces: proving keys, verifying keys
+and proofs. Everything goes through ark-serialize in compressed form, so e.g. a
+BLS12-381 Groth16 proof is 192 bytes (two G1 points and one G2 point).
+Nothing here knows which proof system it is handling, that's up to the caller.
+
+Reading is subgroup checked unless you ask otherwise. A compressed point always
+decodes to something on the curve, but BLS12-381's G1 and G2 (and BN254's G2) have
+points outside the prime order subgroup, and a proof or key made of those can break
+the assumptions the verifier's soundness rests on. So anything that came from
+somebody else (a proof to verify, a verifying key, a qr code, later the server) goes
+through deserialize_checked: `load`, `load_versioned` and `from_bytes` all do.
+deserialize_unchecked skips the subgroup checks (not the field ones) and is for files
+you wrote yourself and trust, like a big proving key from your own setup, where the
+checks cost more than the proof.
+
+Files written by `save` and `save_versioned` start with a build-info stamp, a magic
+string and a BuildInfo (build_info.rs), and every load skips it, so files from before
+the stamp (compat/v0.1.0) read the same. The artifact after it is exactly what
+to_bytes gives, stamped or not. Bytes that travel on their own (a qr code, the
+server's hex) are written without a stamp, but from_bytes and the server take them
+with one too, so the hex of a saved proof file verifies as it is.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validation {
+    Checked,
+    Unchecked,
+}
+
+impl From<Validation> for Validate {
+    fn from(v: Validation) -> Self {
+        match v {
+            Validation::Checked => Validate::Yes,
+            Validation::Unchecked => Validate::No,
+        }
+    }
+}
+
+// all of `bytes`, anything left over after the artifact is refused
+pub fn deserialize_with<T: CanonicalDeserialize>(bytes: &[u8], validation: Validation) -> Result<T, ArtifactError> {
+    let mut rest = bytes;
+    let value = T::deserialize_with_mode(&mut rest, Compress::Yes, validation.into())?;
+    if !rest.is_empty() {
+        return Err(ArtifactError::BadEncoding(format!("{} bytes after the artifact", rest.len())));
+    }
+    Ok(value)
+}
+
+pub fn deserialize_checked<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, ArtifactError> {
+    deserialize_with(bytes, Validation::Checked)
+}
+
+pub fn deserialize_unchecked<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, ArtifactError> {
+    deserialize_with(bytes, Validation::Unchecked)
+}
+
+#[derive(Debug)]
+pub enum ArtifactError {
+    Io(std::io::Error),
+    Serialization(SerializationError),
+    BadEncoding(String),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactError::Io(e) => write!(f, "io error: {e}"),
+            ArtifactError::Serialization(e) => write!(f, "could not (de)serialize artifact: {e}"),
+            ArtifactError::BadEncoding(msg) => write!(f, "bad artifact encoding: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+impl From<std::io::Error> for ArtifactError {
+    fn from(e: std::io::Error) -> Self {
+        ArtifactError::Io(e)
+    }
+}
+
+impl From<SerializationError> for ArtifactError {
+    fn from(e: SerializationError) -> Self {
+        ArtifactError::Serialization(e)
+    }
+}
+
+// a proof together with the public inputs it was made for, this is what a verifier needs
+// besides the verifying key
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofArtifact<P: CanonicalSerialize + CanonicalDeserialize, F: CanonicalSerialize + CanonicalDeserialize> {
+    pub proof: P,
+    pub public_inputs: Vec<F>,
+}
+
+impl<P: CanonicalSerialize + CanonicalDeserialize, F: CanonicalSerialize + CanonicalDeserialize> ProofArtifact<P, F> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ArtifactError> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    // a saved file hexed as it is has the stamp in front, which says nothing here
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArtifactError> {
+        let (_, bytes) = split_stamp(bytes)?;
+        deserialize_checked(bytes)
+    }
+}
+
+const STAMP_MAGIC: &[u8; 4] = b"ZKTS";
+
+fn stamp(build: &BuildInfo) -> Result<Vec<u8>, ArtifactError> {
+    let mut bytes = STAMP_MAGIC.to_vec();
+    build.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+// the stamp (if there is one) and the rest of the file after it
+pub fn split_stamp(bytes: &[u8]) -> Result<(Option<BuildInfo>, &[u8]), ArtifactError> {
+    match bytes.strip_prefix(STAMP_MAGIC.as_slice()) {
+        Some(mut rest) => {
+            let build = BuildInfo::deserialize_compressed(&mut rest)?;
+            Ok((Some(build), rest))
+        }
+        None => Ok((None, bytes)),
+    }
+}
+
+// just the stamp, None for files from before it
+pub fn read_stamp(path: impl AsRef<Path>) -> Result<Option<BuildInfo>, ArtifactError> {
+    Ok(split_stamp(&fs::read(path)?)?.0)
+}
+
+// writes any serializable thing (keys, proofs) to a file, stamped with the build
+pub fn save<T: CanonicalSerialize>(value: &T, build: &BuildInfo, path: impl AsRef<Path>) -> Result<(), ArtifactError> {
+    let mut bytes = stamp(build)?;
+    value.serialize_compressed(&mut bytes)?;
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+// reads it back, checked: every point has to be on the curve and in the subgroup
+pub fn load<T: CanonicalDeserialize>(path: impl AsRef<Path>) -> Result<T, ArtifactError> {
+    load_with(path, Validation::Checked)
+}
+
+pub fn load_with<T: CanonicalDeserialize>(path: impl AsRef<Path>, validation: Validation) -> Result<T, ArtifactError> {
+    deserialize_with(split_stamp(&fs::read(path)?)?.1, validation)
+}
+
+/*
+Keys written by setup start with a small header: a magic string, the circuit id and
+the circuit version (see version.rs). Files without the magic are from before keys
+were versioned (0.1.0), `load` still reads those.
+*/
+const KEY_MAGIC: &[u8; 4] = b"ZKTK";
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KeyHeader {
+    pub circuit_id: String,
+    pub version: CircuitVersion,
+}
+
+impl KeyHeader {
+    pub fn of<C: VersionedCircuit>() -> Self {
+        Self {
+            circuit_id: C::ID.to_string(),
+            version: C::VERSION,
+        }
+    }
+}
+
+pub fn save_versioned<C: VersionedCircuit, T: CanonicalSerialize>(
+    value: &T,
+    build: &BuildInfo,
+    path: impl AsRef<Path>,
+) -> Result<(), ArtifactError> {
+    let mut bytes = stamp(build)?;
+    bytes.extend_from_slice(KEY_MAGIC);
+    KeyHeader::of::<C>().serialize_compressed(&mut bytes)?;
+    value.serialize_compressed(&mut bytes)?;
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+// just the header, None for unversioned files
+pub fn read_header(path: impl AsRef<Path>) -> Result<Option<KeyHeader>, ArtifactError> {
+    Ok(split_header(&fs::read(path)?)?.0)
+}
+
+// the header (if there is one) and the key after it, past the stamp if there is one
+pub fn split_header(bytes: &[u8]) -> Result<(Option<KeyHeader>, &[u8]), ArtifactError> {
+    let (_, bytes) = split_stamp(bytes)?;
+    match bytes.strip_prefix(KEY_MAGIC.as_slice()) {
+        Some(mut rest) => {
+            let header = KeyHeader::deserialize_compressed(&mut rest)?;
+            Ok((Some(header), rest))
+        }
+        None => Ok((None, bytes)),
+    }
+}
+
+// loads a versioned key and refuses it if it was made for another circuit or for an
+// incompatible version of this one
+pub fn load_versioned<C: VersionedCircuit, T: CanonicalDeserialize>(path: impl AsRef<Path>) -> Result<T, ArtifactError> {
+    load_versioned_with::<C, T>(path, Validation::Checked)
+}
+
+pub fn load_versioned_with<C: VersionedCircuit, T: CanonicalDeserialize>(
+    path: impl AsRef<Path>,
+    validation: Validation,
+) -> Result<T, ArtifactError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+    let (_, bytes) = split_stamp(&bytes)?;
+    let mut rest = bytes.strip_prefix(KEY_MAGIC.as_slice()).ok_or_else(|| {
+        ArtifactError::BadEncoding(format!("{} has no version header, see `zk-toy migrate`", path.display()))
+    })?;
+    let header = KeyHeader::deserialize_compressed(&mut rest)?;
+    if header.circuit_id != C::ID {
+        return Err(ArtifactError::BadEncoding(format!(
+            "{} is a key for circuit '{}', expected '{}'",
+            path.display(),
+            header.circuit_id,
+            C::ID
+        )));
+    }
+    if !header.version.keys_compatible_with(&C::VERSION) {
+        return Err(ArtifactError::BadEncoding(format!(
+            "{} was made for {} {}, this build has {}, see `zk-toy migrate`",
+            path.display(),
+            C::ID,
+            header.version,
+            C::VERSION
+        )));
+    }
+    deserialize_with(rest, validation)
+}
+
+// hex helpers, used when an artifact has to travel as text (e.g. inside a qr code)
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+pub fn from_hex(text: &str) -> Result<Vec<u8>, ArtifactError> {
+    if !text.is_ascii() || !text.len().is_multiple_of(2) {
+        return Err(ArtifactError::BadEncoding("not a hex string".into()));
+    }
+    // from_str_radix alone would take "+f" as a byte
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            let pair = &text[i..i + 2];
+            if pair.bytes().all(|b| b.is_ascii_hexdigit()) {
+                Ok(u8::from_str_radix(pair, 16).expect("two hex digits are a byte"))
+            } else {
+                Err(ArtifactError::BadEncoding(format!("invalid hex at position {i}")))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fr, G1Affine};
+    use ark_ec::AffineRepr;
+
+    use super::*;
+
+    fn artifact() -> ProofArtifact<G1Affine, Fr> {
+        ProofArtifact { proof: G1Affine::generator(), public_inputs: vec![Fr::from(17u32), -Fr::from(1u32)] }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let bytes = artifact().to_bytes().unwrap();
+        assert_eq!(ProofArtifact::from_bytes(&bytes).unwrap(), artifact());
+        assert_eq!(from_hex(&to_hex(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn trailing_bytes_are_refused() {
+        let mut bytes = artifact().to_bytes().unwrap();
+        bytes.push(0);
+        assert!(matches!(ProofArtifact::<G1Affine, Fr>::from_bytes(&bytes), Err(ArtifactError::BadEncoding(_))));
+    }
+
+    #[test]
+    fn hex_is_only_hex_digits() {
+        assert_eq!(from_hex("00fF").unwrap(), vec![0, 255]);
+        for bad in ["+f", "-1", " f", "0g", "abc", "é0"] {
+            assert!(from_hex(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn saved_files_round_trip() {
+        let path = std::env::temp_dir().join(format!("zk-core-artifact-{}.bin", std::process::id()));
+        save(&artifact(), &BuildInfo::new("BLS12-381", &["groth16"]), &path).unwrap();
+        let loaded: ProofArtifact<G1Affine, Fr> = load(&path).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.push(0);
+        fs::write(&path, &bytes).unwrap();
+        let padded = load::<ProofArtifact<G1Affine, Fr>>(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded, artifact());
+        assert!(padded.is_err());
+    }
+}
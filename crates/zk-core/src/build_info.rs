@@ -0,0 +1,68 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/*
+What wrote an artifact. Keys and proofs travel, and when somebody else's verifier
+refuses a proof that verifies here, the first questions are the same every time:
+which version made it, with which arkworks, for which curve, with which features
+(a chaos build leaves constraints out, see zk-gadgets/src/chaos.rs), on what
+machine. artifact::save puts a BuildInfo in front of everything it writes, and
+`zk-toy inspect-key` and `zk-toy explain` print it, next to what's different in the
+build reading it.
+
+The arkworks versions and the target come from build.rs. None of it changes the
+artifact itself: the same key or proof serializes to the same bytes on every target,
+only the stamp in front says where it was made (src/bin/build_info.rs checks that).
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BuildInfo {
+    pub crate_version: String,
+    // "ark-ec 0.4.2, ark-ff 0.4.2, ...", as cargo resolved them
+    pub arkworks: String,
+    // the curve's name as zk_circuits::curves::Curve::NAME has it, "BLS12-381"
+    pub curve: String,
+    // the cargo features that decide which circuits exist and what they constrain
+    pub features: Vec<String>,
+    // the target triple
+    pub target: String,
+}
+
+impl BuildInfo {
+    // this build, for an artifact on `curve`
+    pub fn new(curve: &str, features: &[&str]) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            arkworks: env!("ZK_TOY_ARKWORKS").to_string(),
+            curve: curve.to_string(),
+            features: features.iter().map(|f| f.to_string()).collect(),
+            target: env!("ZK_TOY_TARGET").to_string(),
+        }
+    }
+
+    // what `other` has differently from this one, a line each
+    pub fn differences(&self, other: &BuildInfo) -> Vec<String> {
+        let mut differences = Vec::new();
+        let mut compare = |what: &str, ours: &str, theirs: &str| {
+            if ours != theirs {
+                differences.push(format!("{what}: {ours} here, {theirs} there"));
+            }
+        };
+        compare("version", &self.crate_version, &other.crate_version);
+        compare("arkworks", &self.arkworks, &other.arkworks);
+        compare("curve", &self.curve, &other.curve);
+        compare("features", &self.features.join(" "), &other.features.join(" "));
+        compare("target", &self.target, &other.target);
+        differences
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let features = if self.features.is_empty() { "none".to_string() } else { self.features.join(" ") };
+        writeln!(f, "zk-toy {} for {}, on {}", self.crate_version, self.curve, self.target)?;
+        writeln!(f, "  features  {features}")?;
+        write!(f, "  arkworks  {}", self.arkworks)
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::Path;
+
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use num_bigint::BigUint;
+
+use crate::artifact::{ArtifactError, deserialize_checked};
+
+/*
+Proofs of one statement for several verifiers. A real system often has to convince
+more than one: a contract on ethereum only has BN254 pairings, everything off chain
+would rather use BLS12-381. That takes one proof per curve (and per backend, once
+there are others than groth16), from one setup each, and a bundle keeps them
+together with the statement and its public inputs stated once.
+
+The public inputs are stored as integers in canonical decimal, not as field
+elements: each curve has its own scalar field, and the verifier of each proof turns
+the same integers into its own field with `inputs_for`. That refuses an integer that
+isn't below the modulus of that field rather than reducing it, because BN254's r is
+smaller than BLS12-381's and an input between the two would mean x on one curve and
+x - r on the other, two different statements in one bundle.
+
+Nothing here verifies anything, a bundled proof is bytes tagged with its backend and
+curve; zk-circuits/src/bundle.rs proves and verifies the groth16 ones.
+*/
+
+const BUNDLE_MAGIC: &[u8; 4] = b"ZKTB";
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BundledProof {
+    // "groth16"
+    pub backend: String,
+    // the curve's name as zk_circuits::curves::Curve::NAME has it, "BN254"
+    pub curve: String,
+    // the compressed proof
+    pub proof: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Bundle {
+    // the circuit id and version the proofs are for, "addition 1.0.0"
+    pub statement: String,
+    // canonical decimal integers, shared by every proof
+    pub public_inputs: Vec<String>,
+    pub proofs: Vec<BundledProof>,
+}
+
+impl Bundle {
+    pub fn new(statement: impl Into<String>, public_inputs: &[BigUint]) -> Self {
+        Self {
+            statement: statement.into(),
+            public_inputs: public_inputs.iter().map(BigUint::to_string).collect(),
+            proofs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, backend: impl Into<String>, curve: impl Into<String>, proof: Vec<u8>) {
+        self.proofs.push(BundledProof {
+            backend: backend.into(),
+            curve: curve.into(),
+            proof,
+        });
+    }
+
+    // the public inputs as elements of F, refusing any that F would have to reduce
+    pub fn inputs_for<F: PrimeField>(&self) -> Result<Vec<F>, ArtifactError> {
+        let modulus: BigUint = F::MODULUS.into();
+        self.public_inputs
+            .iter()
+            .map(|text| {
+                let n = BigUint::parse_bytes(text.as_bytes(), 10)
+                    .filter(|n| n.to_string() == *text)
+                    .ok_or_else(|| ArtifactError::BadEncoding(format!("public input '{text}' isn't a canonical decimal integer")))?;
+                if n >= modulus {
+                    return Err(ArtifactError::BadEncoding(format!(
+                        "public input {text} doesn't fit in a field of {} bits without reducing it",
+                        F::MODULUS_BIT_SIZE
+                    )));
+                }
+                Ok(F::from(n))
+            })
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ArtifactError> {
+        let mut bytes = BUNDLE_MAGIC.to_vec();
+        self.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArtifactError> {
+        let rest = bytes
+            .strip_prefix(BUNDLE_MAGIC)
+            .ok_or_else(|| ArtifactError::BadEncoding("not a proof bundle (no ZKTB magic)".to_string()))?;
+        deserialize_checked(rest)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ArtifactError> {
+        fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ArtifactError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
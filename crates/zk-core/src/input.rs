@@ -0,0 +1,285 @@
+use std::fmt;
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+/*
+Turning user supplied numbers into field elements. The tempting way is
+F::from_str or from_le_bytes_mod_order, but both quietly reduce mod p, so typing
+p + 19 gives you a proof about 19 and nothing tells you. Here a number has to be
+canonical, that is 0 <= n < p, or it gets rejected with an error, unless the caller
+asks for Reduction::Reduce (zk-toy's --reduce), which takes it mod p on purpose.
+
+Accepted forms, of any size:
+
+  19  1_000_000             decimal, underscores as digit separators
+  0x13  0xdead_beef         hex with a 0x prefix
+  -5                        p - 5, the field's negation of 5 (-0 is 0)
+  1e18  2.5e3  1000e-3      scientific notation, as long as it comes out whole
+
+The same string means the same number everywhere: no locale decides what a comma
+or a dot is. Commas are refused, and so is a dot without an exponent, since "1.000"
+is a thousand in half of Europe and one elsewhere; "1.000e3" is unambiguous.
+Exponents stop at MAX_EXPONENT, past that a number is bigger than any field and
+spelling it out would only cost memory.
+*/
+
+pub const MAX_EXPONENT: u32 = 1000;
+
+// what to do with a number that isn't smaller than the field modulus
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Reduction {
+    #[default]
+    Reject,
+    Reduce,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    Empty,
+    InvalidNumber(String),
+    NotCanonical(String),
+    // a fraction, or a negative number where only naturals make sense
+    NotInteger(String),
+    Missing(String),
+    Malformed(String),
+    // an encrypted witness that couldn't be opened (wrong passphrase, no passphrase, ...)
+    Encrypted(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Empty => write!(f, "empty number"),
+            InputError::InvalidNumber(s) => write!(
+                f,
+                "'{s}' is not a number: use decimal, 0x hex or 1.5e3 style, '_' between digits, no commas"
+            ),
+            InputError::NotCanonical(s) => write!(f, "'{s}' is not smaller than the field modulus, refusing to wrap it around"),
+            InputError::NotInteger(s) => write!(f, "'{s}' is not a whole number"),
+            InputError::Missing(name) => write!(f, "missing value for '{name}'"),
+            InputError::Malformed(msg) => write!(f, "{msg}"),
+            InputError::Encrypted(msg) => write!(f, "encrypted witness: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+// a number as typed, split into its sign and size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Integer {
+    pub negative: bool,
+    pub magnitude: BigUint,
+}
+
+pub fn parse_integer(input: &str) -> Result<Integer, InputError> {
+    let invalid = || InputError::InvalidNumber(input.to_string());
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(InputError::Empty);
+    }
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let magnitude = match unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+        Some(hex) => digits(hex, 16).ok_or_else(invalid)?,
+        None => decimal(input, unsigned)?,
+    };
+    let negative = negative && magnitude.bits() > 0;
+    Ok(Integer { negative, magnitude })
+}
+
+// digits of `radix` with '_' between them, not first, last or doubled
+fn digits(text: &str, radix: u32) -> Option<BigUint> {
+    if text.is_empty() || text.starts_with('_') || text.ends_with('_') || text.contains("__") {
+        return None;
+    }
+    let cleaned: String = text.chars().filter(|&c| c != '_').collect();
+    if !cleaned.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    BigUint::parse_bytes(cleaned.as_bytes(), radix)
+}
+
+// 19, or mantissa e exponent with an optional dot in the mantissa
+fn decimal(input: &str, text: &str) -> Result<BigUint, InputError> {
+    let invalid = || InputError::InvalidNumber(input.to_string());
+    let Some((mantissa, exponent)) = text.split_once(['e', 'E']) else {
+        return digits(text, 10).ok_or_else(invalid);
+    };
+    let (sign, size) = match exponent.strip_prefix('-') {
+        Some(size) => (-1, size),
+        None => (1, exponent.strip_prefix('+').unwrap_or(exponent)),
+    };
+    if size.is_empty() || !size.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    // anything this long is far past MAX_EXPONENT either way
+    let exponent = sign * size.parse::<i64>().unwrap_or(i64::MAX / 2);
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let fraction_digits = fraction.chars().filter(|&c| c != '_').count() as i64;
+    let significand = digits(&format!("{whole}{fraction}"), 10).ok_or_else(invalid)?;
+    let shift = exponent - fraction_digits;
+    if significand.bits() == 0 {
+        return Ok(significand);
+    }
+    if shift > MAX_EXPONENT as i64 {
+        return Err(InputError::Malformed(format!("'{input}': exponents above {MAX_EXPONENT} are bigger than any field")));
+    }
+    if shift >= 0 {
+        return Ok(significand * BigUint::from(10u8).pow(shift as u32));
+    }
+    // fewer digits than places to shift them by can't come out whole
+    let places = shift.unsigned_abs();
+    if places > (whole.len() + fraction.len()) as u64 {
+        return Err(InputError::NotInteger(input.to_string()));
+    }
+    let divisor = BigUint::from(10u8).pow(places as u32);
+    if (&significand % &divisor).bits() > 0 {
+        return Err(InputError::NotInteger(input.to_string()));
+    }
+    Ok(significand / divisor)
+}
+
+// a natural number, negative ones are refused
+pub fn parse_biguint(input: &str) -> Result<BigUint, InputError> {
+    match parse_integer(input)? {
+        Integer { negative: true, .. } => Err(InputError::NotInteger(input.to_string())),
+        Integer { magnitude, .. } => Ok(magnitude),
+    }
+}
+
+pub fn parse_field<F: PrimeField>(input: &str) -> Result<F, InputError> {
+    parse_field_with(input, Reduction::Reject)
+}
+
+// with Reject, the size of a negative number has to be below the modulus too: -p
+// would be 0, which is as much a wrap around as p is
+pub fn parse_field_with<F: PrimeField>(input: &str, reduction: Reduction) -> Result<F, InputError> {
+    let Integer { negative, magnitude } = parse_integer(input)?;
+    let modulus: BigUint = F::MODULUS.into();
+    let magnitude = match reduction {
+        Reduction::Reject if magnitude >= modulus => return Err(InputError::NotCanonical(input.to_string())),
+        Reduction::Reject => magnitude,
+        Reduction::Reduce => magnitude % modulus,
+    };
+    let value = F::from(magnitude);
+    Ok(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    fn modulus() -> BigUint {
+        Fr::MODULUS.into()
+    }
+
+    #[test]
+    fn the_modulus_is_refused_not_wrapped() {
+        let p = modulus().to_string();
+        assert_eq!(parse_field::<Fr>(&p), Err(InputError::NotCanonical(p.clone())));
+        let p_plus_19 = (modulus() + 19u32).to_string();
+        assert!(matches!(parse_field::<Fr>(&p_plus_19), Err(InputError::NotCanonical(_))));
+        let hex = format!("0x{:x}", modulus());
+        assert!(matches!(parse_field::<Fr>(&hex), Err(InputError::NotCanonical(_))));
+        assert!(matches!(parse_field::<Fr>(&format!("-{p}")), Err(InputError::NotCanonical(_))));
+    }
+
+    #[test]
+    fn just_below_the_modulus_is_fine() {
+        let p_minus_1 = (modulus() - 1u32).to_string();
+        assert_eq!(parse_field::<Fr>(&p_minus_1), Ok(-Fr::from(1u32)));
+        assert_eq!(parse_field::<Fr>("0"), Ok(Fr::from(0u32)));
+    }
+
+    #[test]
+    fn reduce_takes_it_mod_p() {
+        let p_plus_19 = (modulus() + 19u32).to_string();
+        assert_eq!(parse_field_with::<Fr>(&p_plus_19, Reduction::Reduce), Ok(Fr::from(19u32)));
+        assert_eq!(parse_field_with::<Fr>(&modulus().to_string(), Reduction::Reduce), Ok(Fr::from(0u32)));
+    }
+
+    #[test]
+    fn negative_numbers_are_the_negation() {
+        assert_eq!(parse_field::<Fr>("-5"), Ok(-Fr::from(5u32)));
+        assert_eq!(parse_field::<Fr>("-0x5"), Ok(-Fr::from(5u32)));
+        assert_eq!(parse_field::<Fr>("-1e3"), Ok(-Fr::from(1000u32)));
+        assert_eq!(parse_field::<Fr>("-5").unwrap() + Fr::from(5u32), Fr::from(0u32));
+        // -0 is just 0, and not a negative number where only naturals are taken
+        assert_eq!(parse_integer("-0"), Ok(Integer { negative: false, magnitude: BigUint::from(0u32) }));
+        assert_eq!(parse_field::<Fr>("-0"), Ok(Fr::from(0u32)));
+        assert_eq!(parse_biguint("-0"), Ok(BigUint::from(0u32)));
+        assert_eq!(parse_biguint("-5"), Err(InputError::NotInteger("-5".to_string())));
+    }
+
+    #[test]
+    fn reduce_takes_negative_numbers_mod_p_too() {
+        let minus_p = format!("-{}", modulus());
+        assert_eq!(parse_field_with::<Fr>(&minus_p, Reduction::Reduce), Ok(Fr::from(0u32)));
+        let minus_p_plus_19 = format!("-{}", modulus() + 19u32);
+        assert_eq!(parse_field_with::<Fr>(&minus_p_plus_19, Reduction::Reduce), Ok(-Fr::from(19u32)));
+        assert_eq!(parse_field_with::<Fr>("-19", Reduction::Reduce), Ok(-Fr::from(19u32)));
+    }
+
+    #[test]
+    fn whole_numbers_in_scientific_notation_are_taken() {
+        let cases = [
+            ("1e18", Fr::from(1_000_000_000_000_000_000u64)),
+            ("1E18", Fr::from(1_000_000_000_000_000_000u64)),
+            ("1e+3", Fr::from(1000u32)),
+            ("2.5e3", Fr::from(2500u32)),
+            ("1.000e3", Fr::from(1000u32)),
+            ("1000e-3", Fr::from(1u32)),
+            ("1500e-2", Fr::from(15u32)),
+            ("12.5e1", Fr::from(125u32)),
+            ("0.0e-99999", Fr::from(0u32)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_field::<Fr>(input), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn fractions_are_not_integers() {
+        for fraction in ["2.5e0", "1e-1", "1e-3", "1500e-3", "1e-99999999999999999999999"] {
+            assert_eq!(parse_field::<Fr>(fraction), Err(InputError::NotInteger(fraction.to_string())));
+            assert!(parse_field_with::<Fr>(fraction, Reduction::Reduce).is_err(), "{fraction}");
+        }
+    }
+
+    #[test]
+    fn exponents_stop_at_max_exponent() {
+        for too_big in [format!("1e{}", MAX_EXPONENT + 1), "1e99999999999999999999999".to_string(), format!("-1e{}", MAX_EXPONENT + 1)] {
+            assert!(matches!(parse_integer(&too_big), Err(InputError::Malformed(_))), "{too_big}");
+            assert!(matches!(parse_field_with::<Fr>(&too_big, Reduction::Reduce), Err(InputError::Malformed(_))), "{too_big}");
+        }
+        // right at it the number is spelled out, and bigger than the field
+        let at_max = format!("1e{MAX_EXPONENT}");
+        assert_eq!(parse_biguint(&at_max), Ok(BigUint::from(10u32).pow(MAX_EXPONENT)));
+        assert!(matches!(parse_field::<Fr>(&at_max), Err(InputError::NotCanonical(_))));
+        // and zero is zero whatever it's shifted by
+        assert_eq!(parse_field::<Fr>(&format!("0e{}", MAX_EXPONENT + 1)), Ok(Fr::from(0u32)));
+    }
+
+    #[test]
+    fn localized_numbers_are_refused() {
+        for localized in ["1,000", "1.000", "1.5", "1 000", "1'000"] {
+            assert_eq!(parse_field::<Fr>(localized), Err(InputError::InvalidNumber(localized.to_string())));
+            assert!(parse_field_with::<Fr>(localized, Reduction::Reduce).is_err(), "{localized}");
+        }
+    }
+
+    #[test]
+    fn garbage_is_not_a_number() {
+        for bad in ["", " ", "abc", "0x", "0xg1", "1_", "_1", "1__0", "--1", "1e", "0x-1"] {
+            assert!(parse_field::<Fr>(bad).is_err(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,13 @@
+// the plumbing every other crate uses: artifacts on disk, the build stamped on them and
+// proof bundles, parsing user input, the names of public inputs, circuit versions, run reports, the scenario runner
+// and the clock and randomness of the platform. Nothing proof system specific lives here.
+pub mod artifact;
+pub mod build_info;
+pub mod bundle;
+pub mod input;
+pub mod platform;
+pub mod report;
+pub mod scenario;
+pub mod spec;
+pub mod version;
+pub mod witness;
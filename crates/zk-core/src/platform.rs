@@ -0,0 +1,102 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/*
+The two things the libraries need from the machine they run on, time and fresh
+randomness, so the same code runs natively and in a browser (wasm32-unknown-unknown),
+where std::time::Instant::now panics and rand's thread_rng doesn't compile because
+getrandom has no operating system to ask.
+
+Time is a Clock. SystemClock is Instant natively and performance.now in the browser;
+ManualClock only moves when told to, for reports that should come out the same every
+run. Randomness keeps the trait arkworks already takes, RngCore + CryptoRng: rng()
+hands back a ChaCha StdRng seeded from the OS natively and from
+crypto.getRandomValues in the browser.
+
+There is no wasm-bindgen here. In the browser both come from two functions the page
+passes in when it instantiates the module, under the import module "zk_toy":
+
+  now_ms()              performance.now()
+  fill_random(ptr, len) crypto.getRandomValues on len bytes of the module's memory at ptr
+
+which is a few lines of JavaScript and keeps a native build free of anything
+JavaScript. The binaries and the cli only ever run natively and keep thread_rng and
+Instant.
+*/
+
+pub trait Clock {
+    // time since some point of this clock's choosing, only differences mean anything
+    fn now(&self) -> Duration;
+
+    fn elapsed(&self, since: Duration) -> Duration {
+        self.now().saturating_sub(since)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        START.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        // performance.now is a float of milliseconds, never negative but said defensively
+        Duration::from_secs_f64(unsafe { host::now_ms() }.max(0.0) / 1000.0)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+// runs f and says how long it took by `clock`
+pub fn timed_by<T>(clock: &impl Clock, f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = clock.now();
+    let result = f();
+    (result, clock.elapsed(start))
+}
+
+// a fresh generator for keys, proofs and blinding, seeded from the platform's entropy
+pub fn rng() -> StdRng {
+    StdRng::from_entropy()
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+mod host {
+    #[link(wasm_import_module = "zk_toy")]
+    unsafe extern "C" {
+        pub fn now_ms() -> f64;
+        pub fn fill_random(ptr: *mut u8, len: usize);
+    }
+
+    // what getrandom (and so StdRng::from_entropy) calls in the browser
+    fn fill(bytes: &mut [u8]) -> Result<(), getrandom::Error> {
+        unsafe { fill_random(bytes.as_mut_ptr(), bytes.len()) };
+        Ok(())
+    }
+
+    getrandom::register_custom_getrandom!(fill);
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{SystemClock, timed_by};
+
+/*
+What an example run hands back: which example it was, how big the circuit, keys and
+proof are, how long each step took, the public inputs the verifier saw, and the two
+answers every example checks. A run only counts as a pass if the proof verifies AND
+the same proof is refused for a statement that is off by a little (a wrong sum, a
+different date, a tampered share, ...).
+
+The binaries print it with `--json`, `zk-toy bench` collects one per example. Sizes
+are compressed arkworks encodings in bytes, durations are milliseconds in the json.
+*/
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub example: String,
+    pub constraints: usize,
+    pub proving_key_bytes: usize,
+    pub verifying_key_bytes: usize,
+    pub proof_bytes: usize,
+    #[serde(with = "millis")]
+    pub setup: Duration,
+    #[serde(with = "millis")]
+    pub prove: Duration,
+    #[serde(with = "millis")]
+    pub verify: Duration,
+    // decimal strings, the way arkworks prints field elements
+    pub public_inputs: Vec<String>,
+    pub verified: bool,
+    pub rejects_wrong_statement: bool,
+}
+
+impl RunReport {
+    pub fn passed(&self) -> bool {
+        self.verified && self.rejects_wrong_statement
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports always serialize")
+    }
+
+    // one line for the human readable output of the binaries
+    pub fn summary(&self) -> String {
+        format!(
+            "setup {:.2?}, prove {:.2?}, verify {:.2?}; proof {} bytes, pk {} bytes, vk {} bytes",
+            self.setup, self.prove, self.verify, self.proof_bytes, self.proving_key_bytes, self.verifying_key_bytes
+        )
+    }
+}
+
+// the example binaries take a single flag, `--json`, to print the report instead of the story
+pub fn json_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--json")
+}
+
+// runs f and says how long it took
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    timed_by(&SystemClock, f)
+}
+
+/*
+Regressions against a baseline, a json array of reports from an earlier `zk-toy bench
+--json` (bench/baseline.json is the one in the repo). Constraint counts are exact, so
+any increase is flagged. Prove and verify times are noisy, they are flagged when they
+grow by more than the threshold (a fraction, 0.5 is 50%) and by more than NOISE, so
+a verify going from 4ms to 7ms on a busy machine doesn't count.
+Examples are matched by name, one missing on either side is reported but isn't a
+regression (a new example, or a build without the bls or evm feature).
+*/
+
+pub const NOISE: Duration = Duration::from_millis(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Metric {
+    Constraints { baseline: usize, current: usize },
+    Prove { baseline: Duration, current: Duration },
+    Verify { baseline: Duration, current: Duration },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub example: String,
+    pub metric: Metric,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let growth = |baseline: f64, current: f64| (current / baseline - 1.0) * 100.0;
+        match &self.metric {
+            Metric::Constraints { baseline, current } => {
+                write!(f, "{}: {baseline} -> {current} constraints (+{})", self.example, current - baseline)
+            }
+            Metric::Prove { baseline, current } | Metric::Verify { baseline, current } => {
+                let step = if matches!(self.metric, Metric::Prove { .. }) { "prove" } else { "verify" };
+                let percent = growth(baseline.as_secs_f64(), current.as_secs_f64());
+                write!(f, "{}: {step} {baseline:.2?} -> {current:.2?} (+{percent:.0}%)", self.example)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Comparison {
+    pub regressions: Vec<Regression>,
+    // in the baseline but not run now, and run now but not in the baseline
+    pub missing: Vec<String>,
+    pub new: Vec<String>,
+}
+
+impl Comparison {
+    pub fn passed(&self) -> bool {
+        self.regressions.is_empty()
+    }
+}
+
+pub fn compare(baseline: &[RunReport], current: &[RunReport], threshold: f64) -> Comparison {
+    let slower = |before: Duration, after: Duration| after > before.mul_f64(1.0 + threshold) && after - before > NOISE;
+    let mut comparison = Comparison::default();
+    for now in current {
+        let Some(before) = baseline.iter().find(|r| r.example == now.example) else {
+            comparison.new.push(now.example.clone());
+            continue;
+        };
+        let mut flag = |metric| {
+            comparison.regressions.push(Regression {
+                example: now.example.clone(),
+                metric,
+            })
+        };
+        if now.constraints > before.constraints {
+            flag(Metric::Constraints {
+                baseline: before.constraints,
+                current: now.constraints,
+            });
+        }
+        if slower(before.prove, now.prove) {
+            flag(Metric::Prove {
+                baseline: before.prove,
+                current: now.prove,
+            });
+        }
+        if slower(before.verify, now.verify) {
+            flag(Metric::Verify {
+                baseline: before.verify,
+                current: now.verify,
+            });
+        }
+    }
+    for before in baseline {
+        if !current.iter().any(|r| r.example == before.example) {
+            comparison.missing.push(before.example.clone());
+        }
+    }
+    comparison
+}
+
+mod millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_f64(d.as_secs_f64() * 1000.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        let ms = f64::deserialize(d)?;
+        Ok(Duration::from_secs_f64(ms.max(0.0) / 1000.0))
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::report::timed;
+
+/*
+A tiny runner for multi step examples (protocols with rounds, several parties, ...).
+Each step gets a number and a name, is timed, and either succeeds or stops the
+scenario with an error saying which step broke. `check` is for the "this had better
+be true" moments in between. At the end `finish` prints a summary.
+A `quiet` scenario does all the same but prints nothing, for when the output is json.
+*/
+
+#[derive(Debug)]
+pub struct ScenarioError {
+    pub step: String,
+    pub message: String,
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step '{}' failed: {}", self.step, self.message)
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+pub struct StepRecord {
+    pub name: String,
+    pub duration: Duration,
+}
+
+pub struct Scenario {
+    name: String,
+    steps: Vec<StepRecord>,
+    quiet: bool,
+}
+
+impl Scenario {
+    pub fn new(name: &str) -> Self {
+        println!("=== SCENARIO: {name} ===\n");
+        Self {
+            name: name.to_string(),
+            steps: Vec::new(),
+            quiet: false,
+        }
+    }
+
+    pub fn quiet(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            steps: Vec::new(),
+            quiet: true,
+        }
+    }
+
+    // runs one step, anything that can be turned into a string works as the error
+    pub fn step<T, E: fmt::Display>(
+        &mut self,
+        name: &str,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, ScenarioError> {
+        if !self.quiet {
+            println!("[{}] {name}", self.steps.len() + 1);
+        }
+        let (result, duration) = timed(f);
+        let result = result.map_err(|e| ScenarioError {
+            step: name.to_string(),
+            message: e.to_string(),
+        });
+        if result.is_ok() && !self.quiet {
+            println!("    ✓ done in {duration:.2?}\n");
+        }
+        self.steps.push(StepRecord {
+            name: name.to_string(),
+            duration,
+        });
+        result
+    }
+
+    pub fn check(&self, what: &str, ok: bool) -> Result<(), ScenarioError> {
+        if ok {
+            if !self.quiet {
+                println!("    ✓ {what}");
+            }
+            Ok(())
+        } else {
+            Err(ScenarioError {
+                step: self.steps.last().map(|s| s.name.clone()).unwrap_or_default(),
+                message: format!("check failed: {what}"),
+            })
+        }
+    }
+
+    pub fn finish(self) -> Vec<StepRecord> {
+        let total: Duration = self.steps.iter().map(|s| s.duration).sum();
+        if !self.quiet {
+            println!("=== {}: {} steps in {total:.2?} ===", self.name, self.steps.len());
+        }
+        self.steps
+    }
+}
@@ -0,0 +1,32 @@
+/*
+What the public inputs of a statement are called. A proof artifact only carries the
+values, in the order the circuit allocated them with new_input, and a verifier
+(or `zk-toy explain`) needs the names to say what was proved: the circuits and
+statement templates know their own, and this is how they hand them over.
+
+The spec belongs to the statement, not the file, so a proof of some other statement
+explained with this spec gets this spec's names; the verifying key is what catches
+that.
+*/
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputSpec {
+    // what was proved, "x + y = z" or a program's text
+    pub statement: String,
+    // one per public input, in allocation order
+    pub public: Vec<String>,
+}
+
+impl InputSpec {
+    pub fn new(statement: impl Into<String>, public: &[&str]) -> Self {
+        Self {
+            statement: statement.into(),
+            public: public.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    // the name of public input `i`, "input i" past the ones the spec knows
+    pub fn name(&self, i: usize) -> String {
+        self.public.get(i).cloned().unwrap_or_else(|| format!("input {i}"))
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/*
+Circuit versions. Keys from a circuit specific setup only work for the exact
+constraint system they were made for, so once keys are handed out, changing the
+circuit is a big deal. Every circuit gets a semantic version with a circuit flavoured
+meaning:
+
+  major   the constraint system changed, old keys are useless, run setup again
+  minor   the constraints are the same but something users see changed
+          (e.g. how the witness is computed), old keys keep working
+  patch   nothing a prover or verifier could notice
+
+Keys written by `zk-toy setup` carry the circuit id and version (see artifact.rs), and
+`zk-toy migrate` compares them with the current code using each circuit's changelog
+(the circuits declare theirs in zk-circuits/src/versions.rs).
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CircuitVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl CircuitVersion {
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self { major, minor, patch }
+    }
+
+    // can keys made for `self` be used with a circuit at version `current`?
+    pub fn keys_compatible_with(&self, current: &CircuitVersion) -> bool {
+        self.major == current.major
+    }
+}
+
+impl fmt::Display for CircuitVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+pub trait VersionedCircuit {
+    const ID: &'static str;
+    const VERSION: CircuitVersion;
+    // newest last, one line per version
+    const CHANGELOG: &'static [(CircuitVersion, &'static str)];
+}
+
+// what has to happen to keys made at version `from` for a circuit now at C::VERSION,
+// in plain words
+pub fn migration_advice<C: VersionedCircuit>(from: &CircuitVersion) -> String {
+    let current = C::VERSION;
+    if *from == current {
+        return format!("{} keys are at {current}, up to date", C::ID);
+    }
+    if *from > current {
+        return format!("{} keys are from {from}, newer than this build ({current}), update zk-toy", C::ID);
+    }
+    let changes: Vec<String> = C::CHANGELOG
+        .iter()
+        .filter(|(v, _)| v > from && *v <= current)
+        .map(|(v, what)| format!("  {v}: {what}"))
+        .collect();
+    let verdict = if from.keys_compatible_with(&current) {
+        "same major version, the constraint system didn't change and the keys still work"
+    } else {
+        "the major version changed, so the constraint system is different: run setup again, \
+         hand the new verifying key to verifiers, and re-prove; old proofs only verify with the old key"
+    };
+    format!("{} keys are from {from}, the circuit is at {current}\n{}\n{verdict}", C::ID, changes.join("\n"))
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use zeroize::Zeroize;
+
+use crate::input::{InputError, Reduction, parse_field_with};
+
+/*
+Witness files: the secret inputs of a circuit as json, one entry per variable name,
+values as strings so they can be bigger than what json numbers hold:
+
+    { "x": "17", "y": "0x02" }
+
+Plain json integers are taken too ({ "x": 17 }), as the same decimal string. Only
+ones that fit in 64 bits: serde_json reads anything bigger, or with a fraction or an
+exponent, as a float, which has already lost digits by the time it gets here.
+
+Every value goes through input::parse_field_with, so anything >= the field modulus
+is rejected instead of reduced, unless get_with is asked to reduce.
+
+A witness is secrets, so two bits of hygiene:
+  - the values are wiped from memory when the Witness is dropped (the field
+    elements you `get` out of it are copies, wipe those yourself when done)
+  - with the `encryption` feature a witness can be saved encrypted with a
+    passphrase, in the age format (the same thing `age -p` writes), so it doesn't
+    sit on disk in the clear until somebody gets around to proving
+*/
+
+// the first line of every binary age file
+const AGE_MAGIC: &[u8] = b"age-encryption.org/v1\n";
+
+pub struct Witness {
+    values: BTreeMap<String, String>,
+}
+
+impl Witness {
+    pub fn new() -> Self {
+        Self { values: BTreeMap::new() }
+    }
+
+    // in decimal, not value.to_string(): arkworks' Display writes zero as an empty string
+    pub fn insert<F: PrimeField>(&mut self, name: &str, value: F) {
+        let value: BigUint = value.into_bigint().into();
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, InputError> {
+        let entries: BTreeMap<String, serde_json::Value> = serde_json::from_str(text)
+            .map_err(|e| InputError::Malformed(format!("witness must be a json object of strings or integers: {e}")))?;
+        let mut values = BTreeMap::new();
+        for (name, value) in entries {
+            let value = match value {
+                serde_json::Value::String(text) => text,
+                serde_json::Value::Number(n) if n.is_u64() || n.is_i64() => n.to_string(),
+                serde_json::Value::Number(n) => {
+                    return Err(InputError::Malformed(format!("{name}: {n} isn't exact as a json number, write it as a string")));
+                }
+                _ => return Err(InputError::Malformed(format!("{name}: values must be strings or integers"))),
+            };
+            values.insert(name, value);
+        }
+        Ok(Self { values })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.values).expect("string maps always serialize")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let path = path.as_ref();
+        let mut bytes = read(path)?;
+        if is_encrypted(&bytes) {
+            bytes.zeroize();
+            return Err(InputError::Encrypted(format!("{} is encrypted, it needs a passphrase", path.display())));
+        }
+        let witness = std::str::from_utf8(&bytes)
+            .map_err(|_| InputError::Malformed(format!("{} is not utf-8", path.display())))
+            .and_then(Self::from_json);
+        bytes.zeroize();
+        witness
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputError> {
+        let mut json = self.to_json() + "\n";
+        let result = write(path.as_ref(), json.as_bytes());
+        json.zeroize();
+        result
+    }
+
+    pub fn get<F: PrimeField>(&self, name: &str) -> Result<F, InputError> {
+        self.get_with(name, Reduction::Reject)
+    }
+
+    pub fn get_with<F: PrimeField>(&self, name: &str, reduction: Reduction) -> Result<F, InputError> {
+        let value = self.values.get(name).ok_or_else(|| InputError::Missing(name.to_string()))?;
+        parse_field_with(value, reduction).map_err(|e| InputError::Malformed(format!("{name}: {e}")))
+    }
+}
+
+impl Default for Witness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Witness {
+    fn drop(&mut self) {
+        for value in self.values.values_mut() {
+            value.zeroize();
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl Witness {
+    pub fn save_encrypted(&self, path: impl AsRef<Path>, passphrase: &str) -> Result<(), InputError> {
+        let recipient = age::scrypt::Recipient::new(passphrase.to_string().into());
+        let mut json = self.to_json();
+        let encrypted = age::encrypt(&recipient, json.as_bytes());
+        json.zeroize();
+        let encrypted = encrypted.map_err(|e| InputError::Encrypted(e.to_string()))?;
+        write(path.as_ref(), &encrypted)
+    }
+
+    // opens encrypted and plain witness files alike
+    pub fn load_encrypted(path: impl AsRef<Path>, passphrase: &str) -> Result<Self, InputError> {
+        let path = path.as_ref();
+        let bytes = read(path)?;
+        if !is_encrypted(&bytes) {
+            return Self::load(path);
+        }
+        let identity = age::scrypt::Identity::new(passphrase.to_string().into());
+        let mut plain = age::decrypt(&identity, &bytes).map_err(|e| InputError::Encrypted(e.to_string()))?;
+        let witness = std::str::from_utf8(&plain)
+            .map_err(|_| InputError::Malformed(format!("{} does not decrypt to utf-8", path.display())))
+            .and_then(Self::from_json);
+        plain.zeroize();
+        witness
+    }
+}
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(AGE_MAGIC)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, InputError> {
+    fs::read(path).map_err(|e| InputError::Malformed(format!("could not read {}: {e}", path.display())))
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<(), InputError> {
+    fs::write(path, bytes).map_err(|e| InputError::Malformed(format!("could not write {}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    #[test]
+    fn values_above_the_modulus_are_refused() {
+        let modulus: BigUint = Fr::MODULUS.into();
+        let witness = Witness::from_json(&format!(r#"{{"x": "{}", "y": "0x02"}}"#, modulus + 2u32)).unwrap();
+        assert!(witness.get::<Fr>("x").is_err());
+        assert_eq!(witness.get_with::<Fr>("x", Reduction::Reduce), Ok(Fr::from(2u32)));
+        assert_eq!(witness.get::<Fr>("y"), Ok(Fr::from(2u32)));
+        assert_eq!(witness.get::<Fr>("z"), Err(InputError::Missing("z".to_string())));
+    }
+
+    #[test]
+    fn inserted_values_read_back() {
+        let mut witness = Witness::new();
+        for (name, value) in [("zero", Fr::from(0u32)), ("x", Fr::from(17u32)), ("minus", -Fr::from(1u32))] {
+            witness.insert(name, value);
+        }
+        let witness = Witness::from_json(&witness.to_json()).unwrap();
+        assert_eq!(witness.get::<Fr>("zero"), Ok(Fr::from(0u32)));
+        assert_eq!(witness.get::<Fr>("x"), Ok(Fr::from(17u32)));
+        assert_eq!(witness.get::<Fr>("minus"), Ok(-Fr::from(1u32)));
+    }
+
+    #[test]
+    fn json_integers_parse_like_their_strings() {
+        let witness = Witness::from_json(r#"{"x": 17, "y": "17", "zero": 0, "minus": -5, "max": 18446744073709551615}"#).unwrap();
+        assert_eq!(witness.get::<Fr>("x"), witness.get::<Fr>("y"));
+        assert_eq!(witness.get::<Fr>("zero"), Ok(Fr::from(0u32)));
+        assert_eq!(witness.get::<Fr>("minus"), Ok(-Fr::from(5u32)));
+        assert_eq!(witness.get::<Fr>("max"), Ok(Fr::from(u64::MAX)));
+    }
+
+    #[test]
+    fn inexact_json_numbers_and_other_values_are_refused() {
+        for text in [r#"{"x": 1.5}"#, r#"{"x": 1e3}"#, r#"{"x": 123456789012345678901234567890}"#, r#"{"x": true}"#, r#"{"x": null}"#, r#"{"x": ["1"]}"#] {
+            assert!(matches!(Witness::from_json(text), Err(InputError::Malformed(_))), "{text}");
+        }
+    }
+
+    #[test]
+    fn string_values_take_every_canonical_form() {
+        let witness = Witness::from_json(r#"{"under": "1_000_000", "hex": "0xdead_beef", "sci": "2.5e3"}"#).unwrap();
+        assert_eq!(witness.get::<Fr>("under"), Ok(Fr::from(1_000_000u32)));
+        assert_eq!(witness.get::<Fr>("hex"), Ok(Fr::from(0xdead_beefu32)));
+        assert_eq!(witness.get::<Fr>("sci"), Ok(Fr::from(2500u32)));
+        let commas = Witness::from_json(r#"{"x": "1,000"}"#).unwrap();
+        assert!(commas.get::<Fr>("x").is_err());
+    }
+
+    #[test]
+    fn files_round_trip() {
+        let path = std::env::temp_dir().join(format!("zk-core-witness-{}.json", std::process::id()));
+        let mut witness = Witness::new();
+        witness.insert("x", Fr::from(17u32));
+        witness.save(&path).unwrap();
+        let loaded = Witness::load(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.unwrap().get::<Fr>("x"), Ok(Fr::from(17u32)));
+    }
+}
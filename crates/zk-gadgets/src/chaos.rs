@@ -0,0 +1,117 @@
+/*
+Failure injection, for the classroom (the chaos feature). A circuit is only as good
+as its constraints, and the quickest way to make that point is to take one away and
+watch a proof of something false go through. Built with --features chaos, the
+constraints named in ZK_TOY_CHAOS (comma separated) are left out as the gadgets
+synthesize:
+
+  ZK_TOY_CHAOS=compare.bits cargo run --release --features chaos --bin boundaries
+
+and the bins that check the gadgets show which of them notice, with the case that
+got through:
+
+  compare.bits, compare.less_or_equal   boundaries (the age credential, the templates)
+  compare.range_checks                  sorted
+  poseidon.length, poseidon.padding     poseidon_padded
+
+src/bin/chaos.rs has a false statement for each of them, and a Groth16 proof of
+one. Without the feature skipped() is false and compiles away, so nothing here
+reaches a normal build.
+
+Because a skipped constraint is missing from the keys as well, setup and proving
+have to run with the same ZK_TOY_CHAOS. The proof is then a real proof, it verifies
+against keys for a circuit that doesn't say what it claims to.
+*/
+
+pub const VAR: &str = "ZK_TOY_CHAOS";
+
+// whether this build has the feature, for the build stamp on artifacts
+pub const ENABLED: bool = cfg!(feature = "chaos");
+
+// (name, the constraint it leaves out)
+pub const CONSTRAINTS: &[(&str, &str)] = &[
+    ("compare.bits", "enforce_bits: the bits add back up to the value"),
+    ("compare.less_or_equal", "enforce_less_or_equal: the whole a <= b check"),
+    ("compare.range_checks", "RangeChecks: each pack equals its bits"),
+    ("poseidon.length", "hash_var_padded: the length is one of 0..=max"),
+    ("poseidon.padding", "hash_var_padded: the slots past the length are zero"),
+];
+
+#[cfg(feature = "chaos")]
+mod injected {
+    use std::cell::RefCell;
+    use std::collections::BTreeSet;
+    use std::sync::Mutex;
+
+    use super::{CONSTRAINTS, VAR};
+
+    thread_local! {
+        // names skipped by skipping(), on top of the environment
+        static SCOPED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // names already warned about, so a circuit synthesized a thousand times says it once
+    static WARNED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+    fn warn_once(name: &str, message: impl FnOnce() -> String) {
+        let mut warned = WARNED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if warned.insert(name.to_string()) {
+            eprintln!("chaos: {}", message());
+        }
+    }
+
+    fn chosen() -> Vec<String> {
+        let from_env = std::env::var(VAR).unwrap_or_default();
+        let mut names: Vec<String> = from_env.split(',').map(str::trim).filter(|n| !n.is_empty()).map(str::to_string).collect();
+        for name in &names {
+            if !CONSTRAINTS.iter().any(|(known, _)| known == name) {
+                warn_once(name, || format!("{VAR} names '{name}', which isn't a constraint that can be skipped"));
+            }
+        }
+        SCOPED.with(|scoped| names.extend(scoped.borrow().iter().cloned()));
+        names
+    }
+
+    pub fn skipped(name: &str) -> bool {
+        let (_, what) = CONSTRAINTS
+            .iter()
+            .find(|(known, _)| *known == name)
+            .unwrap_or_else(|| panic!("{name} isn't in chaos::CONSTRAINTS"));
+        let skip = chosen().iter().any(|n| n == name);
+        if skip {
+            warn_once(name, || format!("skipping {name} ({what}), false statements may prove"));
+        }
+        skip
+    }
+
+    pub fn skipping<T>(names: &[&str], f: impl FnOnce() -> T) -> T {
+        for name in names {
+            assert!(CONSTRAINTS.iter().any(|(known, _)| known == name), "{name} isn't in chaos::CONSTRAINTS");
+        }
+        let before = SCOPED.with(|scoped| scoped.replace(names.iter().map(|n| n.to_string()).collect()));
+        // put the old list back even when f panics
+        struct Restore(Vec<String>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED.with(|scoped| *scoped.borrow_mut() = std::mem::take(&mut self.0));
+            }
+        }
+        let _restore = Restore(before);
+        f()
+    }
+}
+
+// whether the gadgets should leave out the constraint `name` (from CONSTRAINTS)
+#[cfg(feature = "chaos")]
+pub use injected::skipped;
+
+// runs f with `names` skipped on this thread as well as whatever ZK_TOY_CHAOS says,
+// for demonstrations that want a skipped and an honest run in one process
+#[cfg(feature = "chaos")]
+pub use injected::skipping;
+
+#[cfg(not(feature = "chaos"))]
+#[inline(always)]
+pub fn skipped(_name: &str) -> bool {
+    false
+}
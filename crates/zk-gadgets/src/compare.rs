@@ -0,0 +1,162 @@
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::chaos;
+
+/*
+Comparisons. A field has no "less than" ((p - 1) + 1 = 0 after all), so comparing
+only makes sense for values that are known to be small.
+
+enforce_bits is a range check: the value is split into n witness bits, and the bits
+have to add back up to it. That's n constraints for the booleans plus one for the sum,
+instead of the ~255 a full to_bits would cost.
+a <= b then becomes "b - a fits in n bits": when a > b the difference wraps around
+to something close to p, way bigger than 2^n.
+Both sides have to be below 2^n for that to mean anything, range check them too
+unless they are known to be small already (a public value you picked, say).
+*/
+
+pub fn enforce_bits<F: PrimeField>(cs: ConstraintSystemRef<F>, value: &FpVar<F>, bits: usize) -> Result<(), SynthesisError> {
+    assert!(bits < F::MODULUS_BIT_SIZE as usize - 1, "a range check needs fewer bits than the field has");
+    // missing during setup, the bits are only witnesses anyway
+    let le = value.value().ok().map(|v| v.into_bigint().to_bits_le());
+    let decomposed = (0..bits)
+        .map(|i| Boolean::new_witness(cs.clone(), || le.as_ref().map(|b| b[i]).ok_or(SynthesisError::AssignmentMissing)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sum = Boolean::le_bits_to_fp_var(&decomposed)?;
+    if chaos::skipped("compare.bits") {
+        return Ok(());
+    }
+    sum.enforce_equal(value)
+}
+
+/*
+The same range check the way a lookup argument would do it: value is split into
+limbs of limb_bits, and each limb is shown to be a row of the table 0..2^limb_bits.
+R1CS has no lookups, so "is in the table" is the table's vanishing polynomial,
+(limb - 0)(limb - 1)...(limb - (2^w - 1)) = 0, a multiplication (and a witness for
+the running product) per row. In a plonkish system with a lookup argument each limb
+would cost about one row instead, which is why they range check this way; here it
+costs more than the bits do for every width but 1, where it is the bits. The
+arithmetizations example prints the numbers.
+*/
+pub fn enforce_bits_by_table<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    value: &FpVar<F>,
+    bits: usize,
+    limb_bits: usize,
+) -> Result<(), SynthesisError> {
+    assert!(bits < F::MODULUS_BIT_SIZE as usize - 1, "a range check needs fewer bits than the field has");
+    assert!((1..=8).contains(&limb_bits), "a table has 2^limb_bits rows, 1 to 8 limb bits");
+    let le = value.value().ok().map(|v| v.into_bigint().to_bits_le());
+    let mut sum = FpVar::zero();
+    for start in (0..bits).step_by(limb_bits) {
+        let width = limb_bits.min(bits - start);
+        let limb = FpVar::new_witness(cs.clone(), || {
+            let bits = le.as_ref().ok_or(SynthesisError::AssignmentMissing)?;
+            Ok(F::from(bits[start..start + width].iter().rev().fold(0u64, |acc, b| 2 * acc + u64::from(*b))))
+        })?;
+        // the last factor goes into the check itself, 2^w - 1 multiplications per limb
+        let last = (1u64 << width) - 1;
+        let mut product = limb.clone();
+        for row in 1..last {
+            product *= &limb - F::from(row);
+        }
+        product.mul_equals(&(&limb - F::from(last)), &FpVar::zero())?;
+        sum += &limb * F::from(2u32).pow([start as u64]);
+    }
+    sum.enforce_equal(value)
+}
+
+// a <= b, for a and b below 2^bits
+pub fn enforce_less_or_equal<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+    bits: usize,
+) -> Result<(), SynthesisError> {
+    if chaos::skipped("compare.less_or_equal") {
+        return Ok(());
+    }
+    enforce_bits(cs, &(b - a), bits)
+}
+
+/*
+Range checks in bulk. Each enforce_bits is n boolean constraints plus one to tie the
+bits to the value, and the tie is the part that can be shared: pack several values
+into one field element, shifted apart, decompose that once, one tie for the lot.
+
+That is only sound for values that are already known to be small either way, within
+2^n of zero as integers. Packing arbitrary field elements proves nothing about each of
+them: with v0 = 2^(n+1) and v1 = -1 the packed v0 + 2^(n+1) v1 is 0, which decomposes
+just fine. With |v| < 2^n it works out: every value gets a slot of n + 1 bits whose top
+bit is zero, and going up from the lowest slot each value has to equal the bits of its
+own slot (the difference is a multiple of 2^(n+1) smaller than that).
+Differences of values that were range checked themselves are the typical customer,
+sorted lists, balance updates.
+
+So: push(value, n) for each value with |value| < 2^n, and enforce does them all, as
+few packed decompositions as fit in the field, saving one constraint per value but
+the first of each pack.
+*/
+
+pub struct RangeChecks<F: PrimeField> {
+    cs: ConstraintSystemRef<F>,
+    pending: Vec<(FpVar<F>, usize)>,
+}
+
+impl<F: PrimeField> RangeChecks<F> {
+    pub fn new(cs: ConstraintSystemRef<F>) -> Self {
+        Self { cs, pending: Vec::new() }
+    }
+
+    // 0 <= value < 2^bits, for a value already known to be within 2^bits of zero
+    pub fn push(&mut self, value: FpVar<F>, bits: usize) {
+        assert!(bits + 1 < Self::capacity(), "a range check needs fewer bits than the field has");
+        self.pending.push((value, bits));
+    }
+
+    // bits a pack can use without the packed value wrapping around the modulus
+    fn capacity() -> usize {
+        F::MODULUS_BIT_SIZE as usize - 2
+    }
+
+    pub fn enforce(self) -> Result<(), SynthesisError> {
+        let mut packs: Vec<Vec<(FpVar<F>, usize)>> = Vec::new();
+        let mut used = Self::capacity();
+        for (value, bits) in self.pending {
+            if used + bits + 1 > Self::capacity() {
+                packs.push(Vec::new());
+                used = 0;
+            }
+            used += bits + 1;
+            packs.last_mut().expect("a pack was just started").push((value, bits));
+        }
+        for pack in packs {
+            let mut packed = FpVar::zero();
+            let mut decomposed = Vec::new();
+            for (value, bits) in pack {
+                packed += &value * F::from(2u32).pow([decomposed.len() as u64]);
+                let le = value.value().ok().map(|v| v.into_bigint().to_bits_le());
+                for i in 0..bits {
+                    decomposed.push(Boolean::new_witness(self.cs.clone(), || {
+                        le.as_ref().map(|b| b[i]).ok_or(SynthesisError::AssignmentMissing)
+                    })?);
+                }
+                // the top bit of the slot, zero for a value in range
+                decomposed.push(Boolean::FALSE);
+            }
+            let sum = Boolean::le_bits_to_fp_var(&decomposed)?;
+            if !chaos::skipped("compare.range_checks") {
+                sum.enforce_equal(&packed)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use ark_crypto_primitives::crh::sha256::constraints::Sha256Gadget;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::bits::ToBytesGadget;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use sha2::{Digest, Sha256};
+
+use crate::mimc::{self, MimcConfig};
+use crate::packing::{bytes_per_element, pack_chunk, pack_chunk_var};
+use crate::poseidon::{self, poseidon_config};
+
+/*
+One interface for "hash some field elements to one field element", with the native
+version and the circuit version side by side. Anything built on a hash (the merkle
+tree, the membership circuit, ...) takes an H: CircuitHasher<F>, so swapping Poseidon
+for MiMC or SHA-256 is a change of one type parameter and the constraint counts
+can be compared directly.
+
+Roughly, per hash of two elements over BLS12-381:
+  Poseidon   a few hundred constraints, made for circuits
+  MiMC       a few hundred too, older and simpler (one x^5 per round, lots of rounds)
+  SHA-256    tens of thousands, it works on bits. Here because the outside world uses it
+*/
+
+pub trait CircuitHasher<F: PrimeField>: Clone {
+    const NAME: &'static str;
+
+    fn hash(&self, inputs: &[F]) -> F;
+
+    fn hash_var(&self, cs: ConstraintSystemRef<F>, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError>;
+}
+
+#[derive(Clone)]
+pub struct PoseidonHasher<F: PrimeField> {
+    pub config: PoseidonConfig<F>,
+}
+
+impl<F: PrimeField> PoseidonHasher<F> {
+    pub fn new(rate: usize) -> Self {
+        Self { config: poseidon_config(rate) }
+    }
+}
+
+impl<F: PrimeField + Absorb> CircuitHasher<F> for PoseidonHasher<F> {
+    const NAME: &'static str = "poseidon";
+
+    fn hash(&self, inputs: &[F]) -> F {
+        poseidon::hash(&self.config, inputs)
+    }
+
+    fn hash_var(&self, cs: ConstraintSystemRef<F>, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        poseidon::hash_var(cs, &self.config, inputs)
+    }
+}
+
+#[derive(Clone)]
+pub struct MimcHasher<F: PrimeField> {
+    pub config: MimcConfig<F>,
+}
+
+impl<F: PrimeField> Default for MimcHasher<F> {
+    fn default() -> Self {
+        Self { config: MimcConfig::new() }
+    }
+}
+
+impl<F: PrimeField> CircuitHasher<F> for MimcHasher<F> {
+    const NAME: &'static str = "mimc";
+
+    fn hash(&self, inputs: &[F]) -> F {
+        mimc::hash(&self.config, inputs)
+    }
+
+    fn hash_var(&self, _cs: ConstraintSystemRef<F>, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        mimc::hash_var(&self.config, inputs)
+    }
+}
+
+// sha256 of the little-endian bytes of the inputs (32 per element), cut down to the
+// first 31 bytes of the digest so the result fits in a field element without wrapping
+#[derive(Clone, Default)]
+pub struct Sha256Hasher;
+
+impl<F: PrimeField> CircuitHasher<F> for Sha256Hasher {
+    const NAME: &'static str = "sha256";
+
+    fn hash(&self, inputs: &[F]) -> F {
+        let mut hasher = Sha256::new();
+        for x in inputs {
+            hasher.update(x.into_bigint().to_bytes_le());
+        }
+        pack_chunk(&hasher.finalize()[..bytes_per_element::<F>()])
+    }
+
+    fn hash_var(&self, _cs: ConstraintSystemRef<F>, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        let mut bytes: Vec<UInt8<F>> = Vec::new();
+        for x in inputs {
+            // to_bytes is the canonical encoding, same bytes as into_bigint above
+            bytes.extend(x.to_bytes()?);
+        }
+        let digest = Sha256Gadget::digest(&bytes)?;
+        pack_chunk_var(&digest.0[..bytes_per_element::<F>()])
+    }
+}
@@ -0,0 +1,139 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::bits::ToBitsGadget;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_relations::r1cs::SynthesisError;
+use sha3::{Digest, Keccak256};
+
+/*
+Keccak-256, the hash ethereum uses everywhere (addresses, function selectors, storage
+slots). It is the original Keccak submission, not the standardized SHA3-256: same
+permutation, different padding byte (0x01 instead of 0x06).
+
+The state is 25 lanes of 64 bits, and a permutation is 24 rounds of
+
+  theta   every bit xored with the parities of two neighbouring columns
+  rho pi  every lane rotated and moved, free in a circuit (it's just rewiring)
+  chi     a ^= !b & c along the rows, the only non-linear step
+  iota    a round constant xored into lane 0, free too
+
+Here every bit is a Boolean and every XOR and AND a constraint, about 5900 a round and
+142k per permutation. Keccak absorbs 136 bytes per permutation, so anything up to
+135 bytes (a 64 byte public key, say) is one.
+The length of the input is fixed when the circuit is built, so the padding is constant.
+*/
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// rotation of lane (x, y), indexed [x][y]
+const ROTATIONS: [[usize; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+type Lane<F> = Vec<Boolean<F>>;
+
+pub fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    Keccak256::digest(bytes).into()
+}
+
+fn xor<F: PrimeField>(a: &Lane<F>, b: &Lane<F>) -> Result<Lane<F>, SynthesisError> {
+    a.iter().zip(b).map(|(a, b)| a.xor(b)).collect()
+}
+
+// bit i of the result is bit i - n of the lane
+fn rotate_left<F: PrimeField>(lane: &Lane<F>, n: usize) -> Lane<F> {
+    (0..64).map(|i| lane[(i + 64 - n) % 64].clone()).collect()
+}
+
+fn permute<F: PrimeField>(state: &mut [Lane<F>]) -> Result<(), SynthesisError> {
+    let at = |x: usize, y: usize| x + 5 * y;
+    for constant in ROUND_CONSTANTS {
+        let mut columns = Vec::with_capacity(5);
+        for x in 0..5 {
+            let mut parity = state[at(x, 0)].clone();
+            for y in 1..5 {
+                parity = xor(&parity, &state[at(x, y)])?;
+            }
+            columns.push(parity);
+        }
+        for x in 0..5 {
+            let d = xor(&columns[(x + 4) % 5], &rotate_left(&columns[(x + 1) % 5], 1))?;
+            for y in 0..5 {
+                state[at(x, y)] = xor(&state[at(x, y)], &d)?;
+            }
+        }
+
+        let mut moved = vec![Vec::new(); 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                moved[at(y, (2 * x + 3 * y) % 5)] = rotate_left(&state[at(x, y)], ROTATIONS[x][y]);
+            }
+        }
+
+        for y in 0..5 {
+            for x in 0..5 {
+                let (b, c) = (&moved[at((x + 1) % 5, y)], &moved[at((x + 2) % 5, y)]);
+                let mixed = b.iter().zip(c).map(|(b, c)| b.not().and(c)).collect::<Result<Lane<F>, _>>()?;
+                state[at(x, y)] = xor(&moved[at(x, y)], &mixed)?;
+            }
+        }
+
+        let round: Lane<F> = (0..64).map(|i| Boolean::constant(constant >> i & 1 == 1)).collect();
+        state[0] = xor(&state[0], &round)?;
+    }
+    Ok(())
+}
+
+// the same hash as constraints, for an input of a length fixed at setup
+pub fn keccak256_var<F: PrimeField>(bytes: &[UInt8<F>]) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    let mut padded = bytes.to_vec();
+    padded.push(UInt8::constant(0x01));
+    while !padded.len().is_multiple_of(RATE) {
+        padded.push(UInt8::constant(0));
+    }
+    let last = padded.len() - 1;
+    // 0x81 when the 0x01 and the 0x80 land in the same byte
+    padded[last] = UInt8::constant(if last == bytes.len() { 0x81 } else { 0x80 });
+
+    let mut state: Vec<Lane<F>> = vec![vec![Boolean::FALSE; 64]; 25];
+    for block in padded.chunks(RATE) {
+        for (lane, chunk) in block.chunks(8).enumerate() {
+            state[lane] = xor(&state[lane], &chunk.to_bits_le()?)?;
+        }
+        permute(&mut state)?;
+    }
+    // the first 32 bytes of the state, lanes are little-endian
+    let bits: Vec<Boolean<F>> = state[..4].concat();
+    Ok(bits.chunks(8).map(UInt8::from_bits_le).collect())
+}
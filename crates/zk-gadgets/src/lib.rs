@@ -0,0 +1,9 @@
+// circuit building blocks, each with the native computation it mirrors
+pub mod chaos;
+pub mod compare;
+pub mod hasher;
+pub mod keccak;
+pub mod merkle;
+pub mod mimc;
+pub mod packing;
+pub mod poseidon;
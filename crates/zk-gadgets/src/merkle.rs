@@ -0,0 +1,202 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::select::CondSelectGadget;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::hasher::CircuitHasher;
+
+/*
+A fixed shape merkle tree over field elements, generic over the hash. The shape is
+the arity (children per node, a power of two) and the depth, so a tree always has
+arity^depth leaves, missing ones are zero, and a node is H(child_0, ..., child_{k-1}).
+
+The index of a leaf written in base `arity` says at every level where our node sits
+among its siblings. For a power of two that's just the binary index, log2(arity) bits
+per level, and in the circuit those bits drive a multiplexer that puts the node in
+its place, so the verifier can't tell which leaf was used.
+
+Binary vs 4-ary: for the same number of leaves a 4-ary tree is half as deep, so half
+as many hashes, but each hash takes 4 inputs (Poseidon with rate 4, width 5, costs
+only a bit more than rate 2) and the multiplexer is a bit bigger. The merkle example
+prints the numbers.
+*/
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeShape {
+    pub arity: usize,
+    pub depth: usize,
+}
+
+impl TreeShape {
+    pub fn new(arity: usize, depth: usize) -> Self {
+        assert!(arity >= 2 && arity.is_power_of_two(), "the arity has to be a power of two");
+        Self { arity, depth }
+    }
+
+    pub fn binary(depth: usize) -> Self {
+        Self::new(2, depth)
+    }
+
+    pub fn leaves(&self) -> usize {
+        self.arity.pow(self.depth as u32)
+    }
+
+    pub fn bits_per_level(&self) -> usize {
+        self.arity.trailing_zeros() as usize
+    }
+}
+
+// a path gives away which leaf is ours, so it is wiped like any other witness
+#[derive(Clone, Debug, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct MerklePath<F: PrimeField> {
+    #[zeroize(skip)]
+    pub shape: TreeShape,
+    pub index: u64,
+    // bottom up, arity - 1 siblings per level, in tree order without our node
+    pub siblings: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> MerklePath<F> {
+    // where the node sits among its siblings at this level
+    pub fn position(&self, level: usize) -> usize {
+        (self.index as usize >> (level * self.shape.bits_per_level())) & (self.shape.arity - 1)
+    }
+
+    pub fn root<H: CircuitHasher<F>>(&self, hasher: &H, leaf: F) -> F {
+        let mut node = leaf;
+        for (level, siblings) in self.siblings.iter().enumerate() {
+            let mut children = siblings.clone();
+            children.insert(self.position(level), node);
+            node = hasher.hash(&children);
+        }
+        node
+    }
+
+    // the bits of the index, level by level, lowest bit first
+    pub fn index_bits(&self) -> Vec<bool> {
+        let bits = self.shape.depth * self.shape.bits_per_level();
+        (0..bits).map(|i| (self.index >> i) & 1 == 1).collect()
+    }
+}
+
+pub struct MerkleTree<F: PrimeField, H: CircuitHasher<F>> {
+    hasher: H,
+    shape: TreeShape,
+    // levels[0] are the leaves, the last level is just the root
+    levels: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField, H: CircuitHasher<F>> MerkleTree<F, H> {
+    pub fn new(hasher: H, shape: TreeShape, leaves: &[F]) -> Self {
+        assert!(leaves.len() <= shape.leaves(), "too many leaves for a tree of shape {shape:?}");
+        let mut level = leaves.to_vec();
+        level.resize(shape.leaves(), F::zero());
+        let mut levels = vec![level];
+        for _ in 0..shape.depth {
+            let next = levels.last().unwrap().chunks(shape.arity).map(|children| hasher.hash(children)).collect();
+            levels.push(next);
+        }
+        Self { hasher, shape, levels }
+    }
+
+    pub fn shape(&self) -> TreeShape {
+        self.shape
+    }
+
+    pub fn root(&self) -> F {
+        self.levels[self.shape.depth][0]
+    }
+
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    pub fn path(&self, index: u64) -> MerklePath<F> {
+        let mut siblings = Vec::with_capacity(self.shape.depth);
+        let mut i = index as usize;
+        for level in &self.levels[..self.shape.depth] {
+            let first = i - i % self.shape.arity;
+            siblings.push((first..first + self.shape.arity).filter(|&j| j != i).map(|j| level[j]).collect());
+            i /= self.shape.arity;
+        }
+        MerklePath {
+            shape: self.shape,
+            index,
+            siblings,
+        }
+    }
+}
+
+// picks candidates[index] where index is given by its bits, lowest first
+fn mux<F: PrimeField>(bits: &[Boolean<F>], candidates: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+    if candidates.len() == 1 {
+        return Ok(candidates[0].clone());
+    }
+    let halved = candidates
+        .chunks(2)
+        .map(|pair| FpVar::conditionally_select(&bits[0], &pair[1], &pair[0]))
+        .collect::<Result<Vec<_>, _>>()?;
+    mux(&bits[1..], &halved)
+}
+
+// the root a leaf and its path lead to, as constraints. siblings has arity - 1
+// variables per level and index_bits log2(arity) per level
+pub fn root_var<F: PrimeField, H: CircuitHasher<F>>(
+    cs: ConstraintSystemRef<F>,
+    hasher: &H,
+    shape: TreeShape,
+    leaf: &FpVar<F>,
+    siblings: &[Vec<FpVar<F>>],
+    index_bits: &[Boolean<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let per_level = shape.bits_per_level();
+    assert_eq!(siblings.len(), shape.depth, "one set of siblings per level");
+    assert_eq!(index_bits.len(), shape.depth * per_level, "log2(arity) index bits per level");
+    let mut node = leaf.clone();
+    for (level, level_siblings) in siblings.iter().enumerate() {
+        let bits = &index_bits[level * per_level..(level + 1) * per_level];
+        // the children for every position our node could be in, then pick one
+        let orderings: Vec<Vec<FpVar<F>>> = (0..shape.arity)
+            .map(|position| {
+                let mut children = level_siblings.clone();
+                children.insert(position, node.clone());
+                children
+            })
+            .collect();
+        let children = (0..shape.arity)
+            .map(|j| mux(bits, &orderings.iter().map(|o| o[j].clone()).collect::<Vec<_>>()))
+            .collect::<Result<Vec<_>, _>>()?;
+        node = hasher.hash_var(cs.clone(), &children)?;
+    }
+    Ok(node)
+}
+
+// a path as witnesses, level by level: the siblings, then the index bits of that level.
+// None during setup, the shape alone decides what gets allocated
+#[allow(clippy::type_complexity)]
+pub fn path_var<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    shape: TreeShape,
+    path: Option<&MerklePath<F>>,
+) -> Result<(Vec<Vec<FpVar<F>>>, Vec<Boolean<F>>), SynthesisError> {
+    let bits = path.map(|p| p.index_bits());
+    let per_level = shape.bits_per_level();
+    let mut siblings = Vec::with_capacity(shape.depth);
+    let mut index_bits = Vec::with_capacity(shape.depth * per_level);
+    for level in 0..shape.depth {
+        let mut level_siblings = Vec::with_capacity(shape.arity - 1);
+        for j in 0..shape.arity - 1 {
+            let sibling = path.map(|p| p.siblings[level][j]);
+            level_siblings.push(FpVar::new_witness(cs.clone(), || sibling.ok_or(SynthesisError::AssignmentMissing))?);
+        }
+        siblings.push(level_siblings);
+        for i in level * per_level..(level + 1) * per_level {
+            let bit = bits.as_ref().map(|b| b[i]);
+            index_bits.push(Boolean::new_witness(cs.clone(), || bit.ok_or(SynthesisError::AssignmentMissing))?);
+        }
+    }
+    Ok((siblings, index_bits))
+}
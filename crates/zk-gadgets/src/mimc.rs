@@ -0,0 +1,72 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::SynthesisError;
+use sha2::{Digest, Sha256};
+
+/*
+MiMC, one of the first hashes designed for circuits. The block cipher is just
+x -> (x + k + c_i)^5 repeated, with a round constant c_i per round, and the hash
+chains it in Miyaguchi-Preneel mode: h = E_h(m) + h + m for every input m.
+
+x^5 because it has to be a permutation of the field, which x^3 isn't for BLS12-381
+(3 divides r - 1). The number of rounds is ceil(log_5(r)), 110 for a 255 bit field,
+so the degree gets big enough. Each round is 3 multiplications, so about 330
+constraints per absorbed element.
+
+The round constants come from sha256("zk-toy mimc" || i), so nobody picked them.
+Toy parameters, use Poseidon for anything real.
+*/
+
+#[derive(Clone, Debug)]
+pub struct MimcConfig<F: PrimeField> {
+    pub round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> MimcConfig<F> {
+    pub fn new() -> Self {
+        let rounds = (F::MODULUS_BIT_SIZE as f64 / 5f64.log2()).ceil() as u32;
+        let round_constants = (0..rounds)
+            .map(|i| {
+                let digest = Sha256::new().chain_update(b"zk-toy mimc").chain_update(i.to_le_bytes()).finalize();
+                F::from_le_bytes_mod_order(&digest)
+            })
+            .collect();
+        Self { round_constants }
+    }
+}
+
+impl<F: PrimeField> Default for MimcConfig<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encrypt<F: PrimeField>(config: &MimcConfig<F>, key: F, mut x: F) -> F {
+    for c in &config.round_constants {
+        x = (x + key + c).pow([5]);
+    }
+    x + key
+}
+
+pub fn hash<F: PrimeField>(config: &MimcConfig<F>, inputs: &[F]) -> F {
+    inputs.iter().fold(F::zero(), |h, m| encrypt(config, h, *m) + h + m)
+}
+
+fn encrypt_var<F: PrimeField>(config: &MimcConfig<F>, key: &FpVar<F>, x: &FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+    let mut x = x.clone();
+    for c in &config.round_constants {
+        let t = &x + key + *c;
+        let t2 = t.square()?;
+        x = t2.square()? * &t;
+    }
+    Ok(x + key)
+}
+
+pub fn hash_var<F: PrimeField>(config: &MimcConfig<F>, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+    let mut h = FpVar::zero();
+    for m in inputs {
+        h = encrypt_var(config, &h, m)? + &h + m;
+    }
+    Ok(h)
+}
@@ -0,0 +1,41 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::bits::ToBitsGadget;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::SynthesisError;
+
+/*
+Byte packing: circuits over a ~255 bit field are happiest with field elements,
+but documents and hash digests are bytes. Packing squeezes several bytes into a
+single field element (little-endian, so byte 0 is the lowest 8 bits).
+A field element holds floor((bits - 1) / 8) bytes without wrapping around the
+modulus, that's 31 bytes for the BLS12-381 scalar field.
+Inside the circuit the packing is free: it's just a linear combination of the
+bits the bytes are already made of.
+*/
+
+pub fn bytes_per_element<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize - 1) / 8
+}
+
+// packs up to bytes_per_element bytes into one field element
+pub fn pack_chunk<F: PrimeField>(bytes: &[u8]) -> F {
+    assert!(bytes.len() <= bytes_per_element::<F>(), "too many bytes for one field element");
+    F::from_le_bytes_mod_order(bytes)
+}
+
+// splits the bytes into chunks of chunk_size and packs each one
+pub fn pack_bytes<F: PrimeField>(bytes: &[u8], chunk_size: usize) -> Vec<F> {
+    bytes.chunks(chunk_size).map(pack_chunk).collect()
+}
+
+pub fn pack_chunk_var<F: PrimeField>(bytes: &[UInt8<F>]) -> Result<FpVar<F>, SynthesisError> {
+    assert!(bytes.len() <= bytes_per_element::<F>(), "too many bytes for one field element");
+    let bits: Vec<Boolean<F>> = bytes.to_bits_le()?;
+    Boolean::le_bits_to_fp_var(&bits)
+}
+
+pub fn pack_bytes_var<F: PrimeField>(bytes: &[UInt8<F>], chunk_size: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+    bytes.chunks(chunk_size).map(pack_chunk_var).collect()
+}
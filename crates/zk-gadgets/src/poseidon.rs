@@ -0,0 +1,192 @@
+use ark_crypto_primitives::sponge::constraints::CryptographicSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
+use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, PoseidonSponge, find_poseidon_ark_and_mds};
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::chaos;
+
+/*
+Poseidon is a hash function designed to be cheap inside a circuit: it works on
+field elements directly, so hashing costs a few hundred constraints instead of the
+tens of thousands a bit-oriented hash like sha256 or blake2 needs.
+
+ark-crypto-primitives doesn't ship parameters for the BLS12-381 scalar field, so we
+derive them with its grain lfsr (the same procedure the Poseidon paper uses).
+We use x^5 as the s-box, 8 full rounds and 57 partial rounds, which is the
+usual choice for ~255 bit fields, and a capacity of one field element.
+
+A circuit has a fixed number of inputs, so hashing a list whose length is only known
+when proving means hashing it padded: max slots, the first len of them the list and
+the rest zeros, then len itself (without it [a] and [a, 0] would hash the same).
+hash_var_padded takes len as a witness and checks it's one of 0..=max and that every
+slot from len on really is zero, that's about 5 constraints per slot on top of the
+hash. hash_padded is the native side, it's the plain hash of the padded slots and len.
+*/
+
+const ALPHA: u64 = 5;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+
+// rate = how many field elements the sponge absorbs per permutation
+pub fn poseidon_config<F: PrimeField>(rate: usize) -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        rate,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA, mds, ark, rate, 1)
+}
+
+// hashes a list of field elements down to one, outside the circuit
+pub fn hash<F: PrimeField + Absorb>(config: &PoseidonConfig<F>, inputs: &[F]) -> F {
+    let mut sponge = PoseidonSponge::new(config);
+    sponge.absorb(&inputs);
+    sponge.squeeze_field_elements::<F>(1)[0]
+}
+
+// the same hash, but as constraints on circuit variables
+pub fn hash_var<F: PrimeField + Absorb>(
+    cs: ConstraintSystemRef<F>,
+    config: &PoseidonConfig<F>,
+    inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut sponge = PoseidonSpongeVar::new(cs, config);
+    sponge.absorb(&inputs)?;
+    Ok(sponge.squeeze_field_elements(1)?.remove(0))
+}
+
+// the hash of a list of up to max elements: zero padded to max, then the length
+pub fn hash_padded<F: PrimeField + Absorb>(config: &PoseidonConfig<F>, inputs: &[F], max: usize) -> F {
+    assert!(inputs.len() <= max, "{} inputs don't fit in {max} slots", inputs.len());
+    let mut padded = inputs.to_vec();
+    padded.resize(max, F::zero());
+    padded.push(F::from(inputs.len() as u64));
+    hash(config, &padded)
+}
+
+// witnesses for the max slots and the length, None during setup
+pub fn alloc_padded<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    inputs: Option<&[F]>,
+    max: usize,
+) -> Result<(Vec<FpVar<F>>, FpVar<F>), SynthesisError> {
+    if let Some(inputs) = inputs {
+        assert!(inputs.len() <= max, "{} inputs don't fit in {max} slots", inputs.len());
+    }
+    let slot = |i: usize| inputs.map(|xs| xs.get(i).copied().unwrap_or(F::zero())).ok_or(SynthesisError::AssignmentMissing);
+    let padded = (0..max)
+        .map(|i| FpVar::new_witness(cs.clone(), || slot(i)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let len = FpVar::new_witness(cs, || inputs.map(|xs| F::from(xs.len() as u64)).ok_or(SynthesisError::AssignmentMissing))?;
+    Ok((padded, len))
+}
+
+// the same as hash_padded, for len elements out of padded.len() slots
+pub fn hash_var_padded<F: PrimeField + Absorb>(
+    cs: ConstraintSystemRef<F>,
+    config: &PoseidonConfig<F>,
+    padded: &[FpVar<F>],
+    len: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    // len == i for exactly one i in 0..=max (at most one holds anyway, the constants differ)
+    let is_len = (0..=padded.len())
+        .map(|i| len.is_eq(&FpVar::constant(F::from(i as u64))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let lengths = is_len.iter().fold(FpVar::zero(), |sum, b| sum + FpVar::from(b.clone()));
+    if !chaos::skipped("poseidon.length") {
+        lengths.enforce_equal(&FpVar::one())?;
+    }
+    // slot i is padding when len <= i, and padding is zero
+    let mut padding = Boolean::FALSE;
+    for (slot, is_len) in padded.iter().zip(&is_len) {
+        padding = padding.or(is_len)?;
+        if !chaos::skipped("poseidon.padding") {
+            FpVar::from(padding.clone()).mul_equals(slot, &FpVar::zero())?;
+        }
+    }
+    let mut inputs = padded.to_vec();
+    inputs.push(len.clone());
+    hash_var(cs, config, &inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{UniformRand, test_rng};
+
+    use super::*;
+
+    const MAX: usize = 8;
+
+    // the circuit's hash of these slots with this length, and whether the constraints hold
+    fn circuit_hash(slots: &[Fr], len: Fr) -> (Fr, bool) {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let padded = slots.iter().map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap()).collect::<Vec<_>>();
+        let len = FpVar::new_witness(cs.clone(), || Ok(len)).unwrap();
+        let hash = hash_var_padded(cs.clone(), &poseidon_config(2), &padded, &len).unwrap();
+        (hash.value().unwrap(), cs.is_satisfied().unwrap())
+    }
+
+    fn random_list() -> Vec<Fr> {
+        let mut rng = test_rng();
+        (0..MAX).map(|_| Fr::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn every_length_hashes_like_the_native_sponge() {
+        let config = poseidon_config::<Fr>(2);
+        let list = random_list();
+        for len in 0..=MAX {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let (padded, length) = alloc_padded(cs.clone(), Some(&list[..len]), MAX).unwrap();
+            let hash = hash_var_padded(cs.clone(), &config, &padded, &length).unwrap();
+            assert!(cs.is_satisfied().unwrap(), "length {len}");
+            assert_eq!(hash.value().unwrap(), hash_padded(&config, &list[..len], MAX), "length {len}");
+        }
+    }
+
+    #[test]
+    fn the_length_is_part_of_the_hash() {
+        let config = poseidon_config::<Fr>(2);
+        let a = random_list()[0];
+        assert_ne!(hash_padded(&config, &[a], MAX), hash_padded(&config, &[a, Fr::from(0u32)], MAX));
+    }
+
+    #[test]
+    fn nonzero_padding_is_refused() {
+        let mut slots = random_list();
+        slots[3..].fill(Fr::from(0u32));
+        assert!(circuit_hash(&slots, Fr::from(3u32)).1);
+        slots[5] = Fr::from(7u32);
+        assert!(!circuit_hash(&slots, Fr::from(3u32)).1);
+    }
+
+    #[test]
+    fn lengths_past_the_end_are_refused() {
+        let list = random_list();
+        assert!(!circuit_hash(&list, Fr::from(MAX as u64 + 1)).1);
+        assert!(!circuit_hash(&list, -Fr::from(1u32)).1);
+    }
+
+    #[test]
+    fn a_padding_zero_counted_in_hashes_as_the_longer_list() {
+        let config = poseidon_config::<Fr>(2);
+        let mut slots = random_list();
+        slots[3..].fill(Fr::from(0u32));
+        let (hash, ok) = circuit_hash(&slots, Fr::from(4u32));
+        assert!(ok);
+        assert_ne!(hash, hash_padded(&config, &slots[..3], MAX));
+        assert_eq!(hash, hash_padded(&config, &slots[..4], MAX));
+    }
+}
@@ -0,0 +1,141 @@
+use ark_ec::bls12::{Bls12, Bls12Config};
+use ark_ec::hashing::HashToCurve;
+use ark_ec::hashing::curve_maps::wb::{WBConfig, WBMap};
+use ark_ec::hashing::map_to_curve_hasher::MapToCurveBasedHasher;
+use ark_ec::pairing::Pairing;
+use ark_ec::short_weierstrass::{Affine, Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::field_hashers::DefaultFieldHasher;
+use ark_std::UniformRand;
+use rand::Rng;
+use sha2::Sha256;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/*
+BLS signatures (Boneh-Lynn-Shacham). Not to be confused with the BLS in BLS12-381,
+that one is Barreto-Lynn-Scott, the people who found the curve family. Lynn is in
+both, which doesn't help.
+
+The scheme:
+  secret key   sk, a scalar
+  public key   pk = sk * g2           (a point in G2)
+  signature    sig = sk * H(m)        (a point in G1, H hashes the message onto the curve)
+  verify       e(sig, g2) == e(H(m), pk)
+
+It works because e(sk * H(m), g2) = e(H(m), g2)^sk = e(H(m), sk * g2).
+Since signatures are just points, they add up: the sum of signatures verifies against
+the sum of public keys (same message) or a product of pairings (different messages).
+Careful, aggregating public keys naively allows "rogue key" attacks, in real life
+every signer has to prove they know their secret key first.
+
+Everything is generic over the BLS12 curve, so the same code signs on BLS12-381 and on
+BLS12-377 (which the circuit in zk-circuits/src/bls_circuit.rs uses).
+*/
+
+pub const DST: &[u8] = b"ZK-TOY-BLS-SIG-V01";
+
+type G1Config<P> = <P as Bls12Config>::G1Config;
+pub type G1Affine<P> = Affine<G1Config<P>>;
+pub type G2Affine<P> = <Bls12<P> as Pairing>::G2Affine;
+pub type ScalarField<P> = <Bls12<P> as Pairing>::ScalarField;
+
+pub struct SecretKey<P: Bls12Config>(pub ScalarField<P>);
+
+pub struct PublicKey<P: Bls12Config>(pub G2Affine<P>);
+
+pub struct Signature<P: Bls12Config>(pub G1Affine<P>);
+
+// written out by hand, #[derive] would want the curve config itself to be Copy
+impl<P: Bls12Config> Clone for PublicKey<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: Bls12Config> Copy for PublicKey<P> {}
+
+impl<P: Bls12Config> Clone for Signature<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: Bls12Config> Copy for Signature<P> {}
+
+// the secret key is the one thing here that isn't public, so it isn't Copy and it
+// gets wiped when it goes out of scope
+impl<P: Bls12Config> Zeroize for SecretKey<P> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<P: Bls12Config> Drop for SecretKey<P> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<P: Bls12Config> ZeroizeOnDrop for SecretKey<P> {}
+
+// hash to curve as in the hash-to-curve rfc: hash to two field elements, map both
+// onto the curve with the simplified swu map and add them up
+pub fn hash_to_g1<P: Bls12Config>(message: &[u8]) -> G1Affine<P>
+where
+    G1Config<P>: WBConfig,
+{
+    let hasher = MapToCurveBasedHasher::<Projective<G1Config<P>>, DefaultFieldHasher<Sha256, 128>, WBMap<G1Config<P>>>::new(DST)
+        .expect("hash to curve setup");
+    hasher.hash(message).expect("hash to curve")
+}
+
+impl<P: Bls12Config> SecretKey<P>
+where
+    G1Config<P>: WBConfig,
+{
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        Self(ScalarField::<P>::rand(rng))
+    }
+
+    pub fn public_key(&self) -> PublicKey<P> {
+        PublicKey((G2Affine::<P>::generator() * self.0).into_affine())
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature<P> {
+        Signature((hash_to_g1::<P>(message) * self.0).into_affine())
+    }
+}
+
+pub fn verify<P: Bls12Config>(pk: &PublicKey<P>, message: &[u8], sig: &Signature<P>) -> bool
+where
+    G1Config<P>: WBConfig,
+{
+    let h = hash_to_g1::<P>(message);
+    Bls12::<P>::pairing(sig.0, G2Affine::<P>::generator()) == Bls12::<P>::pairing(h, pk.0)
+}
+
+pub fn aggregate_signatures<P: Bls12Config>(sigs: &[Signature<P>]) -> Signature<P> {
+    Signature(sigs.iter().map(|s| s.0.into_group()).sum::<Projective<G1Config<P>>>().into_affine())
+}
+
+pub fn aggregate_public_keys<P: Bls12Config>(pks: &[PublicKey<P>]) -> PublicKey<P> {
+    PublicKey(pks.iter().map(|p| p.0.into_group()).sum::<<Bls12<P> as Pairing>::G2>().into_affine())
+}
+
+// everybody signed the same message: two pairings no matter how many signers
+pub fn verify_aggregate_same_message<P: Bls12Config>(pks: &[PublicKey<P>], message: &[u8], sig: &Signature<P>) -> bool
+where
+    G1Config<P>: WBConfig,
+{
+    verify(&aggregate_public_keys(pks), message, sig)
+}
+
+// different messages: one pairing per signer, but still a single signature to send around
+pub fn verify_aggregate<P: Bls12Config>(signed: &[(PublicKey<P>, &[u8])], sig: &Signature<P>) -> bool
+where
+    G1Config<P>: WBConfig,
+{
+    let hs: Vec<G1Affine<P>> = signed.iter().map(|(_, m)| hash_to_g1::<P>(m)).collect();
+    let pks: Vec<G2Affine<P>> = signed.iter().map(|(pk, _)| pk.0).collect();
+    Bls12::<P>::pairing(sig.0, G2Affine::<P>::generator()) == Bls12::<P>::multi_pairing(hs, pks)
+}
@@ -0,0 +1,6 @@
+// protocols that work without a snark: BLS signatures, Pedersen commitments, and
+// sigma protocols made non-interactive with Fiat-Shamir transcripts
+pub mod bls;
+pub mod pedersen;
+pub mod schnorr;
+pub mod transcript;
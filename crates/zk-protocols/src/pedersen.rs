@@ -0,0 +1,200 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One};
+use ark_std::UniformRand;
+use blake2::Blake2b512;
+use rand::Rng;
+use sha2::Digest;
+
+use crate::transcript::{Transcript, TranscriptHash};
+
+/*
+Pedersen commitments, C = v * G + r * H for a value v and a random blinding r, with
+H a second generator nobody knows the discrete log of (it is hashed to the curve
+from a label, so nobody could). C hides v because r * H is uniformly random, and
+binds it because a second opening of the same C would give log_G H.
+
+The proof that the prover knows an opening (v, r) of C is the two-secret Schnorr,
+Okamoto's protocol:
+
+  commit     A = a * G + b * H for fresh random a and b
+  challenge  c
+  respond    z_v = a + c * v, z_r = b + c * r
+
+checked with z_v * G + z_r * H == A + c * C. A point and two scalars per commitment,
+and four scalar multiplications to check each.
+
+Many openings at once (prove_openings): the verifier's challenge first picks a
+random rho, the commitments are folded into C* = C_1 + rho * C_2 + rho^2 * C_3 + ...,
+and one Okamoto proof is made for C*, whose opening is the same combination of the
+openings. One point and two scalars for any number of commitments, and the check is
+one multi-scalar multiplication for C* plus the single proof's. A prover who doesn't
+know every opening can only pass for the rho it guesses, one in the size of the
+field per power of rho. The transcript holds every commitment before rho is drawn,
+the statement is all of them.
+
+It proves knowledge of openings, not anything about the values, that they are votes
+0 or 1 say; that takes an OR proof per commitment or a circuit.
+*/
+
+pub const PROTOCOL: &[u8] = b"zk-toy pedersen opening v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PedersenParams<G: CurveGroup> {
+    pub g: G::Affine,
+    pub h: G::Affine,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Opening<F> {
+    pub value: F,
+    pub blinding: F,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpeningProof<G: CurveGroup> {
+    pub commitment: G::Affine,
+    pub value: G::ScalarField,
+    pub blinding: G::ScalarField,
+}
+
+// try and increment: the first hash of the label and a counter that is the x of a
+// point, times the cofactor to land in the prime order subgroup
+fn hash_to_curve<A: AffineRepr>(label: &[u8]) -> A {
+    (0u64..)
+        .find_map(|counter| {
+            let digest = Blake2b512::new().chain_update(label).chain_update(counter.to_le_bytes()).finalize();
+            A::from_random_bytes(&digest).map(|p| p.clear_cofactor()).filter(|p| !p.is_zero())
+        })
+        .expect("a point turns up within a few tries")
+}
+
+impl<G: CurveGroup> PedersenParams<G> {
+    pub fn new(label: &[u8]) -> Self {
+        Self {
+            g: G::generator().into_affine(),
+            h: hash_to_curve(label),
+        }
+    }
+
+    pub fn commit(&self, opening: &Opening<G::ScalarField>) -> G::Affine {
+        (self.g * opening.value + self.h * opening.blinding).into_affine()
+    }
+
+    pub fn open<R: Rng>(&self, value: G::ScalarField, rng: &mut R) -> (G::Affine, Opening<G::ScalarField>) {
+        let opening = Opening {
+            value,
+            blinding: G::ScalarField::rand(rng),
+        };
+        (self.commit(&opening), opening)
+    }
+
+    fn append_params<H: TranscriptHash<G::ScalarField>>(&self, transcript: &mut Transcript<G::ScalarField, H>) {
+        transcript.append(b"g", &self.g);
+        transcript.append(b"h", &self.h);
+    }
+
+    fn respond<H: TranscriptHash<G::ScalarField>, R: Rng>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        opening: &Opening<G::ScalarField>,
+        rng: &mut R,
+    ) -> OpeningProof<G> {
+        let nonce = Opening {
+            value: G::ScalarField::rand(rng),
+            blinding: G::ScalarField::rand(rng),
+        };
+        let commitment = self.commit(&nonce);
+        transcript.append(b"nonce commitment", &commitment);
+        let c = transcript.challenge(b"c");
+        OpeningProof {
+            commitment,
+            value: nonce.value + c * opening.value,
+            blinding: nonce.blinding + c * opening.blinding,
+        }
+    }
+
+    fn check<H: TranscriptHash<G::ScalarField>>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        commitment: G,
+        proof: &OpeningProof<G>,
+    ) -> bool {
+        transcript.append(b"nonce commitment", &proof.commitment);
+        let c = transcript.challenge(b"c");
+        self.g * proof.value + self.h * proof.blinding == commitment * c + proof.commitment
+    }
+
+    pub fn prove_opening<H: TranscriptHash<G::ScalarField>, R: Rng>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        commitment: &G::Affine,
+        opening: &Opening<G::ScalarField>,
+        rng: &mut R,
+    ) -> OpeningProof<G> {
+        self.append_params(transcript);
+        transcript.append(b"commitment", commitment);
+        self.respond(transcript, opening, rng)
+    }
+
+    pub fn verify_opening<H: TranscriptHash<G::ScalarField>>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        commitment: &G::Affine,
+        proof: &OpeningProof<G>,
+    ) -> bool {
+        self.append_params(transcript);
+        transcript.append(b"commitment", commitment);
+        self.check(transcript, (*commitment).into(), proof)
+    }
+
+    // 1, rho, rho^2, ... with rho drawn after every commitment is in the transcript
+    fn weights<H: TranscriptHash<G::ScalarField>>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        commitments: &[G::Affine],
+    ) -> Vec<G::ScalarField> {
+        self.append_params(transcript);
+        transcript.append_message(b"count", &(commitments.len() as u64).to_le_bytes());
+        for commitment in commitments {
+            transcript.append(b"commitment", commitment);
+        }
+        let rho = transcript.challenge(b"rho");
+        std::iter::successors(Some(G::ScalarField::one()), |w| Some(*w * rho)).take(commitments.len()).collect()
+    }
+
+    // one proof for all the openings: panics if there aren't as many as commitments
+    pub fn prove_openings<H: TranscriptHash<G::ScalarField>, R: Rng>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        commitments: &[G::Affine],
+        openings: &[Opening<G::ScalarField>],
+        rng: &mut R,
+    ) -> OpeningProof<G> {
+        assert_eq!(commitments.len(), openings.len(), "an opening per commitment");
+        let weights = self.weights(transcript, commitments);
+        let folded = Opening {
+            value: weights.iter().zip(openings).map(|(w, o)| *w * o.value).sum(),
+            blinding: weights.iter().zip(openings).map(|(w, o)| *w * o.blinding).sum(),
+        };
+        self.respond(transcript, &folded, rng)
+    }
+
+    pub fn verify_openings<H: TranscriptHash<G::ScalarField>>(
+        &self,
+        transcript: &mut Transcript<G::ScalarField, H>,
+        commitments: &[G::Affine],
+        proof: &OpeningProof<G>,
+    ) -> bool {
+        let weights = self.weights(transcript, commitments);
+        let folded = G::msm(commitments, &weights).expect("a weight per commitment");
+        self.check(transcript, folded, proof)
+    }
+}
+
+// the sum of the openings opens the sum of the commitments, what a tally relies on
+pub fn sum_openings<F: Field>(openings: &[Opening<F>]) -> Opening<F> {
+    Opening {
+        value: openings.iter().map(|o| o.value).sum(),
+        blinding: openings.iter().map(|o| o.blinding).sum(),
+    }
+}
@@ -0,0 +1,105 @@
+use ark_ec::CurveGroup;
+use ark_std::UniformRand;
+use rand::Rng;
+
+use crate::transcript::{Transcript, TranscriptHash};
+
+/*
+Schnorr's proof of knowledge of a discrete log, the simplest sigma protocol and the
+pattern for the others (commit, challenge, respond). The prover knows x with
+X = x * G and
+
+  commit     R = r * G for a fresh random r
+  challenge  c, from the verifier, or from the transcript
+  respond    s = r + c * x
+
+and the verifier checks s * G == R + c * X. Two accepted answers to different
+challenges for the same R give x away ((s - s') / (c - c')), which is why it proves
+knowledge, and the responses alone are uniformly random, which is why they show
+nothing about x.
+
+Non-interactive with Fiat-Shamir (transcript.rs): c is squeezed from a transcript
+holding the generator, X and R, in that order, on both sides. Leaving any of them out
+is the weak Fiat-Shamir mistake, kept here as prove_weak and verify_weak, whose
+challenge is a hash of the generator and R only. Then c is known before X is: pick R
+and s, get c, and X = (s * G - R) / c passes the check. src/bin/weak_fiat_shamir.rs
+forges a proof for a key nobody knows the secret of that way.
+*/
+
+pub const PROTOCOL: &[u8] = b"zk-toy schnorr v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrProof<G: CurveGroup> {
+    pub commitment: G::Affine,
+    pub response: G::ScalarField,
+}
+
+// what the transcript has in it by the time of the challenge, the strong variant
+// with the public key and the weak one (None) without
+pub fn challenge<G: CurveGroup, H: TranscriptHash<G::ScalarField>>(
+    transcript: &mut Transcript<G::ScalarField, H>,
+    public: Option<&G::Affine>,
+    commitment: &G::Affine,
+) -> G::ScalarField {
+    transcript.append(b"generator", &G::generator().into_affine());
+    if let Some(public) = public {
+        transcript.append(b"public key", public);
+    }
+    transcript.append(b"commitment", commitment);
+    transcript.challenge(b"c")
+}
+
+fn respond<G: CurveGroup, H: TranscriptHash<G::ScalarField>, R: Rng>(
+    transcript: &mut Transcript<G::ScalarField, H>,
+    secret: G::ScalarField,
+    strong: bool,
+    rng: &mut R,
+) -> SchnorrProof<G> {
+    let public = (G::generator() * secret).into_affine();
+    let r = G::ScalarField::rand(rng);
+    let commitment = (G::generator() * r).into_affine();
+    let c = challenge::<G, H>(transcript, strong.then_some(&public), &commitment);
+    SchnorrProof {
+        commitment,
+        response: r + c * secret,
+    }
+}
+
+fn check<G: CurveGroup>(public: &G::Affine, proof: &SchnorrProof<G>, c: G::ScalarField) -> bool {
+    G::generator() * proof.response == proof.commitment.into() + *public * c
+}
+
+pub fn prove<G: CurveGroup, H: TranscriptHash<G::ScalarField>, R: Rng>(
+    transcript: &mut Transcript<G::ScalarField, H>,
+    secret: G::ScalarField,
+    rng: &mut R,
+) -> SchnorrProof<G> {
+    respond(transcript, secret, true, rng)
+}
+
+pub fn verify<G: CurveGroup, H: TranscriptHash<G::ScalarField>>(
+    transcript: &mut Transcript<G::ScalarField, H>,
+    public: &G::Affine,
+    proof: &SchnorrProof<G>,
+) -> bool {
+    let c = challenge::<G, H>(transcript, Some(public), &proof.commitment);
+    check(public, proof, c)
+}
+
+// the broken variant, for the attack: the public key isn't in the transcript
+pub fn prove_weak<G: CurveGroup, H: TranscriptHash<G::ScalarField>, R: Rng>(
+    transcript: &mut Transcript<G::ScalarField, H>,
+    secret: G::ScalarField,
+    rng: &mut R,
+) -> SchnorrProof<G> {
+    respond(transcript, secret, false, rng)
+}
+
+pub fn verify_weak<G: CurveGroup, H: TranscriptHash<G::ScalarField>>(
+    transcript: &mut Transcript<G::ScalarField, H>,
+    public: &G::Affine,
+    proof: &SchnorrProof<G>,
+) -> bool {
+    let c = challenge::<G, H>(transcript, None, &proof.commitment);
+    check(public, proof, c)
+}
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, PoseidonSponge};
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use blake2::Blake2b512;
+use sha2::Digest;
+use sha3::Sha3_512;
+
+/*
+Fiat-Shamir transcripts. An interactive proof becomes a non-interactive one by
+replacing the verifier's random challenges with a hash of everything said so far:
+the prover appends what it sends, the challenge is squeezed out of the hash, and the
+verifier replays the same appends to get the same challenge. So the two only agree
+when they hash the same bytes the same way, in the same order, with the same hash:
+
+  Blake2bHash   blake2b-512, 64 bytes reduced into the field, for everything native
+  Sha3Hash      sha3-512, the same way, what ethereum-adjacent code tends to pick
+  PoseidonHash  Poseidon over the challenge field itself, what a transcript has to
+                use when a circuit is going to recompute the challenges (recursion)
+
+A Transcript starts with a protocol label, so a challenge of one protocol can't be
+replayed as one of another, and every append carries a label and a length, so
+"ab" + "c" and "a" + "bc" don't hash the same. src/bin/transcripts.rs shows that any
+disagreement, another hash or another label, gives the verifier another challenge
+and the proof is refused. Everything that defines the statement has to be appended
+too: the generator, the public key, the commitment (schnorr.rs).
+
+The byte hashes squeeze by hashing the state with a counter and then absorbing the
+output, so every challenge depends on all the ones before it.
+*/
+
+pub trait TranscriptHash<F: PrimeField>: Clone {
+    const NAME: &'static str;
+
+    fn absorb(&mut self, bytes: &[u8]);
+
+    fn squeeze(&mut self) -> F;
+}
+
+#[derive(Clone, Default)]
+pub struct Blake2bHash {
+    state: Blake2b512,
+    squeezed: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct Sha3Hash {
+    state: Sha3_512,
+    squeezed: u64,
+}
+
+// a 512 bit digest of the state and the counter, absorbed back and reduced into F
+fn squeeze_digest<D: Digest + Clone, F: PrimeField>(state: &mut D, squeezed: &mut u64) -> F {
+    let mut copy = state.clone();
+    copy.update(b"squeeze");
+    copy.update(squeezed.to_le_bytes());
+    let out = copy.finalize();
+    *squeezed += 1;
+    state.update(&out);
+    F::from_le_bytes_mod_order(&out)
+}
+
+impl<F: PrimeField> TranscriptHash<F> for Blake2bHash {
+    const NAME: &'static str = "blake2b";
+
+    fn absorb(&mut self, bytes: &[u8]) {
+        self.state.update(bytes);
+    }
+
+    fn squeeze(&mut self) -> F {
+        squeeze_digest(&mut self.state, &mut self.squeezed)
+    }
+}
+
+impl<F: PrimeField> TranscriptHash<F> for Sha3Hash {
+    const NAME: &'static str = "sha3";
+
+    fn absorb(&mut self, bytes: &[u8]) {
+        self.state.update(bytes);
+    }
+
+    fn squeeze(&mut self) -> F {
+        squeeze_digest(&mut self.state, &mut self.squeezed)
+    }
+}
+
+// the sponge over F, with parameters from zk_gadgets::poseidon::poseidon_config
+#[derive(Clone)]
+pub struct PoseidonHash<F: PrimeField> {
+    sponge: PoseidonSponge<F>,
+}
+
+impl<F: PrimeField> PoseidonHash<F> {
+    pub fn new(config: &PoseidonConfig<F>) -> Self {
+        Self { sponge: PoseidonSponge::new(config) }
+    }
+}
+
+impl<F: PrimeField + Absorb> TranscriptHash<F> for PoseidonHash<F> {
+    const NAME: &'static str = "poseidon";
+
+    // bytes go in packed into field elements, see Absorb for [u8]
+    fn absorb(&mut self, bytes: &[u8]) {
+        self.sponge.absorb(&bytes);
+    }
+
+    fn squeeze(&mut self) -> F {
+        self.sponge.squeeze_field_elements::<F>(1)[0]
+    }
+}
+
+#[derive(Clone)]
+pub struct Transcript<F: PrimeField, H: TranscriptHash<F>> {
+    hash: H,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField, H: TranscriptHash<F>> Transcript<F, H> {
+    pub fn new(hash: H, protocol: &[u8]) -> Self {
+        let mut transcript = Self { hash, _field: PhantomData };
+        transcript.append_message(b"protocol", protocol);
+        transcript
+    }
+
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        for part in [label, message] {
+            self.hash.absorb(&(part.len() as u64).to_le_bytes());
+            self.hash.absorb(part);
+        }
+    }
+
+    // a point or a scalar, in its compressed encoding
+    pub fn append<T: CanonicalSerialize>(&mut self, label: &[u8], value: &T) {
+        let mut bytes = Vec::new();
+        value.serialize_compressed(&mut bytes).expect("serializing into a vec");
+        self.append_message(label, &bytes);
+    }
+
+    pub fn challenge(&mut self, label: &[u8]) -> F {
+        self.append_message(b"challenge", label);
+        self.hash.squeeze()
+    }
+
+    pub fn hash_name(&self) -> &'static str {
+        H::NAME
+    }
+}
@@ -0,0 +1,162 @@
+use std::sync::Mutex;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, PreparedVerifyingKey, ProvingKey, Proof, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use serde_json::{Value, json};
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::examples::count_constraints;
+use zk_core::artifact;
+use zk_core::input::parse_field;
+use zk_core::platform;
+use zk_core::report::timed;
+
+/*
+The addition example as a wasm module, for the page `zk-toy demo` serves: the
+browser does the setup, proves x + y = z with x and y never leaving the page, and
+verifies, all on BLS12-381 like src/bin/addition.rs.
+
+No wasm-bindgen, so the interface is plain numbers and one buffer each way. The page
+asks for room with zk_input(len), writes a json request there, calls a function that
+returns the length of the json answer, and reads it at zk_output():
+
+  zk_setup()    -> { "setup_ms", "constraints", "proving_key_bytes", "verifying_key_bytes" }
+  zk_prove()     { "x": "3", "y": "4" } -> { "z", "proof", "proof_bytes", "prove_ms" }
+  zk_verify()    { "z": "7", "proof": "..." } -> { "valid", "verify_ms" }
+
+Numbers are strings the way zk-toy reads them (decimal or 0x hex), the proof is the
+hex of a compressed Proof<Bls12_381>. Anything wrong comes back as { "error": ... }.
+Time and randomness come from the page too (zk_core::platform says how).
+
+The keys live in the module until the page is reloaded. A classroom toy: the setup
+is done by the same page that proves, so whoever runs it could forge proofs, which
+is the point of the trusted setup lesson rather than a bug.
+*/
+
+struct Keys {
+    pk: ProvingKey<Bls12_381>,
+    pvk: PreparedVerifyingKey<Bls12_381>,
+}
+
+struct Demo {
+    keys: Option<Keys>,
+    input: Vec<u8>,
+    output: Vec<u8>,
+}
+
+static DEMO: Mutex<Demo> = Mutex::new(Demo {
+    keys: None,
+    input: Vec::new(),
+    output: Vec::new(),
+});
+
+fn demo() -> std::sync::MutexGuard<'static, Demo> {
+    DEMO.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// room for a request of `len` bytes, valid until the next call
+#[unsafe(no_mangle)]
+pub extern "C" fn zk_input(len: usize) -> *mut u8 {
+    let mut demo = demo();
+    demo.input = vec![0; len];
+    demo.input.as_mut_ptr()
+}
+
+// the last answer, zk_setup, zk_prove and zk_verify return its length
+#[unsafe(no_mangle)]
+pub extern "C" fn zk_output() -> *const u8 {
+    demo().output.as_ptr()
+}
+
+fn answer(demo: &mut Demo, result: Result<Value, String>) -> usize {
+    let value = result.unwrap_or_else(|message| json!({ "error": message }));
+    demo.output = value.to_string().into_bytes();
+    demo.output.len()
+}
+
+fn request(demo: &Demo) -> Result<Value, String> {
+    serde_json::from_slice(&demo.input).map_err(|e| format!("the request isn't json: {e}"))
+}
+
+fn field(request: &Value, name: &str) -> Result<String, String> {
+    request[name].as_str().map(str::to_string).ok_or_else(|| format!("the request needs \"{name}\" as a string"))
+}
+
+fn element(request: &Value, name: &str) -> Result<Fr, String> {
+    parse_field(&field(request, name)?).map_err(|e| format!("{name}: {e}"))
+}
+
+fn millis(took: std::time::Duration) -> f64 {
+    took.as_secs_f64() * 1000.0
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn zk_setup() -> usize {
+    let mut demo = demo();
+    let result = setup(&mut demo);
+    answer(&mut demo, result)
+}
+
+fn setup(demo: &mut Demo) -> Result<Value, String> {
+    let mut rng = platform::rng();
+    let constraints = count_constraints::<Fr, _>(&AdditionCircuit::empty()).map_err(|e| e.to_string())?;
+    let (keys, took) = timed(|| Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut rng));
+    let (pk, vk) = keys.map_err(|e| format!("setup failed: {e}"))?;
+    let answer = json!({
+        "setup_ms": millis(took),
+        "constraints": constraints,
+        "proving_key_bytes": pk.compressed_size(),
+        "verifying_key_bytes": vk.compressed_size(),
+    });
+    demo.keys = Some(Keys {
+        pvk: prepare_verifying_key(&vk),
+        pk,
+    });
+    Ok(answer)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn zk_prove() -> usize {
+    let mut demo = demo();
+    let result = prove(&demo);
+    answer(&mut demo, result)
+}
+
+fn prove(demo: &Demo) -> Result<Value, String> {
+    let keys = demo.keys.as_ref().ok_or("call zk_setup first")?;
+    let request = request(demo)?;
+    let (x, y) = (element(&request, "x")?, element(&request, "y")?);
+    let z = x + y;
+    let mut rng = platform::rng();
+    let (proof, took) = timed(|| Groth16::<Bls12_381>::prove(&keys.pk, AdditionCircuit::new(x, y), &mut rng));
+    let proof = proof.map_err(|e| format!("proving failed: {e}"))?;
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(json!({
+        "z": z.to_string(),
+        "proof": artifact::to_hex(&bytes),
+        "proof_bytes": bytes.len(),
+        "prove_ms": millis(took),
+    }))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn zk_verify() -> usize {
+    let mut demo = demo();
+    let result = verify(&demo);
+    answer(&mut demo, result)
+}
+
+fn verify(demo: &Demo) -> Result<Value, String> {
+    let keys = demo.keys.as_ref().ok_or("call zk_setup first")?;
+    let request = request(demo)?;
+    let z = element(&request, "z")?;
+    let bytes = artifact::from_hex(&field(&request, "proof")?).map_err(|e| e.to_string())?;
+    let proof: Proof<Bls12_381> = artifact::deserialize_checked(&bytes).map_err(|e| format!("not a proof: {e}"))?;
+    let (valid, took) = timed(|| Groth16::<Bls12_381>::verify_with_processed_vk(&keys.pvk, &[z], &proof));
+    Ok(json!({
+        "valid": valid.unwrap_or(false),
+        "verify_ms": millis(took),
+    }))
+}
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use rand::thread_rng;
+use zk_circuits::examples;
+use zk_core::report;
+/*
+A private airdrop: the eligible addresses are leaves of a merkle tree, a claimant
+proves one of them is theirs and publishes a nullifier so it can't claim twice, and
+the payout goes to whatever address the proof names, with nothing linking the two.
+The circuit is crates/zk-circuits/src/airdrop.rs, the solidity verifier and the
+airdrop contract (plus the rust model of both the example runs the claims through)
+are in solidity.rs, the story is examples::airdrop::run.
+
+Writes Verifier.sol, Airdrop.sol and claim_calldata.txt to ./airdrop, or to the
+directory given as the first argument. They haven't been compiled in this repo, take
+them to foundry or hardhat to deploy.
+*/
+
+fn main() -> Result<(), examples::ExampleError> {
+    let mut rng = thread_rng();
+    if report::json_requested() {
+        let report = examples::airdrop::run(&mut rng, true, None)?;
+        println!("{}", report.to_json());
+        return Ok(());
+    }
+    let dir = std::env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("airdrop"));
+    let report = examples::airdrop::run(&mut rng, false, Some(&dir))?;
+    println!("  airdrop claim: {}", report.summary());
+    Ok(())
+}
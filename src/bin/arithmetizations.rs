@@ -0,0 +1,108 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use zk_circuits::arithmetizations::{RangeCheckCircuit, RangeMethod};
+use zk_circuits::dry_run::{Comparison, DryRun};
+use zk_circuits::fixtures::{self, SEED};
+use zk_circuits::membership::MembershipCircuit;
+use zk_gadgets::hasher::PoseidonHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+/*
+There's more than one way to arithmetize a statement. Two pairs here, each one
+statement written as two different circuits (zk-circuits/src/arithmetizations.rs):
+
+  lo <= x <= hi   the range checks by bits, or by limbs looked up in a table
+  membership      16 students (fixtures.rs) as a binary tree of depth 4, or a 4-ary
+                  tree of depth 2
+
+For each pair it is asserted that both circuits accept and refuse the same
+witnesses, and then their sizes are printed side by side, the same table as
+`zk-toy synth range --versus method=table`. Neither is the right one: the table is
+what a lookup argument makes cheap and R1CS doesn't, wider Poseidon costs less per
+leaf than more levels of the narrow one.
+*/
+
+const BITS: usize = 16;
+
+fn satisfied<C: ConstraintSynthesizer<Fr>>(circuit: C) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("synthesis failed");
+    cs.is_satisfied().expect("a witness was missing")
+}
+
+fn dry_run<C: ConstraintSynthesizer<Fr>>(circuit: C) -> DryRun {
+    DryRun::synthesize(circuit).expect("synthesis failed")
+}
+
+fn range(method: RangeMethod, x: u64, lo: u64, hi: u64) -> RangeCheckCircuit<Fr> {
+    // built by hand rather than with new, which refuses the x that are out of range
+    RangeCheckCircuit {
+        method,
+        bits: BITS,
+        lo: Some(lo.into()),
+        hi: Some(hi.into()),
+        x: Some(x.into()),
+    }
+}
+
+fn ranges() {
+    println!("=== lo <= x <= hi, by bits and by a table ===");
+    let (lo, hi) = (1000u64, 40000);
+    let table = RangeMethod::Table { limb_bits: 4 };
+    let cases = [0u64, 999, 1000, 1001, 20000, 39999, 40000, 40001, (1 << BITS) - 1, 1 << BITS, 1 << 40];
+    for x in cases {
+        let holds = (lo..=hi).contains(&x);
+        assert_eq!(satisfied(range(RangeMethod::Bits, x, lo, hi)), holds, "bits, x = {x}");
+        assert_eq!(satisfied(range(table, x, lo, hi)), holds, "table, x = {x}");
+    }
+    println!("✓ both accept and refuse the same {} values of x for [{lo}, {hi}]", cases.len());
+    // p - 1 as x: the differences wrap around to huge numbers
+    let wrapped = RangeCheckCircuit { x: Some(-Fr::from(1u32)), ..range(table, 0, lo, hi) };
+    assert!(!satisfied(wrapped.clone()));
+    assert!(!satisfied(RangeCheckCircuit { method: RangeMethod::Bits, ..wrapped }));
+    println!("✓ x = p - 1 is refused by both");
+
+    for limb_bits in [1, 2, 4, 8] {
+        let table = RangeMethod::Table { limb_bits };
+        assert!(satisfied(range(table, 20000, lo, hi)));
+        let comparison = Comparison::new::<Bls12_381>(
+            (&format!("range by {}", RangeMethod::Bits), dry_run(RangeCheckCircuit::<Fr>::empty(RangeMethod::Bits, BITS))),
+            (&format!("range by {table}"), dry_run(RangeCheckCircuit::<Fr>::empty(table, BITS))),
+        );
+        println!("\n{comparison}");
+    }
+}
+
+fn memberships() {
+    println!("\n=== membership among 16 students, binary and 4-ary ===");
+    let students = &fixtures::students(SEED)[..16];
+    let shapes = [TreeShape::binary(4), TreeShape::new(4, 2)];
+    let mut runs = Vec::new();
+    for shape in shapes {
+        assert_eq!(shape.leaves(), students.len());
+        let hasher = PoseidonHasher::<Fr>::new(shape.arity);
+        let leaves = fixtures::student_leaves(&hasher, students);
+        let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+        for index in [0, 5, 15] {
+            let honest = MembershipCircuit::new(hasher.clone(), leaves[index], tree.path(index as u64));
+            assert_eq!(honest.root, Some(tree.root()));
+            assert!(satisfied(honest.clone()), "{shape:?}, leaf {index}");
+            let mut other_leaf = honest.clone();
+            other_leaf.leaf = Some(leaves[index] + Fr::from(1u32));
+            assert!(!satisfied(other_leaf), "{shape:?}, leaf {index} + 1");
+            let mut other_root = honest;
+            other_root.root = other_root.root.map(|r| r + Fr::from(1u32));
+            assert!(!satisfied(other_root), "{shape:?}, root + 1");
+        }
+        println!("✓ arity {} depth {}: honest paths verify, another leaf or root doesn't", shape.arity, shape.depth);
+        runs.push((format!("arity {} depth {}", shape.arity, shape.depth), dry_run(MembershipCircuit::empty(hasher, shape))));
+    }
+    let after = runs.pop().expect("two shapes");
+    let before = runs.pop().expect("two shapes");
+    println!("\n{}", Comparison::new::<Bls12_381>((&before.0, before.1), (&after.0, after.1)));
+}
+
+fn main() {
+    ranges();
+    memberships();
+    println!("\n✓ two arithmetizations of each statement, the same witnesses accepted");
+}
@@ -0,0 +1,52 @@
+use ark_bls12_381::Fr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use rand::thread_rng;
+use zk_circuits::bitcoin::{self, GENESIS, PowCircuit};
+use zk_circuits::examples;
+use zk_core::report;
+/*
+Bitcoin proof of work in a proof: "I know a block header whose double sha256 is below
+this target", with the header secret. The header here is the genesis block's, whose
+hash everybody knows, so the secret is a bit theoretical, but the statement is the one
+a miner would make about a fresh block. See crates/zk-circuits/src/bitcoin.rs.
+
+Almost every constraint is sha256, which is bit oriented and expensive in a circuit:
+this is the biggest circuit in the repo, expect the proof to take a while.
+*/
+
+fn main() {
+    let mut rng = thread_rng();
+    let hash = bitcoin::block_hash(&GENESIS);
+    let bits = bitcoin::header_bits(&GENESIS);
+    let target = bitcoin::target_from_bits(bits).expect("difficulty 1 is a valid target");
+    let value = bitcoin::hash_value(&hash).expect("the genesis hash starts with zeros");
+    assert!(bitcoin::meets_target(&GENESIS, target) && !bitcoin::meets_target(&GENESIS, value));
+
+    let report = examples::bitcoin::run(&mut rng, &GENESIS).expect("Failed to run the bitcoin example");
+    if report::json_requested() {
+        println!("{}", report.to_json());
+        return;
+    }
+    println!("Secret header:  80 bytes, the genesis block");
+    println!("Its hash:       {}", bitcoin::display_hash(&hash));
+    println!("Public target:  bits {bits:#010x}, {}", report.public_inputs[0]);
+    println!("Constraints: {} (nearly all of them sha256)", report.constraints);
+    println!("✓ Setup, proof and verification done");
+    println!("  {}\n", report.summary());
+
+    println!("=== VERIFIER ===");
+    assert!(report.verified);
+    println!("✓ Somebody knows a header with enough work for the target, the verifier doesn't see which");
+    assert!(report.rejects_wrong_statement);
+    println!("✓ The same proof is refused for a target equal to the hash itself (below, not at)\n");
+
+    println!("=== A HEADER WITHOUT THE WORK ===");
+    let mut lazy = GENESIS;
+    lazy[76] = lazy[76].wrapping_add(1);
+    println!("  nonce + 1 hashes to {}", bitcoin::display_hash(&bitcoin::block_hash(&lazy)));
+    assert!(!bitcoin::meets_target(&lazy, target));
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    PowCircuit::new(lazy, target).generate_constraints(cs.clone()).expect("Failed to synthesize");
+    assert!(!cs.is_satisfied().expect("Failed to check the constraints"));
+    println!("✓ which isn't below the target, and no witness satisfies the circuit with it");
+}
@@ -0,0 +1,67 @@
+use ark_bls12_381::Config as Bls12_381Config;
+use rand::thread_rng;
+use zk_circuits::examples;
+use zk_core::report;
+use zk_protocols::bls::{self, SecretKey};
+/*
+Two meanings of "BLS" in one example.
+
+Part 1 is BLS the signature scheme, on the BLS12-381 curve, no snarks involved:
+three signers, their signatures squashed into one, verified with two pairings.
+
+Part 2 puts a BLS signature check inside a snark. That doesn't work with BLS12-381,
+so we sign on BLS12-377 and prove with Groth16 over BW6-761, see crates/zk-circuits/src/bls_circuit.rs.
+The circuit is big (pairings are expensive) so run this one with --release.
+*/
+
+fn main() {
+    let mut rng = thread_rng();
+    let message = b"transfer 5 toy coins to bob";
+    // the report is about part 2, the only part with a snark in it
+    if report::json_requested() {
+        let report = examples::bls::run(&mut rng, message).expect("Failed to run the bls example");
+        println!("{}", report.to_json());
+        return;
+    }
+
+    println!("=== PART 1: BLS SIGNATURES ON BLS12-381 ===");
+    let signers: Vec<SecretKey<Bls12_381Config>> = (0..3).map(|_| SecretKey::random(&mut rng)).collect();
+    let pks: Vec<_> = signers.iter().map(|sk| sk.public_key()).collect();
+    let sigs: Vec<_> = signers.iter().map(|sk| sk.sign(message)).collect();
+    assert!(bls::verify(&pks[0], message, &sigs[0]));
+    println!("✓ A single signature verifies");
+
+    // same message: add the signatures, add the public keys, still two pairings
+    let aggregate = bls::aggregate_signatures(&sigs);
+    assert!(bls::verify_aggregate_same_message(&pks, message, &aggregate));
+    println!("✓ 3 signatures on the same message aggregated into one and verified");
+
+    // different messages: still one signature, but one pairing per signer
+    let messages: [&[u8]; 3] = [b"alice pays", b"bob pays", b"carol pays"];
+    let sigs: Vec<_> = signers.iter().zip(messages).map(|(sk, m)| sk.sign(m)).collect();
+    let aggregate = bls::aggregate_signatures(&sigs);
+    let signed: Vec<_> = pks.iter().copied().zip(messages).collect();
+    assert!(bls::verify_aggregate(&signed, &aggregate));
+    println!("✓ 3 signatures on different messages aggregated into one and verified");
+
+    if !bls::verify(&pks[0], b"transfer 500 toy coins to bob", &sigs[0]) {
+        println!("Correctly rejected a signature on a different message\n");
+    }
+
+    println!("=== PART 2: A BLS SIGNATURE CHECK INSIDE A SNARK ===");
+    // sign on BLS12-377, prove over BW6-761, see examples::bls::run
+    let report = examples::bls::run(&mut rng, message).expect("Failed to run the bls example");
+    println!("Constraints: {} (two pairings sharing a final exponentiation)", report.constraints);
+    println!("✓ Setup over BW6-761, proof and verification done");
+    println!("  {}", report.summary());
+    if report.verified {
+        println!("  The proof is valid: somebody holds a BLS signature by this key on this message.\n");
+    } else {
+        println!("Proof is invalid, you messed up, or you lyin' ");
+    }
+
+    println!("=== TESTING WITH A DIFFERENT MESSAGE ===");
+    if report.rejects_wrong_statement {
+        println!("Correctly rejected the proof for a different message");
+    }
+}
@@ -0,0 +1,46 @@
+use zk_circuits::boundary::{self, BoundaryCheck};
+use zk_circuits::challenges::Challenge;
+/*
+Boundary fuzzing: every example circuit run on 0, 1, p - 1, the edges of its range
+checks and the same value in every witness, with the constraint system compared to
+the native predicate the circuit stands for. The cases are in
+zk-circuits/src/boundary.rs, whose tests assert them. Everything is asserted here too,
+a disagreement anywhere panics with the case that caught it.
+
+The broken challenge circuits (zk-toy challenge) go last. There a disagreement is the
+bug the challenge is about, so they are the check that the boundaries find bugs.
+*/
+
+fn report(name: &str, check: &BoundaryCheck) {
+    for d in &check.disagreements {
+        println!("✗ {name}: {} (the statement holds: {}, the constraints do: {})", d.case, d.holds, d.satisfied);
+    }
+    assert!(check.agrees(), "{name}: {} of {} boundary cases disagree", check.disagreements.len(), check.cases);
+    println!("✓ {name}: {} cases, the constraints hold exactly when the statement does", check.cases);
+}
+
+fn main() {
+    println!("=== THE EXAMPLE CIRCUITS ===");
+    for (name, cases) in boundary::CIRCUITS {
+        report(name, &cases());
+    }
+    let (sums, thresholds) = boundary::sum_and_threshold();
+    report("sum template", &sums);
+    report("threshold template", &thresholds);
+    println!();
+
+    println!("=== THE BROKEN ONES ===");
+    let mut found = Vec::new();
+    for (challenge, exploit) in boundary::broken() {
+        match exploit {
+            Some(case) => {
+                println!("✓ {}: the boundaries break it with {case}", challenge.name());
+                found.push(challenge);
+            }
+            None => println!("  {}: no boundary case breaks it", challenge.name()),
+        }
+    }
+    // the booleanity bug needs a non-bit balanced by the other bits, no single boundary
+    // value does that. The other three fall over at 0, 1 or p - 1
+    assert_eq!(found, [Challenge::Output, Challenge::Division, Challenge::Wraparound]);
+}
@@ -0,0 +1,108 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, prepare_verifying_key};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
+use ark_snark::SNARK;
+use rand::{Rng, thread_rng};
+use zk_circuits::bristol::{self, Bristol, BristolCircuit};
+/*
+Proofs about boolean circuits from Bristol Fashion files, with the parser and the
+generic synthesizer in zk-circuits/src/bristol.rs.
+
+Without arguments it writes a 32 bit ripple carry adder in the format and proves "I
+know a secret x with x + 1234567 = z (mod 2^32)" for a public z, then feeds the parser
+a few broken files. With a path it loads that file instead (aes_128.txt, sha256.txt or
+anything else from the Bristol Fashion collection), picks random inputs, keeps the
+first input secret and the others public, and proves the outputs:
+
+  cargo run --release --bin bristol -- aes_128.txt
+
+for AES that's "I know a key that encrypts this plaintext to this ciphertext". The
+tests in bristol.rs check the adder, the broken files, and an AES-128 written gate by
+gate against the FIPS-197 vectors.
+*/
+
+const BITS: usize = 32;
+
+fn bits(value: u64, n: usize) -> Vec<bool> {
+    (0..n).map(|i| value >> i & 1 == 1).collect()
+}
+
+fn number(bits: &[bool]) -> u64 {
+    bits.iter().rev().fold(0, |acc, &b| acc << 1 | b as u64)
+}
+
+fn hex(bits: &[bool]) -> String {
+    bits.chunks(8).map(|byte| format!("{:02x}", number(byte))).collect()
+}
+
+// setup, prove and verify, then the same proof against outputs with one bit flipped
+fn prove(circuit: &Bristol, public: Vec<bool>, inputs: Vec<Vec<bool>>) {
+    let mut rng = thread_rng();
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    BristolCircuit::<Fr>::empty(circuit.clone(), public.clone())
+        .generate_constraints(cs.clone())
+        .expect("Failed to synthesize");
+    println!(
+        "  {} gates ({} AND, {} XOR) on {} wires: {} constraints",
+        circuit.gates.len(),
+        circuit.and_gates(),
+        circuit.xor_gates(),
+        circuit.wires,
+        cs.num_constraints()
+    );
+
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(BristolCircuit::<Fr>::empty(circuit.clone(), public.clone()), &mut rng)
+        .expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let outputs = circuit.evaluate(&inputs).expect("the inputs fit the circuit");
+    let statement = bristol::public_inputs::<Fr>(&public, &inputs, &outputs);
+    let proof = Groth16::<Bls12_381>::prove(&pk, BristolCircuit::new(circuit.clone(), public.clone(), inputs.clone()).expect("the inputs fit"), &mut rng)
+        .expect("Failed to generate proof");
+    assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &statement, &proof).expect("the input count fits"));
+    println!("✓ the proof verifies for outputs {}", outputs.iter().map(|o| hex(o)).collect::<Vec<_>>().join(", "));
+
+    let mut wrong = outputs.clone();
+    wrong[0][0] = !wrong[0][0];
+    let statement = bristol::public_inputs::<Fr>(&public, &inputs, &wrong);
+    assert!(!Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &statement, &proof).expect("the input count fits"));
+    println!("✓ and not with the lowest output bit flipped");
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    if let Some(path) = std::env::args().nth(1) {
+        let circuit = Bristol::load(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+        println!("=== {path} ===");
+        let inputs: Vec<Vec<bool>> = circuit.inputs.iter().map(|&n| (0..n).map(|_| rng.r#gen()).collect()).collect();
+        for (i, input) in inputs.iter().enumerate() {
+            println!("  input {i} ({}): {}", if i == 0 { "secret" } else { "public" }, hex(input));
+        }
+        let public = (0..circuit.inputs.len()).map(|i| i > 0).collect();
+        prove(&circuit, public, inputs);
+        return;
+    }
+
+    println!("=== A {BITS} BIT ADDER IN BRISTOL FASHION ===");
+    let text = bristol::ripple_adder(BITS);
+    println!("{}  ...\n", text.lines().take(7).collect::<Vec<_>>().join("\n"));
+    let circuit = Bristol::parse(&text).expect("the adder parses");
+    let (x, y) = (rng.gen_range(0..1u64 << BITS), 1234567u64);
+    let sum = circuit.evaluate(&[bits(x, BITS), bits(y, BITS)]).expect("two 32 bit inputs");
+    assert_eq!(number(&sum[0]), (x + y) % (1 << BITS));
+    println!("✓ evaluated natively: {x} + {y} = {} (mod 2^{BITS})", number(&sum[0]));
+    prove(&circuit, vec![false, true], vec![bits(x, BITS), bits(y, BITS)]);
+    println!();
+
+    println!("=== FILES THE PARSER REFUSES ===");
+    for (what, text) in [
+        ("a wire read before it is set", "1 4\n1 2\n1 1\n\n2 1 0 2 3 AND\n"),
+        ("a wire set twice", "2 4\n1 2\n1 1\n\n2 1 0 1 3 AND\n2 1 0 1 3 XOR\n"),
+        ("the wrong gate count", "2 4\n1 2\n1 1\n\n2 1 0 1 3 AND\n"),
+        ("an unknown gate", "1 4\n1 2\n1 1\n\n2 1 0 1 3 NAND\n"),
+        ("an output never set", "1 5\n1 2\n1 2\n\n2 1 0 1 3 AND\n"),
+    ] {
+        let error = Bristol::parse(text).expect_err("a broken file");
+        println!("✓ {what}: {error}");
+    }
+}
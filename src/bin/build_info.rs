@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::curves::Curve;
+use zk_core::artifact;
+/*
+The build stamp on saved keys and proofs (zk-core/src/build_info.rs). Every file
+artifact::save writes starts with where it was made: the crate version, the arkworks
+versions, the curve, the features and the target. A stamp says where, it mustn't
+change what: the artifact after it has to be the same bytes on every machine, or a
+proof made on a laptop wouldn't verify on the server. Checked here:
+
+  1. a stamped key and proof load back equal, and the stamp reads back as this build
+  2. the bytes after the stamp are exactly the unstamped serialization
+  3. the keys in compat/v0.1.0, written by a release from before there were
+     stamps, still load and re-serialize to the same bytes
+  4. a proof made of fixed points serializes to the bytes pinned below, whatever
+     the target (little endian, lengths as u64)
+  5. differences() names what a reading build has differently
+tests/build_info.rs makes the same checks under cargo test.
+*/
+
+const COMPAT: &str = "compat/v0.1.0/addition-bls12-381";
+
+// A = G1, B = G2, C = 2·G1 and the public input 19, as to_bytes writes them
+const PINNED: &str = "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e\
+024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8\
+a572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e\
+0100000000000000\
+1300000000000000000000000000000000000000000000000000000000000000";
+
+fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing failed");
+    bytes
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn round_trip(dir: &Path) {
+    let mut rng = thread_rng();
+    let build = zk_circuits::build_info(Bls12_381::NAME);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut rng).expect("setup failed");
+    let circuit = AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).expect("proving failed");
+    let proof = Groth16Artifact::<Bls12_381> { proof, public_inputs: vec![z] };
+
+    let (vk_path, proof_path) = (dir.join("addition.vk"), dir.join("proof.bin"));
+    artifact::save_versioned::<AdditionCircuit, _>(&vk, &build, &vk_path).expect("Failed to save vk");
+    artifact::save(&proof, &build, &proof_path).expect("Failed to save proof");
+    let loaded_vk: VerifyingKey<Bls12_381> = artifact::load_versioned::<AdditionCircuit, _>(&vk_path).expect("the key loads");
+    let loaded: Groth16Artifact<Bls12_381> = artifact::load(&proof_path).expect("the proof loads");
+    assert!(loaded_vk == vk && loaded == proof);
+    assert!(Groth16::<Bls12_381>::verify(&loaded_vk, &loaded.public_inputs, &loaded.proof).expect("verification failed"));
+    assert_eq!(artifact::read_stamp(&proof_path).expect("the proof reads"), Some(build.clone()));
+    assert_eq!(artifact::read_stamp(&vk_path).expect("the key reads"), Some(build.clone()));
+    println!("1. ✓ a stamped key and proof load back equal and verify");
+    print!("{}", build.to_string().lines().map(|line| format!("     {line}\n")).collect::<String>());
+
+    let file = fs::read(&proof_path).expect("the proof reads");
+    let (_, rest) = artifact::split_stamp(&file).expect("the stamp reads");
+    assert_eq!(rest, proof.to_bytes().expect("serializing failed"));
+    let stamp = file.len() - rest.len();
+    let file = fs::read(&vk_path).expect("the key reads");
+    let (_, rest) = artifact::split_header(&file).expect("the header reads");
+    assert_eq!(rest, bytes(&vk));
+    println!("2. ✓ after the stamp, the same bytes as without it ({stamp} bytes of stamp)");
+}
+
+fn compat() {
+    let path = Path::new(COMPAT);
+    let vk_file = fs::read(path.join("addition.vk")).expect("Failed to read the compat vk");
+    let pk_file = fs::read(path.join("addition.pk")).expect("Failed to read the compat pk");
+    assert_eq!(artifact::read_stamp(path.join("addition.vk")).expect("the key reads"), None);
+    let vk: VerifyingKey<Bls12_381> = artifact::load(path.join("addition.vk")).expect("the 0.1.0 vk loads");
+    let pk: ProvingKey<Bls12_381> = artifact::load(path.join("addition.pk")).expect("the 0.1.0 pk loads");
+    assert_eq!(bytes(&vk), vk_file);
+    assert_eq!(bytes(&pk), pk_file);
+    println!("3. ✓ the unstamped 0.1.0 keys load, and serialize back to the {} + {} bytes of their files", vk_file.len(), pk_file.len());
+}
+
+fn pinned() {
+    let g1 = G1Projective::generator();
+    let proof = Proof::<Bls12_381> {
+        a: g1.into_affine(),
+        b: G2Projective::generator().into_affine(),
+        c: (g1 + g1).into_affine(),
+    };
+    let artifact = Groth16Artifact::<Bls12_381> { proof, public_inputs: vec![Fr::from(19u32)] };
+    assert_eq!(hex(&artifact.to_bytes().expect("serializing failed")), PINNED);
+    println!("4. ✓ the fixed proof serializes to the pinned {} bytes", PINNED.len() / 2);
+}
+
+fn differences() {
+    let here = zk_circuits::build_info(Bls12_381::NAME);
+    assert!(here.differences(&here).is_empty());
+    let mut there = here.clone();
+    there.target = "wasm32-unknown-unknown".to_string();
+    // chaos the other way round from here, it's on under --all-features
+    if let Some(i) = there.features.iter().position(|feature| feature == "chaos") {
+        there.features.remove(i);
+    } else {
+        there.features.push("chaos".to_string());
+    }
+    let differences = here.differences(&there);
+    assert_eq!(differences.len(), 2);
+    assert!(differences[0].starts_with("features") && differences[1].starts_with("target"));
+    println!("5. ✓ a build for wasm32 with chaos the other way round is told apart by:");
+    for difference in differences {
+        println!("     {difference}");
+    }
+}
+
+fn main() {
+    let dir = std::env::temp_dir().join(format!("zk-toy-build-info-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("Failed to create the temp dir");
+    round_trip(&dir);
+    let _ = fs::remove_dir_all(&dir);
+    compat();
+    pinned();
+    differences();
+    println!("\n✓ stamped artifacts say where they were made and are the same bytes everywhere");
+}
@@ -0,0 +1,75 @@
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
+use ark_snark::SNARK;
+use num_bigint::BigUint;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::bundle::{self, canonical};
+use zk_circuits::curves::Curve;
+use zk_core::bundle::Bundle;
+/*
+One statement, two verifiers: x + y = z proved with groth16 on BN254 (what an
+ethereum contract can check) and on BLS12-381 (everything else), in one bundle with
+z written down once as an integer (zk-core/src/bundle.rs, zk-circuits/src/bundle.rs).
+The cli does the same with zk-toy prove-bundle and verify-bundle.
+
+The part that needs care is that z is the same number for both. The scalar fields
+differ, BN254's r is smaller than BLS12-381's, so an input in between would be a
+different statement on each curve; those are refused instead of reduced.
+
+Everything is asserted.
+*/
+
+type Keys<E> = (ProvingKey<E>, VerifyingKey<E>);
+
+fn keys<E: Curve>() -> Keys<E> {
+    Groth16::<E>::circuit_specific_setup(AdditionCircuit::<E::ScalarField>::empty(), &mut thread_rng()).expect("setup failed")
+}
+
+fn add<E: Curve>(bundle: &mut Bundle, keys: &Keys<E>, x: u64, y: u64) {
+    let circuit = AdditionCircuit::new(E::ScalarField::from(x), E::ScalarField::from(y));
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    bundle::prove(bundle, &keys.0, circuit, &[z], &mut thread_rng()).expect("proving failed");
+}
+
+fn main() {
+    let (bls, bn) = (keys::<Bls12_381>(), keys::<Bn254>());
+    let (x, y) = (17u64, 2u64);
+
+    println!("=== A bundle of x + y = z on two curves ===");
+    let mut proofs = Bundle::new("addition", &[BigUint::from(x + y)]);
+    add(&mut proofs, &bn, x, y);
+    add(&mut proofs, &bls, x, y);
+    let proofs = Bundle::from_bytes(&proofs.to_bytes().expect("serializing failed")).expect("the bundle reads back");
+    assert!(bundle::verify(&proofs, &proofs.proofs[0], &bn.1).expect("a BN254 proof"));
+    assert!(bundle::verify(&proofs, &proofs.proofs[1], &bls.1).expect("a BLS12-381 proof"));
+    let sizes: Vec<String> = proofs.proofs.iter().map(|p| format!("{} {} bytes", p.curve, p.proof.len())).collect();
+    println!("✓ both proofs verify for z = {}: {}", proofs.public_inputs[0], sizes.join(", "));
+
+    assert!(matches!(bundle::verify(&proofs, &proofs.proofs[0], &bls.1), Err(bundle::BundleError::WrongCurve { .. })));
+    println!("✓ the BN254 proof isn't even tried against the BLS12-381 key");
+
+    let mut other = proofs.clone();
+    other.public_inputs = vec!["20".to_string()];
+    assert!(!bundle::verify(&other, &other.proofs[0], &bn.1).unwrap());
+    assert!(!bundle::verify(&other, &other.proofs[1], &bls.1).unwrap());
+    println!("✓ with z = 20 written in the bundle neither verifies");
+
+    println!("\n=== Inputs every curve has to read the same ===");
+    let bn_r: BigUint = ark_bn254::Fr::MODULUS.into();
+    let between = Bundle::new("addition", &[bn_r]);
+    assert!(between.inputs_for::<ark_bls12_381::Fr>().is_ok());
+    assert!(between.inputs_for::<ark_bn254::Fr>().is_err());
+    println!("✓ z = r of BN254 is fine on BLS12-381 and refused on BN254, where it would be 0");
+
+    let mut mismatched = Bundle::new("addition", &[BigUint::from(x + y + 1)]);
+    let circuit = AdditionCircuit::new(x.into(), y.into());
+    let z = circuit.z.unwrap();
+    assert!(bundle::prove(&mut mismatched, &bn.0, circuit, &[z], &mut thread_rng()).is_err());
+    assert!(mismatched.proofs.is_empty());
+    println!("✓ a proof of z = {} isn't added to a bundle that says {}", canonical(&[z])[0], mismatched.public_inputs[0]);
+
+    println!("\n✓ one statement, verified the same on both curves");
+}
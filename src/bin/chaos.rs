@@ -0,0 +1,113 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::Groth16;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::templates::RangeCircuit;
+use zk_gadgets::chaos::{self, CONSTRAINTS};
+use zk_gadgets::compare::{RangeChecks, enforce_bits, enforce_less_or_equal};
+use zk_gadgets::poseidon::{hash_var_padded, poseidon_config};
+/*
+What a missing constraint lets through (needs --features chaos, see
+zk-gadgets/src/chaos.rs). For every constraint the chaos mode can leave out there is
+a witness for something false: with the constraint in, the constraints refuse it;
+with it skipped, they are satisfied. Then one all the way through Groth16: a range
+proof for x = 99 in [10, 20] that verifies, because the keys were made for a range
+check that doesn't check.
+
+Here the skipping is scoped with chaos::skipping so the honest and the broken run
+sit side by side. To break everything else the same way, set the variable and run
+the checks that are meant to notice:
+
+  ZK_TOY_CHAOS=compare.bits cargo run --release --features chaos --bin boundaries
+  ZK_TOY_CHAOS=poseidon.padding cargo run --release --features chaos --bin poseidon_padded
+
+Everything is asserted.
+*/
+
+type Witness = fn() -> Result<bool, SynthesisError>;
+
+fn witnesses(cs: &ConstraintSystemRef<Fr>, values: &[u64]) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+    values.iter().map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v)))).collect()
+}
+
+// a false statement each, true when the constraints are satisfied anyway
+const FALSE_STATEMENTS: &[(&str, &str, Witness)] = &[
+    ("compare.bits", "300 fits in 8 bits", || {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = witnesses(&cs, &[300])?.remove(0);
+        enforce_bits(cs.clone(), &x, 8)?;
+        cs.is_satisfied()
+    }),
+    ("compare.less_or_equal", "5 <= 3", || {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let v = witnesses(&cs, &[5, 3])?;
+        enforce_less_or_equal(cs.clone(), &v[0], &v[1], 8)?;
+        cs.is_satisfied()
+    }),
+    ("compare.range_checks", "3 and 300 both fit in 8 bits, checked together", || {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut checks = RangeChecks::new(cs.clone());
+        for v in witnesses(&cs, &[3, 300])? {
+            checks.push(v, 8);
+        }
+        checks.enforce()?;
+        cs.is_satisfied()
+    }),
+    ("poseidon.length", "a list of 9 elements fits in 4 slots", || {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let padded = witnesses(&cs, &[1, 2, 3, 4])?;
+        let len = witnesses(&cs, &[9])?.remove(0);
+        let _ = hash_var_padded(cs.clone(), &poseidon_config(2), &padded, &len)?;
+        cs.is_satisfied()
+    }),
+    ("poseidon.padding", "[1, 2] padded to 4 slots is 1, 2, 0, 7", || {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let padded = witnesses(&cs, &[1, 2, 0, 7])?;
+        let len = witnesses(&cs, &[2])?.remove(0);
+        let _ = hash_var_padded(cs.clone(), &poseidon_config(2), &padded, &len)?;
+        cs.is_satisfied()
+    }),
+];
+
+// x = 99 claimed to be in [10, 20], which RangeCircuit::new would refuse to build
+fn out_of_range() -> RangeCircuit<Fr> {
+    RangeCircuit {
+        bits: 8,
+        lo: Some(Fr::from(10u32)),
+        hi: Some(Fr::from(20u32)),
+        x: Some(Fr::from(99u32)),
+    }
+}
+
+fn range_proof_verifies() -> bool {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(RangeCircuit::empty(8), &mut rng).expect("setup failed");
+    let proof = Groth16::<Bls12_381>::prove(&pk, out_of_range(), &mut rng).expect("proving failed");
+    Groth16::<Bls12_381>::verify(&vk, &[Fr::from(10u32), Fr::from(20u32)], &proof).unwrap_or(false)
+}
+
+fn main() -> Result<(), SynthesisError> {
+    println!("=== Each skippable constraint, and a false statement it stops ===");
+    assert_eq!(FALSE_STATEMENTS.len(), CONSTRAINTS.len(), "every constraint in chaos::CONSTRAINTS gets a false statement");
+    for ((name, what), (statement_of, statement, satisfied)) in CONSTRAINTS.iter().zip(FALSE_STATEMENTS) {
+        assert_eq!(name, statement_of);
+        assert!(!satisfied()?, "{name}: \"{statement}\" satisfies the constraints without any chaos");
+        assert!(chaos::skipping(&[name], satisfied)?, "{name}: skipping it should let \"{statement}\" through");
+        println!("✓ {name} ({what})");
+        println!("    in: \"{statement}\" is refused, skipped: it's satisfied");
+    }
+
+    println!("\n=== A Groth16 proof of x = 99 in [10, 20] ===");
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    out_of_range().generate_constraints(cs.clone())?;
+    assert!(!cs.is_satisfied()?);
+    // (proving an unsatisfied witness anyway trips a debug assertion in ark-groth16)
+    println!("✓ with enforce_bits intact the witness doesn't satisfy the circuit, there is nothing to prove");
+    assert!(chaos::skipping(&["compare.bits"], range_proof_verifies), "without compare.bits the false range should prove");
+    println!("✓ setup and proving without compare.bits: the proof verifies, for a statement that is false");
+    println!("\n  the verifier can't tell, the keys say whatever the circuit said when they were made");
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::curves::Curve;
+use zk_core::artifact::{self, ArtifactError};
+/*
+Replaying old artifacts. compat/ holds keys and proofs written by earlier versions of
+this crate, one directory per version. For every one of them we check two things:
+
+  1. the old proof still deserializes and verifies against the old verifying key,
+     so an arkworks update didn't silently change the serialization format
+  2. a fresh proof made by today's circuit code with the old proving key verifies
+     against the old verifying key, so the circuit still has the same shape
+
+Run it with no arguments to check everything (it exits non zero if something broke),
+tests/compat.rs does the same under cargo test.
+When a release changes things on purpose, add its artifacts with
+
+    cargo run --release --bin compat -- generate v0.2.0
+
+and keep the old directories around, that's the whole point.
+*/
+
+const COMPAT_DIR: &str = "compat";
+
+// the witness every compat proof is made with, so re-proving doesn't need extra files
+fn witness() -> (Fr, Fr) {
+    (Fr::from(17u32), Fr::from(2u32))
+}
+
+fn generate(version: &str) {
+    let mut rng = thread_rng();
+    let dir = Path::new(COMPAT_DIR).join(version).join("addition-bls12-381");
+    fs::create_dir_all(&dir).expect("Failed to create compat dir");
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut rng)
+        .expect("Failed to setup");
+    let (x, y) = witness();
+    let circuit = AdditionCircuit::new(x, y);
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).expect("Failed to generate proof");
+    let build = zk_circuits::build_info(Bls12_381::NAME);
+    artifact::save_versioned::<AdditionCircuit, _>(&pk, &build, dir.join("addition.pk")).expect("Failed to save pk");
+    artifact::save_versioned::<AdditionCircuit, _>(&vk, &build, dir.join("addition.vk")).expect("Failed to save vk");
+    artifact::save(&Groth16Artifact::<Bls12_381> { proof, public_inputs: vec![z] }, &build, dir.join("proof.bin")).expect("Failed to save proof");
+    println!("✓ Wrote compat artifacts to {}", dir.display());
+}
+
+// keys from 0.1.0 have no version header, everything later does
+fn load_key<T: CanonicalDeserialize>(path: PathBuf) -> Result<T, ArtifactError> {
+    match artifact::read_header(&path)? {
+        Some(_) => artifact::load_versioned::<AdditionCircuit, T>(path),
+        None => artifact::load(path),
+    }
+}
+
+fn check_case(dir: &Path) -> Result<(), String> {
+    let vk: VerifyingKey<Bls12_381> = load_key(dir.join("addition.vk")).map_err(|e| format!("vk: {e}"))?;
+    let pk: ProvingKey<Bls12_381> = load_key(dir.join("addition.pk")).map_err(|e| format!("pk: {e}"))?;
+    let old: Groth16Artifact<Bls12_381> = artifact::load(dir.join("proof.bin")).map_err(|e| format!("proof: {e}"))?;
+
+    // 1. the old proof
+    let ok = Groth16::<Bls12_381>::verify(&vk, &old.public_inputs, &old.proof).map_err(|e| e.to_string())?;
+    if !ok {
+        return Err("the stored proof no longer verifies".into());
+    }
+
+    // 2. a new proof from the old proving key
+    let mut rng = thread_rng();
+    let (x, y) = witness();
+    let circuit = AdditionCircuit::new(x, y);
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).map_err(|e| e.to_string())?;
+    let ok = Groth16::<Bls12_381>::verify(&vk, &[z], &proof).map_err(|e| e.to_string())?;
+    if !ok {
+        return Err("a proof from today's circuit doesn't verify with the old keys, the circuit shape changed".into());
+    }
+    Ok(())
+}
+
+fn check() {
+    let mut cases: Vec<PathBuf> = Vec::new();
+    for version in fs::read_dir(COMPAT_DIR).expect("run this from the repository root") {
+        let version = version.expect("Failed to read compat dir").path();
+        // skip compat/README.md and friends
+        if !version.is_dir() {
+            continue;
+        }
+        for case in fs::read_dir(&version).expect("Failed to read version dir") {
+            cases.push(case.expect("Failed to read case dir").path());
+        }
+    }
+    cases.sort();
+
+    let mut failures = 0;
+    for case in &cases {
+        match check_case(case) {
+            Ok(()) => println!("✓ {}", case.display()),
+            Err(e) => {
+                println!("✗ {}: {e}", case.display());
+                failures += 1;
+            }
+        }
+    }
+    println!("\n{} of {} compat cases ok", cases.len() - failures, cases.len());
+    if failures > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => check(),
+        [cmd, version] if cmd == "generate" => generate(version),
+        _ => {
+            eprintln!("usage: compat [generate <version>]");
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, Proof};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use rand::thread_rng;
+use zk_circuits::airdrop::{self, AirdropCircuit};
+use zk_circuits::compressed::{self, Compressed};
+use zk_circuits::evm;
+use zk_circuits::examples;
+use zk_circuits::expr::{ExprCircuit, Program};
+use zk_circuits::solidity;
+use zk_gadgets::hasher::{PoseidonHasher, Sha256Hasher};
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+/*
+The same statements with all their public inputs, and compressed into one: a hash
+of them (zk-circuits/src/compressed.rs, Compressed wraps the circuits unchanged).
+Two statements on BN254, the curve with precompiles:
+
+  airdrop   the private airdrop claim, 4 public inputs (root, nullifier,
+            recipient, airdrop id)
+  squares   square roots of 32 public numbers, an expr statement standing in for
+            anything with a lot of public inputs
+
+For each layout: the constraints, the public inputs and an estimate of the verifier
+contract's gas (solidity.rs). The compressed verifier still gets the inputs in its
+calldata, it has to hash them, so what it saves is the ecMul and ecAdd per input and
+what it pays is the hash: cheap on chain for SHA-256 (a precompile) and expensive in
+the circuit, the other way around for Poseidon, which has no precompile and whose
+cost in solidity (tens of thousands of gas for a few inputs) isn't counted here.
+
+Every layout is proven and verified, and a proof is refused for another value of
+one of the original inputs, except SHA-256 over the 32 squares, which is only
+counted: it's 700 thousand constraints.
+*/
+
+const SQUARES: usize = 32;
+
+fn constraints<C: ConstraintSynthesizer<Fr> + Clone>(circuit: C) -> usize {
+    examples::count_constraints(&circuit).expect("synthesis failed")
+}
+
+// proves, checks the proof for the inputs and not for the wrong ones
+fn prove<C: ConstraintSynthesizer<Fr> + Clone>(empty: C, circuit: C, inputs: &[Fr], wrong: &[Fr]) -> Proof<Bn254> {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(empty, &mut rng).expect("setup failed");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).expect("proving failed");
+    assert!(Groth16::<Bn254>::verify(&vk, inputs, &proof).expect("the input count is right"));
+    assert!(!Groth16::<Bn254>::verify(&vk, wrong, &proof).expect("the input count is right"));
+    proof
+}
+
+fn row(layout: &str, inputs: usize, constraints: usize, gas: u64, note: &str) {
+    println!("{}", format!("  {layout:<10} {inputs:>6} {constraints:>11} {gas:>9}  {note}").trim_end());
+}
+
+// the compressed verifier's gas: its single input, the original values in the calldata
+// and, for SHA-256, the precompile hashing them
+fn compressed_gas(proof: &Proof<Bn254>, digest: Fr, inputs: &[Fr], sha256: bool) -> u64 {
+    let values = evm::words_to_bytes(&evm::encode_public_inputs(inputs));
+    let hash = if sha256 { solidity::sha256_gas(values.len()) } else { 0 };
+    solidity::verification_gas(proof, &[digest]) + solidity::calldata_gas(&values) + hash
+}
+
+fn compare<C: ConstraintSynthesizer<Fr> + Clone>(name: &str, empty: C, circuit: C, inputs: &[Fr], prove_sha: bool) {
+    let mut wrong = inputs.to_vec();
+    wrong[0] += Fr::from(1u32);
+    println!("\n=== {name}, {} public inputs ===", inputs.len());
+    println!("  {:<10} {:>6} {:>11} {:>9}", "layout", "inputs", "constraints", "gas");
+    let proof = prove(empty.clone(), circuit.clone(), inputs, &wrong);
+    let original = solidity::verification_gas(&proof, inputs);
+    row("original", inputs.len(), constraints(empty.clone()), original, "");
+
+    let poseidon = PoseidonHasher::<Fr>::new(2);
+    let digest = compressed::compress(&poseidon, inputs);
+    let proof = prove(
+        Compressed::new(empty.clone(), poseidon.clone()),
+        Compressed::new(circuit.clone(), poseidon.clone()),
+        &[digest],
+        &[compressed::compress(&poseidon, &wrong)],
+    );
+    let gas = compressed_gas(&proof, digest, inputs, false);
+    row("poseidon", 1, constraints(Compressed::new(empty.clone(), poseidon)), gas, "+ poseidon in solidity");
+    assert!(gas < original, "one input should cost less than {}", inputs.len());
+
+    let sha = Sha256Hasher;
+    let digest = compressed::compress(&sha, inputs);
+    let proof = if prove_sha {
+        prove(
+            Compressed::new(empty.clone(), sha.clone()),
+            Compressed::new(circuit, sha.clone()),
+            &[digest],
+            &[compressed::compress(&sha, &wrong)],
+        )
+    } else {
+        // not proven: the poseidon proof has the same shape and costs the same calldata
+        proof
+    };
+    let gas = compressed_gas(&proof, digest, inputs, true);
+    let note = if prove_sha { "with the sha256 precompile" } else { "with the sha256 precompile, counted but not proven" };
+    row("sha256", 1, constraints(Compressed::new(empty, sha)), gas, note);
+    println!("✓ every layout proves the statement and refuses another input");
+}
+
+fn airdrop() {
+    let mut rng = thread_rng();
+    let hasher = PoseidonHasher::<Fr>::new(2);
+    let shape = TreeShape::binary(4);
+    let members: Vec<(Fr, Fr)> = (0..shape.leaves()).map(|_| (Fr::rand(&mut rng), Fr::rand(&mut rng))).collect();
+    let leaves: Vec<Fr> = members.iter().map(|(address, secret)| airdrop::leaf(&hasher, *address, *secret)).collect();
+    let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+    let (index, airdrop_id, recipient) = (5, Fr::from(2026u32), Fr::rand(&mut rng));
+    let (address, secret) = members[index];
+    let circuit = AirdropCircuit::new(hasher.clone(), airdrop_id, address, secret, tree.path(index as u64), recipient);
+    let inputs = airdrop::public_inputs(tree.root(), airdrop::nullifier(&hasher, secret, airdrop_id), recipient, airdrop_id);
+    compare("airdrop", AirdropCircuit::empty(hasher, shape), circuit, &inputs, true);
+}
+
+fn squares() {
+    let names = |prefix: &str| (0..SQUARES).map(|i| format!("{prefix}{i}")).collect::<Vec<_>>().join(", ");
+    let equations: String = (0..SQUARES).map(|i| format!("r{i} * r{i} == s{i}\n")).collect();
+    let text = format!("public {}\nsecret {}\n{equations}", names("s"), names("r"));
+    let program = Program::<Fr>::parse(&text).expect("the statement parses");
+    let roots: Vec<(String, Fr)> = (0..SQUARES).map(|i| (format!("r{i}"), Fr::from(i as u64 + 2))).collect();
+    let values = program.solve(roots.iter().map(|(name, x)| (name.as_str(), *x))).expect("the squares follow from the roots");
+    let inputs = program.public_inputs(&values);
+    let circuit = ExprCircuit::new(program.clone(), values).expect("the values satisfy the statement");
+    compare("squares", ExprCircuit::empty(program), circuit, &inputs, false);
+}
+
+fn main() {
+    airdrop();
+    squares();
+    println!("\n✓ one public input instead of many, for the price of a hash in the circuit");
+}
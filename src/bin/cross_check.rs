@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::snarkjs;
+/*
+Cross-implementation check: make an addition proof on BN254, export it in the snarkjs
+format and ask snarkjs if it agrees. It has to accept the real proof and reject the
+same proof with a wrong public input. If our encoding or our Groth16 ever drifts from
+the standard, this is where it shows up.
+
+This needs node, so it's behind the cross-check feature and not built by default:
+
+    cargo run --features cross-check --bin cross_check [fixture dir]
+
+The snarkjs version is pinned in zk-circuits/src/snarkjs.rs. Set SNARKJS to a snarkjs
+binary to use that instead of npx (for example when you're offline). --export-only
+just writes the fixtures. tests/snarkjs.rs is the same check as an ignored test.
+*/
+
+fn snarkjs_verify(dir: &Path) -> bool {
+    snarkjs::snarkjs_verify(dir).expect("could not run snarkjs, is node installed?")
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let export_only = args.iter().any(|a| a == "--export-only");
+    let dir = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target/cross-check"));
+
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng)
+        .expect("Failed to setup");
+    let circuit = AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).expect("Failed to generate proof");
+    assert!(Groth16::<Bn254>::verify(&vk, &[z], &proof).expect("Failed to verify"));
+
+    let good = dir.join("valid");
+    let bad = dir.join("wrong-input");
+    snarkjs::export_fixture(&good, &vk, &proof, &[z]).expect("Failed to export fixture");
+    snarkjs::export_fixture(&bad, &vk, &proof, &[z + Fr::from(1u32)]).expect("Failed to export fixture");
+    println!("✓ Fixtures written to {}", dir.display());
+    if export_only {
+        return;
+    }
+
+    let accepted = snarkjs_verify(&good);
+    let rejected = !snarkjs_verify(&bad);
+    if accepted {
+        println!("✓ snarkjs accepts the proof");
+    }
+    if rejected {
+        println!("✓ snarkjs rejects the proof with a wrong public input");
+    }
+    if !(accepted && rejected) {
+        eprintln!("cross check failed: arkworks and snarkjs disagree");
+        std::process::exit(1);
+    }
+}
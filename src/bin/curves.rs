@@ -0,0 +1,128 @@
+use ark_ec::AffineRepr;
+use ark_serialize::CanonicalSerialize;
+use rand::thread_rng;
+use zk_circuits::curves::Curve;
+use zk_circuits::examples::{self, ExampleError};
+use zk_core::report::{self, RunReport};
+use zk_gadgets::merkle::TreeShape;
+/*
+Which curve? The same statement, a Poseidon merkle membership proof, proven with
+groth16 on every curve this build has: BLS12-381 always, BN254 with the evm feature
+and BLS12-377 with the bls feature. Printed side by side: the rough security level,
+the field sizes, the point sizes, proof and key sizes and the timings.
+The curve abstraction is zk-circuits/src/curves.rs, the flow examples::curves::run.
+
+  --depth N            depth of the binary tree, i.e. how big the circuit is (default 8)
+  --min-security BITS  skip the curves estimated below BITS, e.g. 120
+  --json               one run report per line
+
+In short: BN254 if it has to verify on ethereum, BLS12-381 otherwise, BLS12-377 if
+the proof gets verified inside another proof.
+*/
+
+struct Row {
+    name: &'static str,
+    security: u32,
+    notes: &'static str,
+    scalar_bits: u32,
+    base_bits: u32,
+    g1_bytes: usize,
+    g2_bytes: usize,
+    report: RunReport,
+}
+
+fn row<E: Curve>(depth: usize) -> Result<Row, ExampleError> {
+    Ok(Row {
+        name: E::NAME,
+        security: E::SECURITY_BITS,
+        notes: E::NOTES,
+        scalar_bits: E::scalar_bits(),
+        base_bits: E::base_bits(),
+        g1_bytes: E::G1Affine::generator().compressed_size(),
+        g2_bytes: E::G2Affine::generator().compressed_size(),
+        report: examples::curves::run::<E, _>(&mut thread_rng(), TreeShape::binary(depth))?,
+    })
+}
+
+fn arg(name: &str) -> Option<usize> {
+    let args: Vec<String> = std::env::args().collect();
+    let i = args.iter().position(|a| a == name)?;
+    match args.get(i + 1).and_then(|v| v.parse().ok()) {
+        Some(value) => Some(value),
+        None => panic!("{name} wants a number"),
+    }
+}
+
+// (name, estimated bits, run) for every curve in this build
+type Runner = fn(usize) -> Result<Row, ExampleError>;
+
+fn curves() -> Vec<(&'static str, u32, Runner)> {
+    #[allow(unused_mut)] // only pushed to with the evm or bls feature
+    let mut curves: Vec<(&'static str, u32, Runner)> = vec![(
+        ark_bls12_381::Bls12_381::NAME,
+        ark_bls12_381::Bls12_381::SECURITY_BITS,
+        row::<ark_bls12_381::Bls12_381>,
+    )];
+    #[cfg(feature = "evm")]
+    curves.push((ark_bn254::Bn254::NAME, ark_bn254::Bn254::SECURITY_BITS, row::<ark_bn254::Bn254>));
+    #[cfg(feature = "bls")]
+    curves.push((
+        ark_bls12_377::Bls12_377::NAME,
+        ark_bls12_377::Bls12_377::SECURITY_BITS,
+        row::<ark_bls12_377::Bls12_377>,
+    ));
+    curves
+}
+
+fn main() -> Result<(), ExampleError> {
+    let depth = arg("--depth").unwrap_or(8);
+    let min_security = arg("--min-security").unwrap_or(0) as u32;
+    let mut rows = Vec::new();
+    for (name, security, run) in curves() {
+        if security < min_security {
+            if !report::json_requested() {
+                println!("  skipping {name}, estimated at ~{security} bits");
+            }
+            continue;
+        }
+        rows.push(run(depth)?);
+    }
+    if report::json_requested() {
+        for r in &rows {
+            println!("{}", r.report.to_json());
+        }
+        return Ok(());
+    }
+
+    println!("=== POSEIDON MEMBERSHIP, BINARY TREE OF DEPTH {depth}, ON EVERY CURVE ===");
+    println!(
+        "  {:<10} {:>8} {:>6} {:>6} {:>4} {:>4} {:>11} {:>10} {:>10} {:>10} {:>6} {:>10} {:>5}  ok",
+        "curve", "security", "r bits", "q bits", "G1", "G2", "constraints", "setup", "prove", "verify", "proof", "pk", "vk"
+    );
+    for row in &rows {
+        let r = &row.report;
+        println!(
+            "  {:<10} {:>8} {:>6} {:>6} {:>4} {:>4} {:>11} {:>10.2?} {:>10.2?} {:>10.2?} {:>6} {:>10} {:>5}  {}",
+            row.name,
+            format!("~{}", row.security),
+            row.scalar_bits,
+            row.base_bits,
+            row.g1_bytes,
+            row.g2_bytes,
+            r.constraints,
+            r.setup,
+            r.prove,
+            r.verify,
+            r.proof_bytes,
+            r.proving_key_bytes,
+            r.verifying_key_bytes,
+            if r.passed() { "✓" } else { "✗" }
+        );
+    }
+    println!("\n  security is a rough estimate in bits, G1 / G2 / proof / keys are compressed bytes");
+    for row in &rows {
+        println!("  {:<10} {}", row.name, row.notes);
+    }
+    assert!(rows.iter().all(|row| row.report.passed()), "some curve did not verify or accepted a wrong root");
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use rand::thread_rng;
+use zk_circuits::examples;
+use zk_core::report;
+/*
+A distributed key generation between 5 parties with threshold 3, built on the VSS
+circuit. Two parties misbehave:
+  - party 3 is a cheating dealer, it hands party 1 a share that is off by one
+  - party 4 is a troublemaker, it complains about party 2 even though party 2 is honest
+The complaint round sorts this out with zero knowledge proofs: party 3 can't prove
+its share and gets kicked out, party 2 proves its share and stays in.
+See crates/zk-circuits/src/dkg.rs for how the pieces fit together, the rounds
+themselves are in examples::dkg::run.
+*/
+
+fn main() -> Result<(), examples::ExampleError> {
+    let mut rng = thread_rng();
+    let json = report::json_requested();
+    let report = examples::dkg::run(&mut rng, json)?;
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        println!("  one share proof: {}", report.summary());
+    }
+    Ok(())
+}
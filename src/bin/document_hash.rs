@@ -0,0 +1,51 @@
+use rand::thread_rng;
+use zk_circuits::examples;
+use zk_core::report;
+/*
+In this example the secret is a whole document. Someone published a commitment to it
+(a single field element), and now we want to convince a verifier that the document
+behind the commitment was issued on a certain date, without showing anything else.
+
+The document has a fixed layout, so the date always sits at the same offset.
+The commitment is Poseidon(pack(blake2s(document))), see crates/zk-circuits/src/document.rs,
+and the flow itself is examples::document::run.
+Most of the constraints are blake2s: it works on bits, so it is way more expensive in a circuit
+than Poseidon, which is why we only use it once and let Poseidon do the final compression.
+*/
+
+// a toy certificate, padded to 64 bytes so it is exactly one blake2s block
+const DOCUMENT: &[u8; 64] = examples::document::SAMPLE_DOCUMENT;
+
+fn main() {
+    let mut rng = thread_rng();
+    // the date always starts at byte 53 and is 10 bytes long
+    let layout = examples::document::sample_layout();
+    let date = &DOCUMENT[layout.field_offset..layout.field_offset + layout.field_len];
+
+    let report = examples::document::run(&mut rng, DOCUMENT, layout).expect("Failed to run the document example");
+    if report::json_requested() {
+        println!("{}", report.to_json());
+        return;
+    }
+    println!("Secret document: {}", String::from_utf8_lossy(DOCUMENT));
+    println!("Public commitment: {}", report.public_inputs[0]);
+    println!("Public issue date: {}", String::from_utf8_lossy(date));
+    println!("Constraints: {} (almost all of them are blake2s)", report.constraints);
+    println!("✓ Setup, proof and verification done");
+    println!("  {}\n", report.summary());
+
+    println!("=== VERIFIER ===");
+    if report.verified {
+        println!("  The proof is valid.");
+        println!("  The committed document was issued on {}", String::from_utf8_lossy(date));
+        println!("  and the verifier never saw who it was issued to.\n");
+    } else {
+        println!("Proof is invalid, you messed up, or you lyin' ");
+    }
+
+    // claiming a different date with the same proof should not work
+    println!("=== TESTING WITH A DIFFERENT DATE ===");
+    if report.rejects_wrong_statement {
+        println!("Correctly rejected proof with the wrong issue date");
+    }
+}
@@ -0,0 +1,142 @@
+use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{One, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::verifier::verify_constant_work;
+use zk_core::artifact;
+/*
+The edge cases naive verifier integrations trip over, each one checked and printed
+with what the verifier does about it:
+
+  identity points    the point at infinity is a perfectly good group element, so it
+                     deserializes fine. A proof with A, B or C (or all) at infinity
+                     has to be refused by the pairing check, and is
+  small order points BLS12-381's G1 is prime order, but the curve around it has points
+                     of order 3 (the cofactor is 3 * 11^2 * ...). They are refused
+                     at deserialization, the subgroup check sees them
+  zero inputs        a statement with public input 0 is an honest statement, it
+                     proves and verifies, and the proof isn't good for 1
+  wrong input count  Groth16::verify errors before doing any work, the constant
+                     work wrapper says false
+  malleability       (-A, -B, C) verifies whenever (A, B, C) does, e(-A, -B) = e(A, B).
+                     Groth16 proofs are not unique, never use the proof bytes as a
+                     nullifier or a "this was already submitted" key
+
+Everything is asserted, the binary panics if the verifier ever does the wrong thing.
+tests/edge_cases.rs makes the same checks under cargo test.
+*/
+
+// both verifiers, they have to agree on everything but the error
+fn verdict(pvk: &PreparedVerifyingKey<Bls12_381>, proof: &Proof<Bls12_381>, inputs: &[Fr]) -> bool {
+    let plain = Groth16::<Bls12_381>::verify_with_processed_vk(pvk, inputs, proof).unwrap_or(false);
+    assert_eq!(plain, verify_constant_work(pvk, inputs, proof), "the two verifiers disagree");
+    plain
+}
+
+// an order 3 point: a point of the whole curve with everything but the 3 part of its
+// order multiplied away. The cofactor is 3 * 11^2 * 10177^2 * 859267^2 * 52437899^2
+fn order_three_point() -> G1Affine {
+    let mut x = Fq::zero();
+    loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false) {
+            let mut q: G1Projective = p.mul_bigint(Fr::MODULUS);
+            for factor in [121u64, 10177 * 10177, 859267 * 859267, 52437899 * 52437899] {
+                q = q.into_affine().mul_bigint([factor]);
+            }
+            if !q.is_zero() {
+                return q.into_affine();
+            }
+        }
+        x += Fq::one();
+    }
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng)
+        .expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let z = Fr::from(19u32);
+    let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng)
+        .expect("Failed to generate proof");
+    assert!(verdict(&pvk, &proof, &[z]));
+    println!("✓ The honest proof for z = 19 verifies\n");
+
+    println!("=== IDENTITY POINTS ===");
+    let zero_g1 = G1Affine::identity();
+    let zero_g2 = ark_bls12_381::G2Affine::identity();
+    for mask in 1..8u8 {
+        let mut forged = proof.clone();
+        let mut names = Vec::new();
+        if mask & 1 != 0 {
+            forged.a = zero_g1;
+            names.push("A");
+        }
+        if mask & 2 != 0 {
+            forged.b = zero_g2;
+            names.push("B");
+        }
+        if mask & 4 != 0 {
+            forged.c = zero_g1;
+            names.push("C");
+        }
+        let mut bytes = Vec::new();
+        forged.serialize_compressed(&mut bytes).expect("serializing into a vec");
+        let decoded: Proof<Bls12_381> = artifact::deserialize_checked(&bytes).expect("the identity is in the subgroup");
+        assert!(!verdict(&pvk, &decoded, &[z]) && !verdict(&pvk, &decoded, &[Fr::zero()]));
+        println!("✓ {} at infinity: deserializes, and is refused for z = 19 and z = 0", names.join(", "));
+    }
+    println!();
+
+    println!("=== SMALL ORDER POINTS ===");
+    let tiny = order_three_point();
+    assert!(tiny.is_on_curve() && !tiny.is_in_correct_subgroup_assuming_on_curve());
+    assert!(tiny.mul_bigint([3u64]).is_zero());
+    let forged = Proof { a: tiny, ..proof.clone() };
+    let mut bytes = Vec::new();
+    forged.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    assert!(artifact::deserialize_checked::<Proof<Bls12_381>>(&bytes).is_err());
+    println!("✓ A = a point of order 3: refused when the proof is loaded");
+    // what happens when somebody skips that check: the pairing check still says no here,
+    // but nothing promises it will for a cleverer point, so don't skip it
+    let unchecked: Proof<Bls12_381> = artifact::deserialize_unchecked(&bytes).expect("on the curve, so it decodes");
+    assert!(!verdict(&pvk, &unchecked, &[z]));
+    println!("  loaded unchecked it still fails the pairing check, which is luck, not design\n");
+
+    println!("=== ZERO AS A PUBLIC INPUT ===");
+    let zero_proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::zero(), Fr::zero()), &mut rng)
+        .expect("Failed to generate proof");
+    assert!(verdict(&pvk, &zero_proof, &[Fr::zero()]));
+    assert!(!verdict(&pvk, &zero_proof, &[Fr::one()]) && !verdict(&pvk, &proof, &[Fr::zero()]));
+    println!("✓ 0 + 0 = 0 proves and verifies, not for z = 1, and the z = 19 proof isn't good for 0");
+    // vk_x = IC0 when every input is zero, the input terms vanish but the pairing check stays
+    let none = Proof {
+        a: zero_g1,
+        b: zero_g2,
+        c: vk.gamma_abc_g1[0],
+    };
+    assert!(!verdict(&pvk, &none, &[Fr::zero()]));
+    println!("✓ and a proof built from the key's own IC0 for z = 0 is refused\n");
+
+    println!("=== WRONG NUMBER OF INPUTS ===");
+    assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &[], &proof).is_err());
+    assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &[z, z], &proof).is_err());
+    assert!(!verify_constant_work(&pvk, &[], &proof) && !verify_constant_work(&pvk, &[z, z], &proof));
+    println!("✓ none or two inputs: Groth16::verify errors, verify_constant_work says false\n");
+
+    println!("=== MALLEABILITY ===");
+    let negated = Proof {
+        a: (-proof.a.into_group()).into_affine(),
+        b: (-proof.b.into_group()).into_affine(),
+        c: proof.c,
+    };
+    assert!(negated != proof && verdict(&pvk, &negated, &[z]));
+    println!("✓ (-A, -B, C) is a different proof of the same statement and verifies too");
+    let rerandomized = Groth16::<Bls12_381>::rerandomize_proof(&vk, &proof, &mut rng);
+    assert!(rerandomized != proof && verdict(&pvk, &rerandomized, &[z]));
+    println!("✓ so does a rerandomized one: deduplicate on the statement or a nullifier, never on the proof");
+}
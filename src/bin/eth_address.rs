@@ -0,0 +1,72 @@
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, prepare_verifying_key};
+use ark_r1cs_std::R1CSVar;
+use ark_r1cs_std::bits::uint8::UInt8;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
+use ark_snark::SNARK;
+use rand::{Rng, thread_rng};
+use zk_circuits::eth_address::{self, AddressCircuit, PublicKey};
+use zk_gadgets::keccak;
+/*
+Keccak-256 in a circuit, and the ethereum address of a secret public key.
+
+The keccak gadget (zk-gadgets/src/keccak.rs) is checked against the sha3 crate first,
+at the lengths where the padding changes: empty, one byte, 135 bytes (the 0x01 and the
+0x80 of the padding share a byte), 136 (a whole extra block of padding) and two blocks.
+Then the address circuit from zk-circuits/src/eth_address.rs is proved on BN254 for
+the public key of private key 1, whose address every ethereum library has in its tests.
+*/
+
+// the secp256k1 generator, which is the public key of private key 1
+const PUBLIC_KEY_OF_ONE: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                                 483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+const ADDRESS_OF_ONE: &str = "7e5f4552091a69125d5dfcb7b8c2659029395bdf";
+
+fn unhex(text: &str) -> Vec<u8> {
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).expect("hex")).collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn main() {
+    let mut rng = thread_rng();
+
+    println!("=== THE KECCAK GADGET AGAINST THE SHA3 CRATE ===");
+    assert_eq!(hex(&keccak::keccak256(b"")), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+    for len in [0, 1, 64, 135, 136, 200] {
+        let bytes: Vec<u8> = (0..len).map(|_| rng.r#gen()).collect();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let vars = UInt8::new_witness_vec(cs.clone(), &bytes).expect("Failed to allocate");
+        let digest = keccak::keccak256_var(&vars).expect("Failed to synthesize");
+        let value: Vec<u8> = digest.iter().map(|b| b.value().expect("a value")).collect();
+        assert_eq!(value, keccak::keccak256(&bytes), "{len} bytes hash differently in the circuit");
+        assert!(cs.is_satisfied().expect("Failed to check the constraints"));
+        println!("✓ {len:>3} bytes: same digest, {} constraints", cs.num_constraints());
+    }
+    println!();
+
+    println!("=== AN ETHEREUM ADDRESS ===");
+    let public_key: PublicKey = unhex(PUBLIC_KEY_OF_ONE).try_into().expect("64 bytes");
+    let address = eth_address::address_of(&public_key);
+    assert_eq!(hex(&address), ADDRESS_OF_ONE);
+    println!("✓ the public key of private key 1 has address 0x{}", hex(&address));
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    AddressCircuit::<Fr>::empty().generate_constraints(cs.clone()).expect("Failed to synthesize");
+    println!("  the address circuit has {} constraints", cs.num_constraints());
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(AddressCircuit::<Fr>::empty(), &mut rng).expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let proof = Groth16::<Bn254>::prove(&pk, AddressCircuit::new(public_key), &mut rng).expect("Failed to generate proof");
+    let statement = [eth_address::address_value::<Fr>(&address)];
+    assert!(Groth16::<Bn254>::verify_with_processed_vk(&pvk, &statement, &proof).expect("one input"));
+    println!("✓ proved on BN254: I know the public key behind 0x{}", hex(&address));
+
+    let mut other = address;
+    other[19] ^= 1;
+    let statement = [eth_address::address_value::<Fr>(&other)];
+    assert!(!Groth16::<Bn254>::verify_with_processed_vk(&pvk, &statement, &proof).expect("one input"));
+    println!("✓ and the proof is refused for 0x{}", hex(&other));
+}
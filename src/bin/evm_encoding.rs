@@ -0,0 +1,92 @@
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::evm::{self, EvmEncodingError, Word};
+use zk_circuits::examples;
+use zk_core::report;
+/*
+"My proof verifies in rust but the contract says no." Nine times out of ten the
+proof is fine and the encoding is off. This example makes an addition proof on BN254
+(the curve ethereum has precompiles for), encodes it the way the solidity verifier
+wants it, decodes it again and checks everything survives the trip, then shows the
+encodings that a careful decoder has to refuse. See crates/zk-circuits/src/evm.rs
+(examples::evm::run is the same round trip without the printing).
+*/
+
+fn main() {
+    let mut rng = thread_rng();
+    if report::json_requested() {
+        let report = examples::evm::run(&mut rng, Fr::from(17u32), Fr::from(2u32)).expect("Failed to run the evm example");
+        println!("{}", report.to_json());
+        return;
+    }
+
+    // an addition proof, on BN254 this time
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng)
+        .expect("Failed to setup");
+    let circuit = AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+    let z = circuit.z.expect("z is set by AdditionCircuit::new");
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).expect("Failed to generate proof");
+    println!("✓ BN254 proof generated for public z = {}\n", z);
+
+    println!("=== CALLDATA FOR verifyProof(a, b, c, input) ===");
+    let calldata = evm::encode_proof_calldata(&proof, &[z]);
+    let names = ["a[0]", "a[1]", "b[0][0]", "b[0][1]", "b[1][0]", "b[1][1]", "c[0]", "c[1]", "input[0]"];
+    for (name, word) in names.iter().zip(&calldata) {
+        println!("  {:9} {}", name, evm::to_hex_word(word));
+    }
+
+    // round trip: bytes -> words -> proof, and the proof still verifies
+    let bytes = evm::words_to_bytes(&calldata);
+    let (decoded, inputs) = evm::decode_proof_calldata(&evm::bytes_to_words(&bytes).expect("whole words"))
+        .expect("our own encoding decodes");
+    assert_eq!(decoded, proof);
+    assert_eq!(inputs, vec![z]);
+    assert!(Groth16::<Bn254>::verify(&vk, &inputs, &decoded).expect("Failed to verify"));
+    println!("\n✓ Calldata decodes back to the same proof and it verifies");
+
+    // the edges of the scalar field and the identity points round trip too
+    for x in [Fr::from(0u32), Fr::from(1u32), -Fr::from(1u32)] {
+        assert_eq!(evm::decode_field::<Fr>(&evm::encode_field(&x), "x"), Ok(x));
+    }
+    assert_eq!(evm::decode_g1(&evm::encode_g1(&G1Affine::identity())), Ok(G1Affine::identity()));
+    assert_eq!(evm::decode_g2(&evm::encode_g2(&G2Affine::generator())), Ok(G2Affine::generator()));
+    println!("✓ 0, 1, r - 1, the G1 identity and the G2 generator round trip");
+
+    let hashed = evm::hash_public_inputs(&[z]);
+    println!("✓ sha256 public input hash (as in solidity, reduced mod r): {}", evm::to_hex_word(&evm::encode_field(&hashed)));
+    let pairing_input = evm::encode_pairing_input(&[(proof.a, proof.b), (G1Affine::generator(), G2Affine::generator())]);
+    println!("✓ EIP-197 pairing precompile input for 2 pairs: {} bytes\n", pairing_input.len());
+
+    println!("=== THINGS THE DECODER HAS TO REFUSE ===");
+    // the modulus itself, solidity verifiers check input < r
+    let mut modulus: Word = [0u8; 32];
+    modulus.copy_from_slice(&Fr::MODULUS.to_bytes_be());
+    let result = evm::decode_field::<Fr>(&modulus, "public input");
+    assert_eq!(result, Err(EvmEncodingError::NotCanonical("public input")));
+    println!("  public input = r:            {}", result.unwrap_err());
+
+    // little-endian by mistake (what arkworks would hand you)
+    let mut little_endian = evm::encode_field(&z);
+    little_endian.reverse();
+    let decoded_le = evm::decode_field::<Fr>(&little_endian, "public input");
+    println!("  z written little-endian:     decodes to {:?}, not z", decoded_le.map(|x| x.to_string()));
+
+    // G2 coefficients in arkworks order (real part first) instead of EIP-197 order
+    let b = evm::encode_g2(&proof.b);
+    let swapped = [b[1], b[0], b[3], b[2]];
+    let result = evm::decode_g2(&swapped);
+    assert!(result.is_err());
+    println!("  G2 with c0 and c1 swapped:   {}", result.unwrap_err());
+
+    // a G1 point that is off the curve
+    let mut a = evm::encode_g1(&proof.a);
+    a[1][31] ^= 1;
+    let result = evm::decode_g1(&a);
+    assert_eq!(result, Err(EvmEncodingError::NotOnCurve));
+    println!("  G1 with a flipped bit:       {}", result.unwrap_err());
+}
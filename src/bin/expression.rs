@@ -0,0 +1,93 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, prepare_verifying_key};
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::examples::count_constraints;
+use zk_circuits::expr::{ExprCircuit, Program};
+/*
+Statements written as text and compiled to R1CS at runtime, with the parser and the
+synthesizer in zk-circuits/src/expr.rs (zk-toy expr is the same from the command line).
+
+For a few statements: the constraint count, a proof for values that make it hold, the
+same proof against other public values, and values the circuit refuses to be built
+from. Then the solver deriving the values that follow from the others, and what it
+can't derive, and the statements the parser refuses, with its error messages.
+*/
+
+fn prove(text: &str, values: &[(&str, u64)], other_public: &[(&str, u64)]) {
+    let mut rng = thread_rng();
+    let program = Program::<Fr>::parse(text).expect("the statement parses");
+    // what Display prints parses back to the same statement (the fingerprint the cli names keys by)
+    let printed = Program::<Fr>::parse(&program.to_string()).expect("printed statements parse");
+    assert_eq!(printed.fingerprint(), program.fingerprint());
+    let constraints = count_constraints(&ExprCircuit::empty(program.clone())).expect("Failed to synthesize");
+    println!("  {program}\n  {} variables: {constraints} constraints", program.variables.len());
+
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(ExprCircuit::empty(program.clone()), &mut rng).expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let assign = |values: &[(&str, u64)]| program.assign(values.iter().map(|(name, v)| (*name, Fr::from(*v)))).expect("a value for every name");
+    let values = assign(values);
+    let statement = program.public_inputs(&values);
+    let circuit = ExprCircuit::new(program.clone(), values).expect("the values satisfy the statement");
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).expect("Failed to generate proof");
+    assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &statement, &proof).expect("the input count fits"));
+    println!("✓ the proof verifies");
+
+    let other = assign(other_public);
+    let other_statement = program.public_inputs(&other);
+    assert!(!Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &other_statement, &proof).expect("the input count fits"));
+    println!("✓ and not for other public values");
+    let error = ExprCircuit::new(program.clone(), other).err().expect("values that don't satisfy the statement");
+    println!("✓ the circuit isn't built from those: {error}");
+    println!();
+}
+
+fn main() {
+    println!("=== STATEMENTS ===");
+    prove("public z; secret x, y; x*x + y*3 == z", &[("x", 3), ("y", 4), ("z", 21)], &[("x", 3), ("y", 4), ("z", 22)]);
+    // a cube root: x^3 is a square and a multiply, and the equation one more
+    prove("public y\nsecret x\nx^3 + x + 5 == y  # the hello world of zk", &[("x", 3), ("y", 35)], &[("x", 3), ("y", 36)]);
+    // two equations over the same secrets: the product and the sum of two factors
+    prove(
+        "public n, s; secret p, q\np*q == n\np + q == s",
+        &[("p", 101), ("q", 103), ("n", 10403), ("s", 204)],
+        &[("p", 101), ("q", 103), ("n", 10403), ("s", 205)],
+    );
+
+    println!("=== SOLVING FOR THE REST ===");
+    let program = Program::<Fr>::parse("public z; secret x, y; x*x + y*3 == z").expect("the statement parses");
+    let solve = |program: &Program<Fr>, given: &[(&str, u64)]| program.solve(given.iter().map(|(name, v)| (*name, Fr::from(*v))));
+    for (given, expected) in [(&[("x", 3), ("y", 4)][..], [21, 3, 4]), (&[("x", 3), ("z", 21)], [21, 3, 4])] {
+        let values = solve(&program, given).expect("one unknown, linearly");
+        assert_eq!(values, expected.map(Fr::from));
+        let given: Vec<String> = given.iter().map(|(name, v)| format!("{name} = {v}")).collect();
+        println!("✓ {program} from {}: z = {}, x = {}, y = {}", given.join(", "), values[0], values[1], values[2]);
+    }
+    let chain = Program::<Fr>::parse("public z; secret x, y; y == x + 1; z == y*y").expect("the statement parses");
+    assert_eq!(solve(&chain, &[("x", 4)]).expect("a chain of single unknowns"), [25, 4, 5].map(Fr::from));
+    println!("✓ {chain} from x = 4: y = 5, then z = 25");
+    for (what, program, given) in [
+        ("two unknowns in one equation", &program, &[("x", 3)][..]),
+        ("the unknown squared", &program, &[("y", 4), ("z", 21)]),
+        ("values that contradict the equations", &chain, &[("x", 4), ("z", 24)]),
+    ] {
+        let error = solve(program, given).expect_err("nothing to derive");
+        println!("✓ {what}: {error}");
+    }
+    println!();
+
+    println!("=== STATEMENTS THE PARSER REFUSES ===");
+    for (what, text) in [
+        ("an undeclared name", "public z; secret x; x*y == z"),
+        ("a name declared twice", "public z; secret z; z == z"),
+        ("a single =", "public z; secret x; x = z"),
+        ("an open parenthesis", "public z; secret x; (x + 1 == z"),
+        ("a variable exponent", "public z; secret x; x^z == z"),
+        ("a number past the modulus", "public z; secret x; x*0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000002 == z"),
+        ("no equation", "public z; secret x"),
+        ("an expression nested too deep", &format!("public z; secret x; {} == z", vec!["x"; 300].join(" * "))),
+    ] {
+        let error = Program::<Fr>::parse(text).expect_err("a broken statement");
+        println!("✓ {what}: {error}");
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use zk_circuits::credential::{self, AgeCircuit, Credential};
+use zk_circuits::fixtures::{self, CENSUS_DATE, SEED};
+use zk_circuits::membership::MembershipCircuit;
+use zk_circuits::templates::SumCircuit;
+use zk_gadgets::hasher::{CircuitHasher, PoseidonHasher};
+use zk_gadgets::poseidon::poseidon_config;
+/*
+The toy world of zk-circuits/src/fixtures.rs, the students, balances and census the
+examples share, all derived from SEED. Printed for a lesson to refer to, and checked:
+the same seed gives the same world (down to a pinned merkle root, so a change to the
+generator or to Poseidon shows up here), and each dataset makes a statement one of
+the circuits can prove.
+
+No proving, just constraint systems.
+*/
+
+// the root of the Poseidon student tree on BLS12-381 for SEED
+const STUDENT_ROOT: &str = "2556296569116485274672168216108461531167807419628137822864480583800686703174";
+
+fn satisfied<C: ConstraintSynthesizer<Fr>>(circuit: C) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("synthesis failed");
+    cs.is_satisfied().expect("a witness was missing")
+}
+
+fn main() {
+    let students = fixtures::students(SEED);
+    println!("=== The students of seed {SEED} ===");
+    for s in &students[..5] {
+        println!("  {:>4}  {:<20} born {}", s.id, s.name, credential::format_date(s.birth_date));
+    }
+    println!("  ... {} students", students.len());
+    assert_eq!(students, fixtures::students(SEED));
+    assert_ne!(students, fixtures::students(SEED + 1));
+    println!("✓ the same seed gives the same students, another seed others");
+
+    let hasher = PoseidonHasher::<Fr>::new(2);
+    let tree = fixtures::student_tree(hasher.clone(), SEED);
+    println!("\n=== The student tree, depth {} ===", fixtures::STUDENT_TREE_DEPTH);
+    println!("  root {}", tree.root());
+    assert_eq!(tree.root().into_bigint().to_string(), STUDENT_ROOT, "the fixtures or Poseidon changed");
+    println!("✓ the root is the pinned one");
+    let student = &students[7];
+    let leaf = fixtures::student_leaves(&hasher, std::slice::from_ref(student))[0];
+    assert!(satisfied(MembershipCircuit::new(hasher.clone(), leaf, tree.path(student.id))));
+    let mut impostor = MembershipCircuit::new(hasher.clone(), leaf, tree.path(student.id));
+    impostor.leaf = Some(hasher.hash(&[Fr::from(student.id), Fr::from(1u32)]));
+    assert!(!satisfied(impostor));
+    println!("✓ {} (student {}) is in the tree, the same id with another secret isn't", student.name, student.id);
+
+    let balances = fixtures::balances(SEED);
+    println!("\n=== {} accounts ===", balances.len());
+    for a in &balances[..4] {
+        println!("  {:<20} {:>10}", students[a.owner as usize].name, a.balance);
+    }
+    let values: Vec<Fr> = balances[..4].iter().map(|a| Fr::from(a.balance)).collect();
+    let sum = SumCircuit::new(fixtures::BALANCE_BITS, values).expect("the balances fit in their bits");
+    let total: u64 = balances[..4].iter().map(|a| a.balance).sum();
+    assert_eq!(sum.total, Some(Fr::from(total)));
+    assert!(satisfied(sum));
+    println!("✓ the first four add up to {total}, a sum template statement");
+
+    let census = fixtures::census(SEED);
+    let mut districts: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for v in &census {
+        let (voters, people) = districts.entry(v.district).or_default();
+        *people += 1;
+        *voters += usize::from(v.eligible);
+    }
+    println!("\n=== The census on {} ===", credential::format_date(CENSUS_DATE));
+    for (district, (voters, people)) in &districts {
+        println!("  district {district}: {voters} of {people} can vote");
+    }
+    let poseidon = poseidon_config::<Fr>(2);
+    let cutoff = credential::cutoff(CENSUS_DATE, 18);
+    let age = |id: u64| {
+        let s = &students[id as usize];
+        let credential = Credential {
+            attributes: s.attributes(),
+            salt: s.secret(),
+            signature: Default::default(),
+        };
+        satisfied(AgeCircuit::new(poseidon.clone(), &credential, cutoff))
+    };
+    let voter = census.iter().find(|v| v.eligible).expect("somebody can vote");
+    let minor = census.iter().find(|v| !v.eligible).expect("somebody can't");
+    assert!(age(voter.student));
+    assert!(!age(minor.student));
+    println!("✓ the age check agrees with the census for a voter and for a minor");
+
+    println!("\n✓ one toy world, the same every run");
+}
+
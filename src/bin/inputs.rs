@@ -0,0 +1,159 @@
+use ark_bls12_381::Fr;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use zk_core::input::{InputError, MAX_EXPONENT, Reduction, parse_field, parse_field_with};
+/*
+Numbers the way people type them, through zk-core/src/input.rs, the parser behind
+every --x, --set and json input of zk-toy and the witness files. Each form it takes
+is checked against the field element it should be, and each one it refuses against
+the reason it gives: too big for the field (unless asked to reduce), not whole, or
+written in a way that depends on who's reading it (1,000 and 1.000).
+
+Everything is asserted, a parse that comes out different panics with its input. The
+tests in input.rs check the same cases under cargo test.
+*/
+
+fn modulus() -> BigUint {
+    Fr::MODULUS.into()
+}
+
+fn element(n: &BigUint) -> Fr {
+    Fr::from(n % modulus())
+}
+
+fn accepted(cases: &[(&str, Fr)]) {
+    for (input, expected) in cases {
+        match parse_field::<Fr>(input) {
+            Ok(value) => assert_eq!(value, *expected, "'{input}' parsed to the wrong element"),
+            Err(e) => panic!("'{input}' should parse: {e}"),
+        }
+    }
+    println!("✓ {} forms parse to the element they spell", cases.len());
+}
+
+// whether an error is the one a case expects
+type Reason = fn(&InputError) -> bool;
+
+fn refused(cases: &[(&str, Reason)]) {
+    for (input, expected) in cases {
+        match parse_field::<Fr>(input) {
+            Ok(value) => panic!("'{input}' should be refused, it parsed to {value}"),
+            Err(e) => assert!(expected(&e), "'{input}' refused for the wrong reason: {e}"),
+        }
+    }
+    println!("✓ {} malformed numbers refused, each for its reason", cases.len());
+}
+
+fn main() {
+    let p = modulus();
+    let p_hex = format!("0x{}", p.to_str_radix(16));
+    let big = BigUint::parse_bytes(b"123456789012345678901234567890123456789012345678901234567890", 10).unwrap();
+    let minus = |n: u64| -Fr::from(n);
+
+    println!("=== Plain numbers, of any size ===");
+    let p_minus_one = (&p - 1u8).to_string();
+    let big_decimal = big.to_string();
+    let big_hex = format!("0x{}", big.to_str_radix(16));
+    let big_hex_upper = format!("0X{}", big.to_str_radix(16).to_uppercase());
+    accepted(&[
+        ("0", Fr::from(0u8)),
+        ("19", Fr::from(19u8)),
+        ("  19\n", Fr::from(19u8)),
+        ("+19", Fr::from(19u8)),
+        ("1_000_000", Fr::from(1_000_000u32)),
+        ("0x13", Fr::from(19u8)),
+        ("0xdead_BEEF", Fr::from(0xdead_beefu32)),
+        ("18446744073709551616", element(&(BigUint::from(u64::MAX) + 1u8))),
+        (&big_decimal, element(&big)),
+        (&big_hex, element(&big)),
+        (&big_hex_upper, element(&big)),
+        (&p_minus_one, -Fr::from(1u8)),
+    ]);
+
+    println!("\n=== Negative numbers are the field's negation ===");
+    let minus_p_minus_one = format!("-{p_minus_one}");
+    accepted(&[
+        ("-5", minus(5)),
+        ("-0x5", minus(5)),
+        ("-0", Fr::from(0u8)),
+        ("-1e3", minus(1000)),
+        (&minus_p_minus_one, Fr::from(1u8)),
+    ]);
+    assert_eq!(parse_field::<Fr>("-5").unwrap() + Fr::from(5u8), Fr::from(0u8));
+    println!("✓ -5 + 5 = 0 in the field");
+
+    println!("\n=== Scientific notation, as long as it's whole ===");
+    let e75 = format!("1e{}", p.to_string().len() - 2);
+    accepted(&[
+        ("1e18", Fr::from(1_000_000_000_000_000_000u64)),
+        ("1E18", Fr::from(1_000_000_000_000_000_000u64)),
+        ("1e+3", Fr::from(1000u16)),
+        ("2.5e3", Fr::from(2500u16)),
+        ("1.000e3", Fr::from(1000u16)),
+        ("1000e-3", Fr::from(1u8)),
+        ("1500e-2", Fr::from(15u8)),
+        ("0.0e-99999", Fr::from(0u8)),
+        ("12.5e1", Fr::from(125u8)),
+        (&e75, element(&BigUint::from(10u8).pow(p.to_string().len() as u32 - 2))),
+    ]);
+
+    println!("\n=== Refused ===");
+    let p_decimal = p.to_string();
+    let p_plus_19 = (&p + 19u8).to_string();
+    let minus_p = format!("-{p_decimal}");
+    let too_big_exponent = format!("1e{}", MAX_EXPONENT + 1);
+    let not_canonical: Reason = |e| matches!(e, InputError::NotCanonical(_));
+    let invalid: Reason = |e| matches!(e, InputError::InvalidNumber(_));
+    let fraction: Reason = |e| matches!(e, InputError::NotInteger(_));
+    refused(&[
+        ("", |e| matches!(e, InputError::Empty)),
+        ("   ", |e| matches!(e, InputError::Empty)),
+        (&p_decimal, not_canonical),
+        (&p_hex, not_canonical),
+        (&p_plus_19, not_canonical),
+        (&minus_p, not_canonical),
+        ("1e80", not_canonical),
+        (&too_big_exponent, |e| matches!(e, InputError::Malformed(_))),
+        ("1.5", invalid),
+        ("1.000", invalid),
+        ("1,000", invalid),
+        ("1 000", invalid),
+        ("1'000", invalid),
+        ("_1", invalid),
+        ("1_", invalid),
+        ("1__0", invalid),
+        ("0x", invalid),
+        ("0xg1", invalid),
+        ("0x1e3.5", invalid),
+        ("--5", invalid),
+        ("- 5", invalid),
+        ("e5", invalid),
+        ("1e", invalid),
+        ("1e-", invalid),
+        ("1e+-3", invalid),
+        ("1e3.0", invalid),
+        ("1.2.3e4", invalid),
+        ("١٢", invalid),
+        ("12abc", invalid),
+        ("1.5e0", fraction),
+        ("1e-3", fraction),
+        ("1e-99999999999999999999999", fraction),
+        ("1500e-3", fraction),
+    ]);
+
+    println!("\n=== --reduce takes them mod p instead ===");
+    let reduce = |input: &str| parse_field_with::<Fr>(input, Reduction::Reduce).unwrap_or_else(|e| panic!("'{input}' with --reduce: {e}"));
+    assert_eq!(reduce(&p_decimal), Fr::from(0u8));
+    assert_eq!(reduce(&p_plus_19), Fr::from(19u8));
+    assert_eq!(reduce(&p_hex), Fr::from(0u8));
+    assert_eq!(reduce(&minus_p), Fr::from(0u8));
+    assert_eq!(reduce(&format!("-{p_plus_19}")), minus(19));
+    assert_eq!(reduce("1e80"), element(&BigUint::from(10u8).pow(80)));
+    assert_eq!(reduce("19"), Fr::from(19u8));
+    println!("✓ p, p + 19, -p, -(p + 19) and 1e80 reduce to 0, 19, 0, -19 and 10^80 mod p");
+    for input in ["1.5", "1,000", "1e-3"] {
+        assert!(parse_field_with::<Fr>(input, Reduction::Reduce).is_err(), "--reduce shouldn't make '{input}' a number");
+    }
+    println!("✓ reducing doesn't make fractions or localized numbers acceptable");
+    println!("\n✓ every number parsed as expected");
+}
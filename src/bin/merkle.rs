@@ -0,0 +1,90 @@
+use ark_bls12_381::Fr; //scalar field of BLS12-381
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use rand::thread_rng;
+use zk_circuits::examples;
+use zk_circuits::fixtures::{self, SEED};
+use zk_circuits::membership::MembershipCircuit;
+use zk_core::report::{self, RunReport};
+use zk_gadgets::hasher::{CircuitHasher, MimcHasher, PoseidonHasher, Sha256Hasher};
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+/*
+Merkle membership with different hashes and different tree shapes, to see what
+they cost. The statement is always the same: "I know a leaf in the tree with this
+root" (and not which one). The circuit is MembershipCircuit<F, H> in
+crates/zk-circuits/src/membership.rs, switching the hash is just switching H and
+the shape of the tree is picked at setup.
+
+Poseidon and MiMC were designed for circuits, SHA-256 was designed for chips, and
+the constraint counts show it. We prove with the first two; a SHA-256 proof works
+exactly the same way but the setup alone takes minutes, so we only count it.
+
+Then binary vs 4-ary trees with Poseidon, for the same number of leaves. The 4-ary
+tree hashes 4 children at once with a width 5 Poseidon (rate 4), which costs a bit
+more than the width 3 one but there are half as many levels.
+*/
+
+const DEPTH: usize = 8;
+const MEMBERS: usize = 100;
+
+// constraints for one membership proof, without doing a setup. The witness is checked
+// against the constraints too, so the native and the circuit hash had better agree
+fn constraints_for<H: CircuitHasher<Fr>>(hasher: H, shape: TreeShape) -> usize {
+    let leaves: Vec<Fr> = fixtures::student_leaves(&hasher, &fixtures::students(SEED)[..MEMBERS.min(shape.leaves())]);
+    let index = leaves.len() as u64 - 1;
+    let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+    let circuit = MembershipCircuit::new(hasher, leaves[index as usize], tree.path(index));
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).expect("Failed to synthesize");
+    assert!(cs.is_satisfied().expect("Failed to check the constraints"));
+    cs.num_constraints()
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    let binary = TreeShape::binary(DEPTH);
+    let quaternary = TreeShape::new(4, DEPTH / 2);
+    let reports: Vec<RunReport> = vec![
+        examples::merkle::run(&mut rng, PoseidonHasher::<Fr>::new(2), binary, MEMBERS).expect("Failed to run with poseidon"),
+        examples::merkle::run(&mut rng, PoseidonHasher::<Fr>::new(4), quaternary, MEMBERS).expect("Failed to run the 4-ary tree"),
+        examples::merkle::run(&mut rng, MimcHasher::<Fr>::default(), binary, MEMBERS).expect("Failed to run with mimc"),
+    ];
+    if report::json_requested() {
+        for r in &reports {
+            println!("{}", r.to_json());
+        }
+        return;
+    }
+
+    println!("=== ONE MEMBERSHIP PROOF, BINARY TREE OF DEPTH {DEPTH} ({} LEAVES) ===", binary.leaves());
+    println!("  poseidon: {:>7} constraints", constraints_for(PoseidonHasher::<Fr>::new(2), binary));
+    println!("  mimc:     {:>7} constraints", constraints_for(MimcHasher::<Fr>::default(), binary));
+    println!("  sha256:   {:>7} constraints\n", constraints_for(Sha256Hasher, binary));
+
+    println!("=== BINARY VS 4-ARY (POSEIDON) ===");
+    println!("  {:>8}  {:>14}  {:>14}", "leaves", "binary", "4-ary");
+    for depth4 in [2, 4, 6, 8] {
+        let (b, q) = (TreeShape::binary(2 * depth4), TreeShape::new(4, depth4));
+        println!(
+            "  {:>8}  {:>5} levels {:>5}  {:>5} levels {:>5}",
+            q.leaves(),
+            b.depth,
+            constraints_for(PoseidonHasher::<Fr>::new(2), b),
+            q.depth,
+            constraints_for(PoseidonHasher::<Fr>::new(4), q)
+        );
+    }
+    println!();
+
+    for r in &reports {
+        println!("=== PROVING WITH {} ===", r.example);
+        println!("  {}", r.summary());
+        if r.verified {
+            println!("  The proof is valid: the prover's leaf is in the tree with root {}", r.public_inputs[0]);
+        } else {
+            println!("Proof is invalid, you messed up, or you lyin' ");
+        }
+        if r.rejects_wrong_statement {
+            println!("  Correctly rejected the proof against the root of a different tree\n");
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use rand::thread_rng;
+use zk_circuits::examples;
+use zk_core::report;
+/*
+Proving you are over 18 without showing your passport. An issuer (think passport
+office) signs a commitment to your attributes, you keep the credential in a file, and
+a verifier (think bar, or website) gets a proof that the committed birth date is old
+enough, plus the issuer's signature on the commitment. Name, nationality and birth
+date never leave your side.
+The pieces are in crates/zk-circuits/src/credential.rs (the credential and the age
+circuit) and zk-gadgets/src/compare.rs (the <= gadget), the story is examples::passport::run.
+The same flow, one step at a time with files in between, is `zk-toy passport`.
+*/
+
+fn main() -> Result<(), examples::ExampleError> {
+    let mut rng = thread_rng();
+    let json = report::json_requested();
+    let report = examples::passport::run(&mut rng, json)?;
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        println!("  age proof: {}", report.summary());
+    }
+    Ok(())
+}
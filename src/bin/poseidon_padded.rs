@@ -0,0 +1,88 @@
+use ark_bls12_381::Fr;
+use ark_r1cs_std::R1CSVar;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError, SynthesisMode};
+use ark_std::UniformRand;
+use rand::thread_rng;
+use zk_gadgets::poseidon::{self, alloc_padded, hash_var_padded, poseidon_config};
+/*
+The variable length Poseidon gadget from zk-gadgets/src/poseidon.rs against the native
+sponge: every length from 0 to MAX hashes the same in and out of the circuit, and the
+witnesses a cheating prover could try (nonzero padding, a length past the end, a
+length that wrapped around) don't satisfy the constraints.
+Only constraint systems, no proving. Everything is asserted, and the tests at the
+bottom of poseidon.rs make the same checks under cargo test.
+*/
+
+const MAX: usize = 8;
+
+// the circuit's hash of these slots with this length, and whether the constraints hold
+fn circuit_hash(slots: &[Fr], len: Fr) -> Result<(Fr, bool), SynthesisError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let padded = slots
+        .iter()
+        .map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let len = FpVar::new_witness(cs.clone(), || Ok(len))?;
+    let hash = hash_var_padded(cs.clone(), &poseidon_config(2), &padded, &len)?;
+    Ok((hash.value()?, cs.is_satisfied()?))
+}
+
+fn main() -> Result<(), SynthesisError> {
+    let mut rng = thread_rng();
+    let config = poseidon_config::<Fr>(2);
+    let list: Vec<Fr> = (0..MAX).map(|_| Fr::rand(&mut rng)).collect();
+
+    for len in 0..=MAX {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let (padded, length) = alloc_padded(cs.clone(), Some(&list[..len]), MAX)?;
+        let hash = hash_var_padded(cs.clone(), &config, &padded, &length)?;
+        assert!(cs.is_satisfied()?, "length {len} doesn't satisfy the circuit");
+        assert_eq!(hash.value()?, poseidon::hash_padded(&config, &list[..len], MAX), "length {len} hashes differently");
+    }
+    println!("✓ lengths 0 to {MAX}: the circuit and the native sponge agree, the constraints hold");
+
+    let a = Fr::rand(&mut rng);
+    assert_ne!(poseidon::hash_padded(&config, &[a], MAX), poseidon::hash_padded(&config, &[a, Fr::from(0u32)], MAX));
+    println!("✓ [a] and [a, 0] hash differently, the length is part of the hash");
+
+    let setup = ConstraintSystem::<Fr>::new_ref();
+    setup.set_mode(SynthesisMode::Setup);
+    let (padded, length) = alloc_padded(setup.clone(), None, MAX)?;
+    let _ = hash_var_padded(setup.clone(), &config, &padded, &length)?;
+    let fixed = ConstraintSystem::<Fr>::new_ref();
+    let slots = (0..=MAX).map(|_| FpVar::new_witness(fixed.clone(), || Ok(Fr::from(0u32)))).collect::<Result<Vec<_>, _>>()?;
+    let _ = poseidon::hash_var(fixed.clone(), &config, &slots)?;
+    println!(
+        "  {} constraints for up to {MAX} elements, {} of them the hash of the {} slots\n",
+        setup.num_constraints(),
+        fixed.num_constraints(),
+        MAX + 1
+    );
+
+    let mut slots = list.clone();
+    for s in &mut slots[3..] {
+        *s = Fr::from(0u32);
+    }
+    let (_, ok) = circuit_hash(&slots, Fr::from(3u32))?;
+    assert!(ok);
+    slots[5] = Fr::from(7u32);
+    let (_, ok) = circuit_hash(&slots, Fr::from(3u32))?;
+    assert!(!ok);
+    println!("✓ length 3 with a 7 in the padding: refused");
+
+    let (_, ok) = circuit_hash(&list, Fr::from(MAX as u64 + 1))?;
+    assert!(!ok);
+    let (_, ok) = circuit_hash(&list, -Fr::from(1u32))?;
+    assert!(!ok);
+    println!("✓ length {} and length p - 1 for {MAX} slots: refused", MAX + 1);
+
+    // a longer length over zero slots is a different, longer list, and hashes as one
+    slots[5] = Fr::from(0u32);
+    let (hash, ok) = circuit_hash(&slots, Fr::from(4u32))?;
+    assert!(ok && hash != poseidon::hash_padded(&config, &list[..3], MAX));
+    assert_eq!(hash, poseidon::hash_padded(&config, &slots[..4], MAX));
+    println!("✓ counting a zero of the padding as an element makes the hash of the longer list");
+    Ok(())
+}
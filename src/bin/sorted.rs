@@ -0,0 +1,90 @@
+use ark_bls12_381::Fr;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::bits::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+use rand::{Rng, thread_rng};
+use zk_gadgets::compare::{RangeChecks, enforce_bits};
+/*
+Prove a secret list is sorted, which is a range check per element (each one below 2^32)
+and one per neighbouring pair (the next minus this one, also below 2^32), done two ways:
+every check its own enforce_bits, and the differences batched through RangeChecks from
+zk-gadgets/src/compare.rs. The differences are what RangeChecks is for, they are known
+to be within 2^32 of zero because both sides were range checked.
+The elements themselves stay plain enforce_bits: nothing bounds them beforehand.
+
+Both versions accept the sorted list and refuse it shuffled, the batched one with fewer
+constraints. Then the reason for the bound: without it a packed check takes a negative
+"difference" for a positive one. Only constraint systems, everything is asserted.
+*/
+
+const ELEMENTS: usize = 40;
+const BITS: usize = 32;
+
+// the constraint count, and whether the list satisfies the circuit
+fn sorted(list: &[u64], batched: bool) -> Result<(usize, bool), SynthesisError> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let vars = list
+        .iter()
+        .map(|x| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*x))))
+        .collect::<Result<Vec<_>, _>>()?;
+    for v in &vars {
+        enforce_bits(cs.clone(), v, BITS)?;
+    }
+    let mut checks = RangeChecks::new(cs.clone());
+    for pair in vars.windows(2) {
+        let difference = &pair[1] - &pair[0];
+        if batched {
+            checks.push(difference, BITS);
+        } else {
+            enforce_bits(cs.clone(), &difference, BITS)?;
+        }
+    }
+    checks.enforce()?;
+    Ok((cs.num_constraints(), cs.is_satisfied()?))
+}
+
+fn main() -> Result<(), SynthesisError> {
+    let mut rng = thread_rng();
+    let mut list: Vec<u64> = (0..ELEMENTS).map(|_| rng.gen_range(0..1u64 << BITS)).collect();
+    list.sort();
+
+    let (plain, ok) = sorted(&list, false)?;
+    assert!(ok);
+    let (batched, ok) = sorted(&list, true)?;
+    assert!(ok);
+    println!("✓ {ELEMENTS} sorted {BITS} bit values satisfy both versions");
+    println!("  one enforce_bits per check: {plain} constraints");
+    println!("  differences batched:        {batched} constraints ({} fewer)\n", plain - batched);
+    assert!(batched < plain);
+
+    let mut shuffled = list.clone();
+    shuffled.swap(10, 30);
+    assert!(!sorted(&shuffled, false)?.1 && !sorted(&shuffled, true)?.1);
+    println!("✓ two elements swapped: refused by both");
+    let mut tied = list.clone();
+    tied[21] = tied[20];
+    assert!(sorted(&tied, false)?.1 && sorted(&tied, true)?.1);
+    println!("✓ two equal neighbours are still sorted\n");
+
+    // a prover who picks the "differences" freely, nothing bounds them: 2^33 and -1 pack
+    // to 2^33 - 2^33 = 0, and all zero bits are a fine decomposition of 0. This is the
+    // constraint RangeChecks makes for a pack of two, with the bits the prover picked
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let first = FpVar::new_witness(cs.clone(), || Ok(Fr::from(1u64 << (BITS + 1))))?;
+    let second = FpVar::new_witness(cs.clone(), || Ok(-Fr::from(1u32)))?;
+    let packed = &first + &second * Fr::from(1u64 << (BITS + 1));
+    let mut bits = Vec::new();
+    for _ in 0..2 {
+        for _ in 0..BITS {
+            bits.push(Boolean::new_witness(cs.clone(), || Ok(false))?);
+        }
+        bits.push(Boolean::FALSE);
+    }
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(&packed)?;
+    assert!(cs.is_satisfied()?);
+    println!("✓ unbounded values break packing: 2^33 and -1 together pass as two values below 2^32,");
+    println!("  which is why RangeChecks is only for values that are already known to be small");
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::{Groth16, prepare_verifying_key};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
+use ark_snark::SNARK;
+use rand::{Rng, thread_rng};
+use zk_circuits::trie::{self, Key, StorageCircuit, Trie, Value};
+use zk_gadgets::keccak;
+/*
+A storage proof: "slot key of this contract holds value, under this public storage
+root", checked in a circuit over the simplified keccak trie of zk-circuits/src/trie.rs
+(fixed depth, every level a branch, no RLP, see there for what else is left out).
+
+That's what a bridge or a light client on another chain wants: ethereum's state root
+is public, and a succinct proof that a slot under it holds a value stands in for the
+whole trie. The nodes on the path are the witness, root, key and value are public.
+
+The trie here has depth 2, 256 paths, so two branch nodes and a leaf: about 1.4M
+constraints, almost all keccak. Setup and proving take a few minutes.
+*/
+
+const DEPTH: usize = 2;
+
+fn slot(n: u64) -> Value {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&n.to_be_bytes());
+    word
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn main() {
+    let mut rng = thread_rng();
+
+    println!("=== A CONTRACT'S STORAGE ===");
+    let mut storage = Trie::new(DEPTH);
+    // the keys of a real storage trie are keccak256 of the slot number
+    let keys: Vec<Key> = (0..20).map(|n| keccak::keccak256(&slot(n))).collect();
+    let mut collisions = 0;
+    for key in &keys {
+        if storage.insert(*key, slot(rng.gen_range(1..1_000_000))).is_err() {
+            collisions += 1;
+        }
+    }
+    // 20 keys in 256 paths, a couple collide now and then and stay out
+    let root = storage.root();
+    println!("  {} slots set ({collisions} left out for sharing a path), root {}", storage.entries.len(), hex(&root));
+
+    let (key, value) = storage.entries.iter().next().map(|(k, v)| (*k, *v)).expect("one slot at least");
+    let proof = storage.prove(&key).expect("the key is in the trie");
+    assert!(proof.verify(&root, &key, &value));
+    println!("✓ slot {}.. holds {} natively, {} nodes on the path", &hex(&key)[..8], u64::from_be_bytes(value[24..].try_into().unwrap()), proof.nodes.len());
+    assert!(!proof.verify(&root, &key, &slot(0)));
+    assert!(storage.prove(&slot(99)).is_none());
+    println!("✓ and the same nodes don't fit another value, a missing key has no proof");
+
+    let mut other = storage.clone();
+    assert_eq!(other.insert(key, value), Ok(()));
+    let mut sharing = key;
+    sharing[31] ^= 1;
+    assert_eq!(other.insert(sharing, value), Err(key));
+    println!("✓ a second key on the same {DEPTH} nibble path is refused");
+    println!();
+
+    println!("=== IN A CIRCUIT ===");
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    StorageCircuit::empty(DEPTH).generate_constraints(cs.clone()).expect("Failed to synthesize");
+    println!("  depth {DEPTH}: {} constraints, {} public inputs", cs.num_constraints(), cs.num_instance_variables() - 1);
+
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(StorageCircuit::empty(DEPTH), &mut rng).expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let proof = Groth16::<Bn254>::prove(&pk, StorageCircuit::new(key, value, proof), &mut rng).expect("Failed to generate proof");
+    let statement = trie::public_inputs::<Fr>(&root, &key, &value);
+    assert!(Groth16::<Bn254>::verify_with_processed_vk(&pvk, &statement, &proof).expect("six inputs"));
+    println!("✓ proved on BN254: the slot holds this value under root {}..", &hex(&root)[..16]);
+
+    let mut lie = value;
+    lie[31] ^= 1;
+    let statement = trie::public_inputs::<Fr>(&root, &key, &lie);
+    assert!(!Groth16::<Bn254>::verify_with_processed_vk(&pvk, &statement, &proof).expect("six inputs"));
+    println!("✓ and the proof is refused for another value");
+    let mut elsewhere = root;
+    elsewhere[0] ^= 1;
+    let statement = trie::public_inputs::<Fr>(&elsewhere, &key, &value);
+    assert!(!Groth16::<Bn254>::verify_with_processed_vk(&pvk, &statement, &proof).expect("six inputs"));
+    println!("✓ and under another root");
+}
@@ -0,0 +1,103 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::Field;
+use ark_groth16::{Groth16, prepare_verifying_key};
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::verifier::{STREAM_CHUNK, prepare_inputs_streaming, verify_streaming};
+/*
+A statement with a lot of public inputs, like a rollup batch that exposes every
+transaction's hash: here "I know a square root of each of these n numbers", one
+public input and one constraint per number. The verifier's first step folds the
+inputs into one G1 point with an MSM over the key's gamma_abc points, and that is the
+part that grows with n, the pairings don't.
+
+Groth16::prepare_inputs takes the inputs as a slice, so all n have to be in memory at
+once. prepare_inputs_streaming (zk-circuits/src/verifier.rs) takes an iterator and
+does the MSM a chunk of STREAM_CHUNK inputs at a time, here the inputs are computed
+on the fly as they're consumed. The two are checked to give the same point and the
+same verdicts, and then timed for growing n (run it with --release). Streaming
+isn't only smaller but faster: prepare_inputs in ark-groth16 0.4 does one scalar
+multiplication per input and no MSM at all, and a chunk of 256 is already big enough
+for Pippenger to win by a factor that grows with the chunk. The tests in verifier.rs
+check the point at the chunk boundaries and the refusals under cargo test.
+*/
+
+const ROUNDS: usize = 11;
+
+// the i-th public input, computed rather than stored
+fn input(i: usize) -> Fr {
+    Fr::from(i as u64 + 1).square()
+}
+
+#[derive(Clone)]
+struct SquareRoots {
+    n: usize,
+}
+
+impl ConstraintSynthesizer<Fr> for SquareRoots {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        for i in 0..self.n {
+            let square = FpVar::new_input(cs.clone(), || Ok(input(i)))?;
+            let root = FpVar::new_witness(cs.clone(), || Ok(Fr::from(i as u64 + 1)))?;
+            root.square()?.enforce_equal(&square)?;
+        }
+        Ok(())
+    }
+}
+
+fn median<T>(mut f: impl FnMut() -> T) -> Duration {
+    let mut times: Vec<Duration> = (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[ROUNDS / 2]
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    println!(
+        "{:>6} {:>12} {:>12} {:>9} {:>13} {:>13}",
+        "inputs", "collected", "streaming", "ratio", "held by slice", "held by chunk"
+    );
+    let mut ratio = 0.0;
+    for n in [16, 256, 1024, 4096] {
+        let circuit = SquareRoots { n };
+        let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).expect("setup failed");
+        let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).expect("proving failed");
+        let pvk = prepare_verifying_key(&vk);
+
+        let inputs: Vec<Fr> = (0..n).map(input).collect();
+        let streamed = prepare_inputs_streaming(&pvk, (0..n).map(input)).expect("the input count is right");
+        assert_eq!(streamed, Groth16::<Bls12_381>::prepare_inputs(&pvk, &inputs).expect("the input count is right"));
+        assert!(verify_streaming(&pvk, (0..n).map(input), &proof).expect("the input count is right"));
+        assert!(!verify_streaming(&pvk, (1..=n).map(input), &proof).expect("the input count is right"));
+        assert!(verify_streaming(&pvk, (0..n - 1).map(input), &proof).is_err());
+        assert!(verify_streaming(&pvk, (0..=n).map(input), &proof).is_err());
+
+        let collected = median(|| {
+            let inputs: Vec<Fr> = (0..n).map(input).collect();
+            Groth16::<Bls12_381>::prepare_inputs(&pvk, &inputs)
+        });
+        let streaming = median(|| prepare_inputs_streaming(&pvk, (0..n).map(input)));
+        ratio = streaming.as_secs_f64() / collected.as_secs_f64();
+        let scalar = std::mem::size_of::<Fr>();
+        println!(
+            "{n:>6} {collected:>12.2?} {streaming:>12.2?} {ratio:>8.2}x {:>11} B {:>11} B",
+            n * scalar,
+            n.min(STREAM_CHUNK) * scalar
+        );
+    }
+    println!("✓ the streaming path folds to the same point and agrees on every verdict");
+    assert!(ratio < 1.0, "an MSM per chunk should beat a scalar multiplication per input");
+    println!("✓ for 4096 inputs it takes {ratio:.2}x the time, holding {STREAM_CHUNK} inputs at a time");
+}
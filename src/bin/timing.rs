@@ -0,0 +1,125 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_ff::One;
+use ark_groth16::{Groth16, Proof, VerifyingKey, prepare_verifying_key};
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::document::{self, DocumentCircuit};
+use zk_circuits::examples::document::{SAMPLE_DOCUMENT, sample_layout};
+use zk_circuits::verifier::verify_constant_work;
+use zk_circuits::vss::{self, Polynomial, VssCircuit};
+use zk_gadgets::poseidon::poseidon_config;
+
+/*
+Does the verifier take longer to say yes than to say no? If it does, anyone who can
+time it learns something about the proofs it refuses. This times the constant work
+verifier from zk-circuits/src/verifier.rs on three kinds of proof for each example:
+a good one, one with a wrong public input and one with an input missing, and checks
+the medians stay within TOLERANCE of each other. Plain groth16 verification (with the
+same prepared key) is timed on the malformed one too, to see the early exit the wrapper
+gets rid of.
+Timings are noisy, run it with --release on a quiet machine. The tests in verifier.rs
+check the answers under cargo test, and the timing of the addition circuit with
+`cargo test --release -p zk-circuits -- --ignored`.
+*/
+
+// how far the median reject time may be from the median accept time
+const TOLERANCE: f64 = 0.2;
+// rounds per kind of proof, interleaved so a slow patch of the machine hits all of them
+const ROUNDS: usize = 101;
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+fn time(f: impl Fn() -> bool) -> Duration {
+    let start = Instant::now();
+    black_box(f());
+    start.elapsed()
+}
+
+fn check(name: &str, vk: &VerifyingKey<Bls12_381>, proof: &Proof<Bls12_381>, inputs: &[Fr]) {
+    let pvk = prepare_verifying_key(vk);
+    let mut wrong = inputs.to_vec();
+    wrong[0] += Fr::one();
+    let malformed = &inputs[..inputs.len() - 1];
+
+    // first make sure the answers are right, fast and wrong is no use
+    assert!(verify_constant_work(&pvk, inputs, proof), "{name}: the good proof was refused");
+    assert!(!verify_constant_work(&pvk, &wrong, proof), "{name}: a wrong input was accepted");
+    assert!(!verify_constant_work(&pvk, malformed, proof), "{name}: a missing input was accepted");
+
+    let (mut accept, mut reject, mut short, mut plain) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for _ in 0..ROUNDS {
+        accept.push(time(|| verify_constant_work(&pvk, inputs, proof)));
+        reject.push(time(|| verify_constant_work(&pvk, &wrong, proof)));
+        short.push(time(|| verify_constant_work(&pvk, malformed, proof)));
+        plain.push(time(|| Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, malformed, proof).unwrap_or(false)));
+    }
+    let (accept, reject, short, plain) = (median(accept), median(reject), median(short), median(plain));
+
+    println!("=== {} ({} public inputs) ===", name.to_uppercase(), inputs.len());
+    println!("  accept          {accept:>10.2?}");
+    for (what, t) in [("wrong input", reject), ("missing input", short)] {
+        let ratio = t.as_secs_f64() / accept.as_secs_f64();
+        println!("  {what:<15} {t:>10.2?}  ({ratio:.2}x)");
+        assert!(
+            (ratio - 1.0).abs() <= TOLERANCE,
+            "{name}: rejecting with a {what} takes {ratio:.2}x the time of accepting"
+        );
+    }
+    println!("✓ Accepting and rejecting take the same time, within {:.0}%", TOLERANCE * 100.0);
+    println!(
+        "  (plain groth16 refuses the missing input in {plain:.2?}, {:.0}x faster than a real check)\n",
+        accept.as_secs_f64() / plain.as_secs_f64()
+    );
+}
+
+fn main() {
+    let mut rng = thread_rng();
+
+    // addition, the public input is z
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut rng).unwrap();
+    let circuit = AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+    let z = circuit.z.unwrap();
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).unwrap();
+    check("addition", &vk, &proof, &[z]);
+
+    // vss, 3 shares: the commitment and then x, y of every share
+    let poseidon = poseidon_config::<Fr>(2);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(VssCircuit::empty(3, 3, poseidon.clone()), &mut rng).unwrap();
+    let polynomial = Polynomial::random(Fr::rand(&mut rng), 3, &mut rng);
+    let blinding = Fr::rand(&mut rng);
+    let commitment = vss::commit(&poseidon, &polynomial, blinding);
+    let shares = vss::deal(&polynomial, 3);
+    let inputs = vss::public_inputs(commitment, &shares);
+    let proof = Groth16::<Bls12_381>::prove(&pk, VssCircuit::new(poseidon.clone(), polynomial, blinding, shares), &mut rng).unwrap();
+    check("vss", &vk, &proof, &inputs);
+
+    // document, the commitment and the disclosed date
+    let layout = sample_layout();
+    let empty = DocumentCircuit {
+        layout,
+        poseidon: poseidon.clone(),
+        document: None,
+        commitment: None,
+        field_value: None,
+    };
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(empty, &mut rng).unwrap();
+    let commitment = document::commit(&poseidon, SAMPLE_DOCUMENT);
+    let disclosed = document::field_value(&SAMPLE_DOCUMENT[layout.field_offset..layout.field_offset + layout.field_len]);
+    let circuit = DocumentCircuit {
+        layout,
+        poseidon,
+        document: Some(SAMPLE_DOCUMENT.to_vec()),
+        commitment: Some(commitment),
+        field_value: Some(disclosed),
+    };
+    let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).unwrap();
+    check("document_hash", &vk, &proof, &[commitment, disclosed]);
+}
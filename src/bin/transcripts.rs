@@ -0,0 +1,118 @@
+use ark_bls12_381::{Fr, G1Affine, G1Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use rand::rngs::ThreadRng;
+use rand::thread_rng;
+use zk_gadgets::poseidon::poseidon_config;
+use zk_protocols::schnorr::{self, PROTOCOL, SchnorrProof};
+use zk_protocols::transcript::{Blake2bHash, PoseidonHash, Sha3Hash, Transcript, TranscriptHash};
+/*
+Fiat-Shamir only works when the prover and the verifier build the same transcript.
+A Schnorr proof on BLS12-381 G1 (zk-protocols/src/schnorr.rs) under each of the three
+transcript hashes of transcript.rs:
+
+  the same hash and protocol label on both sides   the proof verifies
+  another hash on the verifier's side              another challenge, refused
+  another protocol label                           another challenge, refused
+
+Nothing is wrong with the proof in the second and third case, the verifier just
+asks a different question than the one the prover answered. That's the point of
+domain agreement: a challenge is only meaningful in the transcript it came from,
+so everything that fixes it (the hash, the label, the order of the appends) is as
+much a part of the protocol as the equations.
+tests/transcripts.rs checks every pair of hashes under cargo test.
+*/
+
+// schnorr under one transcript hash, each call on a fresh transcript for the label
+struct Side {
+    name: &'static str,
+    prove: fn(&[u8], Fr, &mut ThreadRng) -> SchnorrProof<G1Projective>,
+    verify: fn(&[u8], &G1Affine, &SchnorrProof<G1Projective>) -> bool,
+    challenge: fn(&[u8]) -> Fr,
+}
+
+fn transcript<H: TranscriptHash<Fr>>(hash: H, protocol: &[u8]) -> Transcript<Fr, H> {
+    Transcript::new(hash, protocol)
+}
+
+fn poseidon() -> PoseidonHash<Fr> {
+    PoseidonHash::new(&poseidon_config::<Fr>(2))
+}
+
+fn sides() -> [Side; 3] {
+    [
+        Side {
+            name: <Blake2bHash as TranscriptHash<Fr>>::NAME,
+            prove: |label, x, rng| schnorr::prove(&mut transcript(Blake2bHash::default(), label), x, rng),
+            verify: |label, public, proof| schnorr::verify(&mut transcript(Blake2bHash::default(), label), public, proof),
+            challenge: |label| transcript(Blake2bHash::default(), label).challenge(b"c"),
+        },
+        Side {
+            name: <Sha3Hash as TranscriptHash<Fr>>::NAME,
+            prove: |label, x, rng| schnorr::prove(&mut transcript(Sha3Hash::default(), label), x, rng),
+            verify: |label, public, proof| schnorr::verify(&mut transcript(Sha3Hash::default(), label), public, proof),
+            challenge: |label| transcript(Sha3Hash::default(), label).challenge(b"c"),
+        },
+        Side {
+            name: <PoseidonHash<Fr> as TranscriptHash<Fr>>::NAME,
+            prove: |label, x, rng| schnorr::prove(&mut transcript(poseidon(), label), x, rng),
+            verify: |label, public, proof| schnorr::verify(&mut transcript(poseidon(), label), public, proof),
+            challenge: |label| transcript(poseidon(), label).challenge(b"c"),
+        },
+    ]
+}
+
+fn short(x: Fr) -> String {
+    let digits = x.into_bigint().to_string();
+    format!("{}...", &digits[..12.min(digits.len())])
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    let secret = Fr::from(0x5eed_u64);
+    let public = (G1Projective::generator() * secret).into_affine();
+    let sides = sides();
+
+    println!("=== The first challenge of an empty schnorr transcript ===");
+    for side in &sides {
+        println!("  {:<9} {}", side.name, short((side.challenge)(PROTOCOL)));
+    }
+    for (i, a) in sides.iter().enumerate() {
+        for b in &sides[i + 1..] {
+            assert_ne!((a.challenge)(PROTOCOL), (b.challenge)(PROTOCOL), "{} and {}", a.name, b.name);
+        }
+    }
+    println!("✓ three hashes, three different challenges for the same transcript");
+
+    println!("\n=== Prover and verifier, every pair of hashes ===");
+    for prover in &sides {
+        let proof = (prover.prove)(PROTOCOL, secret, &mut rng);
+        for verifier in &sides {
+            let accepted = (verifier.verify)(PROTOCOL, &public, &proof);
+            let agree = prover.name == verifier.name;
+            assert_eq!(accepted, agree, "proved with {}, verified with {}", prover.name, verifier.name);
+            let verdict = if accepted { "✓ accepted" } else { "✗ refused" };
+            println!("  proved with {:<9} verified with {:<9} {verdict}", prover.name, verifier.name);
+        }
+    }
+    println!("✓ a proof is only accepted under the hash it was made with");
+
+    println!("\n=== The same hash, another protocol label ===");
+    let other = b"zk-toy schnorr v2";
+    for side in &sides {
+        let proof = (side.prove)(PROTOCOL, secret, &mut rng);
+        assert!((side.verify)(PROTOCOL, &public, &proof));
+        assert!(!(side.verify)(other, &public, &proof), "{}", side.name);
+    }
+    println!("✓ a proof for {:?} is refused as one for {:?}", String::from_utf8_lossy(PROTOCOL), String::from_utf8_lossy(other));
+
+    // the lengths in the transcript keep the boundaries between appends
+    let mut ab_c = transcript(Blake2bHash::default(), PROTOCOL);
+    ab_c.append_message(b"a", b"bc");
+    let mut a_bc = transcript(Blake2bHash::default(), PROTOCOL);
+    a_bc.append_message(b"ab", b"c");
+    assert_ne!(ab_c.challenge(b"c"), a_bc.challenge(b"c"));
+    println!("✓ label \"a\" with \"bc\" and label \"ab\" with \"c\" give different challenges");
+
+    println!("\n✓ Fiat-Shamir needs both sides to agree on the whole transcript, hash included");
+}
@@ -0,0 +1,130 @@
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, Proof, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::credential::{self, AgeCircuit, Attributes, IssuerKey, Presentation};
+use zk_circuits::membership::MembershipCircuit;
+use zk_gadgets::hasher::PoseidonHasher;
+use zk_gadgets::merkle::{MerkleTree, TreeShape};
+use zk_gadgets::poseidon::poseidon_config;
+/*
+What "zero knowledge" promises about two proofs of the same thing: nothing links
+them. Groth16 draws fresh r and s for every proof, so proving the same statement with
+the same witness twice gives two proofs that share no point and look like unrelated
+random bytes. That part is the proof system's, and it always holds.
+
+What's published next to the proof is ours, and it is only as unlinkable as we made
+it. A commitment with a fresh salt is a new random-looking value each time, a
+commitment that is reused is the same value each time, whatever the proof next to it
+looks like. Three cases:
+
+  the same witness, proved again    addition, five proofs of 17 + 2
+  the same member, again            Poseidon membership, the root is all that's public
+  commitments                       the passport credential: two issuances of the same
+                                    attributes have different salts and can't be told
+                                    apart, two presentations of one credential can
+
+The last one is the known limitation from zk-circuits/src/credential.rs, printed as
+such. Everything else is asserted, the binary panics if two proofs ever line up.
+tests/unlinkability.rs makes the same checks under cargo test.
+*/
+
+fn bytes(proof: &Proof<Bls12_381>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    proof.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    bytes
+}
+
+// every pair differs in every point, and in roughly half the bits like random strings do
+fn assert_unlinked(proofs: &[Proof<Bls12_381>]) {
+    for (i, p) in proofs.iter().enumerate() {
+        for q in &proofs[i + 1..] {
+            assert!(p.a != q.a && p.b != q.b && p.c != q.c, "two proofs share a point");
+            let (x, y) = (bytes(p), bytes(q));
+            let differing: u32 = x.iter().zip(&y).map(|(a, b)| (a ^ b).count_ones()).sum();
+            let share = differing as f64 / (x.len() * 8) as f64;
+            assert!(share > 0.4 && share < 0.6, "{:.0}% of the bits differ, that's not random looking", share * 100.0);
+        }
+    }
+}
+
+fn main() {
+    let mut rng = thread_rng();
+
+    println!("=== THE SAME WITNESS, PROVED AGAIN ===");
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng)
+        .expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let z = Fr::from(19u32);
+    let proofs: Vec<_> = (0..5)
+        .map(|_| {
+            Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng)
+                .expect("Failed to generate proof")
+        })
+        .collect();
+    assert!(proofs.iter().all(|p| Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &[z], p).unwrap_or(false)));
+    assert_unlinked(&proofs);
+    println!("✓ five proofs of 17 + 2 = 19 all verify, no two share a point");
+    println!("  each pair differs in about half of its {} bits, like unrelated random strings\n", bytes(&proofs[0]).len() * 8);
+
+    println!("=== THE SAME MEMBER, AGAIN ===");
+    let hasher = PoseidonHasher::<Fr>::new(2);
+    let shape = TreeShape::binary(4);
+    let leaves: Vec<Fr> = (0..shape.leaves()).map(|_| Fr::rand(&mut rng)).collect();
+    let tree = MerkleTree::new(hasher.clone(), shape, &leaves);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(MembershipCircuit::empty(hasher.clone(), shape), &mut rng)
+        .expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let prove_member = |index: u64, rng: &mut _| {
+        let circuit = MembershipCircuit::new(hasher.clone(), leaves[index as usize], tree.path(index));
+        Groth16::<Bls12_381>::prove(&pk, circuit, rng).expect("Failed to generate proof")
+    };
+    let proofs = vec![prove_member(3, &mut rng), prove_member(3, &mut rng), prove_member(11, &mut rng)];
+    let root = tree.root();
+    assert!(proofs.iter().all(|p| Groth16::<Bls12_381>::verify_with_processed_vk(&pvk, &[root], p).unwrap_or(false)));
+    assert_unlinked(&proofs);
+    println!("✓ member 3 twice and member 11 once: the public input is the root every time,");
+    println!("  and the proofs don't say which two came from the same member\n");
+
+    println!("=== COMMITMENTS ===");
+    let poseidon = poseidon_config::<Fr>(2);
+    let issuer = IssuerKey::random(&mut rng);
+    let issuer_pk = issuer.public_key();
+    let attributes = || Attributes {
+        name: "Grace Hopper".to_string(),
+        nationality: "USA".to_string(),
+        birth_date: 19061209,
+    };
+    let cutoff = credential::cutoff(20261014, 18);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AgeCircuit::empty(poseidon.clone()), &mut rng)
+        .expect("Failed to setup");
+    let pvk = prepare_verifying_key(&vk);
+    let present = |held: &credential::Credential, rng: &mut _| Presentation {
+        commitment: held.commitment(&poseidon),
+        signature: held.signature,
+        proof: Groth16::<Bls12_381>::prove(&pk, AgeCircuit::new(poseidon.clone(), held, cutoff), rng).expect("Failed to generate proof"),
+    };
+
+    let first = credential::issue(&issuer, &poseidon, attributes(), &mut rng);
+    let second = credential::issue(&issuer, &poseidon, attributes(), &mut rng);
+    let (a, b) = (present(&first, &mut rng), present(&second, &mut rng));
+    assert!(credential::verify_presentation(&pvk, &issuer_pk, &a, cutoff));
+    assert!(credential::verify_presentation(&pvk, &issuer_pk, &b, cutoff));
+    assert!(a.commitment != b.commitment && a.signature != b.signature);
+    assert_unlinked(&[a.proof.clone(), b.proof.clone()]);
+    println!("✓ the same attributes issued twice: different salts, so different commitments and");
+    println!("  signatures, and both presentations verify. Nothing in them ties the two together");
+
+    let again = present(&first, &mut rng);
+    assert!(credential::verify_presentation(&pvk, &issuer_pk, &again, cutoff));
+    assert_unlinked(&[a.proof.clone(), again.proof.clone()]);
+    assert!(again.commitment == a.commitment && again.signature == a.signature);
+    println!("✓ one credential presented twice: the proofs are unlinkable as always, but");
+    println!("✗ the commitment and the signature are the same bytes both times, two verifiers who");
+    println!("  compare notes know it's the same holder. Fresh salts need a fresh issuance (one");
+    println!("  credential per verifier), or the signature checked inside the circuit so the");
+    println!("  commitment can stay a witness (src/bin/bls.rs has BLS verification in a circuit)");
+}
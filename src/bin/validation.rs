@@ -0,0 +1,134 @@
+use std::time::Instant;
+
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{One, Zero};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::curves::Curve;
+use zk_circuits::explain::explain;
+use zk_circuits::vss::VssCircuit;
+use zk_core::artifact::{self, ArtifactError, ProofArtifact, Validation};
+use zk_gadgets::poseidon::poseidon_config;
+/*
+Points that are on the curve but not in the group. BLS12-381's G1 and G2 are prime
+order subgroups of much bigger curve groups (the cofactors are ~2^125 and ~2^380), and
+a compressed encoding only says "the point with this x", so nothing about the bytes
+keeps an attacker from sending a point outside the subgroup. Pairing based checks are
+only sound on the subgroup.
+
+This builds such points, puts them into a proof, an artifact and a verifying key, and
+checks that the checked paths in zk-core/src/artifact.rs (what `zk-toy verify`, the qr
+decoder and every `load` use) refuse them while the unchecked ones let them through,
+and that `zk-toy explain` (which reads unchecked) points out the bad point.
+Then it times both on a proving key, which is what the unchecked path is for.
+tests/validation.rs makes the same checks under cargo test.
+*/
+
+// the first x that gives a point on the curve outside the subgroup. Almost every
+// point on the curve is one, the subgroup is a tiny part of the group
+fn g1_outside_subgroup() -> G1Affine {
+    let mut x = Fq::zero();
+    loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false)
+            && !p.is_in_correct_subgroup_assuming_on_curve()
+        {
+            return p;
+        }
+        x += Fq::one();
+    }
+}
+
+fn g2_outside_subgroup() -> G2Affine {
+    let mut x = Fq2::zero();
+    loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(x, false)
+            && !p.is_in_correct_subgroup_assuming_on_curve()
+        {
+            return p;
+        }
+        x += Fq2::one();
+    }
+}
+
+fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    bytes
+}
+
+// checked has to refuse it, unchecked has to take it, or the test is testing nothing
+fn expect_rejected<T: ark_serialize::CanonicalDeserialize>(what: &str, bytes: &[u8]) {
+    match artifact::deserialize_checked::<T>(bytes) {
+        Err(ArtifactError::Serialization(e)) => println!("✓ {what}: checked refuses it ({e})"),
+        Err(e) => panic!("{what}: refused for the wrong reason: {e}"),
+        Ok(_) => panic!("{what}: the checked path accepted a point outside the subgroup"),
+    }
+    assert!(artifact::deserialize_unchecked::<T>(bytes).is_ok(), "{what}: unchecked should decode it");
+    println!("  unchecked decodes it without complaint");
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng)
+        .expect("Failed to setup");
+    let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng)
+        .expect("Failed to generate proof");
+    let good = ProofArtifact {
+        proof: proof.clone(),
+        public_inputs: vec![Fr::from(19u32)],
+    };
+    let decoded: Groth16Artifact<Bls12_381> = artifact::deserialize_checked(&bytes(&good)).expect("a good artifact decodes");
+    assert_eq!(decoded, good);
+    println!("✓ An honest proof artifact decodes, checked\n");
+
+    let (bad_g1, bad_g2) = (g1_outside_subgroup(), g2_outside_subgroup());
+    assert!(bad_g1.is_on_curve() && bad_g2.is_on_curve());
+    println!("=== POINTS ON THE CURVE, OUTSIDE THE SUBGROUP ===");
+    let a = Proof { a: bad_g1, ..proof.clone() };
+    expect_rejected::<Proof<Bls12_381>>("proof with a bad A (G1)", &bytes(&a));
+    let b = Proof { b: bad_g2, ..proof.clone() };
+    expect_rejected::<Proof<Bls12_381>>("proof with a bad B (G2)", &bytes(&b));
+
+    // what arrives in a qr code or over the network is the whole artifact
+    let artifact = ProofArtifact {
+        proof: a,
+        public_inputs: vec![Fr::from(19u32)],
+    };
+    let hex = artifact::to_hex(&bytes(&artifact));
+    assert!(Groth16Artifact::<Bls12_381>::from_bytes(&artifact::from_hex(&hex).expect("hex")).is_err());
+    println!("✓ ProofArtifact::from_bytes refuses the same proof inside an artifact");
+    let explained = explain(&AdditionCircuit::<Fr>::input_spec(), &artifact, Some(&vk));
+    assert!(!explained.points[0].in_subgroup && explained.points[1].in_subgroup && !explained.valid());
+    assert!(explain(&AdditionCircuit::<Fr>::input_spec(), &good, Some(&vk)).valid());
+    println!("✓ zk-toy explain shows A outside its subgroup and calls the proof invalid");
+
+    let mut bad_vk = vk.clone();
+    bad_vk.gamma_abc_g1[0] = bad_g1;
+    let path = std::env::temp_dir().join(format!("zk-toy-bad-vk-{}.bin", std::process::id()));
+    artifact::save(&bad_vk, &zk_circuits::build_info(Bls12_381::NAME), &path).expect("Failed to write the key");
+    let checked: Result<VerifyingKey<Bls12_381>, _> = artifact::load(&path);
+    let unchecked: Result<VerifyingKey<Bls12_381>, _> = artifact::load_with(&path, Validation::Unchecked);
+    let _ = std::fs::remove_file(&path);
+    assert!(checked.is_err() && unchecked.is_ok());
+    println!("✓ A verifying key file with a bad point: load refuses it, load_with(Unchecked) doesn't\n");
+
+    // the reason unchecked exists: a subgroup check is a scalar multiplication per point
+    println!("=== LOADING A PROVING KEY ===");
+    let empty = VssCircuit::empty(3, 5, poseidon_config::<Fr>(2));
+    let (vss_pk, _) = Groth16::<Bls12_381>::circuit_specific_setup(empty, &mut rng).expect("Failed to setup");
+    let pk_bytes = bytes(&vss_pk);
+    let timed = |validation| {
+        let start = Instant::now();
+        let pk: ProvingKey<Bls12_381> = artifact::deserialize_with(&pk_bytes, validation).expect("our own key decodes");
+        (pk, start.elapsed())
+    };
+    let (checked, checked_time) = timed(Validation::Checked);
+    let (unchecked, unchecked_time) = timed(Validation::Unchecked);
+    assert!(checked == unchecked && checked == vss_pk);
+    println!("  {} bytes: checked {checked_time:.2?}, unchecked {unchecked_time:.2?}", pk_bytes.len());
+    println!("  unchecked is fine for a key you made yourself, never for one somebody sent you");
+}
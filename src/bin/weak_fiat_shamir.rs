@@ -0,0 +1,89 @@
+use ark_bls12_381::{Fr, G1Affine, G1Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::Field;
+use ark_std::UniformRand;
+use rand::thread_rng;
+use zk_protocols::schnorr::{self, PROTOCOL, SchnorrProof};
+use zk_protocols::transcript::{Blake2bHash, Transcript};
+/*
+Weak Fiat-Shamir: the challenge hashes the prover's commitment but not the statement.
+For Schnorr (zk-protocols/src/schnorr.rs) that's c = H(G, R) instead of H(G, X, R),
+and it looks harmless, the verifier still checks s * G == R + c * X. But c no longer
+depends on X, so a forger can fix R and s first, get c, and only then choose the
+statement:
+
+  R = any point, here somebody else's public key Y
+  s = anything
+  c = H(G, R)
+  X = (s * G - R) / c
+
+(R, s) is an accepted weak proof of knowledge of the secret key of X, and nobody
+knows that secret: it is (s - y) / c, so knowing it would give away Bob's y. The
+strong variant is the fix, one more append: c = H(G, X, R) isn't known before X is,
+so there's no c to solve X from, and the same forgery is refused.
+
+A second weakness of the same kind: an honest weak proof (R, s) for X is also one
+for X + d * G, with s + c * d, a proof for a key its maker never had.
+
+The names are from Bernhard, Pereira and Warinschi, "How not to prove yourself"
+(2012), who found the weak variant in the proofs of Helios, a voting system.
+*/
+
+fn transcript() -> Transcript<Fr, Blake2bHash> {
+    Transcript::new(Blake2bHash::default(), PROTOCOL)
+}
+
+fn main() {
+    let mut rng = thread_rng();
+    let g = G1Projective::generator();
+
+    println!("=== Honest proofs ===");
+    let alice = Fr::rand(&mut rng);
+    let alice_key = (g * alice).into_affine();
+    let strong = schnorr::prove::<G1Projective, _, _>(&mut transcript(), alice, &mut rng);
+    let weak = schnorr::prove_weak::<G1Projective, _, _>(&mut transcript(), alice, &mut rng);
+    assert!(schnorr::verify(&mut transcript(), &alice_key, &strong));
+    assert!(schnorr::verify_weak(&mut transcript(), &alice_key, &weak));
+    println!("✓ Alice proves she knows her secret key, with the strong and the weak transcript");
+
+    println!("\n=== Forging a weak proof for a statement chosen after the challenge ===");
+    // Bob's key pair; the forger only ever sees the key, bob is kept to check the algebra
+    let bob = Fr::rand(&mut rng);
+    let bob_key = (g * bob).into_affine();
+    let commitment = bob_key;
+    let response = Fr::rand(&mut rng);
+    let c = schnorr::challenge::<G1Projective, _>(&mut transcript(), None, &commitment);
+    let c_inverse = c.inverse().expect("a zero challenge, 1 in 2^255");
+    let forged_key: G1Affine = ((g * response - commitment) * c_inverse).into_affine();
+    let forged = SchnorrProof::<G1Projective> { commitment, response };
+    println!("  R = Bob's key, s random, c = H(G, R), X = (s * G - R) / c");
+    assert!(schnorr::verify_weak(&mut transcript(), &forged_key, &forged));
+    println!("✓ the weak verifier accepts a proof of knowledge of the secret key of X");
+    assert_eq!(forged_key, (g * ((response - bob) * c_inverse)).into_affine());
+    println!("✓ and that secret is (s - y) / c with Bob's y: whoever knew it could compute y");
+
+    assert!(!schnorr::verify(&mut transcript(), &forged_key, &forged));
+    let strong_c = schnorr::challenge::<G1Projective, _>(&mut transcript(), Some(&forged_key), &commitment);
+    assert_ne!(strong_c, c);
+    println!("✓ the strong verifier refuses it, its challenge hashes X and isn't the c X was made from");
+
+    println!("\n=== Moving an honest weak proof to another key ===");
+    let d = Fr::rand(&mut rng);
+    let shifted_key = (alice_key + g * d).into_affine();
+    let c = schnorr::challenge::<G1Projective, _>(&mut transcript(), None, &weak.commitment);
+    let shifted = SchnorrProof::<G1Projective> {
+        commitment: weak.commitment,
+        response: weak.response + c * d,
+    };
+    assert!(schnorr::verify_weak(&mut transcript(), &shifted_key, &shifted));
+    println!("✓ Alice's weak proof, with s + c * d, passes for X + d * G");
+    let strong_c = schnorr::challenge::<G1Projective, _>(&mut transcript(), Some(&alice_key), &strong.commitment);
+    let strong_shifted = SchnorrProof::<G1Projective> {
+        commitment: strong.commitment,
+        response: strong.response + strong_c * d,
+    };
+    assert!(!schnorr::verify(&mut transcript(), &shifted_key, &strong_shifted));
+    println!("✓ the same move on her strong proof is refused");
+
+    println!("\n✓ hash the statement into the challenge, all of it");
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "groth16")]
+// the test form of src/bin/build_info.rs: stamped artifacts load back equal, the stamp
+// is all a saved file adds to the bytes, the unstamped 0.1.0 keys still read, and a
+// fixed proof serializes to the same pinned bytes on every target
+
+use std::fs;
+use std::path::Path;
+
+use ark_bls12_381::{Bls12_381, Fr, G1Projective, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::curves::Curve;
+use zk_core::artifact;
+
+const COMPAT: &str = "compat/v0.1.0/addition-bls12-381";
+
+// A = G1, B = G2, C = 2·G1 and the public input 19, as to_bytes writes them
+const PINNED: &str = "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e\
+024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8\
+a572cbea904d67468808c8eb50a9450c9721db309128012543902d0ac358a62ae28f75bb8f1c7c42c39a8c5529bf0f4e\
+0100000000000000\
+1300000000000000000000000000000000000000000000000000000000000000";
+
+fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    bytes
+}
+
+#[test]
+fn stamped_artifacts_round_trip() {
+    let mut rng = thread_rng();
+    let build = zk_circuits::build_info(Bls12_381::NAME);
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::empty(), &mut rng).expect("setup");
+    let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng).expect("proving");
+    let proof = Groth16Artifact::<Bls12_381> { proof, public_inputs: vec![Fr::from(19u32)] };
+
+    let dir = std::env::temp_dir().join(format!("zk-toy-test-build-info-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("the temp dir");
+    let (vk_path, proof_path) = (dir.join("addition.vk"), dir.join("proof.bin"));
+    artifact::save_versioned::<AdditionCircuit, _>(&vk, &build, &vk_path).expect("the key saves");
+    artifact::save(&proof, &build, &proof_path).expect("the proof saves");
+    let loaded_vk: VerifyingKey<Bls12_381> = artifact::load_versioned::<AdditionCircuit, _>(&vk_path).expect("the key loads");
+    let loaded: Groth16Artifact<Bls12_381> = artifact::load(&proof_path).expect("the proof loads");
+    let stamps = (artifact::read_stamp(&vk_path).expect("the key reads"), artifact::read_stamp(&proof_path).expect("the proof reads"));
+    let (proof_file, vk_file) = (fs::read(&proof_path).expect("the proof reads"), fs::read(&vk_path).expect("the key reads"));
+    let _ = fs::remove_dir_all(&dir);
+
+    assert!(loaded_vk == vk && loaded == proof);
+    assert_eq!(stamps, (Some(build.clone()), Some(build)));
+    // after the stamp, the same bytes as without it
+    let (_, rest) = artifact::split_stamp(&proof_file).expect("the stamp reads");
+    assert_eq!(rest, proof.to_bytes().expect("serializing"));
+    assert_eq!(Groth16Artifact::<Bls12_381>::from_bytes(&proof_file).expect("from_bytes skips the stamp"), proof);
+    let (_, rest) = artifact::split_header(&vk_file).expect("the header reads");
+    assert_eq!(rest, bytes(&vk));
+}
+
+#[test]
+fn the_unstamped_keys_still_read_and_reserialize() {
+    let path = Path::new(COMPAT);
+    assert_eq!(artifact::read_stamp(path.join("addition.vk")).expect("the key reads"), None);
+    let vk: VerifyingKey<Bls12_381> = artifact::load(path.join("addition.vk")).expect("the 0.1.0 vk loads");
+    let pk: ProvingKey<Bls12_381> = artifact::load(path.join("addition.pk")).expect("the 0.1.0 pk loads");
+    assert_eq!(bytes(&vk), fs::read(path.join("addition.vk")).expect("the compat vk"));
+    assert_eq!(bytes(&pk), fs::read(path.join("addition.pk")).expect("the compat pk"));
+}
+
+#[test]
+fn a_fixed_proof_serializes_to_the_pinned_bytes() {
+    let g1 = G1Projective::generator();
+    let proof = Proof::<Bls12_381> {
+        a: g1.into_affine(),
+        b: G2Projective::generator().into_affine(),
+        c: (g1 + g1).into_affine(),
+    };
+    let artifact = Groth16Artifact::<Bls12_381> { proof, public_inputs: vec![Fr::from(19u32)] };
+    let encoded = artifact.to_bytes().expect("serializing");
+    assert_eq!(artifact::to_hex(&encoded), PINNED.to_uppercase());
+    assert_eq!(Groth16Artifact::<Bls12_381>::from_bytes(&encoded).expect("it decodes"), artifact);
+}
+
+#[test]
+fn differences_name_what_another_build_has() {
+    let here = zk_circuits::build_info(Bls12_381::NAME);
+    assert!(here.differences(&here).is_empty());
+    let mut there = here.clone();
+    there.target = "wasm32-unknown-unknown".to_string();
+    if let Some(i) = there.features.iter().position(|feature| feature == "chaos") {
+        there.features.remove(i);
+    } else {
+        there.features.push("chaos".to_string());
+    }
+    let differences = here.differences(&there);
+    assert_eq!(differences.len(), 2, "{differences:?}");
+    assert!(differences[0].starts_with("features") && differences[1].starts_with("target"));
+    assert_eq!(differences[0].matches("chaos").count(), 1, "{}", differences[0]);
+}
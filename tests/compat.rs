@@ -0,0 +1,67 @@
+#![cfg(feature = "groth16")]
+// the keys and proofs in compat/ still work with today's code, the test form of
+// src/bin/compat.rs: each old proof verifies against its old key, and a new proof made
+// with the old proving key does too, so neither the encoding nor the circuit moved
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_core::artifact;
+
+fn cases() -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("compat");
+    let mut cases = Vec::new();
+    for version in fs::read_dir(root).unwrap() {
+        let version = version.unwrap().path();
+        if version.is_dir() {
+            cases.extend(fs::read_dir(&version).unwrap().map(|case| case.unwrap().path()));
+        }
+    }
+    cases.sort();
+    cases
+}
+
+// keys from 0.1.0 have no version header, everything later does
+fn load_key<T: CanonicalDeserialize>(path: PathBuf) -> T {
+    match artifact::read_header(&path).unwrap() {
+        Some(_) => artifact::load_versioned::<AdditionCircuit, T>(&path).unwrap(),
+        None => artifact::load(&path).unwrap(),
+    }
+}
+
+#[test]
+fn there_are_cases() {
+    assert!(cases().iter().any(|case| case.ends_with("v0.1.0/addition-bls12-381")));
+}
+
+#[test]
+fn old_proofs_verify() {
+    for case in cases() {
+        let vk: VerifyingKey<Bls12_381> = load_key(case.join("addition.vk"));
+        let old: Groth16Artifact<Bls12_381> = artifact::load(case.join("proof.bin")).unwrap();
+        assert!(Groth16::<Bls12_381>::verify(&vk, &old.public_inputs, &old.proof).unwrap(), "{}", case.display());
+        let wrong: Vec<Fr> = old.public_inputs.iter().map(|x| *x + Fr::from(1u32)).collect();
+        assert!(!Groth16::<Bls12_381>::verify(&vk, &wrong, &old.proof).unwrap(), "{}", case.display());
+    }
+}
+
+#[test]
+fn old_proving_keys_still_fit_the_circuit() {
+    let mut rng = thread_rng();
+    for case in cases() {
+        let vk: VerifyingKey<Bls12_381> = load_key(case.join("addition.vk"));
+        let pk: ProvingKey<Bls12_381> = load_key(case.join("addition.pk"));
+        // the witness every compat proof is made with
+        let circuit = AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32));
+        let z = circuit.z.unwrap();
+        let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify(&vk, &[z], &proof).unwrap(), "{}: the circuit shape changed", case.display());
+    }
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "groth16")]
+// the test form of src/bin/edge_cases.rs: identity and small order points, zero as a
+// public input, the wrong number of inputs and malleable proofs, each against both
+// the arkworks verifier and verify_constant_work
+
+use ark_bls12_381::{Bls12_381, Fq, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{One, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey, VerifyingKey, prepare_verifying_key};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::verifier::verify_constant_work;
+use zk_core::artifact;
+
+struct Setup {
+    pk: ProvingKey<Bls12_381>,
+    vk: VerifyingKey<Bls12_381>,
+    pvk: PreparedVerifyingKey<Bls12_381>,
+    // a proof of 17 + 2 = 19
+    proof: Proof<Bls12_381>,
+}
+
+fn setup() -> Setup {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng).expect("setup");
+    let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng).expect("proving");
+    let pvk = prepare_verifying_key(&vk);
+    Setup { pk, vk, pvk, proof }
+}
+
+// both verifiers, they have to agree on everything but the error
+fn verdict(pvk: &PreparedVerifyingKey<Bls12_381>, proof: &Proof<Bls12_381>, inputs: &[Fr]) -> bool {
+    let plain = Groth16::<Bls12_381>::verify_with_processed_vk(pvk, inputs, proof).unwrap_or(false);
+    assert_eq!(plain, verify_constant_work(pvk, inputs, proof), "the two verifiers disagree");
+    plain
+}
+
+fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    bytes
+}
+
+// a point of the whole curve with everything but the 3 part of its order multiplied away
+fn order_three_point() -> G1Affine {
+    let mut x = Fq::zero();
+    loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false) {
+            let mut q: G1Projective = p.mul_bigint(Fr::MODULUS);
+            for factor in [121u64, 10177 * 10177, 859267 * 859267, 52437899 * 52437899] {
+                q = q.into_affine().mul_bigint([factor]);
+            }
+            if !q.is_zero() {
+                return q.into_affine();
+            }
+        }
+        x += Fq::one();
+    }
+}
+
+#[test]
+fn the_honest_proof_verifies() {
+    let s = setup();
+    assert!(verdict(&s.pvk, &s.proof, &[Fr::from(19u32)]));
+}
+
+#[test]
+fn identity_points_decode_and_are_refused() {
+    let s = setup();
+    for mask in 1..8u8 {
+        let mut forged = s.proof.clone();
+        if mask & 1 != 0 {
+            forged.a = G1Affine::identity();
+        }
+        if mask & 2 != 0 {
+            forged.b = G2Affine::identity();
+        }
+        if mask & 4 != 0 {
+            forged.c = G1Affine::identity();
+        }
+        let decoded: Proof<Bls12_381> = artifact::deserialize_checked(&bytes(&forged)).expect("the identity is in the subgroup");
+        assert!(!verdict(&s.pvk, &decoded, &[Fr::from(19u32)]), "mask {mask}");
+        assert!(!verdict(&s.pvk, &decoded, &[Fr::zero()]), "mask {mask}");
+    }
+}
+
+#[test]
+fn a_small_order_point_is_refused_at_loading() {
+    let s = setup();
+    let tiny = order_three_point();
+    assert!(tiny.is_on_curve() && !tiny.is_in_correct_subgroup_assuming_on_curve());
+    assert!(tiny.mul_bigint([3u64]).is_zero());
+    let encoded = bytes(&Proof { a: tiny, ..s.proof });
+    assert!(artifact::deserialize_checked::<Proof<Bls12_381>>(&encoded).is_err());
+    let unchecked: Proof<Bls12_381> = artifact::deserialize_unchecked(&encoded).expect("on the curve, so it decodes");
+    assert!(!verdict(&s.pvk, &unchecked, &[Fr::from(19u32)]));
+}
+
+#[test]
+fn zero_is_an_honest_public_input() {
+    let s = setup();
+    let zero = Groth16::<Bls12_381>::prove(&s.pk, AdditionCircuit::new(Fr::zero(), Fr::zero()), &mut thread_rng()).expect("proving");
+    assert!(verdict(&s.pvk, &zero, &[Fr::zero()]));
+    assert!(!verdict(&s.pvk, &zero, &[Fr::one()]));
+    assert!(!verdict(&s.pvk, &s.proof, &[Fr::zero()]));
+    // with every input zero vk_x is IC0, a proof made of it still has to fail
+    let none = Proof { a: G1Affine::identity(), b: G2Affine::identity(), c: s.vk.gamma_abc_g1[0] };
+    assert!(!verdict(&s.pvk, &none, &[Fr::zero()]));
+}
+
+#[test]
+fn the_wrong_number_of_inputs_is_refused() {
+    let s = setup();
+    let z = Fr::from(19u32);
+    assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&s.pvk, &[], &s.proof).is_err());
+    assert!(Groth16::<Bls12_381>::verify_with_processed_vk(&s.pvk, &[z, z], &s.proof).is_err());
+    assert!(!verify_constant_work(&s.pvk, &[], &s.proof));
+    assert!(!verify_constant_work(&s.pvk, &[z, z], &s.proof));
+}
+
+#[test]
+fn proofs_are_malleable() {
+    let s = setup();
+    let z = Fr::from(19u32);
+    let negated = Proof {
+        a: (-s.proof.a.into_group()).into_affine(),
+        b: (-s.proof.b.into_group()).into_affine(),
+        c: s.proof.c,
+    };
+    assert!(negated != s.proof && verdict(&s.pvk, &negated, &[z]));
+    let rerandomized = Groth16::<Bls12_381>::rerandomize_proof(&s.vk, &s.proof, &mut thread_rng());
+    assert!(rerandomized != s.proof && verdict(&s.pvk, &rerandomized, &[z]));
+}
@@ -0,0 +1,71 @@
+#![cfg(all(feature = "groth16", feature = "evm"))]
+// the snarkjs export (zk-circuits/src/snarkjs.rs), and snarkjs itself checking our proofs:
+// `cargo test --test snarkjs -- --ignored` with node installed, or SNARKJS set to a binary
+
+use ark_bn254::{Bn254, Fr};
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use rand::thread_rng;
+use serde_json::json;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::key_info::MalformedKey;
+use zk_circuits::{snarkjs, solidity};
+
+fn fixture(x: u32, y: u32) -> (ark_groth16::VerifyingKey<Bn254>, ark_groth16::Proof<Bn254>, Fr) {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng).unwrap();
+    let circuit = AdditionCircuit::new(Fr::from(x), Fr::from(y));
+    let z = circuit.z.unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+    (vk, proof, z)
+}
+
+#[test]
+fn the_export_is_snarkjs_json() {
+    let (vk, proof, z) = fixture(17, 2);
+    let key = snarkjs::verifying_key_json(&vk).unwrap();
+    assert_eq!(key["protocol"], "groth16");
+    assert_eq!(key["curve"], "bn128");
+    assert_eq!(key["nPublic"], 1);
+    assert_eq!(key["IC"].as_array().unwrap().len(), 2);
+    // projective with z = 1, G2 real part first
+    assert_eq!(key["vk_alpha_1"][2], "1");
+    assert_eq!(key["vk_beta_2"][2][0], "1");
+    assert_eq!(key["vk_beta_2"][2][1], "0");
+    let proof = snarkjs::proof_json(&proof);
+    assert!(proof["pi_a"][0].as_str().unwrap().bytes().all(|b| b.is_ascii_digit()));
+    assert_eq!(snarkjs::public_json(&[z]), json!(["19"]));
+}
+
+// zero has to be "0", not the empty string arkworks' Display makes of it
+#[test]
+fn zero_is_written_as_zero() {
+    assert_eq!(snarkjs::public_json(&[Fr::from(0u32), Fr::from(7u32)]), json!(["0", "7"]));
+}
+
+// a crafted key with no IC points has no input count, the exports refuse it
+#[test]
+fn a_key_without_ic_points_is_refused() {
+    let (mut vk, proof, z) = fixture(17, 2);
+    vk.gamma_abc_g1.clear();
+    assert_eq!(snarkjs::verifying_key_json(&vk), Err(MalformedKey));
+    assert_eq!(solidity::verifier_contract(&vk), Err(MalformedKey));
+    let dir = std::env::temp_dir().join(format!("zk-toy-snarkjs-empty-{}", std::process::id()));
+    assert!(snarkjs::export_fixture(&dir, &vk, &proof, &[z]).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+#[ignore = "needs node, runs snarkjs through npx (or SNARKJS)"]
+fn snarkjs_agrees() {
+    let (vk, proof, z) = fixture(17, 2);
+    let dir = std::env::temp_dir().join(format!("zk-toy-snarkjs-{}", std::process::id()));
+    let (good, bad) = (dir.join("valid"), dir.join("wrong-input"));
+    snarkjs::export_fixture(&good, &vk, &proof, &[z]).unwrap();
+    snarkjs::export_fixture(&bad, &vk, &proof, &[z + Fr::from(1u32)]).unwrap();
+    let accepted = snarkjs::snarkjs_verify(&good).expect("could not run snarkjs");
+    let rejected = !snarkjs::snarkjs_verify(&bad).expect("could not run snarkjs");
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(accepted, "snarkjs refuses a proof arkworks accepts");
+    assert!(rejected, "snarkjs accepts a wrong public input");
+}
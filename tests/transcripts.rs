@@ -0,0 +1,35 @@
+// the test form of src/bin/transcripts.rs: a schnorr proof made under one transcript
+// hash is refused by a verifier replaying the transcript under another
+
+use ark_bls12_381::{Fr, G1Affine, G1Projective};
+use ark_ec::{CurveGroup, Group};
+use rand::thread_rng;
+use zk_gadgets::poseidon::poseidon_config;
+use zk_protocols::schnorr::{self, PROTOCOL, SchnorrProof};
+use zk_protocols::transcript::{Blake2bHash, PoseidonHash, Sha3Hash, Transcript, TranscriptHash};
+
+// prove and verify under the hash H, each on a fresh transcript
+fn prove<H: TranscriptHash<Fr>>(hash: H, secret: Fr) -> SchnorrProof<G1Projective> {
+    schnorr::prove(&mut Transcript::new(hash, PROTOCOL), secret, &mut thread_rng())
+}
+
+fn verify<H: TranscriptHash<Fr>>(hash: H, public: &G1Affine, proof: &SchnorrProof<G1Projective>) -> bool {
+    schnorr::verify(&mut Transcript::new(hash, PROTOCOL), public, proof)
+}
+
+#[test]
+fn a_proof_is_only_accepted_under_the_hash_it_was_made_with() {
+    let poseidon = || PoseidonHash::new(&poseidon_config::<Fr>(2));
+    let secret = Fr::from(0x5eed_u64);
+    let public = (G1Projective::generator() * secret).into_affine();
+    let proofs = [prove(Blake2bHash::default(), secret), prove(Sha3Hash::default(), secret), prove(poseidon(), secret)];
+    for (i, proof) in proofs.iter().enumerate() {
+        let verdicts = [
+            verify(Blake2bHash::default(), &public, proof),
+            verify(Sha3Hash::default(), &public, proof),
+            verify(poseidon(), &public, proof),
+        ];
+        let expected: Vec<bool> = (0..3).map(|j| i == j).collect();
+        assert_eq!(verdicts.to_vec(), expected, "proof {i}");
+    }
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "groth16")]
+// the test form of src/bin/validation.rs: points outside the subgroup, and x
+// coordinates with no point on the curve at all, fed to the checked and unchecked
+// paths of zk-core/src/artifact.rs
+
+use ark_bls12_381::{Bls12_381, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use rand::thread_rng;
+use zk_circuits::Groth16Artifact;
+use zk_circuits::addition::AdditionCircuit;
+use zk_circuits::curves::Curve;
+use zk_circuits::explain::explain;
+use zk_core::artifact::{self, ArtifactError, ProofArtifact, Validation};
+
+fn g1_outside_subgroup() -> G1Affine {
+    let mut x = Fq::zero();
+    loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false)
+            && !p.is_in_correct_subgroup_assuming_on_curve()
+        {
+            return p;
+        }
+        x += Fq::one();
+    }
+}
+
+fn g2_outside_subgroup() -> G2Affine {
+    let mut x = Fq2::zero();
+    loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(x, false)
+            && !p.is_in_correct_subgroup_assuming_on_curve()
+        {
+            return p;
+        }
+        x += Fq2::one();
+    }
+}
+
+// the compressed G1 encoding of x: big endian, the top bit says compressed
+fn g1_compressed(x: Fq) -> Vec<u8> {
+    let mut bytes = x.into_bigint().to_bytes_be();
+    bytes[0] |= 0x80;
+    bytes
+}
+
+// the first x with no y on the curve
+fn g1_off_curve() -> Vec<u8> {
+    let mut x = Fq::zero();
+    while G1Affine::get_point_from_x_unchecked(x, false).is_some() {
+        x += Fq::one();
+    }
+    g1_compressed(x)
+}
+
+fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).expect("serializing into a vec");
+    bytes
+}
+
+fn honest() -> (VerifyingKey<Bls12_381>, Proof<Bls12_381>) {
+    let mut rng = thread_rng();
+    let (pk, vk) = Groth16::<Bls12_381>::circuit_specific_setup(AdditionCircuit::<Fr>::empty(), &mut rng).expect("setup");
+    let proof = Groth16::<Bls12_381>::prove(&pk, AdditionCircuit::new(Fr::from(17u32), Fr::from(2u32)), &mut rng).expect("proving");
+    (vk, proof)
+}
+
+// checked refuses it, unchecked takes it, or the test tests nothing
+fn only_unchecked<T: CanonicalDeserialize>(bytes: &[u8]) {
+    assert!(matches!(artifact::deserialize_checked::<T>(bytes), Err(ArtifactError::Serialization(_))));
+    assert!(artifact::deserialize_unchecked::<T>(bytes).is_ok());
+}
+
+#[test]
+fn an_honest_artifact_decodes_checked() {
+    let (_, proof) = honest();
+    let good = ProofArtifact { proof, public_inputs: vec![Fr::from(19u32)] };
+    let decoded: Groth16Artifact<Bls12_381> = artifact::deserialize_checked(&bytes(&good)).expect("it decodes");
+    assert_eq!(decoded, good);
+}
+
+#[test]
+fn points_outside_the_subgroup_are_refused_checked() {
+    let (_, proof) = honest();
+    let (bad_g1, bad_g2) = (g1_outside_subgroup(), g2_outside_subgroup());
+    assert!(bad_g1.is_on_curve() && bad_g2.is_on_curve());
+    only_unchecked::<G1Affine>(&bytes(&bad_g1));
+    only_unchecked::<G2Affine>(&bytes(&bad_g2));
+    only_unchecked::<Proof<Bls12_381>>(&bytes(&Proof { a: bad_g1, ..proof.clone() }));
+    only_unchecked::<Proof<Bls12_381>>(&bytes(&Proof { b: bad_g2, ..proof.clone() }));
+    only_unchecked::<Proof<Bls12_381>>(&bytes(&Proof { c: bad_g1, ..proof }));
+}
+
+#[test]
+fn an_artifact_off_the_wire_is_checked() {
+    let (vk, proof) = honest();
+    let artifact = ProofArtifact {
+        proof: Proof { a: g1_outside_subgroup(), ..proof },
+        public_inputs: vec![Fr::from(19u32)],
+    };
+    let hex = artifact::to_hex(&bytes(&artifact));
+    assert!(Groth16Artifact::<Bls12_381>::from_bytes(&artifact::from_hex(&hex).expect("hex")).is_err());
+    // explain reads unchecked, so it can say which point is the bad one
+    let explained = explain(&AdditionCircuit::<Fr>::input_spec(), &artifact, Some(&vk));
+    assert!(!explained.points[0].in_subgroup && explained.points[1].in_subgroup && !explained.valid());
+}
+
+#[test]
+fn a_key_file_with_a_bad_point_only_loads_unchecked() {
+    let (mut vk, _) = honest();
+    vk.gamma_abc_g1[0] = g1_outside_subgroup();
+    let path = std::env::temp_dir().join(format!("zk-toy-test-bad-vk-{}.bin", std::process::id()));
+    artifact::save(&vk, &zk_circuits::build_info(Bls12_381::NAME), &path).expect("the key saves");
+    let checked: Result<VerifyingKey<Bls12_381>, _> = artifact::load(&path);
+    let unchecked: Result<VerifyingKey<Bls12_381>, _> = artifact::load_with(&path, Validation::Unchecked);
+    let _ = std::fs::remove_file(&path);
+    assert!(checked.is_err());
+    assert_eq!(unchecked.expect("unchecked loads it"), vk);
+}
+
+#[test]
+fn an_x_with_no_point_on_the_curve_is_refused_either_way() {
+    // the same encoding of an x that is on the curve decodes, so it's the x being refused
+    let on = g1_outside_subgroup();
+    assert_eq!(artifact::deserialize_unchecked::<G1Affine>(&g1_compressed(on.x)).expect("on the curve").x, on.x);
+
+    let off = g1_off_curve();
+    assert!(artifact::deserialize_checked::<G1Affine>(&off).is_err());
+    assert!(artifact::deserialize_unchecked::<G1Affine>(&off).is_err());
+
+    let (_, proof) = honest();
+    let mut encoded = bytes(&proof);
+    encoded[..off.len()].copy_from_slice(&off);
+    assert!(artifact::deserialize_checked::<Proof<Bls12_381>>(&encoded).is_err());
+    assert!(artifact::deserialize_unchecked::<Proof<Bls12_381>>(&encoded).is_err());
+}